@@ -4,17 +4,23 @@
     reason = "CLI binary uses process::exit for fatal errors"
 )]
 
+use core::fmt::Write as _;
+use std::collections::HashMap;
 use std::io::{self, Write as _};
 use std::path::PathBuf;
 use std::process::ExitCode;
 
-use clap::{Args, Parser, Subcommand};
+use chrono::{Datelike as _, Days, Local};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{Cell, Color, Table};
 use indicatif::{ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
+use zenmoney_rs::error::ZenMoneyError;
 use zenmoney_rs::models::{
-    Account, DiffResponse, NaiveDate, SuggestRequest, SuggestResponse, Tag, TagId, Transaction,
+    Account, AccountId, Budget, DiffResponse, InstrumentId, NaiveDate, Reminder, ReminderMarker,
+    ReminderMarkerState, SuggestRequest, SuggestResponse, Tag, TagId, Transaction, Utc,
+    mcc_description, sort_accounts_for_display, to_csv,
 };
 use zenmoney_rs::storage::{BlockingStorage, FileStorage};
 use zenmoney_rs::zen_money::{TransactionFilter, ZenMoneyBlocking};
@@ -22,6 +28,20 @@ use zenmoney_rs::zen_money::{TransactionFilter, ZenMoneyBlocking};
 /// Environment variable name for the API token.
 const TOKEN_ENV: &str = "ZENMONEY_TOKEN";
 
+/// Default maximum number of suggest calls made by `categorize`.
+const DEFAULT_CATEGORIZE_LIMIT: usize = 20;
+
+/// Tag ID ZenMoney uses for the aggregate budget; a `None` tag is the
+/// uncategorized budget instead.
+const OVERALL_BUDGET_TAG: &str = "00000000-0000-0000-0000-000000000000";
+
+/// Number of payees listed by `stats`.
+const TOP_PAYEES: usize = 5;
+
+/// Days per reminder step searched for a reminder's next occurrence;
+/// enough to reach the next yearly occurrence.
+const REMINDER_HORIZON_DAYS: u64 = 366;
+
 /// ZenMoney API CLI — sync and browse personal finance data.
 #[derive(Debug, Parser)]
 #[command(name = "zenmoney", version, about)]
@@ -44,7 +64,7 @@ enum Command {
     /// List active (non-archived) accounts.
     Accounts,
     /// List transactions, optionally filtered by date range, account,
-    /// tag, payee, or amount.
+    /// tag, payee, comment, or amount.
     Transactions(TransactionArgs),
     /// List all tags.
     Tags,
@@ -57,11 +77,127 @@ enum Command {
         #[arg(long)]
         comment: Option<String>,
     },
+    /// Suggest tags for uncategorized transactions, optionally applying
+    /// them.
+    Categorize(CategorizeArgs),
+    /// Export transactions as CSV, with the same filters as
+    /// `transactions`, or the whole storage as diff-shaped JSON.
+    Export(ExportArgs),
+    /// List reminders that still have an occurrence ahead, soonest first.
+    Reminders,
+    /// List reminder markers by date, only planned ones unless
+    /// --all-states is given.
+    ReminderMarkers(ReminderMarkerArgs),
+    /// Compare a month's budgets with actual spending per tag.
+    Budgets {
+        /// Budget month (YYYY-MM).
+        #[arg(long, value_parser = parse_month)]
+        month: NaiveDate,
+    },
+    /// Summarize transactions in a date range: totals, average, and top
+    /// payees.
+    Stats {
+        /// Start date (inclusive, YYYY-MM-DD).
+        #[arg(long, value_parser = parse_date)]
+        from: NaiveDate,
+        /// End date (inclusive, YYYY-MM-DD).
+        #[arg(long, value_parser = parse_date)]
+        to: NaiveDate,
+    },
+}
+
+/// Arguments for the `reminder-markers` subcommand.
+#[derive(Debug, Args)]
+struct ReminderMarkerArgs {
+    /// Only show markers dated today or later.
+    #[arg(long)]
+    upcoming: bool,
+    /// Show processed and deleted markers too.
+    #[arg(long)]
+    all_states: bool,
+}
+
+/// Arguments for the `categorize` subcommand.
+#[derive(Debug, Args)]
+struct CategorizeArgs {
+    /// Push the suggested tags to the server instead of only printing
+    /// them.
+    #[arg(long)]
+    apply: bool,
+    /// Maximum number of transactions to query suggestions for.
+    #[arg(long, default_value_t = DEFAULT_CATEGORIZE_LIMIT)]
+    limit: usize,
+}
+
+/// One line of the `budgets` table.
+#[derive(Debug, PartialEq)]
+struct BudgetRow {
+    /// Tag title, `Overall` for the aggregate budget, or `Uncategorized`
+    /// for the budget without a tag.
+    label: String,
+    /// Planned outcome.
+    planned: f64,
+    /// Actual outcome in the month.
+    actual: f64,
+    /// Planned minus actual; negative when over budget.
+    remaining: f64,
+}
+
+/// Aggregates printed by the `stats` subcommand.
+#[derive(Debug, PartialEq)]
+struct TransactionStats {
+    /// Number of transactions.
+    count: usize,
+    /// Sum of `income`.
+    income: f64,
+    /// Sum of `outcome`.
+    outcome: f64,
+    /// Mean of the larger side of each transaction.
+    average: f64,
+    /// Payees with the highest outcome, largest first.
+    top_payees: Vec<(String, f64)>,
+}
+
+/// A tag suggestion for a single uncategorized transaction.
+#[derive(Debug)]
+struct Categorization {
+    /// Transaction the suggestion applies to.
+    transaction: Transaction,
+    /// Suggested tags.
+    tags: Vec<TagId>,
 }
 
 /// Arguments for the `transactions` subcommand.
 #[derive(Debug, Args)]
 struct TransactionArgs {
+    /// Transaction filters.
+    #[command(flatten)]
+    filter: FilterArgs,
+    /// Output format.
+    #[arg(long, alias = "output", value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+}
+
+/// Arguments for the `export` subcommand.
+#[derive(Debug, Args)]
+struct ExportArgs {
+    /// Transaction filters.
+    #[command(flatten)]
+    filter: FilterArgs,
+    /// Export format.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    format: ExportFormat,
+    /// Indent JSON output.
+    #[arg(long)]
+    pretty: bool,
+    /// Write to this file instead of stdout.
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+/// Transaction filters shared by `transactions` and `export`.
+#[derive(Debug, Args)]
+struct FilterArgs {
     /// Start date (inclusive, YYYY-MM-DD). Requires --to.
     #[arg(long, requires = "to", value_parser = parse_date)]
     from: Option<NaiveDate>,
@@ -77,6 +213,9 @@ struct TransactionArgs {
     /// Filter by payee name (case-insensitive substring match).
     #[arg(long)]
     payee: Option<String>,
+    /// Filter by comment text (case-insensitive substring match).
+    #[arg(long)]
+    comment: Option<String>,
     /// Minimum transaction amount (income or outcome).
     #[arg(long)]
     min_amount: Option<f64>,
@@ -85,11 +224,87 @@ struct TransactionArgs {
     max_amount: Option<f64>,
 }
 
+/// File format of the `export` subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// The whole storage as one diff-shaped JSON document; filters are
+    /// ignored.
+    Json,
+}
+
+/// Output format of the `transactions` subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable table.
+    Table,
+    /// Plaintext-accounting journal entries (ledger / hledger).
+    Ledger,
+}
+
+/// Display names used when rendering ledger entries.
+#[derive(Debug, Default)]
+struct LedgerNames {
+    /// Account titles by ID.
+    accounts: HashMap<AccountId, String>,
+    /// Tag titles by ID.
+    tags: HashMap<TagId, String>,
+    /// Currency codes by instrument ID.
+    commodities: HashMap<InstrumentId, String>,
+}
+
+impl LedgerNames {
+    /// Loads account, tag, and instrument names from storage.
+    fn load<S: BlockingStorage>(client: &ZenMoneyBlocking<S>) -> zenmoney_rs::error::Result<Self> {
+        Ok(Self {
+            accounts: client
+                .accounts()?
+                .into_iter()
+                .map(|acc| (acc.id, acc.title))
+                .collect(),
+            tags: client
+                .tags()?
+                .into_iter()
+                .map(|tag| (tag.id, tag.title))
+                .collect(),
+            commodities: client
+                .instruments()?
+                .into_iter()
+                .map(|inst| (inst.id, inst.short_title))
+                .collect(),
+        })
+    }
+
+    /// Returns the ledger account name for a ZenMoney account.
+    fn account(&self, id: &AccountId) -> String {
+        let title = self
+            .accounts
+            .get(id)
+            .map_or_else(|| id.to_string(), Clone::clone);
+        format!("Assets:{title}")
+    }
+
+    /// Returns the currency code for an instrument.
+    fn commodity(&self, id: InstrumentId) -> String {
+        self.commodities
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| format!("I{id}"))
+    }
+}
+
 /// Parses a date string in `YYYY-MM-DD` format for clap.
 fn parse_date(s: &str) -> Result<NaiveDate, String> {
     NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|err| format!("{err}"))
 }
 
+/// Parses a month string in `YYYY-MM` format for clap, returning its
+/// first day.
+fn parse_month(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{s}-01"), "%Y-%m-%d").map_err(|err| format!("{err}"))
+}
+
 /// Reads the API token from the environment.
 fn read_token() -> io::Result<Option<String>> {
     match std::env::var(TOKEN_ENV) {
@@ -127,7 +342,7 @@ fn run() -> io::Result<ExitCode> {
     let cli = Cli::parse();
 
     let Some(token) = read_token()? else {
-        return Ok(ExitCode::FAILURE);
+        return Ok(ExitCode::from(ZenMoneyError::TokenExpired.exit_code()));
     };
 
     let storage = match create_storage(cli.data_dir) {
@@ -138,7 +353,7 @@ fn run() -> io::Result<ExitCode> {
                 "{} failed to initialize storage: {err}",
                 "error:".red().bold()
             )?;
-            return Ok(ExitCode::FAILURE);
+            return Ok(ExitCode::from(err.exit_code()));
         }
     };
 
@@ -154,7 +369,7 @@ fn run() -> io::Result<ExitCode> {
                 "{} failed to build client: {err}",
                 "error:".red().bold()
             )?;
-            return Ok(ExitCode::FAILURE);
+            return Ok(ExitCode::from(err.exit_code()));
         }
     };
 
@@ -183,6 +398,12 @@ fn dispatch<S: BlockingStorage>(
         Command::Transactions(args) => cmd_transactions(client, &args),
         Command::Tags => cmd_tags(client),
         Command::Suggest { payee, comment } => cmd_suggest(client, payee, comment),
+        Command::Categorize(args) => cmd_categorize(client, &args),
+        Command::Export(args) => cmd_export(client, &args),
+        Command::Reminders => cmd_reminders(client),
+        Command::ReminderMarkers(args) => cmd_reminder_markers(client, &args),
+        Command::Budgets { month } => cmd_budgets(client, month),
+        Command::Stats { from, to } => cmd_stats(client, from, to),
     }
 }
 
@@ -203,7 +424,7 @@ fn cmd_diff<S: BlockingStorage>(client: &ZenMoneyBlocking<S>) -> io::Result<Exit
                 "{} sync failed: {err}",
                 "error:".red().bold()
             )?;
-            Ok(ExitCode::FAILURE)
+            Ok(ExitCode::from(err.exit_code()))
         }
     }
 }
@@ -226,7 +447,7 @@ fn cmd_full_sync<S: BlockingStorage>(client: &ZenMoneyBlocking<S>) -> io::Result
                 "{} full sync failed: {err}",
                 "error:".red().bold()
             )?;
-            Ok(ExitCode::FAILURE)
+            Ok(ExitCode::from(err.exit_code()))
         }
     }
 }
@@ -234,7 +455,8 @@ fn cmd_full_sync<S: BlockingStorage>(client: &ZenMoneyBlocking<S>) -> io::Result
 /// Executes the `accounts` subcommand: lists all active accounts.
 fn cmd_accounts<S: BlockingStorage>(client: &ZenMoneyBlocking<S>) -> io::Result<ExitCode> {
     match client.active_accounts() {
-        Ok(accounts) => {
+        Ok(mut accounts) => {
+            sort_accounts_for_display(&mut accounts);
             print_accounts_table(&accounts)?;
             Ok(ExitCode::SUCCESS)
         }
@@ -244,7 +466,7 @@ fn cmd_accounts<S: BlockingStorage>(client: &ZenMoneyBlocking<S>) -> io::Result<
                 "{} failed to read accounts: {err}",
                 "error:".red().bold()
             )?;
-            Ok(ExitCode::FAILURE)
+            Ok(ExitCode::from(err.exit_code()))
         }
     }
 }
@@ -282,7 +504,7 @@ where
 /// to IDs via the client.
 fn build_transaction_filter<S: BlockingStorage>(
     client: &ZenMoneyBlocking<S>,
-    args: &TransactionArgs,
+    args: &FilterArgs,
 ) -> io::Result<Option<TransactionFilter>> {
     let mut filter = TransactionFilter::new();
 
@@ -304,6 +526,9 @@ fn build_transaction_filter<S: BlockingStorage>(
     if let Some(payee_str) = args.payee.as_deref() {
         filter = filter.payee(payee_str);
     }
+    if let Some(comment_str) = args.comment.as_deref() {
+        filter = filter.comment(comment_str);
+    }
     match (args.min_amount, args.max_amount) {
         (Some(min), Some(max)) => filter = filter.amount_range(min, max),
         (Some(min), None) => filter.min_amount = Some(min),
@@ -319,24 +544,79 @@ fn cmd_transactions<S: BlockingStorage>(
     client: &ZenMoneyBlocking<S>,
     args: &TransactionArgs,
 ) -> io::Result<ExitCode> {
-    let Some(filter) = build_transaction_filter(client, args)? else {
+    let Some(filter) = build_transaction_filter(client, &args.filter)? else {
         return Ok(ExitCode::FAILURE);
     };
 
-    match client.filter_transactions(&filter) {
-        Ok(txs) => {
+    let result = client.filter_transactions(&filter).and_then(|txs| {
+        let names = match args.format {
+            OutputFormat::Table => None,
+            OutputFormat::Ledger => Some(LedgerNames::load(client)?),
+        };
+        Ok((txs, names))
+    });
+    match result {
+        Ok((txs, None)) => {
             print_transactions_table(&txs)?;
             Ok(ExitCode::SUCCESS)
         }
+        Ok((txs, Some(names))) => {
+            print_ledger(&txs, &names)?;
+            Ok(ExitCode::SUCCESS)
+        }
         Err(err) => {
             writeln!(
                 io::stderr().lock(),
                 "{} failed to read transactions: {err}",
                 "error:".red().bold()
             )?;
-            Ok(ExitCode::FAILURE)
+            Ok(ExitCode::from(err.exit_code()))
+        }
+    }
+}
+
+/// Executes the `export` subcommand: writes filtered transactions as
+/// CSV, or the whole storage as JSON, to stdout or a file.
+fn cmd_export<S: BlockingStorage>(
+    client: &ZenMoneyBlocking<S>,
+    args: &ExportArgs,
+) -> io::Result<ExitCode> {
+    let result = match args.format {
+        ExportFormat::Csv => {
+            let Some(filter) = build_transaction_filter(client, &args.filter)? else {
+                return Ok(ExitCode::FAILURE);
+            };
+            client.filter_transactions(&filter).and_then(|txs| {
+                let mut csv = Vec::new();
+                to_csv(&mut csv, &txs, &client.accounts()?, &client.tags()?)?;
+                Ok(csv)
+            })
+        }
+        ExportFormat::Json => client.export_diff().and_then(|diff| {
+            let json = if args.pretty {
+                serde_json::to_vec_pretty(&diff)?
+            } else {
+                serde_json::to_vec(&diff)?
+            };
+            Ok(json)
+        }),
+    };
+    let rendered = match result {
+        Ok(rendered) => rendered,
+        Err(err) => {
+            writeln!(
+                io::stderr().lock(),
+                "{} export failed: {err}",
+                "error:".red().bold()
+            )?;
+            return Ok(ExitCode::from(err.exit_code()));
         }
+    };
+    match args.output.as_deref() {
+        Some(path) => std::fs::write(path, rendered)?,
+        None => io::stdout().lock().write_all(&rendered)?,
     }
+    Ok(ExitCode::SUCCESS)
 }
 
 /// Executes the `tags` subcommand: lists all tags.
@@ -352,7 +632,243 @@ fn cmd_tags<S: BlockingStorage>(client: &ZenMoneyBlocking<S>) -> io::Result<Exit
                 "{} failed to read tags: {err}",
                 "error:".red().bold()
             )?;
-            Ok(ExitCode::FAILURE)
+            Ok(ExitCode::from(err.exit_code()))
+        }
+    }
+}
+
+/// Pairs each reminder with its next occurrence on or after `today`,
+/// dropping reminders that have ended, sorted by that date.
+fn upcoming_reminders(reminders: Vec<Reminder>, today: NaiveDate) -> Vec<(Reminder, NaiveDate)> {
+    let mut upcoming: Vec<(Reminder, NaiveDate)> = reminders
+        .into_iter()
+        .filter_map(|reminder| {
+            let step = reminder.step.and_then(|step| u64::try_from(step).ok());
+            let horizon = today
+                .checked_add_days(Days::new(REMINDER_HORIZON_DAYS * step.unwrap_or(1).max(1)))
+                .unwrap_or(NaiveDate::MAX);
+            let next = reminder.occurrences(today, horizon).first().copied()?;
+            Some((reminder, next))
+        })
+        .collect();
+    upcoming.sort_by_key(|&(_, date)| date);
+    upcoming
+}
+
+/// Keeps the markers selected by `args`, sorted by date.
+fn select_reminder_markers(
+    markers: Vec<ReminderMarker>,
+    args: &ReminderMarkerArgs,
+    today: NaiveDate,
+) -> Vec<ReminderMarker> {
+    let mut selected: Vec<ReminderMarker> = markers
+        .into_iter()
+        .filter(|marker| args.all_states || marker.state == ReminderMarkerState::Planned)
+        .filter(|marker| !args.upcoming || marker.date >= today)
+        .collect();
+    selected.sort_by_key(|marker| marker.date);
+    selected
+}
+
+/// Executes the `reminders` subcommand: lists upcoming reminders.
+fn cmd_reminders<S: BlockingStorage>(client: &ZenMoneyBlocking<S>) -> io::Result<ExitCode> {
+    match client.reminders() {
+        Ok(reminders) => {
+            let today = Local::now().date_naive();
+            print_reminders_table(&upcoming_reminders(reminders, today))?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(err) => {
+            writeln!(
+                io::stderr().lock(),
+                "{} failed to read reminders: {err}",
+                "error:".red().bold()
+            )?;
+            Ok(ExitCode::from(err.exit_code()))
+        }
+    }
+}
+
+/// Executes the `reminder-markers` subcommand: lists reminder markers.
+fn cmd_reminder_markers<S: BlockingStorage>(
+    client: &ZenMoneyBlocking<S>,
+    args: &ReminderMarkerArgs,
+) -> io::Result<ExitCode> {
+    match client.reminder_markers() {
+        Ok(markers) => {
+            let today = Local::now().date_naive();
+            print_reminder_markers_table(&select_reminder_markers(markers, args, today))?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(err) => {
+            writeln!(
+                io::stderr().lock(),
+                "{} failed to read reminder markers: {err}",
+                "error:".red().bold()
+            )?;
+            Ok(ExitCode::from(err.exit_code()))
+        }
+    }
+}
+
+/// Pairs each budget of `month` with the outcome actually spent.
+///
+/// `spending` is the month's outcome per tag as returned by
+/// `spending_by_tag`, and `total` the month's whole outcome. The aggregate
+/// budget (the all-zero tag) is compared with `total` and listed first,
+/// the uncategorized budget (no tag) with the untagged spending next, and
+/// tag budgets follow in title order, each compared with the spending on
+/// that tag.
+fn budget_rows(
+    budgets: &[Budget],
+    spending: &HashMap<Option<TagId>, f64>,
+    total: f64,
+    tags: &[Tag],
+    month: NaiveDate,
+) -> Vec<BudgetRow> {
+    let titles: HashMap<&TagId, &str> = tags
+        .iter()
+        .map(|tag| (&tag.id, tag.title.as_str()))
+        .collect();
+    let spent = |tag: Option<&TagId>| spending.get(&tag.cloned()).copied().unwrap_or_default();
+
+    let mut rows: Vec<(u8, BudgetRow)> = budgets
+        .iter()
+        .filter(|budget| budget.date.year() == month.year() && budget.date.month() == month.month())
+        .map(|budget| {
+            let (rank, label, actual) = match budget.tag.as_ref() {
+                Some(tag) if tag.as_inner() == OVERALL_BUDGET_TAG => {
+                    (0, "Overall".to_owned(), total)
+                }
+                None => (1, "Uncategorized".to_owned(), spent(None)),
+                Some(tag) => (
+                    2,
+                    titles
+                        .get(tag)
+                        .copied()
+                        .unwrap_or_else(|| tag.as_inner())
+                        .to_owned(),
+                    spent(Some(tag)),
+                ),
+            };
+            let row = BudgetRow {
+                label,
+                planned: budget.outcome,
+                actual,
+                remaining: budget.remaining(actual),
+            };
+            (rank, row)
+        })
+        .collect();
+    rows.sort_by(|left, right| {
+        left.0
+            .cmp(&right.0)
+            .then_with(|| left.1.label.cmp(&right.1.label))
+    });
+    rows.into_iter().map(|(_, row)| row).collect()
+}
+
+/// Executes the `budgets` subcommand: compares the month's budgets with
+/// actual spending.
+fn cmd_budgets<S: BlockingStorage>(
+    client: &ZenMoneyBlocking<S>,
+    month: NaiveDate,
+) -> io::Result<ExitCode> {
+    let filter = TransactionFilter::new().month(month.year(), month.month());
+    let result = client.budgets().and_then(|budgets| {
+        let spending = client.spending_by_tag(&filter)?;
+        let total = client
+            .filter_transactions(&filter)?
+            .iter()
+            .map(|tx| tx.outcome)
+            .sum();
+        let tags = client.tags()?;
+        Ok(budget_rows(&budgets, &spending, total, &tags, month))
+    });
+    match result {
+        Ok(rows) => {
+            print_budgets_table(&rows, month)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(err) => {
+            writeln!(
+                io::stderr().lock(),
+                "{} failed to read budgets: {err}",
+                "error:".red().bold()
+            )?;
+            Ok(ExitCode::from(err.exit_code()))
+        }
+    }
+}
+
+/// Computes totals, the average size, and the top payees by outcome;
+/// `None` if there are no transactions.
+///
+/// Payees are grouped case-insensitively and shown with the spelling
+/// seen first. Transactions without a payee are not ranked.
+fn transaction_stats(transactions: &[Transaction]) -> Option<TransactionStats> {
+    if transactions.is_empty() {
+        return None;
+    }
+    let mut payees: HashMap<String, (String, f64)> = HashMap::new();
+    for tx in transactions {
+        if let Some(payee) = tx.payee.as_deref().filter(|payee| !payee.is_empty()) {
+            payees
+                .entry(payee.to_lowercase())
+                .or_insert_with(|| (payee.to_owned(), 0.0_f64))
+                .1 += tx.outcome;
+        }
+    }
+    let mut top_payees: Vec<(String, f64)> = payees
+        .into_values()
+        .filter(|&(_, spent)| spent > 0.0_f64)
+        .collect();
+    top_payees.sort_by(|left, right| {
+        right
+            .1
+            .total_cmp(&left.1)
+            .then_with(|| left.0.cmp(&right.0))
+    });
+    top_payees.truncate(TOP_PAYEES);
+
+    let sizes: f64 = transactions
+        .iter()
+        .map(|tx| tx.outcome.max(tx.income))
+        .sum();
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "transaction counts are far below 2^52"
+    )]
+    let average = sizes / transactions.len() as f64;
+    Some(TransactionStats {
+        count: transactions.len(),
+        income: transactions.iter().map(|tx| tx.income).sum(),
+        outcome: transactions.iter().map(|tx| tx.outcome).sum(),
+        average,
+        top_payees,
+    })
+}
+
+/// Executes the `stats` subcommand: summarizes transactions in a date
+/// range.
+fn cmd_stats<S: BlockingStorage>(
+    client: &ZenMoneyBlocking<S>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> io::Result<ExitCode> {
+    let filter = TransactionFilter::new().date_range(from, to);
+    match client.filter_transactions(&filter) {
+        Ok(txs) => {
+            print_stats(transaction_stats(&txs).as_ref())?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(err) => {
+            writeln!(
+                io::stderr().lock(),
+                "{} failed to read transactions: {err}",
+                "error:".red().bold()
+            )?;
+            Ok(ExitCode::from(err.exit_code()))
         }
     }
 }
@@ -389,13 +905,164 @@ fn cmd_suggest<S: BlockingStorage>(
                 "{} suggest failed: {err}",
                 "error:".red().bold()
             )?;
-            Ok(ExitCode::FAILURE)
+            Ok(ExitCode::from(err.exit_code()))
+        }
+    }
+}
+
+/// Selects up to `limit` non-deleted transactions that have a payee but
+/// no tags.
+fn uncategorized_transactions(txs: Vec<Transaction>, limit: usize) -> Vec<Transaction> {
+    txs.into_iter()
+        .filter(|tx| {
+            !tx.deleted
+                && tx.tag.as_ref().is_none_or(Vec::is_empty)
+                && tx.payee.as_ref().is_some_and(|payee| !payee.is_empty())
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Queries the suggest endpoint for each transaction's payee, keeping
+/// only suggestions that contain at least one tag.
+fn collect_categorizations<S: BlockingStorage>(
+    client: &ZenMoneyBlocking<S>,
+    candidates: Vec<Transaction>,
+) -> zenmoney_rs::error::Result<Vec<Categorization>> {
+    let mut categorizations = Vec::new();
+    for transaction in candidates {
+        let request = SuggestRequest {
+            payee: transaction.payee.clone(),
+            comment: None,
+        };
+        let suggested = client.suggest(&request)?.tag.unwrap_or_default();
+        if !suggested.is_empty() {
+            categorizations.push(Categorization {
+                transaction,
+                tags: suggested,
+            });
+        }
+    }
+    Ok(categorizations)
+}
+
+/// Pushes the suggested tags for every categorization to the server.
+fn apply_categorizations<S: BlockingStorage>(
+    client: &ZenMoneyBlocking<S>,
+    categorizations: Vec<Categorization>,
+) -> zenmoney_rs::error::Result<usize> {
+    let now = Utc::now();
+    let updated: Vec<Transaction> = categorizations
+        .into_iter()
+        .map(|item| {
+            let mut tx = item.transaction;
+            tx.tag = Some(item.tags);
+            tx.changed = now;
+            tx
+        })
+        .collect();
+    let count = updated.len();
+    drop(client.push_transactions(updated)?);
+    Ok(count)
+}
+
+/// Executes the `categorize` subcommand: suggests tags for untagged
+/// transactions and optionally pushes them.
+fn cmd_categorize<S: BlockingStorage>(
+    client: &ZenMoneyBlocking<S>,
+    args: &CategorizeArgs,
+) -> io::Result<ExitCode> {
+    let spinner = make_spinner("Querying suggestions...");
+    let result = client
+        .transactions()
+        .map(|txs| uncategorized_transactions(txs, args.limit))
+        .and_then(|candidates| collect_categorizations(client, candidates))
+        .and_then(|categorizations| Ok((categorizations, client.tags()?)));
+    spinner.finish_and_clear();
+
+    let (categorizations, tags) = match result {
+        Ok(found) => found,
+        Err(err) => {
+            writeln!(
+                io::stderr().lock(),
+                "{} categorize failed: {err}",
+                "error:".red().bold()
+            )?;
+            return Ok(ExitCode::from(err.exit_code()));
+        }
+    };
+    print_categorizations_table(&categorizations, &tags)?;
+    if !args.apply || categorizations.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    match apply_categorizations(client, categorizations) {
+        Ok(count) => {
+            writeln!(
+                io::stdout().lock(),
+                "{} {count} transaction(s) updated",
+                "Applied:".green().bold()
+            )?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(err) => {
+            writeln!(
+                io::stderr().lock(),
+                "{} failed to push tags: {err}",
+                "error:".red().bold()
+            )?;
+            Ok(ExitCode::from(err.exit_code()))
         }
     }
 }
 
 // ── Output formatting ────────────────────────────────────────────────
 
+/// Prints proposed categorizations in a table, showing tag titles where
+/// known.
+fn print_categorizations_table(categorizations: &[Categorization], tags: &[Tag]) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    if categorizations.is_empty() {
+        writeln!(out, "{}", "No suggestions found.".dimmed())?;
+        return Ok(());
+    }
+
+    let titles: HashMap<&TagId, &str> = tags
+        .iter()
+        .map(|tag| (&tag.id, tag.title.as_str()))
+        .collect();
+    let mut table = Table::new();
+    _ = table.load_preset(UTF8_FULL);
+    _ = table.set_header(vec![
+        Cell::new("Date").fg(Color::Cyan),
+        Cell::new("Payee").fg(Color::Cyan),
+        Cell::new("Suggested Tags").fg(Color::Cyan),
+    ]);
+
+    for item in categorizations {
+        let tag_list: Vec<&str> = item
+            .tags
+            .iter()
+            .map(|id| titles.get(id).copied().unwrap_or_else(|| id.as_inner()))
+            .collect();
+        _ = table.add_row(vec![
+            Cell::new(item.transaction.date),
+            Cell::new(item.transaction.payee.as_deref().unwrap_or("\u{2014}")),
+            Cell::new(tag_list.join(", ")).fg(Color::Green),
+        ]);
+    }
+
+    writeln!(
+        out,
+        "{} {}",
+        "Suggested Categories".green().bold(),
+        format_args!("({})", categorizations.len()).dimmed()
+    )?;
+    writeln!(out)?;
+    writeln!(out, "{table}")?;
+    Ok(())
+}
+
 /// Prints the suggest response in a human-readable format.
 fn print_suggest_result(response: &SuggestResponse) -> io::Result<()> {
     let mut out = io::stdout().lock();
@@ -468,12 +1135,16 @@ fn print_transactions_table(txs: &[Transaction]) -> io::Result<()> {
         Cell::new("Payee").fg(Color::Cyan),
         Cell::new("Outcome").fg(Color::Cyan),
         Cell::new("Income").fg(Color::Cyan),
+        Cell::new("Category").fg(Color::Cyan),
         Cell::new("Comment").fg(Color::Cyan),
     ]);
 
     for tx in txs {
         let payee = tx.payee.as_deref().unwrap_or("\u{2014}");
         let comment = tx.comment.as_deref().unwrap_or("");
+        let category = tx.mcc.map_or_else(String::new, |code| {
+            mcc_description(code).map_or_else(|| format!("MCC {code}"), str::to_owned)
+        });
 
         let outcome_cell = if tx.outcome > 0.0_f64 {
             Cell::new(format!("{:.2}", tx.outcome)).fg(Color::Red)
@@ -492,6 +1163,7 @@ fn print_transactions_table(txs: &[Transaction]) -> io::Result<()> {
             Cell::new(payee),
             outcome_cell,
             income_cell,
+            Cell::new(category),
             Cell::new(comment),
         ]);
     }
@@ -507,6 +1179,86 @@ fn print_transactions_table(txs: &[Transaction]) -> io::Result<()> {
     Ok(())
 }
 
+/// Prints transactions as ledger journal entries.
+fn print_ledger(txs: &[Transaction], names: &LedgerNames) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    for entry in txs.iter().filter_map(|tx| ledger_entry(tx, names)) {
+        writeln!(out, "{entry}")?;
+    }
+    Ok(())
+}
+
+/// Formats one posting line, aligning the amount column.
+fn ledger_posting(account: &str, amount: f64, commodity: &str) -> String {
+    format!("    {account:<40}  {amount:>12.2} {commodity}")
+}
+
+/// Renders a transaction as a ledger entry with two balanced postings.
+///
+/// Expenses and incomes are balanced against `Expenses:<tag>` or
+/// `Income:<tag>`; transfers post to both accounts, recording a currency
+/// conversion as a total price (`@@`) on the receiving side. Returns
+/// `None` for deleted transactions and ones with no amounts.
+fn ledger_entry(tx: &Transaction, names: &LedgerNames) -> Option<String> {
+    if tx.deleted {
+        return None;
+    }
+    let payee = tx.payee.as_deref().unwrap_or_default();
+    let mut entry = format!("{} {payee}\n", tx.date);
+    if let Some(comment) = tx.comment.as_deref().filter(|text| !text.is_empty()) {
+        _ = writeln!(entry, "    ; {comment}");
+    }
+    let mut postings = tx.postings().into_iter();
+    match (postings.next(), postings.next()) {
+        (Some(single), None) => {
+            let category = tx
+                .tag
+                .as_ref()
+                .and_then(|tags| tags.first())
+                .and_then(|id| names.tags.get(id))
+                .map_or("Uncategorized", String::as_str);
+            let root = if single.amount < 0.0_f64 {
+                "Expenses"
+            } else {
+                "Income"
+            };
+            let commodity = names.commodity(single.instrument);
+            entry.push_str(&ledger_posting(
+                &names.account(&single.account),
+                single.amount,
+                &commodity,
+            ));
+            entry.push('\n');
+            entry.push_str(&ledger_posting(
+                &format!("{root}:{category}"),
+                -single.amount,
+                &commodity,
+            ));
+        }
+        (Some(source), Some(destination)) => {
+            let source_commodity = names.commodity(source.instrument);
+            let mut received = ledger_posting(
+                &names.account(&destination.account),
+                destination.amount,
+                &names.commodity(destination.instrument),
+            );
+            if destination.instrument != source.instrument {
+                _ = write!(received, " @@ {:.2} {source_commodity}", -source.amount);
+            }
+            entry.push_str(&received);
+            entry.push('\n');
+            entry.push_str(&ledger_posting(
+                &names.account(&source.account),
+                source.amount,
+                &source_commodity,
+            ));
+        }
+        _ => return None,
+    }
+    entry.push('\n');
+    Some(entry)
+}
+
 /// Prints tags in a table.
 fn print_tags_table(tags: &[Tag]) -> io::Result<()> {
     let mut out = io::stdout().lock();
@@ -522,22 +1274,212 @@ fn print_tags_table(tags: &[Tag]) -> io::Result<()> {
         Cell::new("Parent").fg(Color::Cyan),
     ]);
 
-    for tag in tags {
-        let parent = tag
-            .parent
-            .as_ref()
-            .map_or_else(|| "\u{2014}".to_owned(), ToString::to_string);
-        _ = table.add_row(vec![Cell::new(&tag.title), Cell::new(parent)]);
-    }
-
-    writeln!(
-        out,
-        "{} {}",
-        "Tags".green().bold(),
-        format_args!("({})", tags.len()).dimmed()
-    )?;
+    for tag in tags {
+        let parent = tag
+            .parent
+            .as_ref()
+            .map_or_else(|| "\u{2014}".to_owned(), ToString::to_string);
+        _ = table.add_row(vec![Cell::new(&tag.title), Cell::new(parent)]);
+    }
+
+    writeln!(
+        out,
+        "{} {}",
+        "Tags".green().bold(),
+        format_args!("({})", tags.len()).dimmed()
+    )?;
+    writeln!(out)?;
+    writeln!(out, "{table}")?;
+    Ok(())
+}
+
+/// Formats an amount for a table cell, or a dash if it is zero.
+fn amount_cell(amount: f64) -> Cell {
+    if amount > 0.0_f64 {
+        Cell::new(format!("{amount:.2}")).fg(Color::Red)
+    } else {
+        Cell::new("\u{2014}").fg(Color::DarkGrey)
+    }
+}
+
+/// Prints reminders with their next occurrence in a table.
+fn print_reminders_table(reminders: &[(Reminder, NaiveDate)]) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    if reminders.is_empty() {
+        writeln!(out, "{}", "No upcoming reminders.".dimmed())?;
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    _ = table.load_preset(UTF8_FULL);
+    _ = table.set_header(vec![
+        Cell::new("Payee").fg(Color::Cyan),
+        Cell::new("Interval").fg(Color::Cyan),
+        Cell::new("Step").fg(Color::Cyan),
+        Cell::new("Next Date").fg(Color::Cyan),
+        Cell::new("Outcome").fg(Color::Cyan),
+    ]);
+
+    for (reminder, next) in reminders.iter().map(|item| (&item.0, item.1)) {
+        let interval = reminder
+            .interval
+            .map_or_else(|| "once".to_owned(), |interval| format!("{interval:?}"));
+        let step = reminder
+            .step
+            .map_or_else(|| "\u{2014}".to_owned(), |step| step.to_string());
+        _ = table.add_row(vec![
+            Cell::new(reminder.payee.as_deref().unwrap_or("\u{2014}")),
+            Cell::new(interval),
+            Cell::new(step),
+            Cell::new(next),
+            amount_cell(reminder.outcome),
+        ]);
+    }
+
+    writeln!(
+        out,
+        "{} {}",
+        "Upcoming Reminders".green().bold(),
+        format_args!("({})", reminders.len()).dimmed()
+    )?;
+    writeln!(out)?;
+    writeln!(out, "{table}")?;
+    Ok(())
+}
+
+/// Prints reminder markers in a table.
+fn print_reminder_markers_table(markers: &[ReminderMarker]) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    if markers.is_empty() {
+        writeln!(out, "{}", "No reminder markers found.".dimmed())?;
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    _ = table.load_preset(UTF8_FULL);
+    _ = table.set_header(vec![
+        Cell::new("Date").fg(Color::Cyan),
+        Cell::new("Payee").fg(Color::Cyan),
+        Cell::new("State").fg(Color::Cyan),
+        Cell::new("Outcome").fg(Color::Cyan),
+    ]);
+
+    for marker in markers {
+        _ = table.add_row(vec![
+            Cell::new(marker.date),
+            Cell::new(marker.payee.as_deref().unwrap_or("\u{2014}")),
+            Cell::new(format!("{:?}", marker.state)),
+            amount_cell(marker.outcome),
+        ]);
+    }
+
+    writeln!(
+        out,
+        "{} {}",
+        "Reminder Markers".green().bold(),
+        format_args!("({})", markers.len()).dimmed()
+    )?;
+    writeln!(out)?;
+    writeln!(out, "{table}")?;
+    Ok(())
+}
+
+/// Prints budget rows in a table, with overspent remainders in red.
+fn print_budgets_table(rows: &[BudgetRow], month: NaiveDate) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    if rows.is_empty() {
+        writeln!(out, "{}", "No budgets found.".dimmed())?;
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    _ = table.load_preset(UTF8_FULL);
+    _ = table.set_header(vec![
+        Cell::new("Tag").fg(Color::Cyan),
+        Cell::new("Planned").fg(Color::Cyan),
+        Cell::new("Actual").fg(Color::Cyan),
+        Cell::new("Remaining").fg(Color::Cyan),
+    ]);
+
+    for row in rows {
+        let remaining_cell = if row.remaining < 0.0_f64 {
+            Cell::new(format!("{:.2}", row.remaining)).fg(Color::Red)
+        } else {
+            Cell::new(format!("{:.2}", row.remaining)).fg(Color::Green)
+        };
+        _ = table.add_row(vec![
+            Cell::new(&row.label),
+            Cell::new(format!("{:.2}", row.planned)),
+            Cell::new(format!("{:.2}", row.actual)),
+            remaining_cell,
+        ]);
+    }
+
+    writeln!(
+        out,
+        "{} {}",
+        "Budgets".green().bold(),
+        format_args!("({})", month.format("%Y-%m")).dimmed()
+    )?;
+    writeln!(out)?;
+    writeln!(out, "{table}")?;
+    Ok(())
+}
+
+/// Prints transaction statistics, or a notice if there is no data.
+fn print_stats(summary: Option<&TransactionStats>) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    let Some(stats) = summary else {
+        writeln!(out, "{}", "No data for this period.".dimmed())?;
+        return Ok(());
+    };
+
+    let mut table = Table::new();
+    _ = table.load_preset(UTF8_FULL);
+    _ = table.set_header(vec![
+        Cell::new("Metric").fg(Color::Cyan),
+        Cell::new("Value").fg(Color::Cyan),
+    ]);
+    let net = stats.income - stats.outcome;
+    let net_cell = if net < 0.0_f64 {
+        Cell::new(format!("{net:.2}")).fg(Color::Red)
+    } else {
+        Cell::new(format!("{net:.2}")).fg(Color::Green)
+    };
+    _ = table.add_row(vec![Cell::new("Transactions"), Cell::new(stats.count)]);
+    _ = table.add_row(vec![
+        Cell::new("Income"),
+        Cell::new(format!("{:.2}", stats.income)).fg(Color::Green),
+    ]);
+    _ = table.add_row(vec![
+        Cell::new("Outcome"),
+        Cell::new(format!("{:.2}", stats.outcome)).fg(Color::Red),
+    ]);
+    _ = table.add_row(vec![Cell::new("Net"), net_cell]);
+    _ = table.add_row(vec![
+        Cell::new("Average size"),
+        Cell::new(format!("{:.2}", stats.average)),
+    ]);
+
+    writeln!(out, "{}", "Statistics".green().bold())?;
     writeln!(out)?;
     writeln!(out, "{table}")?;
+
+    if !stats.top_payees.is_empty() {
+        let mut payees = Table::new();
+        _ = payees.load_preset(UTF8_FULL);
+        _ = payees.set_header(vec![
+            Cell::new("Payee").fg(Color::Cyan),
+            Cell::new("Spent").fg(Color::Cyan),
+        ]);
+        for (payee, spent) in stats.top_payees.iter().map(|item| (&item.0, item.1)) {
+            _ = payees.add_row(vec![Cell::new(payee), Cell::new(format!("{spent:.2}"))]);
+        }
+        writeln!(out)?;
+        writeln!(out, "{}", "Top Payees".green().bold())?;
+        writeln!(out)?;
+        writeln!(out, "{payees}")?;
+    }
     Ok(())
 }
 
@@ -617,9 +1559,10 @@ mod tests {
     use super::*;
 
     use chrono::DateTime;
+    use zenmoney_rs::error::EXIT_NETWORK;
     use zenmoney_rs::models::{
-        AccountId, AccountType, DiffResponse, InstrumentId, MerchantId, SuggestResponse, TagId,
-        TransactionId, UserId,
+        AccountId, AccountType, DiffResponse, InstrumentId, Interval, MerchantId, ReminderId,
+        ReminderMarkerId, SuggestResponse, TagId, TransactionId, UserId,
     };
     use zenmoney_rs::storage::InMemoryStorage;
 
@@ -768,9 +1711,7 @@ mod tests {
     #[test]
     fn resolve_name_lookup_error() {
         let result = resolve_name::<i32, _>("account", "Bad", |_| {
-            Err(zenmoney_rs::error::ZenMoneyError::Storage(Box::from(
-                "lookup failed",
-            )))
+            Err(ZenMoneyError::Storage(Box::from("lookup failed")))
         })
         .unwrap();
         assert!(result.is_none());
@@ -781,12 +1722,13 @@ mod tests {
     #[test]
     fn build_filter_no_args() {
         let client = mock_client();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: None,
             to: None,
             account: None,
             tag: None,
             payee: None,
+            comment: None,
             min_amount: None,
             max_amount: None,
         };
@@ -797,12 +1739,13 @@ mod tests {
     #[test]
     fn build_filter_with_date_range() {
         let client = mock_client();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
             to: Some(NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()),
             account: None,
             tag: None,
             payee: None,
+            comment: None,
             min_amount: None,
             max_amount: None,
         };
@@ -814,12 +1757,13 @@ mod tests {
     #[test]
     fn build_filter_account_not_found_returns_none() {
         let client = mock_client();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: None,
             to: None,
             account: Some("Nonexistent".to_owned()),
             tag: None,
             payee: None,
+            comment: None,
             min_amount: None,
             max_amount: None,
         };
@@ -830,12 +1774,13 @@ mod tests {
     #[test]
     fn build_filter_tag_not_found_returns_none() {
         let client = mock_client();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: None,
             to: None,
             account: None,
             tag: Some("Nonexistent".to_owned()),
             payee: None,
+            comment: None,
             min_amount: None,
             max_amount: None,
         };
@@ -854,12 +1799,13 @@ mod tests {
             .storage(storage)
             .build()
             .unwrap();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: None,
             to: None,
             account: Some("Checking".to_owned()),
             tag: None,
             payee: None,
+            comment: None,
             min_amount: None,
             max_amount: None,
         };
@@ -876,28 +1822,31 @@ mod tests {
             .storage(storage)
             .build()
             .unwrap();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: None,
             to: None,
             account: None,
             tag: Some("Food".to_owned()),
             payee: None,
+            comment: None,
             min_amount: None,
             max_amount: None,
         };
         let filter = build_transaction_filter(&client, &args).unwrap().unwrap();
-        assert!(filter.tag.is_some());
+        assert_eq!(filter.tags.len(), 1);
     }
 
     #[test]
     fn build_filter_with_payee() {
         let client = mock_client();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: None,
             to: None,
             account: None,
             tag: None,
             payee: Some("Coffee".to_owned()),
+
+            comment: None,
             min_amount: None,
             max_amount: None,
         };
@@ -905,15 +1854,33 @@ mod tests {
         assert!(filter.payee.is_some());
     }
 
+    #[test]
+    fn build_filter_with_comment() {
+        let client = mock_client();
+        let args = FilterArgs {
+            from: None,
+            to: None,
+            account: None,
+            tag: None,
+            payee: None,
+            comment: Some("reimbursable".to_owned()),
+            min_amount: None,
+            max_amount: None,
+        };
+        let filter = build_transaction_filter(&client, &args).unwrap().unwrap();
+        assert_eq!(filter.comment.as_deref(), Some("reimbursable"));
+    }
+
     #[test]
     fn build_filter_with_amount_range() {
         let client = mock_client();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: None,
             to: None,
             account: None,
             tag: None,
             payee: None,
+            comment: None,
             min_amount: Some(10.0),
             max_amount: Some(100.0),
         };
@@ -925,12 +1892,13 @@ mod tests {
     #[test]
     fn build_filter_with_min_only() {
         let client = mock_client();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: None,
             to: None,
             account: None,
             tag: None,
             payee: None,
+            comment: None,
             min_amount: Some(10.0),
             max_amount: None,
         };
@@ -942,12 +1910,13 @@ mod tests {
     #[test]
     fn build_filter_with_max_only() {
         let client = mock_client();
-        let args = TransactionArgs {
+        let args = FilterArgs {
             from: None,
             to: None,
             account: None,
             tag: None,
             payee: None,
+            comment: None,
             min_amount: None,
             max_amount: Some(100.0),
         };
@@ -988,12 +1957,77 @@ mod tests {
                 tx.outcome = 0.0;
                 tx.payee = None;
                 tx.comment = None;
+                tx.mcc = Some(5411);
                 tx
             },
         ];
         assert!(print_transactions_table(&txs).is_ok());
     }
 
+    /// Builds ledger names for accounts `a-1` (Cash, RUB) and `a-2`
+    /// (Card, USD) and tag `t-food` (Food).
+    fn test_ledger_names() -> LedgerNames {
+        LedgerNames {
+            accounts: HashMap::from([
+                (AccountId::new("a-1".to_owned()), "Cash".to_owned()),
+                (AccountId::new("a-2".to_owned()), "Card".to_owned()),
+            ]),
+            tags: HashMap::from([(TagId::new("t-food".to_owned()), "Food".to_owned())]),
+            commodities: HashMap::from([
+                (InstrumentId::new(1_i32), "RUB".to_owned()),
+                (InstrumentId::new(2_i32), "USD".to_owned()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn ledger_entry_balances_expense_against_tag() {
+        let mut tx = test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 5).unwrap());
+        tx.tag = Some(vec![TagId::new("t-food".to_owned())]);
+        tx.comment = None;
+        let entry = ledger_entry(&tx, &test_ledger_names()).unwrap();
+        let lines: Vec<&str> = entry.lines().map(str::trim_end).collect();
+        assert_eq!(lines[0], "2024-01-05 Test Payee");
+        assert_eq!(
+            lines[1].split_whitespace().collect::<Vec<_>>(),
+            ["Assets:Cash", "-50.00", "RUB"]
+        );
+        assert_eq!(
+            lines[2].split_whitespace().collect::<Vec<_>>(),
+            ["Expenses:Food", "50.00", "RUB"]
+        );
+    }
+
+    #[test]
+    fn ledger_entry_records_currency_conversion_on_transfer() {
+        let mut tx = test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 5).unwrap());
+        tx.outcome = 9250.0;
+        tx.income = 100.0;
+        tx.income_account = AccountId::new("a-2".to_owned());
+        tx.income_instrument = InstrumentId::new(2_i32);
+        tx.payee = None;
+        tx.comment = Some("Exchange".to_owned());
+        let entry = ledger_entry(&tx, &test_ledger_names()).unwrap();
+        let lines: Vec<Vec<&str>> = entry
+            .lines()
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        assert_eq!(lines[0], ["2024-01-05"]);
+        assert_eq!(lines[1], [";", "Exchange"]);
+        assert_eq!(
+            lines[2],
+            ["Assets:Card", "100.00", "USD", "@@", "9250.00", "RUB"]
+        );
+        assert_eq!(lines[3], ["Assets:Cash", "-9250.00", "RUB"]);
+    }
+
+    #[test]
+    fn ledger_entry_skips_deleted() {
+        let mut tx = test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 5).unwrap());
+        tx.deleted = true;
+        assert!(ledger_entry(&tx, &test_ledger_names()).is_none());
+    }
+
     #[test]
     fn print_tags_table_empty() {
         assert!(print_tags_table(&[]).is_ok());
@@ -1105,13 +2139,17 @@ mod tests {
     fn cmd_transactions_empty() {
         let client = mock_client();
         let args = TransactionArgs {
-            from: None,
-            to: None,
-            account: None,
-            tag: None,
-            payee: None,
-            min_amount: None,
-            max_amount: None,
+            filter: FilterArgs {
+                from: None,
+                to: None,
+                account: None,
+                tag: None,
+                payee: None,
+                comment: None,
+                min_amount: None,
+                max_amount: None,
+            },
+            format: OutputFormat::Table,
         };
         let code = cmd_transactions(&client, &args).unwrap();
         assert_eq!(code, ExitCode::SUCCESS);
@@ -1133,13 +2171,17 @@ mod tests {
             .build()
             .unwrap();
         let args = TransactionArgs {
-            from: None,
-            to: None,
-            account: None,
-            tag: None,
-            payee: None,
-            min_amount: None,
-            max_amount: None,
+            filter: FilterArgs {
+                from: None,
+                to: None,
+                account: None,
+                tag: None,
+                payee: None,
+                comment: None,
+                min_amount: None,
+                max_amount: None,
+            },
+            format: OutputFormat::Table,
         };
         let code = cmd_transactions(&client, &args).unwrap();
         assert_eq!(code, ExitCode::SUCCESS);
@@ -1149,18 +2191,105 @@ mod tests {
     fn cmd_transactions_filter_not_found() {
         let client = mock_client();
         let args = TransactionArgs {
-            from: None,
-            to: None,
-            account: Some("Nonexistent".to_owned()),
-            tag: None,
-            payee: None,
-            min_amount: None,
-            max_amount: None,
+            filter: FilterArgs {
+                from: None,
+                to: None,
+                account: Some("Nonexistent".to_owned()),
+                tag: None,
+                payee: None,
+                comment: None,
+                min_amount: None,
+                max_amount: None,
+            },
+            format: OutputFormat::Table,
         };
         let code = cmd_transactions(&client, &args).unwrap();
         assert_eq!(code, ExitCode::FAILURE);
     }
 
+    #[test]
+    fn cmd_export_writes_csv_file() {
+        let storage = InMemoryStorage::new();
+        storage
+            .upsert_accounts(vec![test_account("a-1", "Checking", false)])
+            .unwrap();
+        storage
+            .upsert_transactions(vec![test_transaction(
+                "tx-1",
+                "a-1",
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            )])
+            .unwrap();
+        let client = ZenMoneyBlocking::builder()
+            .token("test")
+            .storage(storage)
+            .build()
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.csv");
+        let args = ExportArgs {
+            filter: FilterArgs {
+                from: None,
+                to: None,
+                account: None,
+                tag: None,
+                payee: None,
+                comment: None,
+                min_amount: None,
+                max_amount: None,
+            },
+            format: ExportFormat::Csv,
+            pretty: false,
+            output: Some(path.clone()),
+        };
+        let code = cmd_export(&client, &args).unwrap();
+        assert_eq!(code, ExitCode::SUCCESS);
+        let written = std::fs::read_to_string(&path).unwrap();
+        let mut lines = written.lines();
+        assert_eq!(
+            lines.next(),
+            Some("date,payee,outcome,income,tags,comment,account")
+        );
+        assert!(lines.next().unwrap().ends_with(",Checking"));
+    }
+
+    #[test]
+    fn cmd_export_writes_storage_as_diff_json() {
+        let storage = InMemoryStorage::new();
+        storage
+            .upsert_accounts(vec![test_account("a-1", "Checking", false)])
+            .unwrap();
+        let client = ZenMoneyBlocking::builder()
+            .token("test")
+            .storage(storage)
+            .build()
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.json");
+        let args = ExportArgs {
+            filter: FilterArgs {
+                from: None,
+                to: None,
+                account: None,
+                tag: None,
+                payee: None,
+                comment: None,
+                min_amount: None,
+                max_amount: None,
+            },
+            format: ExportFormat::Json,
+            pretty: true,
+            output: Some(path.clone()),
+        };
+        let code = cmd_export(&client, &args).unwrap();
+        assert_eq!(code, ExitCode::SUCCESS);
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains('\n'));
+        let parsed: DiffResponse = serde_json::from_str(&written).unwrap();
+        assert_eq!(parsed.account.len(), 1);
+        assert_eq!(parsed.server_timestamp, DateTime::UNIX_EPOCH);
+    }
+
     #[test]
     fn cmd_suggest_no_args() {
         let client = mock_client();
@@ -1168,6 +2297,350 @@ mod tests {
         assert_eq!(code, ExitCode::FAILURE);
     }
 
+    // ── categorize tests ─────────────────────────────────────────────
+
+    /// Starts a mock server answering suggest with the given tag and
+    /// expecting `diff_calls` diff requests.
+    fn mock_categorize_server(
+        rt: &tokio::runtime::Runtime,
+        tag_id: &str,
+        diff_calls: u64,
+    ) -> wiremock::MockServer {
+        let mock_server = rt.block_on(wiremock::MockServer::start());
+        rt.block_on(async {
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/suggest/"))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({"payee": "Test Payee", "tag": [tag_id]})),
+                )
+                .mount(&mock_server)
+                .await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({"serverTimestamp": 1_700_000_100_i64})),
+                )
+                .expect(diff_calls)
+                .mount(&mock_server)
+                .await;
+        });
+        mock_server
+    }
+
+    /// Creates a client against `uri` with one untagged transaction and
+    /// one tag.
+    fn categorize_client(uri: String) -> ZenMoneyBlocking<InMemoryStorage> {
+        let storage = InMemoryStorage::new();
+        storage
+            .upsert_transactions(vec![test_transaction(
+                "tx-1",
+                "a-1",
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            )])
+            .unwrap();
+        storage.upsert_tags(vec![test_tag("t-1", "Food")]).unwrap();
+        ZenMoneyBlocking::builder()
+            .token("test")
+            .base_url(uri)
+            .storage(storage)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn uncategorized_transactions_filters_and_limits() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut tagged = test_transaction("tx-1", "a-1", date);
+        tagged.tag = Some(vec![TagId::new("t-1".to_owned())]);
+        let mut no_payee = test_transaction("tx-2", "a-1", date);
+        no_payee.payee = None;
+        let mut deleted = test_transaction("tx-3", "a-1", date);
+        deleted.deleted = true;
+        let mut empty_tags = test_transaction("tx-4", "a-1", date);
+        empty_tags.tag = Some(Vec::new());
+        let untagged = test_transaction("tx-5", "a-1", date);
+
+        let txs = vec![tagged, no_payee, deleted, empty_tags, untagged];
+        let selected = uncategorized_transactions(txs.clone(), 10);
+        let ids: Vec<&str> = selected.iter().map(|tx| tx.id.as_inner()).collect();
+        assert_eq!(ids, ["tx-4", "tx-5"]);
+        assert_eq!(uncategorized_transactions(txs, 1).len(), 1);
+    }
+
+    #[test]
+    fn cmd_categorize_prints_without_pushing() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let mock_server = mock_categorize_server(&rt, "t-1", 0);
+        let client = categorize_client(mock_server.uri());
+        let args = CategorizeArgs {
+            apply: false,
+            limit: DEFAULT_CATEGORIZE_LIMIT,
+        };
+        let code = cmd_categorize(&client, &args).unwrap();
+        assert_eq!(code, ExitCode::SUCCESS);
+    }
+
+    #[test]
+    fn cmd_categorize_apply_pushes_tags() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let mock_server = mock_categorize_server(&rt, "t-1", 1);
+        let client = categorize_client(mock_server.uri());
+        let args = CategorizeArgs {
+            apply: true,
+            limit: DEFAULT_CATEGORIZE_LIMIT,
+        };
+        let code = cmd_categorize(&client, &args).unwrap();
+        assert_eq!(code, ExitCode::SUCCESS);
+
+        let requests = rt.block_on(mock_server.received_requests()).unwrap();
+        let diff = requests
+            .iter()
+            .find(|request| request.url.path() == "/v8/diff/")
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&diff.body).unwrap();
+        assert_eq!(body["transaction"][0]["id"], "tx-1");
+        assert_eq!(body["transaction"][0]["tag"], serde_json::json!(["t-1"]));
+    }
+
+    #[test]
+    fn cmd_categorize_suggest_failure() {
+        // No server is listening, so the suggest call fails with a
+        // network error.
+        let client = categorize_client("http://127.0.0.1:1".to_owned());
+        let args = CategorizeArgs {
+            apply: false,
+            limit: DEFAULT_CATEGORIZE_LIMIT,
+        };
+        let code = cmd_categorize(&client, &args).unwrap();
+        assert_eq!(code, ExitCode::from(EXIT_NETWORK));
+    }
+
+    #[test]
+    fn print_categorizations_table_empty() {
+        assert!(print_categorizations_table(&[], &[]).is_ok());
+    }
+
+    // ── reminder tests ───────────────────────────────────────────────
+
+    /// Creates a monthly test reminder starting on `start`.
+    fn test_reminder(id: &str, start: NaiveDate) -> Reminder {
+        Reminder {
+            id: ReminderId::new(id.to_owned()),
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            user: UserId::new(1_i64),
+            income_instrument: InstrumentId::new(1_i32),
+            income_account: AccountId::new("a-1".to_owned()),
+            income: 0.0,
+            outcome_instrument: InstrumentId::new(1_i32),
+            outcome_account: AccountId::new("a-1".to_owned()),
+            outcome: 100.0,
+            tag: None,
+            merchant: None,
+            payee: Some(id.to_owned()),
+            comment: None,
+            interval: Some(Interval::Month),
+            step: Some(1_i32),
+            points: None,
+            start_date: start,
+            end_date: None,
+            notify: false,
+        }
+    }
+
+    /// Creates a test reminder marker on `date` in `state`.
+    fn test_marker(id: &str, date: NaiveDate, state: ReminderMarkerState) -> ReminderMarker {
+        ReminderMarker {
+            id: ReminderMarkerId::new(id.to_owned()),
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            user: UserId::new(1_i64),
+            income_instrument: InstrumentId::new(1_i32),
+            income_account: AccountId::new("a-1".to_owned()),
+            income: 0.0,
+            outcome_instrument: InstrumentId::new(1_i32),
+            outcome_account: AccountId::new("a-1".to_owned()),
+            outcome: 100.0,
+            tag: None,
+            merchant: None,
+            payee: None,
+            comment: None,
+            date,
+            reminder: ReminderId::new("rem-1".to_owned()),
+            state,
+            notify: false,
+            is_forecast: None,
+        }
+    }
+
+    #[test]
+    fn upcoming_reminders_sorted_by_next_date_without_ended_ones() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 10).unwrap();
+        let rent = test_reminder("rent", NaiveDate::from_ymd_opt(2024, 1, 25).unwrap());
+        let gym = test_reminder("gym", NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        let mut ended = test_reminder("ended", NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        ended.end_date = Some(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+
+        let upcoming = upcoming_reminders(vec![rent, ended, gym], today);
+        let summary: Vec<(&str, NaiveDate)> = upcoming
+            .iter()
+            .map(|item| (item.0.id.as_inner(), item.1))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("gym", NaiveDate::from_ymd_opt(2024, 6, 15).unwrap()),
+                ("rent", NaiveDate::from_ymd_opt(2024, 6, 25).unwrap()),
+            ]
+        );
+        assert!(print_reminders_table(&upcoming).is_ok());
+    }
+
+    #[test]
+    fn select_reminder_markers_defaults_to_planned() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 10).unwrap();
+        let markers = vec![
+            test_marker("m-3", today, ReminderMarkerState::Planned),
+            test_marker(
+                "m-1",
+                today.pred_opt().unwrap(),
+                ReminderMarkerState::Planned,
+            ),
+            test_marker("m-2", today, ReminderMarkerState::Processed),
+        ];
+        let ids = |args: &ReminderMarkerArgs| -> Vec<String> {
+            select_reminder_markers(markers.clone(), args, today)
+                .into_iter()
+                .map(|marker| marker.id.to_string())
+                .collect()
+        };
+        let default_args = ReminderMarkerArgs {
+            upcoming: false,
+            all_states: false,
+        };
+        assert_eq!(ids(&default_args), vec!["m-1", "m-3"]);
+        let upcoming_args = ReminderMarkerArgs {
+            upcoming: true,
+            all_states: false,
+        };
+        assert_eq!(ids(&upcoming_args), vec!["m-3"]);
+        let all_args = ReminderMarkerArgs {
+            upcoming: false,
+            all_states: true,
+        };
+        assert_eq!(ids(&all_args), vec!["m-1", "m-3", "m-2"]);
+    }
+
+    #[test]
+    fn cmd_reminders_and_markers_empty() {
+        let client = mock_client();
+        assert_eq!(cmd_reminders(&client).unwrap(), ExitCode::SUCCESS);
+        let args = ReminderMarkerArgs {
+            upcoming: true,
+            all_states: false,
+        };
+        assert_eq!(
+            cmd_reminder_markers(&client, &args).unwrap(),
+            ExitCode::SUCCESS
+        );
+    }
+
+    // ── budget tests ─────────────────────────────────────────────────
+
+    #[test]
+    fn parse_month_returns_first_day() {
+        assert_eq!(
+            parse_month("2024-02").unwrap(),
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+        );
+        assert!(parse_month("2024-13").is_err());
+    }
+
+    #[test]
+    fn budget_rows_compare_planned_with_actual() {
+        let month = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let user = UserId::new(1_i64);
+        let food = TagId::new("t-food".to_owned());
+        let budgets = vec![
+            Budget::new(user, Some(food.clone()), month, 0.0, 100.0),
+            Budget::new(user, None, month, 0.0, 1000.0),
+            Budget::new(
+                user,
+                Some(TagId::new(OVERALL_BUDGET_TAG.to_owned())),
+                month,
+                0.0,
+                900.0,
+            ),
+            Budget::new(
+                user,
+                Some(food.clone()),
+                month.pred_opt().unwrap(),
+                0.0,
+                1.0,
+            ),
+        ];
+        let spending = HashMap::from([(Some(food), 150.0), (None, 50.0)]);
+        let rows = budget_rows(
+            &budgets,
+            &spending,
+            200.0,
+            &[test_tag("t-food", "Food")],
+            month,
+        );
+        let summary: Vec<(&str, f64, f64)> = rows
+            .iter()
+            .map(|row| (row.label.as_str(), row.actual, row.remaining))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Overall", 200.0, 700.0),
+                ("Uncategorized", 50.0, 950.0),
+                ("Food", 150.0, -50.0),
+            ]
+        );
+        assert!(print_budgets_table(&rows, month).is_ok());
+    }
+
+    #[test]
+    fn cmd_budgets_empty() {
+        let client = mock_client();
+        let month = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(cmd_budgets(&client, month).unwrap(), ExitCode::SUCCESS);
+    }
+
+    // ── stats tests ──────────────────────────────────────────────────
+
+    #[test]
+    fn transaction_stats_merges_payees_case_insensitively() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut coffee = test_transaction("tx-1", "a-1", date);
+        coffee.payee = Some("Starbucks".to_owned());
+        let mut more_coffee = test_transaction("tx-2", "a-1", date);
+        more_coffee.payee = Some("STARBUCKS".to_owned());
+        more_coffee.outcome = 30.0;
+        let mut salary = test_transaction("tx-3", "a-1", date);
+        salary.payee = Some("Employer".to_owned());
+        salary.outcome = 0.0;
+        salary.income = 220.0;
+
+        let stats = transaction_stats(&[coffee, more_coffee, salary]).unwrap();
+        assert_eq!(stats.count, 3);
+        assert!((stats.income - 220.0).abs() < f64::EPSILON);
+        assert!((stats.outcome - 80.0).abs() < f64::EPSILON);
+        assert!((stats.average - 100.0).abs() < f64::EPSILON);
+        assert_eq!(stats.top_payees, vec![("Starbucks".to_owned(), 80.0)]);
+        assert!(print_stats(Some(&stats)).is_ok());
+    }
+
+    #[test]
+    fn cmd_stats_reports_no_data_for_empty_range() {
+        assert!(transaction_stats(&[]).is_none());
+        let client = mock_client();
+        let day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert_eq!(cmd_stats(&client, day, day).unwrap(), ExitCode::SUCCESS);
+    }
+
     // ── dispatch tests ───────────────────────────────────────────────
 
     #[test]
@@ -1190,13 +2663,17 @@ mod tests {
         let code = dispatch(
             &client,
             Command::Transactions(TransactionArgs {
-                from: None,
-                to: None,
-                account: None,
-                tag: None,
-                payee: None,
-                min_amount: None,
-                max_amount: None,
+                filter: FilterArgs {
+                    from: None,
+                    to: None,
+                    account: None,
+                    tag: None,
+                    payee: None,
+                    comment: None,
+                    min_amount: None,
+                    max_amount: None,
+                },
+                format: OutputFormat::Table,
             }),
         )
         .unwrap();
@@ -2,14 +2,257 @@
 //!
 //! Provides both async and blocking client variants behind feature flags.
 
+use alloc::sync::Arc;
+use core::fmt;
+use core::hash::BuildHasher as _;
+use core::time::Duration;
+use std::hash::RandomState;
+use std::time::Instant;
+
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
+
+use crate::error::ZenMoneyError;
+
 /// Base URL for the ZenMoney API.
 const DEFAULT_BASE_URL: &str = "https://api.zenmoney.ru";
 
 /// Diff endpoint path.
-const DIFF_PATH: &str = "/v8/diff/";
+const DIFF_PATH: &str = concat!("/v", api_version!(), "/diff/");
 
 /// Suggest endpoint path.
-const SUGGEST_PATH: &str = "/v8/suggest/";
+const SUGGEST_PATH: &str = concat!("/v", api_version!(), "/suggest/");
+
+/// Default header carrying the request signature.
+const DEFAULT_SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Default limit on the duration of a single HTTP request.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of requests a suggest batch sends at once.
+const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// `User-Agent` sent when none is configured.
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Callback computing a request signature from a path and body bytes.
+type SignFn = dyn Fn(&str, &[u8]) -> String + Send + Sync;
+
+/// Shared request-signing callback.
+#[derive(Clone)]
+pub(crate) struct RequestSigner(Arc<SignFn>);
+
+impl RequestSigner {
+    /// Wraps a signing callback.
+    pub(crate) fn new<F>(sign: F) -> Self
+    where
+        F: Fn(&str, &[u8]) -> String + Send + Sync + 'static,
+    {
+        Self(Arc::new(sign))
+    }
+
+    /// Signs a request to `path` carrying `body`.
+    pub(crate) fn sign(&self, path: &str, body: &[u8]) -> String {
+        (self.0)(path, body)
+    }
+}
+
+impl fmt::Debug for RequestSigner {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RequestSigner(..)")
+    }
+}
+
+/// HTTP protocol version the client speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum HttpVersion {
+    /// Let reqwest negotiate the version (HTTP/2 via ALPN over TLS).
+    #[default]
+    Auto,
+    /// Only ever use HTTP/1.1.
+    Http1Only,
+    /// Use HTTP/2 from the first request, without negotiation.
+    Http2PriorKnowledge,
+}
+
+/// How requests are retried after transient failures.
+///
+/// A request is retried when it fails to connect or loses its connection,
+/// or when the server answers `429 Too Many Requests` or any `5xx` status.
+/// Other `4xx` responses are never retried. Before retry `n` (starting at
+/// zero) the client waits for the server's `Retry-After` seconds if given,
+/// or otherwise `base_delay * 2^n`, randomly reduced by up to half so that
+/// clients failing together do not retry in lockstep. Either wait is
+/// capped at `max_delay`, so a server asking for a long pause cannot stall
+/// the caller beyond it. The default policy never retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt.
+    max_retries: u32,
+    /// Delay before the first retry when the server gives no hint.
+    base_delay: Duration,
+    /// Upper bound on the computed backoff delay.
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates a policy retrying up to `max_retries` times with
+    /// exponential backoff starting at `base_delay` and never exceeding
+    /// `max_delay`.
+    #[inline]
+    #[must_use]
+    pub const fn new(max_retries: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            base_delay,
+            max_delay,
+        }
+    }
+
+    /// Returns the maximum number of retries.
+    #[inline]
+    #[must_use]
+    pub const fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Returns the delay before the first retry.
+    #[inline]
+    #[must_use]
+    pub const fn base_delay(&self) -> Duration {
+        self.base_delay
+    }
+
+    /// Returns the upper bound on the backoff delay.
+    #[inline]
+    #[must_use]
+    pub const fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// Returns the backoff before retry `attempt`, before jitter.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+
+    /// Returns the delay before retry `attempt` of a failure, or `None`
+    /// if the failure is not `retryable` or the retries are used up.
+    ///
+    /// A server-provided `retry_after` wins over the backoff but is still
+    /// clamped to `max_delay`.
+    fn delay(
+        &self,
+        retryable: bool,
+        attempt: u32,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        (retryable && attempt < self.max_retries).then(|| {
+            retry_after.map_or_else(
+                || jittered(self.backoff(attempt)),
+                |hint| hint.min(self.max_delay),
+            )
+        })
+    }
+}
+
+/// Returns `true` for response statuses worth retrying: `429` and `5xx`.
+const fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 429 | 500..=599)
+}
+
+/// Returns `true` for transport errors worth retrying: failures to
+/// connect and connections dropped while sending.
+fn is_retryable_error(err: &reqwest::Error) -> bool {
+    err.is_connect() || (err.is_request() && !err.is_timeout())
+}
+
+/// Picks a random delay in `[delay / 2, delay]`.
+fn jittered(delay: Duration) -> Duration {
+    let half = delay / 2;
+    let span = u64::try_from(half.as_nanos()).unwrap_or(u64::MAX);
+    let random = RandomState::new().hash_one(Instant::now());
+    let offset = random.checked_rem(span.saturating_add(1)).unwrap_or(0);
+    half.saturating_add(Duration::from_nanos(offset))
+}
+
+/// Parses a `Retry-After` header given in whole seconds.
+fn parse_retry_after(value: Option<&str>) -> Option<Duration> {
+    value?.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Converts custom `(name, value)` headers into a header map, later
+/// entries replacing earlier ones. `Authorization` is dropped so the
+/// client's bearer token always wins.
+fn header_map(headers: Vec<(String, String)>) -> Result<HeaderMap, ZenMoneyError> {
+    let mut map = HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes()).map_err(|err| {
+            ZenMoneyError::Validation(format!("invalid header name {name}: {err}"))
+        })?;
+        let header_value = HeaderValue::from_str(&value).map_err(|err| {
+            ZenMoneyError::Validation(format!("invalid value for header {name}: {err}"))
+        })?;
+        if header_name == AUTHORIZATION {
+            tracing::warn!("ignoring custom Authorization header");
+            continue;
+        }
+        let _previous = map.insert(header_name, header_value);
+    }
+    Ok(map)
+}
+
+/// Known JSON shape of a ZenMoney error response body.
+#[derive(serde::Deserialize)]
+struct ApiErrorBody {
+    /// Error code, or the message itself when no description is given.
+    error: String,
+    /// Human-readable description accompanying the code.
+    #[serde(default, alias = "error_description", alias = "errorMessage")]
+    message: Option<String>,
+}
+
+/// Splits an error response body into an optional code and a message.
+///
+/// A JSON body of the form `{"error": "..."}` yields its text as the
+/// message; if a description (`message`, `error_description`, or
+/// `errorMessage`) is present too, `error` becomes the code and the
+/// description the message. Any other body is kept verbatim.
+fn split_error_body(body: String) -> (Option<String>, String) {
+    match serde_json::from_str::<ApiErrorBody>(&body) {
+        Ok(ApiErrorBody {
+            error,
+            message: Some(message),
+        }) => (Some(error), message),
+        Ok(ApiErrorBody {
+            error,
+            message: None,
+        }) => (None, error),
+        Err(_) => (None, body),
+    }
+}
+
+/// Builds the error for a non-success response.
+///
+/// `400`, `401`, and `429` get dedicated variants; any other status
+/// becomes [`ZenMoneyError::Api`]. `retry_after` is the parsed
+/// `Retry-After` header, kept only for `429`.
+fn api_error(status: u16, retry_after: Option<Duration>, body: String) -> ZenMoneyError {
+    if status == 429 {
+        return ZenMoneyError::RateLimited { retry_after };
+    }
+    let (code, message) = split_error_body(body);
+    match status {
+        400 => ZenMoneyError::BadRequest { code, message },
+        401 => ZenMoneyError::Unauthorized { code, message },
+        _ => ZenMoneyError::Api {
+            status,
+            code,
+            message,
+        },
+    }
+}
 
 /// Generates a ZenMoney client (async or blocking) with builder, methods, and tests.
 macro_rules! define_client {
@@ -20,6 +263,7 @@ macro_rules! define_client {
         response_type: $resp_type:ty,
         client_doc: $client_doc:expr,
         builder_doc: $builder_doc:expr,
+        sleep: $sleep:path,
         $(async_kw: $async_kw:tt,)?
         $(await_kw: $await_ext:tt,)?
         $(send_bound: $send_bound:tt,)?
@@ -31,6 +275,22 @@ macro_rules! define_client {
             token: Option<String>,
             /// Base URL override (for testing).
             base_url: Option<String>,
+            /// Optional request-signing callback.
+            signer: Option<RequestSigner>,
+            /// Header name override for the request signature.
+            signature_header: Option<String>,
+            /// Retry behaviour for transiently failing requests.
+            retry_policy: RetryPolicy,
+            /// Limit on the duration of each HTTP request.
+            timeout: Duration,
+            /// Extra headers sent with every request, in insertion order.
+            headers: Vec<(String, String)>,
+            /// `User-Agent` override.
+            user_agent: Option<String>,
+            /// Maximum number of suggest requests of a batch sent at once.
+            batch_concurrency: usize,
+            /// HTTP protocol version selection.
+            http_version: HttpVersion,
         }
 
         impl $builder {
@@ -50,26 +310,173 @@ macro_rules! define_client {
                 self
             }
 
+            /// Signs every request for gateways that require it.
+            ///
+            /// `signer` is called with the endpoint path (e.g. `/v8/diff/`)
+            /// and the exact JSON body bytes sent; its result is placed in
+            /// the signature header (`X-Signature` unless overridden with
+            /// [`Self::signature_header`]).
+            #[inline]
+            #[must_use]
+            pub fn request_signer<F>(mut self, signer: F) -> Self
+            where
+                F: Fn(&str, &[u8]) -> String + Send + Sync + 'static,
+            {
+                self.signer = Some(RequestSigner::new(signer));
+                self
+            }
+
+            /// Overrides the header carrying the request signature.
+            ///
+            /// Has no effect unless a [`Self::request_signer`] is set.
+            #[inline]
+            #[must_use]
+            pub fn signature_header<T: Into<String>>(mut self, name: T) -> Self {
+                self.signature_header = Some(name.into());
+                self
+            }
+
+            /// Sets how requests failing transiently are retried.
+            ///
+            /// Applies to every endpoint. Defaults to no retries.
+            #[inline]
+            #[must_use]
+            pub const fn retry(mut self, policy: RetryPolicy) -> Self {
+                self.retry_policy = policy;
+                self
+            }
+
+            /// Adds a header sent with every request.
+            ///
+            /// Later calls with the same name replace earlier ones, and
+            /// custom headers replace the default `Content-Type` (use this
+            /// for `Accept` or gateway-specific headers too). The
+            /// `Authorization` header and the request signature header are
+            /// always set by the client and cannot be overridden.
+            #[inline]
+            #[must_use]
+            pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+                self.headers.push((name.into(), value.into()));
+                self
+            }
+
+            /// Sets the `User-Agent` header identifying the application.
+            ///
+            /// Defaults to `zenmoney-rs/<version>`.
+            #[inline]
+            #[must_use]
+            pub fn user_agent<T: Into<String>>(mut self, user_agent: T) -> Self {
+                self.user_agent = Some(user_agent.into());
+                self
+            }
+
+            /// Limits how long each HTTP request may take, from connecting
+            /// until the response body has been read.
+            ///
+            /// Defaults to 30 seconds. A request exceeding it fails with
+            /// [`ZenMoneyError::Timeout`]; each retry gets a fresh timeout.
+            #[inline]
+            #[must_use]
+            pub const fn timeout(mut self, timeout: Duration) -> Self {
+                self.timeout = timeout;
+                self
+            }
+
+            /// Restricts the client to HTTP/1.1, for proxies that mishandle
+            /// HTTP/2.
+            ///
+            /// Replaces an earlier [`Self::http2_prior_knowledge`]; passing `false`
+            /// restores reqwest's automatic negotiation, the default.
+            #[inline]
+            #[must_use]
+            pub const fn http1_only(mut self, enabled: bool) -> Self {
+                self.http_version = if enabled {
+                    HttpVersion::Http1Only
+                } else {
+                    HttpVersion::Auto
+                };
+                self
+            }
+
+            /// Speaks HTTP/2 from the first request without negotiating
+            /// the version (HTTP/2 prior knowledge).
+            ///
+            /// Only use it when the server is known to accept HTTP/2 on
+            /// every connection, since HTTP/1.1-only servers and proxies
+            /// will reject the requests. The default negotiation already
+            /// picks HTTP/2 over TLS when the server offers it.
+            ///
+            /// Replaces an earlier [`Self::http1_only`]; passing `false`
+            /// restores reqwest's automatic negotiation, the default.
+            #[inline]
+            #[must_use]
+            pub const fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+                self.http_version = if enabled {
+                    HttpVersion::Http2PriorKnowledge
+                } else {
+                    HttpVersion::Auto
+                };
+                self
+            }
+
+            /// Limits how many requests `suggest_batch` sends at once.
+            ///
+            /// Defaults to 4; zero is treated as one. Only the async client
+            /// sends requests concurrently, the blocking client sends them
+            /// one after another.
+            #[inline]
+            #[must_use]
+            pub const fn batch_concurrency(mut self, limit: usize) -> Self {
+                self.batch_concurrency = if limit == 0 { 1 } else { limit };
+                self
+            }
+
             /// Builds the client.
             ///
             /// # Errors
             ///
             /// Returns [`ZenMoneyError::TokenExpired`] if no token was provided.
-            /// Returns [`ZenMoneyError::Http`] if the HTTP client fails to build.
+            /// Returns [`ZenMoneyError::Validation`] if a custom header name or
+            /// value is invalid.
+            /// Returns [`ZenMoneyError::Http`] if the HTTP client fails to build,
+            /// including when the user agent is not a valid header value.
             #[inline]
             #[tracing::instrument(skip_all)]
             pub fn build(self) -> Result<$client> {
                 let token = self.token.ok_or(ZenMoneyError::TokenExpired)?;
+                let mut headers = header_map(self.headers)?;
+                let signature_header = self
+                    .signature_header
+                    .unwrap_or_else(|| DEFAULT_SIGNATURE_HEADER.to_owned());
+                if self.signer.is_some() {
+                    let _custom = headers.remove(signature_header.as_str());
+                }
                 let base_url = self
                     .base_url
                     .unwrap_or_else(|| DEFAULT_BASE_URL.to_owned());
                 tracing::debug!(base_url = %base_url, "building client");
-                let http = <$http_type>::builder().build()?;
+                let user_agent = self
+                    .user_agent
+                    .unwrap_or_else(|| DEFAULT_USER_AGENT.to_owned());
+                let http_builder = <$http_type>::builder()
+                    .timeout(self.timeout)
+                    .user_agent(user_agent);
+                let http = match self.http_version {
+                    HttpVersion::Auto => http_builder,
+                    HttpVersion::Http1Only => http_builder.http1_only(),
+                    HttpVersion::Http2PriorKnowledge => http_builder.http2_prior_knowledge(),
+                }
+                .build()?;
 
                 Ok($client {
                     http,
                     token,
                     base_url,
+                    signer: self.signer,
+                    signature_header,
+                    retry_policy: self.retry_policy,
+                    headers,
+                    batch_concurrency: self.batch_concurrency,
                 })
             }
         }
@@ -83,6 +490,16 @@ macro_rules! define_client {
             token: String,
             /// API base URL.
             base_url: String,
+            /// Optional request-signing callback.
+            signer: Option<RequestSigner>,
+            /// Header carrying the request signature.
+            signature_header: String,
+            /// Retry behaviour for transiently failing requests.
+            retry_policy: RetryPolicy,
+            /// Extra headers sent with every request.
+            headers: HeaderMap,
+            /// Maximum number of suggest requests of a batch sent at once.
+            batch_concurrency: usize,
         }
 
         impl $client {
@@ -93,6 +510,14 @@ macro_rules! define_client {
                 $builder {
                     token: None,
                     base_url: None,
+                    signer: None,
+                    signature_header: None,
+                    retry_policy: RetryPolicy::new(0, Duration::ZERO, Duration::ZERO),
+                    timeout: DEFAULT_TIMEOUT,
+                    headers: Vec::new(),
+                    user_agent: None,
+                    batch_concurrency: DEFAULT_BATCH_CONCURRENCY,
+                    http_version: HttpVersion::Auto,
                 }
             }
 
@@ -131,9 +556,28 @@ macro_rules! define_client {
                 self.post_json(SUGGEST_PATH, request) $( .$await_ext )?
             }
 
+            /// Gets suggestions for several requests, returning one result
+            /// per request in input order.
+            ///
+            /// Requests are sent in groups of at most the builder's
+            /// `batch_concurrency`; a failing request does not stop the
+            /// others, its error is returned in its slot instead.
+            #[inline]
+            #[tracing::instrument(skip_all, fields(count = requests.len()))]
+            pub $($async_kw)? fn suggest_batch(
+                &self,
+                requests: &[SuggestRequest],
+            ) -> Vec<Result<SuggestResponse>> {
+                let mut responses = Vec::with_capacity(requests.len());
+                for chunk in requests.chunks(self.batch_concurrency) {
+                    responses.extend(self.suggest_chunk(chunk) $( .$await_ext )?);
+                }
+                responses
+            }
+
             /// Sends an authenticated JSON POST request and deserializes the
             /// response.
-            #[tracing::instrument(skip_all, fields(path = %path))]
+            #[tracing::instrument(skip_all, fields(path = %path, attempts = tracing::field::Empty))]
             $($async_kw)? fn post_json<
                 Req: serde::Serialize $(+ $send_bound)?,
                 Resp: serde::de::DeserializeOwned,
@@ -144,16 +588,79 @@ macro_rules! define_client {
             ) -> Result<Resp> {
                 let url = format!("{}{path}", self.base_url);
                 tracing::trace!(url = %url, "sending POST request");
-                let response: $resp_type = self
-                    .http
-                    .post(&url)
-                    .header(AUTHORIZATION, format!("Bearer {}", self.token))
-                    .header(CONTENT_TYPE, "application/json")
-                    .json(request)
-                    .send()
-                    $( .$await_ext )?
-                    ?;
+                let body = serde_json::to_vec(request)?;
+                let signature = self
+                    .signer
+                    .as_ref()
+                    .map(|signer| signer.sign(path, &body));
+                let mut attempt: u32 = 0;
+                let response: $resp_type = loop {
+                    #[expect(unused_results, reason = "record returns the span only for chaining")]
+                    tracing::Span::current().record("attempts", attempt.saturating_add(1));
+                    let mut builder = self
+                        .http
+                        .post(&url)
+                        .header(CONTENT_TYPE, "application/json")
+                        .headers(self.headers.clone())
+                        .header(AUTHORIZATION, format!("Bearer {}", self.token));
+                    if let Some(value) = signature.as_deref() {
+                        builder = builder.header(self.signature_header.as_str(), value);
+                    }
+                    let delay = match builder.body(body.clone()).send() $( .$await_ext )? {
+                        Ok(response) => {
+                            let Some(delay) = self.response_retry_delay(&response, attempt) else {
+                                break response;
+                            };
+                            delay
+                        }
+                        Err(err) => {
+                            let Some(delay) =
+                                self.retry_policy.delay(is_retryable_error(&err), attempt, None)
+                            else {
+                                return Err(err.into());
+                            };
+                            tracing::debug!(
+                                error = %err,
+                                attempt,
+                                delay_ms = delay.as_millis(),
+                                "retrying request"
+                            );
+                            delay
+                        }
+                    };
+                    $sleep(delay) $( .$await_ext )?;
+                    attempt = attempt.saturating_add(1);
+                };
+                Self::parse_response(response) $( .$await_ext )?
+            }
+
+            /// Returns the delay before retrying a request that got
+            /// `response` on `attempt`, or `None` to accept the response.
+            fn response_retry_delay(&self, response: &$resp_type, attempt: u32) -> Option<Duration> {
+                let status = response.status().as_u16();
+                let retry_after = parse_retry_after(
+                    response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok()),
+                );
+                let delay =
+                    self.retry_policy
+                        .delay(is_retryable_status(status), attempt, retry_after)?;
+                tracing::debug!(
+                    status,
+                    attempt,
+                    delay_ms = delay.as_millis(),
+                    "retrying request"
+                );
+                Some(delay)
+            }
 
+            /// Deserializes a successful response body, or turns an error
+            /// response into the matching [`ZenMoneyError`] variant.
+            $($async_kw)? fn parse_response<Resp: serde::de::DeserializeOwned>(
+                response: $resp_type,
+            ) -> Result<Resp> {
                 let status = response.status();
                 tracing::debug!(status = %status, "received response");
                 if status.is_success() {
@@ -161,15 +668,18 @@ macro_rules! define_client {
                     tracing::trace!(body_len = body.len(), "parsing response body");
                     serde_json::from_str(&body).map_err(ZenMoneyError::from)
                 } else {
+                    let retry_after = parse_retry_after(
+                        response
+                            .headers()
+                            .get(RETRY_AFTER)
+                            .and_then(|value| value.to_str().ok()),
+                    );
                     let message = response
                         .text()
                         $( .$await_ext )?
                         .unwrap_or_else(|_| "unknown error".to_owned());
                     tracing::debug!(status = status.as_u16(), message = %message, "API error");
-                    Err(ZenMoneyError::Api {
-                        status: status.as_u16(),
-                        message,
-                    })
+                    Err(api_error(status.as_u16(), retry_after, message))
                 }
             }
         }
@@ -202,6 +712,75 @@ macro_rules! define_client {
                     .unwrap();
                 assert_eq!(client.base_url, "http://localhost:8080");
             }
+
+            #[test]
+            fn builder_signature_header_defaults() {
+                let client = $client::builder()
+                    .token("test-token")
+                    .request_signer(|_, _| "sig".to_owned())
+                    .build()
+                    .unwrap();
+                assert_eq!(client.signature_header, DEFAULT_SIGNATURE_HEADER);
+                assert_eq!(client.signer.unwrap().sign("/v8/diff/", b"{}"), "sig");
+            }
+
+            #[test]
+            fn builder_drops_custom_headers_set_by_the_client() {
+                let client = $client::builder()
+                    .token("test-token")
+                    .header("X-Tenant", "family")
+                    .header("authorization", "Bearer other")
+                    .header("X-Signature", "forged")
+                    .request_signer(|_, _| "sig".to_owned())
+                    .build()
+                    .unwrap();
+                assert_eq!(client.headers.len(), 1);
+                assert_eq!(client.headers["x-tenant"], "family");
+            }
+
+            #[test]
+            fn builder_timeout_defaults_and_overrides() {
+                assert_eq!($client::builder().timeout, DEFAULT_TIMEOUT);
+                let builder = $client::builder().timeout(Duration::from_secs(5));
+                assert_eq!(builder.timeout, Duration::from_secs(5));
+                assert!(builder.token("test-token").build().is_ok());
+            }
+
+            #[test]
+            fn builder_batch_concurrency_defaults_and_clamps_zero() {
+                assert_eq!($client::builder().batch_concurrency, DEFAULT_BATCH_CONCURRENCY);
+                assert_eq!($client::builder().batch_concurrency(0).batch_concurrency, 1);
+                let client = $client::builder()
+                    .token("test-token")
+                    .batch_concurrency(8)
+                    .build()
+                    .unwrap();
+                assert_eq!(client.batch_concurrency, 8);
+            }
+
+            #[test]
+            fn builder_http_version_options_build() {
+                assert_eq!($client::builder().http_version, HttpVersion::Auto);
+                let http1 = $client::builder().http1_only(true);
+                assert_eq!(http1.http_version, HttpVersion::Http1Only);
+                assert!(http1.token("test-token").build().is_ok());
+                let http2 = $client::builder().http1_only(true).http2_prior_knowledge(true);
+                assert_eq!(http2.http_version, HttpVersion::Http2PriorKnowledge);
+                assert!(http2.token("test-token").build().is_ok());
+                assert_eq!(
+                    $client::builder().http2_prior_knowledge(true).http2_prior_knowledge(false).http_version,
+                    HttpVersion::Auto
+                );
+            }
+
+            #[test]
+            fn builder_rejects_invalid_user_agent() {
+                let result = $client::builder()
+                    .token("test-token")
+                    .user_agent("line\nbreak")
+                    .build();
+                assert!(matches!(result, Err(ZenMoneyError::Http(_))));
+            }
         }
     };
 }
@@ -210,9 +789,16 @@ macro_rules! define_client {
 mod async_client {
     //! Async HTTP client for the ZenMoney API.
 
-    use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+    use core::time::Duration;
+
+    use futures::future::join_all;
+    use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, RETRY_AFTER};
 
-    use super::{DEFAULT_BASE_URL, DIFF_PATH, SUGGEST_PATH};
+    use super::{
+        DEFAULT_BASE_URL, DEFAULT_BATCH_CONCURRENCY, DEFAULT_SIGNATURE_HEADER, DEFAULT_TIMEOUT,
+        DEFAULT_USER_AGENT, DIFF_PATH, HttpVersion, RequestSigner, RetryPolicy, SUGGEST_PATH,
+        api_error, header_map, is_retryable_error, is_retryable_status, parse_retry_after,
+    };
     use crate::error::{Result, ZenMoneyError};
     use crate::models::{DiffRequest, DiffResponse, SuggestRequest, SuggestResponse};
 
@@ -223,19 +809,33 @@ mod async_client {
         response_type: reqwest::Response,
         client_doc: "Async client for the ZenMoney API.\n\nUse [`ZenMoneyClient::builder()`] to construct an instance.",
         builder_doc: "Builder for constructing a [`ZenMoneyClient`].",
+        sleep: tokio::time::sleep,
         async_kw: async,
         await_kw: await,
         send_bound: Sync,
     }
+
+    impl ZenMoneyClient {
+        /// Sends the suggest requests of one batch chunk concurrently.
+        async fn suggest_chunk(&self, chunk: &[SuggestRequest]) -> Vec<Result<SuggestResponse>> {
+            join_all(chunk.iter().map(|request| self.suggest(request))).await
+        }
+    }
 }
 
 #[cfg(feature = "blocking")]
 mod blocking_client {
     //! Blocking (synchronous) HTTP client for the ZenMoney API.
 
-    use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+    use core::time::Duration;
 
-    use super::{DEFAULT_BASE_URL, DIFF_PATH, SUGGEST_PATH};
+    use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, RETRY_AFTER};
+
+    use super::{
+        DEFAULT_BASE_URL, DEFAULT_BATCH_CONCURRENCY, DEFAULT_SIGNATURE_HEADER, DEFAULT_TIMEOUT,
+        DEFAULT_USER_AGENT, DIFF_PATH, HttpVersion, RequestSigner, RetryPolicy, SUGGEST_PATH,
+        api_error, header_map, is_retryable_error, is_retryable_status, parse_retry_after,
+    };
     use crate::error::{Result, ZenMoneyError};
     use crate::models::{DiffRequest, DiffResponse, SuggestRequest, SuggestResponse};
 
@@ -246,6 +846,14 @@ mod blocking_client {
         response_type: reqwest::blocking::Response,
         client_doc: "Blocking (synchronous) client for the ZenMoney API.\n\nUse [`ZenMoneyBlockingClient::builder()`] to construct an instance.",
         builder_doc: "Builder for constructing a [`ZenMoneyBlockingClient`].",
+        sleep: std::thread::sleep,
+    }
+
+    impl ZenMoneyBlockingClient {
+        /// Sends the suggest requests of one batch chunk one after another.
+        fn suggest_chunk(&self, chunk: &[SuggestRequest]) -> Vec<Result<SuggestResponse>> {
+            chunk.iter().map(|request| self.suggest(request)).collect()
+        }
     }
 }
 
@@ -253,3 +861,186 @@ mod blocking_client {
 pub use async_client::{ZenMoneyClient, ZenMoneyClientBuilder};
 #[cfg(feature = "blocking")]
 pub use blocking_client::{ZenMoneyBlockingClient, ZenMoneyBlockingClientBuilder};
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::{
+        DIFF_PATH, RetryPolicy, SUGGEST_PATH, api_error, is_retryable_status, parse_retry_after,
+    };
+    use crate::error::ZenMoneyError;
+    use crate::models::API_VERSION;
+
+    #[test]
+    fn api_error_parses_json_code_and_message() {
+        let body = r#"{"error": "invalid_token", "error_description": "Token expired"}"#;
+        let err = api_error(401, None, body.to_owned());
+        assert!(matches!(
+            err,
+            ZenMoneyError::Unauthorized { code: Some(ref code), ref message }
+                if code == "invalid_token" && message == "Token expired"
+        ));
+    }
+
+    #[test]
+    fn api_error_parses_json_error_only() {
+        let err = api_error(400, None, r#"{"error": "Bad diff"}"#.to_owned());
+        assert!(matches!(
+            err,
+            ZenMoneyError::BadRequest { code: None, ref message } if message == "Bad diff"
+        ));
+    }
+
+    #[test]
+    fn api_error_keeps_plain_text_body() {
+        let err = api_error(502, None, "Bad Gateway".to_owned());
+        assert!(matches!(
+            err,
+            ZenMoneyError::Api { status: 502, code: None, ref message } if message == "Bad Gateway"
+        ));
+    }
+
+    #[test]
+    fn api_error_rate_limit_keeps_retry_after() {
+        let err = api_error(429, Some(Duration::from_secs(7)), "slow down".to_owned());
+        assert!(matches!(
+            err,
+            ZenMoneyError::RateLimited { retry_after: Some(delay) } if delay == Duration::from_secs(7)
+        ));
+    }
+
+    #[test]
+    fn retry_policy_backs_off_exponentially_up_to_max_delay() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(100), Duration::from_millis(300));
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(300));
+        assert_eq!(policy.backoff(30), Duration::from_millis(300));
+
+        let delay = policy.delay(true, 1, None).unwrap();
+        assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        assert_eq!(policy.delay(true, 3, None), None);
+        assert_eq!(policy.delay(false, 0, None), None);
+        assert_eq!(RetryPolicy::default().delay(true, 0, None), None);
+    }
+
+    #[test]
+    fn retry_after_is_honoured_but_clamped_to_max_delay() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(100), Duration::from_secs(5));
+        assert_eq!(
+            policy.delay(true, 0, parse_retry_after(Some(" 3 "))),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            policy.delay(true, 0, parse_retry_after(Some("3600"))),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(policy.delay(false, 0, Some(Duration::from_secs(1))), None);
+    }
+
+    #[test]
+    fn only_rate_limits_and_server_errors_are_retryable() {
+        assert!(is_retryable_status(429));
+        assert!(is_retryable_status(500));
+        assert!(is_retryable_status(503));
+        assert!(!is_retryable_status(400));
+        assert!(!is_retryable_status(401));
+        assert!(!is_retryable_status(404));
+        assert!(!is_retryable_status(200));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn suggest_batch_keeps_order_and_reports_failures_per_item() {
+        use super::ZenMoneyClient;
+        use crate::models::SuggestRequest;
+
+        let mock_server = wiremock::MockServer::start().await;
+        for (payee, status) in [("cafe", 200), ("broken", 400), ("shop", 200)] {
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path(SUGGEST_PATH))
+                .and(wiremock::matchers::body_partial_json(
+                    serde_json::json!({"payee": payee}),
+                ))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(status)
+                        .set_body_json(serde_json::json!({"payee": payee.to_uppercase()})),
+                )
+                .mount(&mock_server)
+                .await;
+        }
+        let client = ZenMoneyClient::builder()
+            .token("test-token")
+            .base_url(mock_server.uri())
+            .batch_concurrency(2)
+            .build()
+            .unwrap();
+
+        let requests: Vec<SuggestRequest> = ["cafe", "broken", "shop"]
+            .into_iter()
+            .map(|payee| SuggestRequest {
+                payee: Some(payee.to_owned()),
+                comment: None,
+            })
+            .collect();
+        let responses = client.suggest_batch(&requests).await;
+        assert_eq!(responses.len(), 3);
+        assert_eq!(
+            responses[0].as_ref().unwrap().payee.as_deref(),
+            Some("CAFE")
+        );
+        assert!(matches!(
+            responses[1],
+            Err(ZenMoneyError::BadRequest { .. })
+        ));
+        assert_eq!(
+            responses[2].as_ref().unwrap().payee.as_deref(),
+            Some("SHOP")
+        );
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 3);
+        assert!(client.suggest_batch(&[]).await.is_empty());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn http2_prior_knowledge_speaks_http2_to_the_server() {
+        use super::ZenMoneyClient;
+        use crate::models::SuggestRequest;
+
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path(SUGGEST_PATH))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&mock_server)
+            .await;
+        let request = SuggestRequest {
+            payee: Some("cafe".to_owned()),
+            comment: None,
+        };
+        for prior_knowledge in [false, true] {
+            let client = ZenMoneyClient::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .http2_prior_knowledge(prior_knowledge)
+                .build()
+                .unwrap();
+            let _response = client.suggest(&request).await.unwrap();
+        }
+
+        // HTTP/1.1 carries the authority in a `Host` header, HTTP/2 in the
+        // `:authority` pseudo-header instead.
+        let received = mock_server.received_requests().await.unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(received[0].headers.contains_key("host"));
+        assert!(!received[1].headers.contains_key("host"));
+    }
+
+    #[test]
+    fn endpoint_paths_include_api_version() {
+        let prefix = format!("/v{API_VERSION}/");
+        assert!(DIFF_PATH.starts_with(&prefix));
+        assert!(SUGGEST_PATH.starts_with(&prefix));
+        assert_eq!(DIFF_PATH, "/v8/diff/");
+    }
+}
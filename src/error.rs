@@ -6,29 +6,111 @@ pub enum ZenMoneyError {
     /// HTTP request failed.
     #[cfg(any(feature = "async", feature = "blocking"))]
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(#[source] reqwest::Error),
 
-    /// API returned a non-success status code.
+    /// HTTP request did not complete within the configured timeout.
+    ///
+    /// Unlike other HTTP failures, this is usually transient and safe to
+    /// retry.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[error("request timed out: {0}")]
+    Timeout(#[source] reqwest::Error),
+
+    /// API returned a non-success status code without a dedicated
+    /// variant.
     #[cfg(any(feature = "async", feature = "blocking"))]
     #[error("API error (status {status}): {message}")]
     Api {
         /// HTTP status code.
         status: u16,
-        /// Error message from the API response body.
+        /// Machine-readable error code, if the body was a JSON error
+        /// object that carried one.
+        code: Option<String>,
+        /// Error message parsed from a JSON error body, or the raw body
+        /// text otherwise.
         message: String,
     },
 
+    /// API rejected the access token (`401 Unauthorized`).
+    ///
+    /// The token is missing, expired, or revoked; refresh it or obtain a
+    /// new one before retrying.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[error("unauthorized: {message}")]
+    Unauthorized {
+        /// Machine-readable error code, if the body carried one.
+        code: Option<String>,
+        /// Error message from the response body.
+        message: String,
+    },
+
+    /// API rate limit exceeded (`429 Too Many Requests`).
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[error("rate limited by the API")]
+    RateLimited {
+        /// Delay requested by the server's `Retry-After` header, if any.
+        retry_after: Option<core::time::Duration>,
+    },
+
+    /// API rejected the request as malformed (`400 Bad Request`).
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[error("bad request: {message}")]
+    BadRequest {
+        /// Machine-readable error code, if the body carried one.
+        code: Option<String>,
+        /// Error message from the response body.
+        message: String,
+    },
+
+    /// Server returned a timestamp older than the one sent in the request.
+    ///
+    /// Applying it would make every following sync re-fetch old data.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[error("server timestamp regressed: sent {sent}, received {received}")]
+    TimestampRegression {
+        /// Server timestamp sent in the diff request.
+        sent: chrono::DateTime<chrono::Utc>,
+        /// Older server timestamp returned in the diff response.
+        received: chrono::DateTime<chrono::Utc>,
+    },
+
     /// JSON serialization or deserialization failed.
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// Reading or writing CSV failed.
+    #[cfg(feature = "csv")]
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// Input failed validation before being sent or stored.
+    #[error("validation error: {0}")]
+    Validation(String),
+
+    /// A referenced entity is not in local storage.
+    #[error("{entity} not found: {id}")]
+    NotFound {
+        /// Entity type name (e.g. "transaction").
+        entity: &'static str,
+        /// Identifier that was looked up.
+        id: String,
+    },
+
     /// Token storage backend failed.
     #[error("token storage error: {0}")]
-    TokenStorage(Box<dyn core::error::Error + Send + Sync>),
+    TokenStorage(#[source] Box<dyn core::error::Error + Send + Sync>),
 
     /// Storage backend operation failed.
     #[error("storage error: {0}")]
-    Storage(Box<dyn core::error::Error + Send + Sync>),
+    Storage(#[source] Box<dyn core::error::Error + Send + Sync>),
+
+    /// Timed out waiting for the storage directory lock.
+    #[cfg(feature = "storage-file")]
+    #[error("timed out after {timeout:?} waiting for storage lock")]
+    LockTimeout {
+        /// Configured lock timeout that elapsed.
+        timeout: core::time::Duration,
+    },
 
     /// Access token has expired and cannot be refreshed.
     #[error("access token expired and no refresh mechanism is available")]
@@ -43,6 +125,82 @@ pub enum ZenMoneyError {
 /// Convenience type alias for results using [`ZenMoneyError`].
 pub type Result<T> = core::result::Result<T, ZenMoneyError>;
 
+/// Exit code for errors without a more specific category.
+pub const EXIT_GENERAL: u8 = 1;
+
+/// Exit code for missing, expired, or rejected credentials.
+pub const EXIT_AUTH: u8 = 2;
+
+/// Exit code for failures talking to the API.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub const EXIT_NETWORK: u8 = 3;
+
+/// Exit code for storage backend failures.
+pub const EXIT_STORAGE: u8 = 4;
+
+/// Exit code for invalid input or missing entities.
+pub const EXIT_VALIDATION: u8 = 5;
+
+impl ZenMoneyError {
+    /// Returns a process exit code identifying the error category, so
+    /// scripts can branch on the kind of failure. The codes are also
+    /// exported as the `EXIT_*` constants.
+    ///
+    /// | Code | Category                                             |
+    /// |------|------------------------------------------------------|
+    /// | 2    | Authentication: expired token, `401`/`403`, OAuth    |
+    /// | 3    | Network: HTTP failures, timeouts, rate limits, other |
+    /// |      | API errors                                           |
+    /// | 4    | Storage: backend failures and lock timeouts          |
+    /// | 5    | Validation: invalid input, `400`, entity not found   |
+    /// | 1    | Anything else                                        |
+    #[inline]
+    #[must_use]
+    pub const fn exit_code(&self) -> u8 {
+        match *self {
+            #[cfg(any(feature = "async", feature = "blocking"))]
+            Self::Unauthorized { .. } | Self::Api { status: 403, .. } => EXIT_AUTH,
+            Self::TokenExpired | Self::TokenStorage(_) => EXIT_AUTH,
+            #[cfg(feature = "oauth")]
+            Self::OAuth(_) => EXIT_AUTH,
+            #[cfg(any(feature = "async", feature = "blocking"))]
+            Self::Http(_) | Self::Timeout(_) | Self::RateLimited { .. } | Self::Api { .. } => {
+                EXIT_NETWORK
+            }
+            #[cfg(any(feature = "async", feature = "blocking"))]
+            Self::BadRequest { .. } => EXIT_VALIDATION,
+            Self::Storage(_) => EXIT_STORAGE,
+            #[cfg(feature = "storage-file")]
+            Self::LockTimeout { .. } => EXIT_STORAGE,
+            Self::Validation(_) | Self::NotFound { .. } => EXIT_VALIDATION,
+            #[cfg(any(feature = "async", feature = "blocking"))]
+            Self::TimestampRegression { .. } => EXIT_GENERAL,
+            Self::Serialization(_) => EXIT_GENERAL,
+            #[cfg(feature = "csv")]
+            Self::Csv(_) => EXIT_GENERAL,
+        }
+    }
+}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl From<reqwest::Error> for ZenMoneyError {
+    #[inline]
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            Self::Timeout(err)
+        } else {
+            Self::Http(err)
+        }
+    }
+}
+
+impl From<crate::models::IdError> for ZenMoneyError {
+    #[inline]
+    fn from(err: crate::models::IdError) -> Self {
+        Self::Validation(err.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,6 +214,13 @@ mod tests {
         assert!(msg.contains("serialization error"));
     }
 
+    #[test]
+    fn error_from_id_error_is_validation() {
+        let id_err = crate::models::AccountId::parse("acc-1").unwrap_err();
+        let err = ZenMoneyError::from(id_err);
+        assert!(matches!(err, ZenMoneyError::Validation(ref msg) if msg.contains("acc-1")));
+    }
+
     #[test]
     fn error_token_storage_display() {
         let inner = std::io::Error::new(std::io::ErrorKind::NotFound, "file missing");
@@ -74,6 +239,62 @@ mod tests {
         assert!(msg.contains("disk full"));
     }
 
+    #[test]
+    fn error_storage_exposes_source() {
+        use core::error::Error as _;
+
+        let inner = std::io::Error::new(std::io::ErrorKind::Other, "disk full");
+        let err = ZenMoneyError::Storage(Box::new(inner));
+        let source = err.source().unwrap();
+        assert_eq!(source.to_string(), "disk full");
+    }
+
+    #[test]
+    fn error_token_storage_and_serialization_expose_source() {
+        use core::error::Error as _;
+
+        let inner = std::io::Error::new(std::io::ErrorKind::NotFound, "file missing");
+        let err = ZenMoneyError::TokenStorage(Box::new(inner));
+        assert!(err.source().is_some());
+
+        let serde_err = serde_json::from_str::<String>("not json").unwrap_err();
+        assert!(ZenMoneyError::from(serde_err).source().is_some());
+        assert!(ZenMoneyError::TokenExpired.source().is_none());
+    }
+
+    #[test]
+    fn exit_code_distinguishes_categories() {
+        assert_eq!(ZenMoneyError::TokenExpired.exit_code(), EXIT_AUTH);
+        let inner = std::io::Error::new(std::io::ErrorKind::Other, "disk full");
+        assert_eq!(
+            ZenMoneyError::Storage(Box::new(inner)).exit_code(),
+            EXIT_STORAGE
+        );
+        assert_eq!(
+            ZenMoneyError::Validation("bad".to_owned()).exit_code(),
+            EXIT_VALIDATION
+        );
+    }
+
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[test]
+    fn exit_code_of_api_errors() {
+        let api = |status| ZenMoneyError::Api {
+            status,
+            code: None,
+            message: String::new(),
+        };
+        assert_eq!(api(403).exit_code(), EXIT_AUTH);
+        assert_eq!(api(503).exit_code(), EXIT_NETWORK);
+        let unauthorized = ZenMoneyError::Unauthorized {
+            code: None,
+            message: String::new(),
+        };
+        assert_eq!(unauthorized.exit_code(), EXIT_AUTH);
+        let rate_limited = ZenMoneyError::RateLimited { retry_after: None };
+        assert_eq!(rate_limited.exit_code(), EXIT_NETWORK);
+    }
+
     #[test]
     fn error_token_expired_display() {
         let err = ZenMoneyError::TokenExpired;
@@ -85,6 +306,7 @@ mod tests {
     fn error_api_display() {
         let err = ZenMoneyError::Api {
             status: 401,
+            code: None,
             message: "Unauthorized".to_owned(),
         };
         let msg = err.to_string();
@@ -92,6 +314,16 @@ mod tests {
         assert!(msg.contains("Unauthorized"));
     }
 
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[test]
+    fn error_timestamp_regression_display() {
+        let err = ZenMoneyError::TimestampRegression {
+            sent: chrono::DateTime::from_timestamp(200, 0).unwrap(),
+            received: chrono::DateTime::from_timestamp(100, 0).unwrap(),
+        };
+        assert!(err.to_string().contains("regressed"));
+    }
+
     #[test]
     fn error_is_send_sync() {
         fn assert_send_sync<T: Send + Sync>() {}
@@ -3,6 +3,18 @@
 //! This crate provides a typed client for interacting with the
 //! [ZenMoney](https://zenmoney.ru/) personal finance API.
 
+extern crate alloc;
+
+/// Expands to the ZenMoney API major version as an integer literal.
+///
+/// Single source for [`models::API_VERSION`] and the endpoint paths, which
+/// need a literal to build their strings with `concat!`.
+macro_rules! api_version {
+    () => {
+        8
+    };
+}
+
 #[cfg(any(feature = "async", feature = "blocking"))]
 pub mod client;
 pub mod error;
@@ -4,38 +4,59 @@
 //! entities, newtype ID wrappers, and enumeration types for constrained
 //! values.
 
+/// Major version of the ZenMoney API this crate speaks (`/v8/`).
+pub const API_VERSION: u8 = api_version!();
+
 mod account;
+mod approx;
 mod budget;
+mod color;
 mod company;
 mod country;
 mod diff;
 mod enums;
+#[cfg(feature = "csv")]
+mod export;
 mod ids;
 mod instrument;
+mod mcc;
 mod merchant;
 mod reminder;
 mod reminder_marker;
+#[cfg(feature = "schema")]
+mod schema;
 mod suggest;
 mod tag;
 mod transaction;
 mod user;
+#[cfg(feature = "csv")]
+mod zenmoney_csv;
 
-pub use account::Account;
+pub use account::{Account, AccountBuilder, sort_accounts_for_display};
+pub use approx::ApproxEq;
 pub use budget::Budget;
 pub use chrono::{DateTime, NaiveDate, Utc};
+pub use color::{Color, ColorError};
 pub use company::Company;
 pub use country::Country;
-pub use diff::{Deletion, DiffRequest, DiffResponse};
+pub use diff::{Deletion, DiffRequest, DiffRequestBuilder, DiffResponse};
 pub use enums::{AccountType, Interval, PayoffInterval, ReminderMarkerState};
+#[cfg(feature = "csv")]
+pub use export::to_csv;
 pub use ids::{
-    AccountId, CompanyId, InstrumentId, MerchantId, ReminderId, ReminderMarkerId, TagId,
+    AccountId, CompanyId, IdError, InstrumentId, MerchantId, ReminderId, ReminderMarkerId, TagId,
     TransactionId, UserId,
 };
 pub use instrument::Instrument;
+pub use mcc::mcc_description;
 pub use merchant::Merchant;
 pub use reminder::Reminder;
 pub use reminder_marker::ReminderMarker;
+#[cfg(feature = "schema")]
+pub use schema::json_schema;
 pub use suggest::{SuggestRequest, SuggestResponse};
 pub use tag::Tag;
-pub use transaction::Transaction;
+pub use transaction::{Posting, Transaction, TransactionBuilder, TransferBuilder};
 pub use user::User;
+#[cfg(feature = "csv")]
+pub use zenmoney_csv::{CsvReferences, from_zenmoney_csv, to_zenmoney_csv};
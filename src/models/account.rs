@@ -4,9 +4,11 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::{AccountId, AccountType, CompanyId, InstrumentId, PayoffInterval, UserId};
+use crate::error::{Result, ZenMoneyError};
 
 /// A user's financial account (bank account, credit card, cash, etc.).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[allow(
     clippy::struct_excessive_bools,
@@ -17,6 +19,7 @@ pub struct Account {
     pub id: AccountId,
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// Owner user identifier.
     pub user: UserId,
@@ -73,6 +76,188 @@ pub struct Account {
     pub private: Option<bool>,
 }
 
+/// Sorts accounts into a stable display order.
+///
+/// Active accounts come before archived ones; within each group accounts
+/// are ordered by [`AccountType`] and then by title.
+#[inline]
+pub fn sort_accounts_for_display(accounts: &mut [Account]) {
+    accounts.sort_by(|lhs, rhs| {
+        (lhs.archive, lhs.kind, &lhs.title).cmp(&(rhs.archive, rhs.kind, &rhs.title))
+    });
+}
+
+/// Builder for a new [`Account`] to push.
+///
+/// Only the owner, title, and kind are required. The account is included
+/// in the total balance, not archived, gets a fresh UUID unless
+/// [`Self::id`] is called, and is stamped with the current time.
+///
+/// # Examples
+///
+/// ```
+/// use zenmoney_rs::models::{AccountBuilder, AccountType, InstrumentId, UserId};
+///
+/// let wallet = AccountBuilder::new(UserId::new(1), "Wallet", AccountType::Cash)
+///     .instrument(InstrumentId::new(1))
+///     .build()
+///     .unwrap();
+/// assert!(wallet.in_balance);
+/// assert!(wallet.id.is_valid_uuid());
+/// ```
+#[derive(Debug, Clone)]
+pub struct AccountBuilder {
+    /// Explicit identifier; a fresh UUID is used when unset.
+    id: Option<AccountId>,
+    /// Owner user identifier.
+    user: UserId,
+    /// Display name.
+    title: String,
+    /// Type of account.
+    kind: AccountType,
+    /// Currency instrument identifier.
+    instrument: Option<InstrumentId>,
+    /// Associated financial company.
+    company: Option<CompanyId>,
+    /// Current balance.
+    balance: Option<f64>,
+    /// Credit limit.
+    credit_limit: Option<f64>,
+    /// Whether to include in total balance calculation.
+    in_balance: bool,
+    /// Start date of the deposit/loan.
+    start_date: Option<NaiveDate>,
+}
+
+impl AccountBuilder {
+    /// Starts an account with the given owner, title, and kind.
+    #[inline]
+    #[must_use]
+    pub fn new<T: Into<String>>(user: UserId, title: T, kind: AccountType) -> Self {
+        Self {
+            id: None,
+            user,
+            title: title.into(),
+            kind,
+            instrument: None,
+            company: None,
+            balance: None,
+            credit_limit: None,
+            in_balance: true,
+            start_date: None,
+        }
+    }
+
+    /// Uses `id` instead of a freshly generated UUID.
+    #[inline]
+    #[must_use]
+    pub fn id(mut self, id: AccountId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the currency instrument.
+    #[inline]
+    #[must_use]
+    pub const fn instrument(mut self, instrument: InstrumentId) -> Self {
+        self.instrument = Some(instrument);
+        self
+    }
+
+    /// Sets the financial company.
+    #[inline]
+    #[must_use]
+    pub const fn company(mut self, company: CompanyId) -> Self {
+        self.company = Some(company);
+        self
+    }
+
+    /// Sets the current balance.
+    #[inline]
+    #[must_use]
+    pub const fn balance(mut self, balance: f64) -> Self {
+        self.balance = Some(balance);
+        self
+    }
+
+    /// Sets the credit limit.
+    #[inline]
+    #[must_use]
+    pub const fn credit_limit(mut self, limit: f64) -> Self {
+        self.credit_limit = Some(limit);
+        self
+    }
+
+    /// Sets whether the account counts towards the total balance
+    /// (defaults to `true`).
+    #[inline]
+    #[must_use]
+    pub const fn in_balance(mut self, included: bool) -> Self {
+        self.in_balance = included;
+        self
+    }
+
+    /// Sets the start date, required for [`AccountType::Deposit`].
+    #[inline]
+    #[must_use]
+    pub const fn start_date(mut self, date: NaiveDate) -> Self {
+        self.start_date = Some(date);
+        self
+    }
+
+    /// Builds the account, stamped with the current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZenMoneyError::Validation`] if a deposit has no start
+    /// date or the credit limit is negative or not finite.
+    #[inline]
+    pub fn build(self) -> Result<Account> {
+        if self.kind == AccountType::Deposit && self.start_date.is_none() {
+            return Err(ZenMoneyError::Validation(
+                "deposit account requires a start date".to_owned(),
+            ));
+        }
+        if let Some(limit) = self.credit_limit
+            && (!limit.is_finite() || limit < 0.0_f64)
+        {
+            return Err(ZenMoneyError::Validation(format!(
+                "credit limit must be a non-negative number, got {limit}"
+            )));
+        }
+        Ok(Account {
+            id: self
+                .id
+                .unwrap_or_else(|| AccountId::new(uuid::Uuid::new_v4().to_string())),
+            changed: Utc::now(),
+            user: self.user,
+            role: None,
+            instrument: self.instrument,
+            company: self.company,
+            kind: self.kind,
+            title: self.title,
+            sync_id: None,
+            balance: self.balance,
+            start_balance: None,
+            credit_limit: self.credit_limit,
+            in_balance: self.in_balance,
+            savings: None,
+            enable_correction: false,
+            enable_sms: false,
+            archive: false,
+            capitalization: None,
+            percent: None,
+            start_date: self.start_date,
+            end_date_offset: None,
+            end_date_offset_interval: None,
+            payoff_step: None,
+            payoff_interval: None,
+            balance_correction_type: None,
+            private: None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +377,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sort_accounts_for_display_orders_by_archive_kind_title() {
+        let make = |id: &str, kind: AccountType, title: &str, archive: bool| Account {
+            id: AccountId::new(id.to_owned()),
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            user: UserId::new(1),
+            role: None,
+            instrument: None,
+            company: None,
+            kind,
+            title: title.to_owned(),
+            sync_id: None,
+            balance: None,
+            start_balance: None,
+            credit_limit: None,
+            in_balance: true,
+            savings: None,
+            enable_correction: false,
+            enable_sms: false,
+            archive,
+            capitalization: None,
+            percent: None,
+            start_date: None,
+            end_date_offset: None,
+            end_date_offset_interval: None,
+            payoff_step: None,
+            payoff_interval: None,
+            balance_correction_type: None,
+            private: None,
+        };
+        let mut accounts = vec![
+            make("1", AccountType::Cash, "Old Wallet", true),
+            make("2", AccountType::Checking, "Salary", false),
+            make("3", AccountType::Cash, "Wallet", false),
+            make("4", AccountType::Checking, "Bills", false),
+            make("5", AccountType::CreditCard, "Visa", false),
+        ];
+        sort_accounts_for_display(&mut accounts);
+        let order: Vec<&str> = accounts.iter().map(|acc| acc.id.as_inner()).collect();
+        assert_eq!(order, ["3", "5", "4", "2", "1"]);
+    }
+
     #[test]
     fn serialize_roundtrip() {
         let account = Account {
@@ -226,4 +453,41 @@ mod tests {
         let deserialized: Account = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized, account);
     }
+
+    #[test]
+    fn account_builder_fills_defaults() {
+        let account = AccountBuilder::new(UserId::new(1), "Wallet", AccountType::Cash)
+            .instrument(InstrumentId::new(1))
+            .balance(500.0)
+            .build()
+            .unwrap();
+        assert!(account.id.is_valid_uuid());
+        assert_eq!(account.title, "Wallet");
+        assert_eq!(account.kind, AccountType::Cash);
+        assert_eq!(account.instrument, Some(InstrumentId::new(1)));
+        assert_eq!(account.balance, Some(500.0));
+        assert!(account.in_balance);
+        assert!(!account.archive);
+        assert!(account.credit_limit.is_none());
+    }
+
+    #[test]
+    fn account_builder_requires_deposit_start_date() {
+        let builder = AccountBuilder::new(UserId::new(1), "Deposit", AccountType::Deposit);
+        let err = builder.clone().build().unwrap_err();
+        assert!(matches!(err, ZenMoneyError::Validation(_)));
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let deposit = builder.start_date(date).build().unwrap();
+        assert_eq!(deposit.start_date, Some(date));
+    }
+
+    #[test]
+    fn account_builder_rejects_negative_credit_limit() {
+        let err = AccountBuilder::new(UserId::new(1), "Card", AccountType::CreditCard)
+            .credit_limit(-1.0)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ZenMoneyError::Validation(_)));
+    }
 }
@@ -0,0 +1,120 @@
+//! Equality that tolerates floating-point noise in amounts.
+
+use super::{Account, Budget, Transaction};
+
+/// Equality with a tolerance for floating-point fields.
+///
+/// Two values are approximately equal when every non-float field is
+/// equal and every pair of float fields differs by at most `epsilon`.
+/// Optional floats must both be set or both be unset. `NaN` never
+/// matches.
+pub trait ApproxEq {
+    /// Returns `true` if `self` and `other` are equal up to `epsilon` in
+    /// their float fields.
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool;
+}
+
+impl ApproxEq for Transaction {
+    #[inline]
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let mut snapped = other.clone();
+        snap(&mut snapped.income, self.income, epsilon);
+        snap(&mut snapped.outcome, self.outcome, epsilon);
+        snap_opt(&mut snapped.op_income, self.op_income, epsilon);
+        snap_opt(&mut snapped.op_outcome, self.op_outcome, epsilon);
+        snap_opt(&mut snapped.latitude, self.latitude, epsilon);
+        snap_opt(&mut snapped.longitude, self.longitude, epsilon);
+        *self == snapped
+    }
+}
+
+impl ApproxEq for Account {
+    #[inline]
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let mut snapped = other.clone();
+        snap_opt(&mut snapped.balance, self.balance, epsilon);
+        snap_opt(&mut snapped.start_balance, self.start_balance, epsilon);
+        snap_opt(&mut snapped.credit_limit, self.credit_limit, epsilon);
+        snap_opt(&mut snapped.percent, self.percent, epsilon);
+        *self == snapped
+    }
+}
+
+impl ApproxEq for Budget {
+    #[inline]
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let mut snapped = other.clone();
+        snap(&mut snapped.income, self.income, epsilon);
+        snap(&mut snapped.outcome, self.outcome, epsilon);
+        *self == snapped
+    }
+}
+
+/// Replaces `value` with `target` if they are within `epsilon`.
+fn snap(value: &mut f64, target: f64, epsilon: f64) {
+    if (*value - target).abs() <= epsilon {
+        *value = target;
+    }
+}
+
+/// Like [`snap`], for optional floats that are both set.
+fn snap_opt(value: &mut Option<f64>, target: Option<f64>, epsilon: f64) {
+    if let (Some(inner), Some(goal)) = (value.as_mut(), target) {
+        snap(inner, goal, epsilon);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::models::{
+        AccountBuilder, AccountId, AccountType, InstrumentId, TransactionBuilder, UserId,
+    };
+
+    /// Tolerance used by the tests.
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn transactions_within_epsilon_are_approx_eq_but_not_equal() {
+        let tx = TransactionBuilder::expense(AccountId::new("a-1".to_owned()), 100.0)
+            .user(UserId::new(1))
+            .instrument(InstrumentId::new(1))
+            .date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+            .build()
+            .unwrap();
+        let mut noisy = tx.clone();
+        noisy.outcome += 1e-12;
+        assert_ne!(tx, noisy);
+        assert!(tx.approx_eq(&noisy, EPSILON));
+
+        noisy.outcome += 1.0;
+        assert!(!tx.approx_eq(&noisy, EPSILON));
+    }
+
+    #[test]
+    fn account_optional_floats_must_both_be_set() {
+        let account = AccountBuilder::new(UserId::new(1), "Card", AccountType::Cash)
+            .instrument(InstrumentId::new(1))
+            .balance(10.0)
+            .build()
+            .unwrap();
+        let mut noisy = account.clone();
+        noisy.balance = Some(10.0 + 1e-12);
+        assert!(account.approx_eq(&noisy, EPSILON));
+        noisy.balance = None;
+        assert!(!account.approx_eq(&noisy, EPSILON));
+    }
+
+    #[test]
+    fn budget_compares_non_float_fields_exactly() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let budget = Budget::new(UserId::new(1), None, date, 0.0, 500.0);
+        let mut noisy = budget.clone();
+        noisy.outcome -= 1e-12;
+        assert!(budget.approx_eq(&noisy, EPSILON));
+        noisy.outcome_lock = true;
+        assert!(!budget.approx_eq(&noisy, EPSILON));
+    }
+}
@@ -7,14 +7,16 @@ use super::{TagId, UserId};
 
 /// A monthly income/outcome budget target for a category.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Budget {
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// Owner user identifier.
     pub user: UserId,
-    /// Category tag (null or special UUID
+    /// Category tag (null for the uncategorized budget, special UUID
     /// "00000000-0000-0000-0000-000000000000" for aggregate).
     pub tag: Option<TagId>,
     /// Budget month start date.
@@ -35,6 +37,44 @@ pub struct Budget {
     pub is_outcome_forecast: Option<bool>,
 }
 
+impl Budget {
+    /// Creates an unlocked budget for `tag` in the month starting at
+    /// `date`, stamped with the current time.
+    ///
+    /// Pass `None` as `tag` for the uncategorized budget; the aggregate
+    /// budget uses the all-zero tag ID.
+    #[inline]
+    #[must_use]
+    pub fn new(
+        user: UserId,
+        tag: Option<TagId>,
+        date: NaiveDate,
+        income: f64,
+        outcome: f64,
+    ) -> Self {
+        Self {
+            changed: Utc::now(),
+            user,
+            tag,
+            date,
+            income,
+            income_lock: false,
+            outcome,
+            outcome_lock: false,
+            is_income_forecast: None,
+            is_outcome_forecast: None,
+        }
+    }
+
+    /// Returns how much of the outcome target is left after spending
+    /// `actual_outcome`; negative when the budget is overspent.
+    #[inline]
+    #[must_use]
+    pub fn remaining(&self, actual_outcome: f64) -> f64 {
+        self.outcome - actual_outcome
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,4 +132,27 @@ mod tests {
         let deserialized: Budget = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized, budget);
     }
+
+    #[test]
+    fn new_budget_is_unlocked_and_fresh() {
+        let before = Utc::now();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let tag = Some(TagId::new("t-1".to_owned()));
+        let budget = Budget::new(UserId::new(1), tag.clone(), date, 0.0, 5000.0);
+        assert!(budget.changed >= before);
+        assert_eq!(budget.tag, tag);
+        assert_eq!(budget.date, date);
+        assert!((budget.outcome - 5000.0).abs() < f64::EPSILON);
+        assert!(!budget.income_lock);
+        assert!(!budget.outcome_lock);
+        assert!(budget.is_outcome_forecast.is_none());
+    }
+
+    #[test]
+    fn remaining_goes_negative_when_overspent() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let budget = Budget::new(UserId::new(1), None, date, 0.0, 5000.0);
+        assert!((budget.remaining(1200.0) - 3800.0).abs() < f64::EPSILON);
+        assert!((budget.remaining(6000.0) + 1000.0).abs() < f64::EPSILON);
+    }
 }
@@ -0,0 +1,145 @@
+//! Tag colors in ZenMoney's integer encoding.
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// An ARGB color as ZenMoney stores it for tags.
+///
+/// On the wire a color is a single integer holding the alpha, red, green
+/// and blue bytes from most to least significant, sent as a signed 32-bit
+/// value (so opaque colors are negative). Deserialization also accepts the
+/// same bits written as an unsigned number.
+///
+/// # Examples
+///
+/// ```
+/// use zenmoney_rs::models::Color;
+///
+/// let green: Color = serde_json::from_str("-16711936").unwrap();
+/// assert_eq!(green.to_rgb(), (0, 255, 0));
+/// assert_eq!(green, Color::from_rgb(0, 255, 0));
+/// assert_eq!(serde_json::to_string(&green).unwrap(), "-16711936");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "i64", into = "i32")]
+pub struct Color(u32);
+
+impl Color {
+    /// Creates a color from its packed `0xAARRGGBB` value.
+    #[inline]
+    #[must_use]
+    pub const fn from_argb(argb: u32) -> Self {
+        Self(argb)
+    }
+
+    /// Creates a fully opaque color from its red, green and blue bytes.
+    #[inline]
+    #[must_use]
+    pub const fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self(u32::from_be_bytes([u8::MAX, red, green, blue]))
+    }
+
+    /// Returns the packed `0xAARRGGBB` value.
+    #[inline]
+    #[must_use]
+    pub const fn argb(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the red, green and blue bytes, ignoring alpha.
+    #[inline]
+    #[must_use]
+    pub const fn to_rgb(self) -> (u8, u8, u8) {
+        let [_alpha, red, green, blue] = self.0.to_be_bytes();
+        (red, green, blue)
+    }
+
+    /// Returns the alpha byte, where `255` is fully opaque.
+    #[inline]
+    #[must_use]
+    pub const fn alpha(self) -> u8 {
+        let [alpha, ..] = self.0.to_be_bytes();
+        alpha
+    }
+}
+
+/// Formats the color as `#RRGGBB`, or `#RRGGBBAA` if not fully opaque.
+impl fmt::Display for Color {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (red, green, blue) = self.to_rgb();
+        write!(f, "#{red:02x}{green:02x}{blue:02x}")?;
+        match self.alpha() {
+            u8::MAX => Ok(()),
+            alpha => write!(f, "{alpha:02x}"),
+        }
+    }
+}
+
+impl TryFrom<i64> for Color {
+    type Error = ColorError;
+
+    /// Accepts the signed wire encoding as well as the same bits written
+    /// as an unsigned 32-bit number.
+    #[inline]
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        u32::try_from(value)
+            .or_else(|_err| i32::try_from(value).map(i32::cast_unsigned))
+            .map(Self)
+            .map_err(|_err| ColorError { value })
+    }
+}
+
+impl From<Color> for i32 {
+    #[inline]
+    fn from(color: Color) -> Self {
+        color.0.cast_signed()
+    }
+}
+
+/// Error returned when an integer does not fit the 32-bit color encoding.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("color value out of 32-bit range: {value}")]
+pub struct ColorError {
+    /// The rejected input.
+    pub value: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_wire_integer_to_rgb() {
+        let color: Color = serde_json::from_str("-16711936").unwrap();
+        assert_eq!(color.argb(), 0xFF00_FF00);
+        assert_eq!(color.to_rgb(), (0, 255, 0));
+        assert_eq!(color.alpha(), 255);
+        assert_eq!(color.to_string(), "#00ff00");
+    }
+
+    #[test]
+    fn serializes_as_signed_wire_integer() {
+        let color = Color::from_rgb(0x12, 0x34, 0x56);
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(json, "-15584170");
+        assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+    }
+
+    #[test]
+    fn accepts_unsigned_encoding_and_rejects_out_of_range() {
+        let color: Color = serde_json::from_str("4278255360").unwrap();
+        assert_eq!(color, Color::from_rgb(0, 255, 0));
+        assert_eq!(
+            Color::try_from(1_i64 << 32),
+            Err(ColorError { value: 1 << 32 })
+        );
+        assert!(serde_json::from_str::<Color>("-2147483649").is_err());
+    }
+
+    #[test]
+    fn display_includes_alpha_when_translucent() {
+        assert_eq!(Color::from_argb(0x80FF_0000).to_string(), "#ff000080");
+    }
+}
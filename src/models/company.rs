@@ -7,12 +7,14 @@ use super::CompanyId;
 
 /// A financial institution (bank, payment provider, etc.).
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Company {
     /// Unique identifier.
     pub id: CompanyId,
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// Short company name.
     pub title: String,
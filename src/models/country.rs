@@ -6,6 +6,7 @@ use super::InstrumentId;
 
 /// A country with its associated currency.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Country {
     /// Unique identifier.
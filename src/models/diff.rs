@@ -7,9 +7,11 @@ use super::{
     Account, Budget, Company, Country, Instrument, Merchant, Reminder, ReminderMarker, Tag,
     Transaction, User,
 };
+use crate::error::{Result, ZenMoneyError};
 
 /// A deletion record identifying a removed entity.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Deletion {
     /// Entity identifier.
     pub id: String,
@@ -17,6 +19,7 @@ pub struct Deletion {
     pub object: String,
     /// Timestamp of deletion.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub stamp: DateTime<Utc>,
     /// User who deleted the entity.
     pub user: i64,
@@ -24,13 +27,16 @@ pub struct Deletion {
 
 /// Request body for the `/v8/diff/` synchronization endpoint.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DiffRequest {
     /// Client's current timestamp (for server time correction).
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub current_client_timestamp: DateTime<Utc>,
     /// Last known server timestamp (epoch for initial sync).
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub server_timestamp: DateTime<Utc>,
     /// Entity types to force-fetch completely.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -83,14 +89,219 @@ impl DiffRequest {
             deletion: Vec::new(),
         }
     }
+
+    /// Returns `true` if the request carries any local entity changes,
+    /// deletions, or force-fetch entries.
+    #[inline]
+    #[must_use]
+    pub const fn has_changes(&self) -> bool {
+        !(self.force_fetch.is_empty()
+            && self.account.is_empty()
+            && self.tag.is_empty()
+            && self.merchant.is_empty()
+            && self.transaction.is_empty()
+            && self.reminder.is_empty()
+            && self.reminder_marker.is_empty()
+            && self.budget.is_empty()
+            && self.deletion.is_empty())
+    }
+
+    /// Returns a builder for assembling a request with entity changes.
+    #[inline]
+    #[must_use]
+    pub fn builder() -> DiffRequestBuilder {
+        DiffRequestBuilder::default()
+    }
+
+    /// Checks the request for values the server would reject.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZenMoneyError::Validation`] if a transaction amount is
+    /// negative or not finite, a deletion has an empty ID or object type,
+    /// or a force-fetch entry is empty.
+    #[inline]
+    pub fn validate(&self) -> Result<()> {
+        for tx in &self.transaction {
+            for amount in [tx.income, tx.outcome] {
+                if !amount.is_finite() || amount < 0.0_f64 {
+                    return Err(ZenMoneyError::Validation(format!(
+                        "transaction {} has invalid amount {amount}",
+                        tx.id
+                    )));
+                }
+            }
+        }
+        if let Some(deletion) = self
+            .deletion
+            .iter()
+            .find(|deletion| deletion.id.is_empty() || deletion.object.is_empty())
+        {
+            return Err(ZenMoneyError::Validation(format!(
+                "deletion must have an ID and object type, got {deletion:?}"
+            )));
+        }
+        if self.force_fetch.iter().any(String::is_empty) {
+            return Err(ZenMoneyError::Validation(
+                "force-fetch entity type must not be empty".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Builder for a [`DiffRequest`] carrying entity changes.
+///
+/// The server timestamp defaults to [`DateTime::UNIX_EPOCH`] and the
+/// client timestamp to the time of [`DiffRequestBuilder::build`].
+#[derive(Debug, Clone, Default)]
+pub struct DiffRequestBuilder {
+    /// Last known server timestamp, or `None` for epoch.
+    server_timestamp: Option<DateTime<Utc>>,
+    /// Client timestamp, or `None` for the build time.
+    current_client_timestamp: Option<DateTime<Utc>>,
+    /// Entity types to force-fetch.
+    force_fetch: Vec<String>,
+    /// Accounts to create or update.
+    account: Vec<Account>,
+    /// Tags to create or update.
+    tag: Vec<Tag>,
+    /// Merchants to create or update.
+    merchant: Vec<Merchant>,
+    /// Transactions to create or update.
+    transaction: Vec<Transaction>,
+    /// Reminders to create or update.
+    reminder: Vec<Reminder>,
+    /// Reminder markers to create or update.
+    reminder_marker: Vec<ReminderMarker>,
+    /// Budgets to create or update.
+    budget: Vec<Budget>,
+    /// Entities to delete.
+    deletion: Vec<Deletion>,
+}
+
+impl DiffRequestBuilder {
+    /// Sets the last known server timestamp.
+    #[inline]
+    #[must_use]
+    pub const fn server_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.server_timestamp = Some(timestamp);
+        self
+    }
+
+    /// Sets the client's current timestamp.
+    #[inline]
+    #[must_use]
+    pub const fn current_client_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.current_client_timestamp = Some(timestamp);
+        self
+    }
+
+    /// Sets the entity types to force-fetch completely.
+    #[inline]
+    #[must_use]
+    pub fn force_fetch_kinds(mut self, kinds: Vec<String>) -> Self {
+        self.force_fetch = kinds;
+        self
+    }
+
+    /// Sets the accounts to create or update.
+    #[inline]
+    #[must_use]
+    pub fn accounts(mut self, items: Vec<Account>) -> Self {
+        self.account = items;
+        self
+    }
+
+    /// Sets the tags to create or update.
+    #[inline]
+    #[must_use]
+    pub fn tags(mut self, items: Vec<Tag>) -> Self {
+        self.tag = items;
+        self
+    }
+
+    /// Sets the merchants to create or update.
+    #[inline]
+    #[must_use]
+    pub fn merchants(mut self, items: Vec<Merchant>) -> Self {
+        self.merchant = items;
+        self
+    }
+
+    /// Sets the transactions to create or update.
+    #[inline]
+    #[must_use]
+    pub fn transactions(mut self, items: Vec<Transaction>) -> Self {
+        self.transaction = items;
+        self
+    }
+
+    /// Sets the reminders to create or update.
+    #[inline]
+    #[must_use]
+    pub fn reminders(mut self, items: Vec<Reminder>) -> Self {
+        self.reminder = items;
+        self
+    }
+
+    /// Sets the reminder markers to create or update.
+    #[inline]
+    #[must_use]
+    pub fn reminder_markers(mut self, items: Vec<ReminderMarker>) -> Self {
+        self.reminder_marker = items;
+        self
+    }
+
+    /// Sets the budgets to create or update.
+    #[inline]
+    #[must_use]
+    pub fn budgets(mut self, items: Vec<Budget>) -> Self {
+        self.budget = items;
+        self
+    }
+
+    /// Sets the entities to delete.
+    #[inline]
+    #[must_use]
+    pub fn deletions(mut self, items: Vec<Deletion>) -> Self {
+        self.deletion = items;
+        self
+    }
+
+    /// Builds and validates the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`DiffRequest::validate`] rejects the request.
+    #[inline]
+    pub fn build(self) -> Result<DiffRequest> {
+        let request = DiffRequest {
+            current_client_timestamp: self.current_client_timestamp.unwrap_or_else(Utc::now),
+            server_timestamp: self.server_timestamp.unwrap_or(DateTime::UNIX_EPOCH),
+            force_fetch: self.force_fetch,
+            account: self.account,
+            tag: self.tag,
+            merchant: self.merchant,
+            transaction: self.transaction,
+            reminder: self.reminder,
+            reminder_marker: self.reminder_marker,
+            budget: self.budget,
+            deletion: self.deletion,
+        };
+        request.validate()?;
+        Ok(request)
+    }
 }
 
 /// Response body from the `/v8/diff/` synchronization endpoint.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DiffResponse {
     /// New server timestamp to use for the next sync.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub server_timestamp: DateTime<Utc>,
     /// Updated instruments.
     #[serde(default)]
@@ -222,4 +433,80 @@ mod tests {
             DateTime::from_timestamp(200, 0).unwrap()
         );
     }
+
+    #[test]
+    fn builder_assembles_mixed_request() {
+        use crate::models::{
+            AccountId, InstrumentId, NaiveDate, TransactionId, TransferBuilder, UserId,
+        };
+
+        let transfer = TransferBuilder::new(
+            UserId::new(1_i64),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        )
+        .id(TransactionId::new("tx-1".to_owned()))
+        .source(
+            AccountId::new("a-1".to_owned()),
+            100.0,
+            InstrumentId::new(1_i32),
+        )
+        .destination(
+            AccountId::new("a-2".to_owned()),
+            100.0,
+            InstrumentId::new(1_i32),
+        )
+        .build()
+        .unwrap();
+        let stamp = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let req = DiffRequest::builder()
+            .server_timestamp(stamp)
+            .current_client_timestamp(stamp)
+            .transactions(vec![transfer])
+            .deletions(vec![Deletion {
+                id: "tag-old".to_owned(),
+                object: "tag".to_owned(),
+                stamp,
+                user: 1,
+            }])
+            .force_fetch_kinds(vec!["instrument".to_owned()])
+            .build()
+            .unwrap();
+        assert_eq!(req.server_timestamp, stamp);
+        assert_eq!(req.current_client_timestamp, stamp);
+        assert_eq!(req.transaction.len(), 1);
+        assert_eq!(req.deletion.len(), 1);
+        assert_eq!(req.force_fetch, vec!["instrument".to_owned()]);
+        assert!(req.account.is_empty());
+    }
+
+    #[test]
+    fn sync_only_request_has_no_changes() {
+        let req = DiffRequest::sync_only(DateTime::UNIX_EPOCH, DateTime::UNIX_EPOCH);
+        assert!(!req.has_changes());
+        let with_fetch = DiffRequest::builder()
+            .force_fetch_kinds(vec!["tag".to_owned()])
+            .build()
+            .unwrap();
+        assert!(with_fetch.has_changes());
+    }
+
+    #[test]
+    fn builder_defaults_server_timestamp_to_epoch() {
+        let req = DiffRequest::builder().build().unwrap();
+        assert_eq!(req.server_timestamp, DateTime::UNIX_EPOCH);
+    }
+
+    #[test]
+    fn builder_rejects_deletion_without_id() {
+        let err = DiffRequest::builder()
+            .deletions(vec![Deletion {
+                id: String::new(),
+                object: "tag".to_owned(),
+                stamp: DateTime::UNIX_EPOCH,
+                user: 1,
+            }])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ZenMoneyError::Validation(_)));
+    }
 }
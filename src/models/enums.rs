@@ -3,7 +3,11 @@
 use serde::{Deserialize, Serialize};
 
 /// Type of a financial account.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Variants are ordered as declared, which is the order used by
+/// [`super::sort_accounts_for_display`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum AccountType {
     /// Physical cash.
@@ -26,6 +30,7 @@ pub enum AccountType {
 
 /// Time interval unit used for reminders and account offsets.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum Interval {
     /// Daily interval.
@@ -43,6 +48,7 @@ pub enum Interval {
 /// A subset of [`Interval`] — only month and year are valid for payoff
 /// schedules.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum PayoffInterval {
     /// Monthly payoff.
@@ -53,6 +59,7 @@ pub enum PayoffInterval {
 
 /// State of a reminder marker instance.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum ReminderMarkerState {
     /// Scheduled but not yet executed.
@@ -0,0 +1,187 @@
+//! Spreadsheet-friendly CSV export of transactions.
+//!
+//! Unlike `to_zenmoney_csv`, which mirrors the official export layout,
+//! this writes a compact comma-separated table with the columns `date`,
+//! `payee`, `outcome`, `income`, `tags`, `comment`, and `account`.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use super::{Account, AccountId, Tag, TagId, Transaction};
+use crate::error::{Result, ZenMoneyError};
+
+/// Header row of the export.
+const HEADER: [&str; 7] = [
+    "date", "payee", "outcome", "income", "tags", "comment", "account",
+];
+/// Separator between tag titles in the `tags` column.
+const TAG_SEPARATOR: &str = ";";
+
+/// Writes transactions as CSV, one row per transaction after a header.
+///
+/// Tags and the account are written by title, falling back to the raw ID
+/// when missing from `accounts` or `tags`. The account is the outcome
+/// account for expenses and transfers and the income account otherwise.
+/// Fields containing commas, quotes, or line breaks are quoted.
+///
+/// # Errors
+///
+/// Returns [`ZenMoneyError::Csv`] if writing fails.
+#[inline]
+pub fn to_csv<W: Write>(
+    writer: W,
+    transactions: &[Transaction],
+    accounts: &[Account],
+    tags: &[Tag],
+) -> Result<()> {
+    let account_titles: HashMap<&AccountId, &str> = accounts
+        .iter()
+        .map(|acc| (&acc.id, acc.title.as_str()))
+        .collect();
+    let tag_titles: HashMap<&TagId, &str> = tags
+        .iter()
+        .map(|tag| (&tag.id, tag.title.as_str()))
+        .collect();
+
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(HEADER)?;
+    for tx in transactions {
+        let tag_list: Vec<&str> = tx
+            .tag
+            .iter()
+            .flatten()
+            .map(|id| tag_titles.get(id).copied().unwrap_or_else(|| id.as_inner()))
+            .collect();
+        let account = if tx.outcome > 0.0_f64 || tx.income == 0.0_f64 {
+            &tx.outcome_account
+        } else {
+            &tx.income_account
+        };
+        let account_title = account_titles
+            .get(account)
+            .copied()
+            .unwrap_or_else(|| account.as_inner());
+        csv.write_record([
+            tx.date.to_string().as_str(),
+            tx.payee.as_deref().unwrap_or_default(),
+            tx.outcome.to_string().as_str(),
+            tx.income.to_string().as_str(),
+            tag_list.join(TAG_SEPARATOR).as_str(),
+            tx.comment.as_deref().unwrap_or_default(),
+            account_title,
+        ])?;
+    }
+    csv.flush().map_err(|err| ZenMoneyError::Csv(err.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate};
+
+    use super::*;
+    use crate::models::{AccountType, InstrumentId, TransactionBuilder, UserId};
+
+    /// Creates a minimal account.
+    fn account(id: &str, title: &str) -> Account {
+        Account {
+            id: AccountId::new(id.to_owned()),
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            user: UserId::new(1),
+            role: None,
+            instrument: Some(InstrumentId::new(1)),
+            company: None,
+            kind: AccountType::Cash,
+            title: title.to_owned(),
+            sync_id: None,
+            balance: None,
+            start_balance: None,
+            credit_limit: None,
+            in_balance: true,
+            savings: None,
+            enable_correction: false,
+            enable_sms: false,
+            archive: false,
+            capitalization: None,
+            percent: None,
+            start_date: None,
+            end_date_offset: None,
+            end_date_offset_interval: None,
+            payoff_step: None,
+            payoff_interval: None,
+            balance_correction_type: None,
+            private: None,
+        }
+    }
+
+    /// Creates a minimal tag.
+    fn tag(id: &str, title: &str) -> Tag {
+        Tag {
+            id: TagId::new(id.to_owned()),
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            user: UserId::new(1),
+            title: title.to_owned(),
+            parent: None,
+            icon: None,
+            picture: None,
+            color: None,
+            show_income: false,
+            show_outcome: true,
+            budget_income: false,
+            budget_outcome: false,
+            required: None,
+            static_id: None,
+            archive: None,
+        }
+    }
+
+    /// Creates an expense from account `a-1`.
+    fn expense(payee: &str, comment: &str, tags: &[&str]) -> Transaction {
+        tags.iter()
+            .fold(
+                TransactionBuilder::expense(AccountId::new("a-1".to_owned()), 12.5),
+                |builder, id| builder.tag(TagId::new((*id).to_owned())),
+            )
+            .user(UserId::new(1))
+            .instrument(InstrumentId::new(1))
+            .date(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())
+            .payee(payee)
+            .comment(comment)
+            .build()
+            .unwrap()
+    }
+
+    /// Runs [`to_csv`] into a string.
+    fn render(transactions: &[Transaction], accounts: &[Account], tags: &[Tag]) -> String {
+        let mut out = Vec::new();
+        to_csv(&mut out, transactions, accounts, tags).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn writes_header_and_resolved_titles() {
+        let csv = render(
+            &[expense("Cafe", "lunch", &["t-1", "t-2"])],
+            &[account("a-1", "Wallet")],
+            &[tag("t-1", "Food"), tag("t-2", "Work")],
+        );
+        assert_eq!(
+            csv,
+            "date,payee,outcome,income,tags,comment,account\n\
+             2024-03-01,Cafe,12.5,0,Food;Work,lunch,Wallet\n"
+        );
+    }
+
+    #[test]
+    fn quotes_commas_quotes_and_newlines() {
+        let csv = render(
+            &[expense("Smith, \"Jr\"", "two\nlines", &["t-9"])],
+            &[],
+            &[],
+        );
+        let row = csv.lines().skip(1).collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            row,
+            "2024-03-01,\"Smith, \"\"Jr\"\"\",12.5,0,t-9,\"two\nlines\",a-1"
+        );
+    }
+}
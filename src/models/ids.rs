@@ -12,7 +12,8 @@ macro_rules! define_copy_id {
         $name:ident($inner:ty)
     ) => {
         $(#[$meta])*
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
         #[serde(transparent)]
         pub struct $name($inner);
 
@@ -55,6 +56,24 @@ macro_rules! define_copy_id {
     };
 }
 
+/// Error returned when a string identifier is not a well-formed UUID.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("malformed UUID identifier: '{value}'")]
+pub struct IdError {
+    /// The rejected input.
+    pub value: String,
+}
+
+/// Returns `true` if `value` has the canonical hyphenated UUID shape
+/// (`8-4-4-4-12` hex digits, either case).
+fn is_uuid_shape(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(index, ch)| match index {
+            8 | 13 | 18 | 23 => ch == '-',
+            _ => ch.is_ascii_hexdigit(),
+        })
+}
+
 /// Macro to define a newtype ID wrapping a `String` inner type.
 macro_rules! define_string_id {
     (
@@ -62,7 +81,8 @@ macro_rules! define_string_id {
         $name:ident
     ) => {
         $(#[$meta])*
-        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
         #[serde(transparent)]
         pub struct $name(String);
 
@@ -74,6 +94,33 @@ macro_rules! define_string_id {
                 Self(value)
             }
 
+            /// Creates an identifier after checking it is a well-formed
+            /// UUID.
+            ///
+            /// Use this when creating new entities to catch malformed IDs
+            /// before they reach the server; [`Self::new`] and
+            /// deserialization accept any string.
+            ///
+            /// # Errors
+            ///
+            /// Returns [`IdError`] if `value` is not a hyphenated UUID.
+            #[inline]
+            pub fn parse<T: Into<String>>(value: T) -> Result<Self, IdError> {
+                let value = value.into();
+                if is_uuid_shape(&value) {
+                    Ok(Self(value))
+                } else {
+                    Err(IdError { value })
+                }
+            }
+
+            /// Returns `true` if the identifier is a well-formed UUID.
+            #[inline]
+            #[must_use]
+            pub fn is_valid_uuid(&self) -> bool {
+                is_uuid_shape(&self.0)
+            }
+
             /// Returns a reference to the inner string.
             #[inline]
             #[must_use]
@@ -181,6 +228,33 @@ mod tests {
         assert_eq!(deserialized, id);
     }
 
+    #[test]
+    fn parse_accepts_valid_uuid() {
+        let id = AccountId::parse("550E8400-e29b-41d4-a716-446655440000").unwrap();
+        assert!(id.is_valid_uuid());
+        assert_eq!(id.as_inner(), "550E8400-e29b-41d4-a716-446655440000");
+        assert!(TransactionId::parse("a1b2c3d4-0000-0000-0000-000000000000".to_owned()).is_ok());
+    }
+
+    #[test]
+    fn parse_rejects_malformed_uuid() {
+        for raw in [
+            "",
+            "acc-1",
+            "550e8400e29b41d4a716446655440000",
+            "550e8400-e29b-41d4-a716-44665544000g",
+            "550e8400-e29b-41d4-a716_446655440000",
+        ] {
+            let err = TransactionId::parse(raw).unwrap_err();
+            assert_eq!(err.value, raw);
+        }
+        assert!(!AccountId::new("acc-1".to_owned()).is_valid_uuid());
+        assert_eq!(
+            AccountId::parse("acc-1").unwrap_err().to_string(),
+            "malformed UUID identifier: 'acc-1'"
+        );
+    }
+
     #[test]
     fn account_id_serde_roundtrip() {
         let id = AccountId::new("550e8400-e29b-41d4-a716-446655440000".to_owned());
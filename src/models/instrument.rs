@@ -7,12 +7,14 @@ use super::InstrumentId;
 
 /// A currency or financial instrument with its exchange rate.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Instrument {
     /// Unique identifier.
     pub id: InstrumentId,
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// Full name of the instrument (e.g. "US Dollar").
     pub title: String,
@@ -0,0 +1,115 @@
+//! Merchant category code (MCC) descriptions.
+//!
+//! Card transactions carry an ISO 18245 merchant category code in
+//! [`Transaction::mcc`](super::Transaction::mcc). The table here maps the
+//! most common codes to short human-readable categories for display.
+
+/// Common MCCs and their descriptions, sorted by code.
+const MCC_TABLE: &[(i32, &str)] = &[
+    (4111, "Commuter transport"),
+    (4121, "Taxi"),
+    (4131, "Bus lines"),
+    (4511, "Airlines"),
+    (4722, "Travel agencies"),
+    (4784, "Tolls"),
+    (4812, "Telecom equipment"),
+    (4814, "Telecom services"),
+    (4816, "Internet services"),
+    (4829, "Money transfers"),
+    (4899, "Cable and streaming"),
+    (4900, "Utilities"),
+    (5045, "Computers and software"),
+    (5200, "Home supply stores"),
+    (5251, "Hardware stores"),
+    (5300, "Wholesale clubs"),
+    (5311, "Department stores"),
+    (5331, "Variety stores"),
+    (5399, "General merchandise"),
+    (5411, "Groceries"),
+    (5441, "Candy stores"),
+    (5462, "Bakeries"),
+    (5499, "Food stores"),
+    (5541, "Service stations"),
+    (5542, "Fuel"),
+    (5651, "Clothing"),
+    (5661, "Shoe stores"),
+    (5691, "Clothing"),
+    (5732, "Electronics"),
+    (5812, "Restaurants"),
+    (5813, "Bars"),
+    (5814, "Fast food"),
+    (5912, "Pharmacies"),
+    (5921, "Liquor stores"),
+    (5941, "Sporting goods"),
+    (5942, "Book stores"),
+    (5945, "Toy stores"),
+    (5977, "Cosmetics"),
+    (5992, "Florists"),
+    (5995, "Pet shops"),
+    (5999, "Retail"),
+    (6011, "ATM cash withdrawal"),
+    (6012, "Financial services"),
+    (6538, "Money transfers"),
+    (7011, "Hotels"),
+    (7230, "Beauty and barber shops"),
+    (7298, "Spas"),
+    (7512, "Car rental"),
+    (7523, "Parking"),
+    (7538, "Car service"),
+    (7832, "Cinemas"),
+    (7997, "Sports clubs"),
+    (8011, "Doctors"),
+    (8021, "Dentists"),
+    (8062, "Hospitals"),
+    (8099, "Medical services"),
+    (8220, "Universities"),
+    (8299, "Education"),
+    (9311, "Taxes"),
+    (9399, "Government services"),
+];
+
+/// Returns a short description of a merchant category code.
+///
+/// Only common codes are covered; unknown codes return `None`.
+///
+/// # Examples
+///
+/// ```
+/// use zenmoney_rs::models::mcc_description;
+///
+/// assert_eq!(mcc_description(5411), Some("Groceries"));
+/// assert_eq!(mcc_description(1), None);
+/// ```
+#[inline]
+#[must_use]
+pub fn mcc_description(code: i32) -> Option<&'static str> {
+    MCC_TABLE
+        .binary_search_by_key(&code, |entry| entry.0)
+        .ok()
+        .and_then(|index| MCC_TABLE.get(index))
+        .map(|entry| entry.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted_by_code() {
+        assert!(MCC_TABLE.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn known_codes_have_descriptions() {
+        assert_eq!(mcc_description(5411), Some("Groceries"));
+        assert_eq!(mcc_description(5812), Some("Restaurants"));
+        assert_eq!(mcc_description(4121), Some("Taxi"));
+        assert_eq!(mcc_description(9399), Some("Government services"));
+    }
+
+    #[test]
+    fn unknown_code_returns_none() {
+        assert_eq!(mcc_description(1234), None);
+        assert_eq!(mcc_description(-1), None);
+    }
+}
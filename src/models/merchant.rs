@@ -7,12 +7,14 @@ use super::{MerchantId, UserId};
 
 /// A merchant or payee associated with transactions.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Merchant {
     /// Unique identifier (UUID).
     pub id: MerchantId,
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// Owner user identifier.
     pub user: UserId,
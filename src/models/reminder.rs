@@ -1,18 +1,20 @@
 //! Recurring transaction reminder model.
 
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Days, Months, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::{AccountId, InstrumentId, Interval, MerchantId, ReminderId, TagId, UserId};
 
 /// A recurring transaction template.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Reminder {
     /// Unique identifier (UUID).
     pub id: ReminderId,
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// Owner user identifier.
     pub user: UserId,
@@ -50,6 +52,47 @@ pub struct Reminder {
     pub notify: bool,
 }
 
+impl Reminder {
+    /// Returns the dates this reminder falls on within `[from, to]`.
+    ///
+    /// Occurrences start at `start_date` and repeat every `step`
+    /// intervals (a missing or non-positive step counts as one) until
+    /// `end_date`, inclusive. A reminder without an interval occurs once.
+    /// Monthly and yearly occurrences keep the start day, clamped to the
+    /// end of shorter months. `points` is not interpreted.
+    #[inline]
+    #[must_use]
+    pub fn occurrences(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let last = self.end_date.map_or(to, |end_date| end_date.min(to));
+        let step = self
+            .step
+            .and_then(|step| u32::try_from(step).ok())
+            .filter(|&step| step > 0)
+            .unwrap_or(1);
+        let nth = |index: u32| -> Option<NaiveDate> {
+            let count = index.checked_mul(step)?;
+            match self.interval {
+                None => (index == 0).then_some(self.start_date),
+                Some(Interval::Day) => self
+                    .start_date
+                    .checked_add_days(Days::new(u64::from(count))),
+                Some(Interval::Week) => self
+                    .start_date
+                    .checked_add_days(Days::new(u64::from(count).checked_mul(7)?)),
+                Some(Interval::Month) => self.start_date.checked_add_months(Months::new(count)),
+                Some(Interval::Year) => self
+                    .start_date
+                    .checked_add_months(Months::new(count.checked_mul(12)?)),
+            }
+        };
+        (0_u32..)
+            .map_while(nth)
+            .take_while(|&date| date <= last)
+            .filter(|&date| date >= from)
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,6 +157,63 @@ mod tests {
         assert!(!reminder.notify);
     }
 
+    fn monthly_rent() -> Reminder {
+        serde_json::from_str(
+            r#"{
+                "id": "rem-001", "changed": 1700000000, "user": 123,
+                "incomeInstrument": 1, "incomeAccount": "acc-001", "income": 0,
+                "outcomeInstrument": 1, "outcomeAccount": "acc-001", "outcome": 5000.0,
+                "tag": null, "merchant": null, "payee": "Landlord", "comment": null,
+                "interval": "month", "step": 1, "points": [31],
+                "startDate": "2024-01-31", "endDate": null, "notify": true
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn monthly_occurrences_clamp_to_month_end() {
+        let reminder = monthly_rent();
+        assert_eq!(
+            reminder.occurrences(date(2024, 1, 1), date(2024, 4, 30)),
+            vec![
+                date(2024, 1, 31),
+                date(2024, 2, 29),
+                date(2024, 3, 31),
+                date(2024, 4, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn occurrences_respect_window_step_and_end_date() {
+        let mut reminder = monthly_rent();
+        reminder.interval = Some(Interval::Week);
+        reminder.step = Some(2);
+        reminder.end_date = Some(date(2024, 3, 13));
+        assert_eq!(
+            reminder.occurrences(date(2024, 2, 1), date(2024, 12, 31)),
+            vec![date(2024, 2, 14), date(2024, 2, 28), date(2024, 3, 13)]
+        );
+    }
+
+    #[test]
+    fn one_time_reminder_occurs_once() {
+        let mut reminder = monthly_rent();
+        reminder.interval = None;
+        let window = reminder.occurrences(date(2024, 1, 1), date(2024, 12, 31));
+        assert_eq!(window, vec![date(2024, 1, 31)]);
+        assert!(
+            reminder
+                .occurrences(date(2024, 2, 1), date(2024, 12, 31))
+                .is_empty()
+        );
+    }
+
     #[test]
     fn serialize_roundtrip() {
         let reminder = Reminder {
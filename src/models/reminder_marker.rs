@@ -10,12 +10,14 @@ use super::{
 
 /// A generated instance of a recurring reminder.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ReminderMarker {
     /// Unique identifier (UUID).
     pub id: ReminderMarkerId,
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// Owner user identifier.
     pub user: UserId,
@@ -0,0 +1,89 @@
+//! JSON Schema export of the entity models.
+//!
+//! Lets tools outside Rust (e.g. TypeScript type generators) stay in sync
+//! with the wire format. Field names are the camelCase names used by the
+//! API, and timestamps are Unix seconds.
+
+use schemars::{JsonSchema, schema_for};
+use serde_json::{Map, Value};
+
+use super::{
+    Account, Budget, Company, Country, Instrument, Merchant, Reminder, ReminderMarker, Tag,
+    Transaction, User,
+};
+
+/// Returns the JSON Schema of a single type as a JSON value.
+fn schema_of<T: JsonSchema>() -> Value {
+    schema_for!(T).to_value()
+}
+
+/// Returns JSON Schemas for every synced entity type, keyed by type name
+/// (`"Transaction"`, `"Account"`, ...).
+///
+/// # Examples
+///
+/// ```
+/// let schemas = zenmoney_rs::models::json_schema();
+/// assert!(schemas["Transaction"]["properties"]["incomeAccount"].is_object());
+/// ```
+#[inline]
+#[must_use]
+pub fn json_schema() -> Value {
+    let schemas: Map<String, Value> = [
+        ("Account", schema_of::<Account>()),
+        ("Budget", schema_of::<Budget>()),
+        ("Company", schema_of::<Company>()),
+        ("Country", schema_of::<Country>()),
+        ("Instrument", schema_of::<Instrument>()),
+        ("Merchant", schema_of::<Merchant>()),
+        ("Reminder", schema_of::<Reminder>()),
+        ("ReminderMarker", schema_of::<ReminderMarker>()),
+        ("Tag", schema_of::<Tag>()),
+        ("Transaction", schema_of::<Transaction>()),
+        ("User", schema_of::<User>()),
+    ]
+    .into_iter()
+    .map(|(name, schema)| (name.to_owned(), schema))
+    .collect();
+    Value::Object(schemas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transaction_schema_uses_camel_case_properties() {
+        let schemas = json_schema();
+        let properties = schemas["Transaction"]["properties"].as_object().unwrap();
+        for name in [
+            "id",
+            "changed",
+            "incomeInstrument",
+            "incomeAccount",
+            "outcomeAccount",
+            "originalPayee",
+            "reminderMarker",
+            "opIncomeInstrument",
+            "qrCode",
+        ] {
+            assert!(properties.contains_key(name), "missing property {name}");
+        }
+        assert!(!properties.contains_key("income_account"));
+        assert_eq!(properties["changed"]["type"], "integer");
+    }
+
+    #[test]
+    fn covers_every_entity() {
+        let schemas = json_schema();
+        let names: Vec<&str> = schemas
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(names.len(), 11);
+        assert!(names.contains(&"Account"));
+        assert!(names.contains(&"ReminderMarker"));
+    }
+}
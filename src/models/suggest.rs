@@ -6,6 +6,7 @@ use super::{MerchantId, TagId};
 
 /// A partial transaction used as input for the suggest endpoint.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SuggestRequest {
     /// Payee name to get suggestions for.
@@ -18,6 +19,7 @@ pub struct SuggestRequest {
 
 /// Suggestion result from the `/v8/suggest/` endpoint.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SuggestResponse {
     /// Normalized payee name.
@@ -3,12 +3,13 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::{TagId, UserId};
+use super::{Color, TagId, UserId};
 
 /// A transaction category tag with optional hierarchy.
 ///
 /// Tags can be nested one level deep via the `parent` field.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[allow(
     clippy::struct_excessive_bools,
@@ -19,6 +20,7 @@ pub struct Tag {
     pub id: TagId,
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// Owner user identifier.
     pub user: UserId,
@@ -30,8 +32,9 @@ pub struct Tag {
     pub icon: Option<String>,
     /// Picture URL.
     pub picture: Option<String>,
-    /// Color in ARGB format.
-    pub color: Option<i64>,
+    /// Display color.
+    #[cfg_attr(feature = "schema", schemars(with = "Option<i64>"))]
+    pub color: Option<Color>,
     /// Whether to show in income reports.
     pub show_income: bool,
     /// Whether to show in outcome reports.
@@ -75,6 +78,7 @@ mod tests {
         let tag: Tag = serde_json::from_str(json).unwrap();
         assert_eq!(tag.id, TagId::new("tag-001".to_owned()));
         assert_eq!(tag.title, "Groceries");
+        assert_eq!(tag.color, Some(Color::from_rgb(0, 255, 0)));
         assert!(tag.parent.is_none());
         assert!(tag.show_outcome);
         assert!(tag.required.is_none());
@@ -4,18 +4,22 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::{AccountId, InstrumentId, MerchantId, ReminderMarkerId, TagId, TransactionId, UserId};
+use crate::error::{Result, ZenMoneyError};
 
 /// A financial transaction between accounts.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
     /// Unique identifier (UUID).
     pub id: TransactionId,
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// Creation timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub created: DateTime<Utc>,
     /// Owner user identifier.
     pub user: UserId,
@@ -80,6 +84,529 @@ pub struct Transaction {
     pub viewed: Option<bool>,
 }
 
+/// A single balance change applied by a [`Transaction`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Posting {
+    /// Account whose balance changes.
+    pub account: AccountId,
+    /// Signed amount: negative when money leaves the account.
+    pub amount: f64,
+    /// Currency instrument of `amount`.
+    pub instrument: InstrumentId,
+}
+
+impl Transaction {
+    /// Returns the balance changes this transaction applies, outcome
+    /// side first.
+    ///
+    /// A plain expense or income yields a single posting; a transfer
+    /// yields two, possibly in different currencies. Zero amounts are
+    /// omitted.
+    #[inline]
+    #[must_use]
+    pub fn postings(&self) -> Vec<Posting> {
+        let mut postings = Vec::with_capacity(2);
+        if self.outcome > 0.0_f64 {
+            postings.push(Posting {
+                account: self.outcome_account.clone(),
+                amount: -self.outcome,
+                instrument: self.outcome_instrument,
+            });
+        }
+        if self.income > 0.0_f64 {
+            postings.push(Posting {
+                account: self.income_account.clone(),
+                amount: self.income,
+                instrument: self.income_instrument,
+            });
+        }
+        postings
+    }
+
+    /// Returns the best available timestamp of when the transaction
+    /// occurred.
+    ///
+    /// [`Self::date`] carries no time of day, so this returns
+    /// [`Self::created`]: the moment the transaction was recorded. That is
+    /// only an approximation; a transaction entered by hand or imported
+    /// later may have been created hours or days after it happened.
+    #[inline]
+    #[must_use]
+    pub const fn datetime(&self) -> DateTime<Utc> {
+        self.created
+    }
+
+    /// Returns a copy with personally identifying details removed, for
+    /// attaching to bug reports.
+    ///
+    /// Clears the payee, original payee, comment, coordinates, bank
+    /// transaction IDs, and QR code. IDs, accounts, amounts, and dates are
+    /// kept so the copy still reproduces the original structure.
+    #[inline]
+    #[must_use]
+    pub fn anonymized(&self) -> Self {
+        Self {
+            payee: None,
+            original_payee: None,
+            comment: None,
+            latitude: None,
+            longitude: None,
+            income_bank_id: None,
+            outcome_bank_id: None,
+            qr_code: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// One leg of a transfer: the account, amount, and currency it moves.
+#[derive(Debug, Clone)]
+struct TransferSide {
+    /// Account debited or credited.
+    account: AccountId,
+    /// Amount in the account's currency (>= 0).
+    amount: f64,
+    /// Currency instrument of `amount`.
+    instrument: InstrumentId,
+}
+
+/// Builder for a transfer [`Transaction`] between two accounts.
+///
+/// The source side fills the `outcome_*` fields and the destination side
+/// the `income_*` fields; the two amounts may be in different currencies.
+/// Like [`TransactionBuilder`], it assigns a fresh UUID unless
+/// [`Self::id`] is called.
+///
+/// # Examples
+///
+/// ```
+/// use zenmoney_rs::models::{AccountId, InstrumentId, NaiveDate, TransferBuilder, UserId};
+///
+/// let transfer = TransferBuilder::new(UserId::new(1), NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+///     .source(AccountId::new("rub".to_owned()), 9250.0, InstrumentId::new(1))
+///     .destination(AccountId::new("usd".to_owned()), 100.0, InstrumentId::new(2))
+///     .comment("Exchange")
+///     .build()
+///     .unwrap();
+/// assert!((transfer.outcome - 9250.0).abs() < f64::EPSILON);
+/// assert!(transfer.id.is_valid_uuid());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransferBuilder {
+    /// Explicit identifier; a fresh UUID is used when unset.
+    id: Option<TransactionId>,
+    /// Owner user identifier.
+    user: UserId,
+    /// Transfer date.
+    date: NaiveDate,
+    /// Source account side.
+    from: Option<TransferSide>,
+    /// Destination account side.
+    to: Option<TransferSide>,
+    /// Optional user comment.
+    comment: Option<String>,
+}
+
+impl TransferBuilder {
+    /// Starts a transfer with the given owner and date.
+    #[inline]
+    #[must_use]
+    pub const fn new(user: UserId, date: NaiveDate) -> Self {
+        Self {
+            id: None,
+            user,
+            date,
+            from: None,
+            to: None,
+            comment: None,
+        }
+    }
+
+    /// Uses `id` instead of a freshly generated UUID.
+    #[inline]
+    #[must_use]
+    pub fn id(mut self, id: TransactionId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the source account, the amount leaving it, and its currency.
+    #[inline]
+    #[must_use]
+    pub fn source(mut self, account: AccountId, amount: f64, instrument: InstrumentId) -> Self {
+        self.from = Some(TransferSide {
+            account,
+            amount,
+            instrument,
+        });
+        self
+    }
+
+    /// Sets the destination account, the amount arriving, and its currency.
+    #[inline]
+    #[must_use]
+    pub fn destination(
+        mut self,
+        account: AccountId,
+        amount: f64,
+        instrument: InstrumentId,
+    ) -> Self {
+        self.to = Some(TransferSide {
+            account,
+            amount,
+            instrument,
+        });
+        self
+    }
+
+    /// Sets the user comment.
+    #[inline]
+    #[must_use]
+    pub fn comment<T: Into<String>>(mut self, comment: T) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Builds the transfer transaction, stamped with the current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZenMoneyError::Validation`] if either side is missing or
+    /// an amount is negative or not finite.
+    #[inline]
+    pub fn build(self) -> Result<Transaction> {
+        let from = validate_side(self.from, "transfer source")?;
+        let to = validate_side(self.to, "transfer destination")?;
+        let id = self
+            .id
+            .unwrap_or_else(|| TransactionId::new(uuid::Uuid::new_v4().to_string()));
+        let mut transfer = new_transaction(id, self.user, self.date, from, to);
+        transfer.comment = self.comment;
+        Ok(transfer)
+    }
+}
+
+/// Builder for a new expense, income, or other [`Transaction`] to push.
+///
+/// Fills every optional field with `None`, assigns a fresh UUID unless
+/// [`Self::id`] is called, and stamps `changed`/`created` with the current
+/// time.
+///
+/// # Examples
+///
+/// ```
+/// use zenmoney_rs::models::{AccountId, InstrumentId, NaiveDate, TransactionBuilder, UserId};
+///
+/// let expense = TransactionBuilder::expense(AccountId::new("card".to_owned()), 350.0)
+///     .user(UserId::new(1))
+///     .date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+///     .instrument(InstrumentId::new(1))
+///     .payee("Shop")
+///     .build()
+///     .unwrap();
+/// assert!((expense.outcome - 350.0).abs() < f64::EPSILON);
+/// assert!(expense.id.is_valid_uuid());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBuilder {
+    /// Explicit identifier; a fresh UUID is used when unset.
+    id: Option<TransactionId>,
+    /// Owner user identifier.
+    user: Option<UserId>,
+    /// Transaction date.
+    date: Option<NaiveDate>,
+    /// Income destination account.
+    income_account: Option<AccountId>,
+    /// Income currency instrument.
+    income_instrument: Option<InstrumentId>,
+    /// Income amount.
+    income: f64,
+    /// Outcome source account.
+    outcome_account: Option<AccountId>,
+    /// Outcome currency instrument.
+    outcome_instrument: Option<InstrumentId>,
+    /// Outcome amount.
+    outcome: f64,
+    /// Payee name.
+    payee: Option<String>,
+    /// User comment.
+    comment: Option<String>,
+    /// Category tags.
+    tag: Option<Vec<TagId>>,
+    /// Associated merchant.
+    merchant: Option<MerchantId>,
+    /// Merchant Category Code.
+    mcc: Option<i32>,
+}
+
+impl TransactionBuilder {
+    /// Creates an empty builder; every required field must be set.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts an expense of `amount` from `account`.
+    ///
+    /// Both sides use the same account, as ZenMoney expects for
+    /// non-transfer transactions.
+    #[inline]
+    #[must_use]
+    pub fn expense(account: AccountId, amount: f64) -> Self {
+        Self::new()
+            .income_account(account.clone())
+            .outcome_account(account)
+            .outcome_amount(amount)
+    }
+
+    /// Starts an income of `amount` into `account`.
+    ///
+    /// Both sides use the same account, as ZenMoney expects for
+    /// non-transfer transactions.
+    #[inline]
+    #[must_use]
+    pub fn income(account: AccountId, amount: f64) -> Self {
+        Self::new()
+            .income_account(account.clone())
+            .outcome_account(account)
+            .income_amount(amount)
+    }
+
+    /// Uses `id` instead of a freshly generated UUID.
+    #[inline]
+    #[must_use]
+    pub fn id(mut self, id: TransactionId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the owner user (required).
+    #[inline]
+    #[must_use]
+    pub const fn user(mut self, user: UserId) -> Self {
+        self.user = Some(user);
+        self
+    }
+
+    /// Sets the transaction date (required).
+    #[inline]
+    #[must_use]
+    pub const fn date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// Sets the income and outcome instruments to the same currency.
+    #[inline]
+    #[must_use]
+    pub const fn instrument(mut self, instrument: InstrumentId) -> Self {
+        self.income_instrument = Some(instrument);
+        self.outcome_instrument = Some(instrument);
+        self
+    }
+
+    /// Sets the income destination account (required).
+    #[inline]
+    #[must_use]
+    pub fn income_account(mut self, account: AccountId) -> Self {
+        self.income_account = Some(account);
+        self
+    }
+
+    /// Sets the income currency instrument (required).
+    #[inline]
+    #[must_use]
+    pub const fn income_instrument(mut self, instrument: InstrumentId) -> Self {
+        self.income_instrument = Some(instrument);
+        self
+    }
+
+    /// Sets the income amount (defaults to zero).
+    #[inline]
+    #[must_use]
+    pub const fn income_amount(mut self, amount: f64) -> Self {
+        self.income = amount;
+        self
+    }
+
+    /// Sets the outcome source account (required).
+    #[inline]
+    #[must_use]
+    pub fn outcome_account(mut self, account: AccountId) -> Self {
+        self.outcome_account = Some(account);
+        self
+    }
+
+    /// Sets the outcome currency instrument (required).
+    #[inline]
+    #[must_use]
+    pub const fn outcome_instrument(mut self, instrument: InstrumentId) -> Self {
+        self.outcome_instrument = Some(instrument);
+        self
+    }
+
+    /// Sets the outcome amount (defaults to zero).
+    #[inline]
+    #[must_use]
+    pub const fn outcome_amount(mut self, amount: f64) -> Self {
+        self.outcome = amount;
+        self
+    }
+
+    /// Sets the payee name.
+    #[inline]
+    #[must_use]
+    pub fn payee<T: Into<String>>(mut self, payee: T) -> Self {
+        self.payee = Some(payee.into());
+        self
+    }
+
+    /// Sets the user comment.
+    #[inline]
+    #[must_use]
+    pub fn comment<T: Into<String>>(mut self, comment: T) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Adds a category tag; repeated calls add more tags.
+    #[inline]
+    #[must_use]
+    pub fn tag(mut self, id: TagId) -> Self {
+        self.tag.get_or_insert_with(Vec::new).push(id);
+        self
+    }
+
+    /// Sets the merchant.
+    #[inline]
+    #[must_use]
+    pub fn merchant(mut self, id: MerchantId) -> Self {
+        self.merchant = Some(id);
+        self
+    }
+
+    /// Sets the merchant category code.
+    #[inline]
+    #[must_use]
+    pub const fn mcc(mut self, code: i32) -> Self {
+        self.mcc = Some(code);
+        self
+    }
+
+    /// Builds the transaction, stamped with the current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZenMoneyError::Validation`] if a required field is
+    /// missing, an amount is negative or not finite, or both amounts are
+    /// zero.
+    #[inline]
+    pub fn build(self) -> Result<Transaction> {
+        let outcome = validate_side(
+            side(self.outcome_account, self.outcome, self.outcome_instrument),
+            "transaction outcome",
+        )?;
+        let income = validate_side(
+            side(self.income_account, self.income, self.income_instrument),
+            "transaction income",
+        )?;
+        if income.amount == 0.0_f64 && outcome.amount == 0.0_f64 {
+            return Err(ZenMoneyError::Validation(
+                "income and outcome cannot both be zero".to_owned(),
+            ));
+        }
+        let user = self
+            .user
+            .ok_or_else(|| ZenMoneyError::Validation("transaction user is not set".to_owned()))?;
+        let date = self
+            .date
+            .ok_or_else(|| ZenMoneyError::Validation("transaction date is not set".to_owned()))?;
+        let id = self
+            .id
+            .unwrap_or_else(|| TransactionId::new(uuid::Uuid::new_v4().to_string()));
+        let mut tx = new_transaction(id, user, date, outcome, income);
+        tx.payee = self.payee;
+        tx.comment = self.comment;
+        tx.tag = self.tag;
+        tx.merchant = self.merchant;
+        tx.mcc = self.mcc;
+        Ok(tx)
+    }
+}
+
+/// Combines an account, amount, and instrument into a side, if both the
+/// account and instrument are set.
+fn side(
+    account: Option<AccountId>,
+    amount: f64,
+    instrument: Option<InstrumentId>,
+) -> Option<TransferSide> {
+    Some(TransferSide {
+        account: account?,
+        amount,
+        instrument: instrument?,
+    })
+}
+
+/// Creates a non-deleted transaction moving `from` into `to`, with every
+/// optional field empty and both timestamps set to now.
+fn new_transaction(
+    id: TransactionId,
+    user: UserId,
+    date: NaiveDate,
+    from: TransferSide,
+    to: TransferSide,
+) -> Transaction {
+    let now = Utc::now();
+    Transaction {
+        id,
+        changed: now,
+        created: now,
+        user,
+        deleted: false,
+        hold: None,
+        income_instrument: to.instrument,
+        income_account: to.account,
+        income: to.amount,
+        outcome_instrument: from.instrument,
+        outcome_account: from.account,
+        outcome: from.amount,
+        tag: None,
+        merchant: None,
+        payee: None,
+        original_payee: None,
+        comment: None,
+        date,
+        mcc: None,
+        reminder_marker: None,
+        op_income: None,
+        op_income_instrument: None,
+        op_outcome: None,
+        op_outcome_instrument: None,
+        latitude: None,
+        longitude: None,
+        income_bank_id: None,
+        outcome_bank_id: None,
+        qr_code: None,
+        source: None,
+        viewed: None,
+    }
+}
+
+/// Checks that a transaction side is present and has a valid amount.
+fn validate_side(maybe_side: Option<TransferSide>, label: &str) -> Result<TransferSide> {
+    let side =
+        maybe_side.ok_or_else(|| ZenMoneyError::Validation(format!("{label} is not set")))?;
+    if !side.amount.is_finite() || side.amount < 0.0_f64 {
+        return Err(ZenMoneyError::Validation(format!(
+            "{label} amount must be a non-negative number, got {}",
+            side.amount
+        )));
+    }
+    Ok(side)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,4 +726,162 @@ mod tests {
         let deserialized: Transaction = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized, tx);
     }
+
+    fn transfer_builder(from_amount: f64, to_amount: f64, to_instrument: i32) -> TransferBuilder {
+        TransferBuilder::new(
+            UserId::new(1_i64),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        )
+        .source(
+            AccountId::new("acc-rub".to_owned()),
+            from_amount,
+            InstrumentId::new(1_i32),
+        )
+        .destination(
+            AccountId::new("acc-dst".to_owned()),
+            to_amount,
+            InstrumentId::new(to_instrument),
+        )
+    }
+
+    #[test]
+    fn transfer_builder_same_currency() {
+        let tx = transfer_builder(500.0, 500.0, 1_i32).build().unwrap();
+        assert_eq!(tx.outcome_account, AccountId::new("acc-rub".to_owned()));
+        assert_eq!(tx.income_account, AccountId::new("acc-dst".to_owned()));
+        assert_eq!(tx.income_instrument, tx.outcome_instrument);
+        assert!((tx.income - 500.0).abs() < f64::EPSILON);
+        assert!((tx.outcome - 500.0).abs() < f64::EPSILON);
+        assert!(!tx.deleted);
+        assert!(tx.id.is_valid_uuid());
+    }
+
+    #[test]
+    fn transfer_builder_keeps_explicit_id() {
+        let id = TransactionId::new("tx-t".to_owned());
+        let tx = transfer_builder(500.0, 500.0, 1_i32)
+            .id(id.clone())
+            .build()
+            .unwrap();
+        assert_eq!(tx.id, id);
+    }
+
+    #[test]
+    fn transfer_builder_cross_currency() {
+        let tx = transfer_builder(9250.0, 100.0, 2_i32)
+            .comment("Exchange")
+            .build()
+            .unwrap();
+        assert_eq!(tx.outcome_instrument, InstrumentId::new(1_i32));
+        assert_eq!(tx.income_instrument, InstrumentId::new(2_i32));
+        assert!((tx.outcome - 9250.0).abs() < f64::EPSILON);
+        assert!((tx.income - 100.0).abs() < f64::EPSILON);
+        assert_eq!(tx.comment.as_deref(), Some("Exchange"));
+    }
+
+    #[test]
+    fn postings_of_transfer_have_both_sides() {
+        let tx = transfer_builder(9250.0, 100.0, 2_i32).build().unwrap();
+        let postings = tx.postings();
+        assert_eq!(postings.len(), 2);
+        assert_eq!(postings[0].account, AccountId::new("acc-rub".to_owned()));
+        assert!((postings[0].amount + 9250.0).abs() < f64::EPSILON);
+        assert_eq!(postings[1].instrument, InstrumentId::new(2_i32));
+        assert!((postings[1].amount - 100.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn postings_of_expense_skip_zero_income() {
+        let mut tx = transfer_builder(300.0, 0.0, 1_i32).build().unwrap();
+        tx.income_account = tx.outcome_account.clone();
+        let postings = tx.postings();
+        assert_eq!(postings.len(), 1);
+        assert!((postings[0].amount + 300.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn datetime_uses_created_timestamp() {
+        let mut tx = transfer_builder(300.0, 0.0, 1_i32).build().unwrap();
+        let created = DateTime::from_timestamp(1_705_312_800, 0).unwrap();
+        tx.created = created;
+        assert_eq!(tx.datetime(), created);
+    }
+
+    #[test]
+    fn transfer_builder_rejects_negative_amount() {
+        let err = transfer_builder(-1.0, 100.0, 1_i32).build().unwrap_err();
+        assert!(matches!(err, ZenMoneyError::Validation(_)));
+    }
+
+    #[test]
+    fn transfer_builder_requires_both_sides() {
+        let err = TransferBuilder::new(
+            UserId::new(1_i64),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        )
+        .source(
+            AccountId::new("acc-rub".to_owned()),
+            10.0,
+            InstrumentId::new(1_i32),
+        )
+        .build()
+        .unwrap_err();
+        assert!(matches!(err, ZenMoneyError::Validation(_)));
+    }
+
+    /// Creates a single-currency expense builder with all required fields.
+    fn expense_builder(amount: f64) -> TransactionBuilder {
+        TransactionBuilder::expense(AccountId::new("acc-card".to_owned()), amount)
+            .user(UserId::new(1_i64))
+            .date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+            .instrument(InstrumentId::new(1_i32))
+    }
+
+    #[test]
+    fn transaction_builder_fills_defaults() {
+        let tx = expense_builder(350.0)
+            .payee("Shop")
+            .tag(TagId::new("tag-food".to_owned()))
+            .mcc(5411)
+            .build()
+            .unwrap();
+        assert!(tx.id.is_valid_uuid());
+        assert!(!tx.deleted);
+        assert_eq!(tx.changed, tx.created);
+        assert_eq!(tx.income_account, tx.outcome_account);
+        assert!((tx.outcome - 350.0).abs() < f64::EPSILON);
+        assert!(tx.income.abs() < f64::EPSILON);
+        assert_eq!(tx.payee.as_deref(), Some("Shop"));
+        assert_eq!(tx.tag, Some(vec![TagId::new("tag-food".to_owned())]));
+        assert_eq!(tx.mcc, Some(5411));
+        assert!(tx.merchant.is_none());
+
+        let other = expense_builder(350.0).build().unwrap();
+        assert_ne!(other.id, tx.id);
+        let fixed = expense_builder(1.0)
+            .id(TransactionId::new("tx-fixed".to_owned()))
+            .build()
+            .unwrap();
+        assert_eq!(fixed.id, TransactionId::new("tx-fixed".to_owned()));
+    }
+
+    #[test]
+    fn transaction_builder_validates() {
+        let zero = expense_builder(0.0).build().unwrap_err();
+        assert!(matches!(zero, ZenMoneyError::Validation(_)));
+        let negative = expense_builder(-5.0).build().unwrap_err();
+        assert!(matches!(negative, ZenMoneyError::Validation(_)));
+        let no_user = TransactionBuilder::income(AccountId::new("acc".to_owned()), 10.0)
+            .date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+            .instrument(InstrumentId::new(1_i32))
+            .build()
+            .unwrap_err();
+        assert!(matches!(no_user, ZenMoneyError::Validation(_)));
+        let no_instrument = TransactionBuilder::income(AccountId::new("acc".to_owned()), 10.0)
+            .user(UserId::new(1_i64))
+            .date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+            .build()
+            .unwrap_err();
+        assert!(matches!(no_instrument, ZenMoneyError::Validation(_)));
+    }
 }
@@ -7,12 +7,14 @@ use super::{InstrumentId, UserId};
 
 /// A ZenMoney user account.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct User {
     /// Unique identifier.
     pub id: UserId,
     /// Last modification timestamp.
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "schema", schemars(with = "i64"))]
     pub changed: DateTime<Utc>,
     /// User login (email or username).
     pub login: Option<String>,
@@ -37,6 +39,7 @@ pub struct User {
     pub month_start_day: Option<i32>,
     /// Subscription paid-until timestamp.
     #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<i64>"))]
     pub paid_till: Option<DateTime<Utc>>,
     /// Balance planning mode.
     #[serde(default)]
@@ -49,9 +52,32 @@ pub struct User {
     pub subscription: Option<String>,
     /// Subscription renewal timestamp.
     #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<i64>"))]
     pub subscription_renewal_date: Option<DateTime<Utc>>,
 }
 
+impl User {
+    /// Returns whether the subscription is paid at `now`.
+    ///
+    /// Returns `None` if the server did not report a `paid_till` date.
+    #[inline]
+    #[must_use]
+    pub fn is_subscription_active(&self, now: DateTime<Utc>) -> Option<bool> {
+        self.paid_till.map(|paid_till| paid_till > now)
+    }
+
+    /// Returns the number of whole days from `now` until the subscription
+    /// renews, negative if the renewal date has passed.
+    ///
+    /// Returns `None` if the server did not report a renewal date.
+    #[inline]
+    #[must_use]
+    pub fn days_until_renewal(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.subscription_renewal_date
+            .map(|renewal| renewal.signed_duration_since(now).num_days())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,6 +153,53 @@ mod tests {
         );
     }
 
+    fn subscribed_user(paid_till: i64, renewal: i64) -> User {
+        let json = format!(
+            r#"{{
+                "id": 1,
+                "changed": 1700000000,
+                "login": null,
+                "currency": 1,
+                "parent": null,
+                "paidTill": {paid_till},
+                "subscriptionRenewalDate": {renewal}
+            }}"#
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn active_subscription_renews_in_days() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let ten_days = 10 * 86_400;
+        let user = subscribed_user(1_700_000_000 + ten_days, 1_700_000_000 + ten_days);
+        assert_eq!(user.is_subscription_active(now), Some(true));
+        assert_eq!(user.days_until_renewal(now), Some(10));
+    }
+
+    #[test]
+    fn expired_subscription() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let user = subscribed_user(1_700_000_000 - 86_400, 1_700_000_000 - 3 * 86_400);
+        assert_eq!(user.is_subscription_active(now), Some(false));
+        assert_eq!(user.days_until_renewal(now), Some(-3));
+    }
+
+    #[test]
+    fn missing_subscription_fields_yield_none() {
+        let json = r#"{
+            "id": 1,
+            "changed": 1700000000,
+            "login": null,
+            "currency": 1,
+            "parent": null
+        }"#;
+        let user: User = serde_json::from_str(json).unwrap();
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(user.is_subscription_active(now), None);
+        assert_eq!(user.days_until_renewal(now), None);
+    }
+
     #[test]
     fn serialize_roundtrip() {
         let user = User {
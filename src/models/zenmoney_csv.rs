@@ -0,0 +1,526 @@
+//! Conversion to and from the CSV layout of ZenMoney's own export.
+//!
+//! The web UI exports one row per transaction with the columns
+//! `date`, `categoryName`, `payee`, `comment`, `outcomeAccountName`,
+//! `outcome`, `outcomeCurrencyShortTitle`, `incomeAccountName`, `income`,
+//! `incomeCurrencyShortTitle`, `createdDate`, `changedDate`, and `qrCode`,
+//! separated by semicolons. Accounts, categories, and currencies are
+//! referred to by name, so both directions need the reference data in
+//! [`CsvReferences`].
+
+use std::io::{Read, Write};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Account, AccountId, Instrument, InstrumentId, Tag, TagId, Transaction, TransactionBuilder,
+    UserId,
+};
+use crate::error::{Result, ZenMoneyError};
+
+/// Field separator used by the official export.
+const DELIMITER: u8 = b';';
+/// Separator between category names of a multi-tag transaction.
+const CATEGORY_SEPARATOR: &str = ", ";
+/// Format of the `date` column.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Format of the `createdDate` and `changedDate` columns.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Entities that CSV rows refer to by name.
+#[derive(Debug, Clone, Copy)]
+pub struct CsvReferences<'refs> {
+    /// Accounts, matched by title.
+    pub accounts: &'refs [Account],
+    /// Categories, matched by title.
+    pub tags: &'refs [Tag],
+    /// Currencies, matched by short title (e.g. `RUB`).
+    pub instruments: &'refs [Instrument],
+}
+
+/// One row of the official export.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CsvRow {
+    /// Transaction date.
+    date: String,
+    /// Category titles joined by [`CATEGORY_SEPARATOR`].
+    #[serde(default)]
+    category_name: String,
+    /// Payee name.
+    #[serde(default)]
+    payee: String,
+    /// User comment.
+    #[serde(default)]
+    comment: String,
+    /// Title of the account money leaves.
+    #[serde(default)]
+    outcome_account_name: String,
+    /// Amount leaving the outcome account.
+    #[serde(default)]
+    outcome: String,
+    /// Short title of the outcome currency.
+    #[serde(default)]
+    outcome_currency_short_title: String,
+    /// Title of the account money arrives in.
+    #[serde(default)]
+    income_account_name: String,
+    /// Amount arriving in the income account.
+    #[serde(default)]
+    income: String,
+    /// Short title of the income currency.
+    #[serde(default)]
+    income_currency_short_title: String,
+    /// Creation time.
+    #[serde(default)]
+    created_date: String,
+    /// Last modification time.
+    #[serde(default)]
+    changed_date: String,
+    /// QR code data.
+    #[serde(default)]
+    qr_code: String,
+}
+
+/// Writes `transactions` in the layout of ZenMoney's CSV export.
+///
+/// A side (account, amount, currency) is left empty when its amount is
+/// zero, as in the official export.
+///
+/// # Errors
+///
+/// Returns [`ZenMoneyError::NotFound`] if a transaction references an
+/// account, tag, or instrument missing from `refs`, or an error if
+/// writing fails.
+#[inline]
+pub fn to_zenmoney_csv<W: Write>(
+    writer: W,
+    transactions: &[Transaction],
+    refs: CsvReferences<'_>,
+) -> Result<()> {
+    let mut csv = csv::WriterBuilder::new()
+        .delimiter(DELIMITER)
+        .quote_style(csv::QuoteStyle::Always)
+        .from_writer(writer);
+    for tx in transactions {
+        csv.serialize(row_of(tx, refs)?)?;
+    }
+    csv.flush().map_err(|err| ZenMoneyError::Csv(err.into()))
+}
+
+/// Parses transactions from ZenMoney's CSV export.
+///
+/// Columns are matched by header name, so `createdDate`, `changedDate`,
+/// and `qrCode` may be absent. Both `;` and `,` separators are accepted,
+/// and amounts may use a decimal comma. A row with only one side filled
+/// in gets the same account and currency on the other side, with a zero
+/// amount. Each transaction gets a fresh ID.
+///
+/// # Errors
+///
+/// Returns [`ZenMoneyError::Validation`] for malformed dates or amounts
+/// and rows without any account, [`ZenMoneyError::NotFound`] for names
+/// missing from `refs`, or an error if the input is not valid CSV.
+#[inline]
+pub fn from_zenmoney_csv<R: Read>(
+    mut reader: R,
+    refs: CsvReferences<'_>,
+    user: UserId,
+) -> Result<Vec<Transaction>> {
+    let mut input = String::new();
+    let _len = reader
+        .read_to_string(&mut input)
+        .map_err(|err| ZenMoneyError::Csv(err.into()))?;
+    let header = input.lines().next().unwrap_or_default();
+    let delimiter = if header.contains(char::from(DELIMITER)) {
+        DELIMITER
+    } else {
+        b','
+    };
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(input.as_bytes())
+        .deserialize::<CsvRow>()
+        .map(|row| transaction_of(row?, refs, user))
+        .collect()
+}
+
+/// Renders one transaction as an export row.
+fn row_of(tx: &Transaction, refs: CsvReferences<'_>) -> Result<CsvRow> {
+    let categories = tx
+        .tag
+        .iter()
+        .flatten()
+        .map(|id| tag_title(refs, id))
+        .collect::<Result<Vec<_>>>()?;
+    let both_zero = tx.outcome == 0.0_f64 && tx.income == 0.0_f64;
+    let (outcome_account_name, outcome, outcome_currency_short_title) =
+        if tx.outcome != 0.0_f64 || both_zero {
+            side_columns(refs, &tx.outcome_account, tx.outcome, tx.outcome_instrument)?
+        } else {
+            Default::default()
+        };
+    let (income_account_name, income, income_currency_short_title) = if tx.income == 0.0_f64 {
+        Default::default()
+    } else {
+        side_columns(refs, &tx.income_account, tx.income, tx.income_instrument)?
+    };
+    Ok(CsvRow {
+        date: tx.date.format(DATE_FORMAT).to_string(),
+        category_name: categories.join(CATEGORY_SEPARATOR),
+        payee: tx.payee.clone().unwrap_or_default(),
+        comment: tx.comment.clone().unwrap_or_default(),
+        outcome_account_name,
+        outcome,
+        outcome_currency_short_title,
+        income_account_name,
+        income,
+        income_currency_short_title,
+        created_date: tx.created.format(TIMESTAMP_FORMAT).to_string(),
+        changed_date: tx.changed.format(TIMESTAMP_FORMAT).to_string(),
+        qr_code: tx.qr_code.clone().unwrap_or_default(),
+    })
+}
+
+/// Renders the account name, amount, and currency of one side.
+fn side_columns(
+    refs: CsvReferences<'_>,
+    account: &AccountId,
+    amount: f64,
+    instrument: InstrumentId,
+) -> Result<(String, String, String)> {
+    let title = refs
+        .accounts
+        .iter()
+        .find(|acc| acc.id == *account)
+        .map(|acc| acc.title.clone())
+        .ok_or_else(|| not_found("account", account.to_string()))?;
+    let currency = refs
+        .instruments
+        .iter()
+        .find(|instr| instr.id == instrument)
+        .map(|instr| instr.short_title.clone())
+        .ok_or_else(|| not_found("instrument", instrument.to_string()))?;
+    Ok((title, amount.to_string(), currency))
+}
+
+/// Looks up the title of a tag by ID.
+fn tag_title(refs: CsvReferences<'_>, id: &TagId) -> Result<String> {
+    refs.tags
+        .iter()
+        .find(|tag| tag.id == *id)
+        .map(|tag| tag.title.clone())
+        .ok_or_else(|| not_found("tag", id.to_string()))
+}
+
+/// One side of a parsed row.
+struct ParsedSide {
+    /// Account of the side.
+    account: AccountId,
+    /// Amount, zero when the column was empty.
+    amount: f64,
+    /// Currency of the side.
+    instrument: InstrumentId,
+}
+
+/// Builds a transaction from one export row.
+fn transaction_of(row: CsvRow, refs: CsvReferences<'_>, user: UserId) -> Result<Transaction> {
+    let date = NaiveDate::parse_from_str(&row.date, DATE_FORMAT)
+        .map_err(|err| invalid("date", &row.date, &err))?;
+    let outcome_side = parse_side(
+        refs,
+        &row.outcome_account_name,
+        &row.outcome,
+        &row.outcome_currency_short_title,
+    )?;
+    let income_side = parse_side(
+        refs,
+        &row.income_account_name,
+        &row.income,
+        &row.income_currency_short_title,
+    )?;
+    let (outcome, income) = match (outcome_side, income_side) {
+        (Some(out), Some(inc)) => (out, inc),
+        (Some(out), None) => {
+            let inc = mirror(&out);
+            (out, inc)
+        }
+        (None, Some(inc)) => (mirror(&inc), inc),
+        (None, None) => {
+            return Err(ZenMoneyError::Validation(format!(
+                "CSV row dated {} has no account",
+                row.date
+            )));
+        }
+    };
+    let mut builder = TransactionBuilder::new()
+        .user(user)
+        .date(date)
+        .outcome_account(outcome.account)
+        .outcome_amount(outcome.amount)
+        .outcome_instrument(outcome.instrument)
+        .income_account(income.account)
+        .income_amount(income.amount)
+        .income_instrument(income.instrument);
+    for name in row
+        .category_name
+        .split(CATEGORY_SEPARATOR)
+        .filter(|name| !name.is_empty())
+    {
+        builder = builder.tag(tag_by_title(refs, name)?);
+    }
+    if !row.payee.is_empty() {
+        builder = builder.payee(row.payee);
+    }
+    if !row.comment.is_empty() {
+        builder = builder.comment(row.comment);
+    }
+    let mut tx = builder.build()?;
+    tx.created = parse_timestamp(&row.created_date, tx.created)?;
+    tx.changed = parse_timestamp(&row.changed_date, tx.changed)?;
+    tx.qr_code = Some(row.qr_code).filter(|code| !code.is_empty());
+    Ok(tx)
+}
+
+/// Parses one side of a row; `None` if its account column is empty.
+fn parse_side(
+    refs: CsvReferences<'_>,
+    account_name: &str,
+    amount: &str,
+    currency: &str,
+) -> Result<Option<ParsedSide>> {
+    if account_name.is_empty() {
+        return Ok(None);
+    }
+    let account = refs
+        .accounts
+        .iter()
+        .find(|acc| acc.title == account_name)
+        .ok_or_else(|| not_found("account", account_name.to_owned()))?;
+    let instrument = if currency.is_empty() {
+        account.instrument
+    } else {
+        refs.instruments
+            .iter()
+            .find(|instr| instr.short_title == currency)
+            .map(|instr| instr.id)
+    }
+    .ok_or_else(|| not_found("instrument", currency.to_owned()))?;
+    Ok(Some(ParsedSide {
+        account: account.id.clone(),
+        amount: parse_amount(amount)?,
+        instrument,
+    }))
+}
+
+/// Returns the zero-amount counterpart of a side.
+fn mirror(side: &ParsedSide) -> ParsedSide {
+    ParsedSide {
+        account: side.account.clone(),
+        amount: 0.0,
+        instrument: side.instrument,
+    }
+}
+
+/// Parses an amount, accepting a decimal comma, spaces as digit group
+/// separators, and an empty column as zero.
+fn parse_amount(text: &str) -> Result<f64> {
+    let normalized: String = text
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| if ch == ',' { '.' } else { ch })
+        .collect();
+    if normalized.is_empty() {
+        return Ok(0.0);
+    }
+    normalized
+        .parse()
+        .map_err(|err| invalid("amount", text, &err))
+}
+
+/// Parses a `createdDate`/`changedDate` column, falling back to
+/// `default` when it is empty.
+fn parse_timestamp(text: &str, default: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if text.is_empty() {
+        return Ok(default);
+    }
+    NaiveDateTime::parse_from_str(text, TIMESTAMP_FORMAT)
+        .map(|naive| naive.and_utc())
+        .map_err(|err| invalid("timestamp", text, &err))
+}
+
+/// Looks up a tag ID by title.
+fn tag_by_title(refs: CsvReferences<'_>, title: &str) -> Result<TagId> {
+    refs.tags
+        .iter()
+        .find(|tag| tag.title == title)
+        .map(|tag| tag.id.clone())
+        .ok_or_else(|| not_found("tag", title.to_owned()))
+}
+
+/// Error for a name or ID missing from the reference data.
+const fn not_found(entity: &'static str, id: String) -> ZenMoneyError {
+    ZenMoneyError::NotFound { entity, id }
+}
+
+/// Error for a column value that cannot be parsed.
+fn invalid(what: &str, text: &str, err: &dyn core::fmt::Display) -> ZenMoneyError {
+    ZenMoneyError::Validation(format!("invalid CSV {what} {text:?}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AccountType;
+
+    /// Creates a minimal account.
+    fn account(id: &str, title: &str) -> Account {
+        Account {
+            id: AccountId::new(id.to_owned()),
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            user: UserId::new(1),
+            role: None,
+            instrument: Some(InstrumentId::new(2)),
+            company: None,
+            kind: AccountType::Cash,
+            title: title.to_owned(),
+            sync_id: None,
+            balance: None,
+            start_balance: None,
+            credit_limit: None,
+            in_balance: true,
+            savings: None,
+            enable_correction: false,
+            enable_sms: false,
+            archive: false,
+            capitalization: None,
+            percent: None,
+            start_date: None,
+            end_date_offset: None,
+            end_date_offset_interval: None,
+            payoff_step: None,
+            payoff_interval: None,
+            balance_correction_type: None,
+            private: None,
+        }
+    }
+
+    /// Creates a minimal tag.
+    fn tag(id: &str, title: &str) -> Tag {
+        Tag {
+            id: TagId::new(id.to_owned()),
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            user: UserId::new(1),
+            title: title.to_owned(),
+            parent: None,
+            icon: None,
+            picture: None,
+            color: None,
+            show_income: false,
+            show_outcome: true,
+            budget_income: false,
+            budget_outcome: false,
+            required: None,
+            static_id: None,
+            archive: None,
+        }
+    }
+
+    /// Creates an instrument with the given short title.
+    fn instrument(id: i32, short_title: &str) -> Instrument {
+        Instrument {
+            id: InstrumentId::new(id),
+            title: short_title.to_owned(),
+            short_title: short_title.to_owned(),
+            symbol: short_title.to_owned(),
+            rate: 1.0,
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        }
+    }
+
+    /// Sample export with an expense, an income, and a transfer.
+    const SAMPLE: &str = "\
+\"date\";\"categoryName\";\"payee\";\"comment\";\"outcomeAccountName\";\"outcome\";\"outcomeCurrencyShortTitle\";\"incomeAccountName\";\"income\";\"incomeCurrencyShortTitle\";\"createdDate\";\"changedDate\";\"qrCode\"
+\"2024-01-15\";\"Food, Cafe\";\"Coffee House\";\"latte\";\"Cash\";\"350,50\";\"RUB\";\"\";\"\";\"\";\"2024-01-15 09:30:00\";\"2024-01-15 09:31:00\";\"\"
+\"2024-01-20\";\"\";\"Employer\";\"\";\"\";\"\";\"\";\"Card\";\"100 000\";\"RUB\";\"\";\"\";\"\"
+\"2024-01-21\";\"\";\"\";\"\";\"Card\";\"9250\";\"RUB\";\"Wallet\";\"100\";\"USD\";\"\";\"\";\"\"
+";
+
+    #[test]
+    fn parses_official_export_rows() {
+        let accounts = [
+            account("a-cash", "Cash"),
+            account("a-card", "Card"),
+            account("a-usd", "Wallet"),
+        ];
+        let tags = [tag("t-food", "Food"), tag("t-cafe", "Cafe")];
+        let instruments = [instrument(2, "RUB"), instrument(3, "USD")];
+        let refs = CsvReferences {
+            accounts: &accounts,
+            tags: &tags,
+            instruments: &instruments,
+        };
+        let parsed = from_zenmoney_csv(SAMPLE.as_bytes(), refs, UserId::new(1)).unwrap();
+        assert_eq!(parsed.len(), 3);
+
+        let expense = &parsed[0];
+        assert_eq!(expense.date, NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        assert_eq!(
+            expense.tag,
+            Some(vec![
+                TagId::new("t-food".to_owned()),
+                TagId::new("t-cafe".to_owned())
+            ])
+        );
+        assert_eq!(expense.payee.as_deref(), Some("Coffee House"));
+        assert_eq!(expense.comment.as_deref(), Some("latte"));
+        assert!((expense.outcome - 350.5).abs() < f64::EPSILON);
+        assert_eq!(expense.income_account, expense.outcome_account);
+        assert_eq!(
+            expense.created,
+            DateTime::from_timestamp(1_705_311_000, 0).unwrap()
+        );
+
+        let income = &parsed[1];
+        assert!((income.income - 100_000.0).abs() < f64::EPSILON);
+        assert!(income.outcome.abs() < f64::EPSILON);
+        assert_eq!(income.outcome_account, AccountId::new("a-card".to_owned()));
+
+        let transfer = &parsed[2];
+        assert_eq!(transfer.income_instrument, InstrumentId::new(3));
+        assert_eq!(transfer.outcome_instrument, InstrumentId::new(2));
+
+        let mut written = Vec::new();
+        to_zenmoney_csv(&mut written, &parsed, refs).unwrap();
+        let reparsed = from_zenmoney_csv(written.as_slice(), refs, UserId::new(1)).unwrap();
+        assert_eq!(reparsed.len(), parsed.len());
+        for (before, after) in parsed.iter().zip(&reparsed) {
+            let restamped = Transaction {
+                id: before.id.clone(),
+                created: before.created,
+                changed: before.changed,
+                ..after.clone()
+            };
+            assert_eq!(restamped, *before);
+        }
+        assert_eq!(reparsed[0].created, parsed[0].created);
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        let refs = CsvReferences {
+            accounts: &[],
+            tags: &[],
+            instruments: &[],
+        };
+        let err = from_zenmoney_csv(SAMPLE.as_bytes(), refs, UserId::new(1)).unwrap_err();
+        assert!(matches!(
+            err,
+            ZenMoneyError::NotFound {
+                entity: "account",
+                ..
+            }
+        ));
+    }
+}
@@ -4,13 +4,23 @@
 //! (blocking) traits via a shared macro, mirroring the client generation
 //! pattern in [`crate::client`].
 
+mod cached;
+mod change_log;
+#[cfg(feature = "async")]
+mod dyn_storage;
 #[cfg(feature = "storage-file")]
 mod file;
 mod memory;
+mod snapshot;
 
+pub use cached::CachedStorage;
+pub use change_log::{Change, ChangeOperation, EntityKind};
+#[cfg(feature = "async")]
+pub use dyn_storage::{BoxFuture, DynStorage};
 #[cfg(feature = "storage-file")]
-pub use file::FileStorage;
-pub use memory::InMemoryStorage;
+pub use file::{FileStorage, QuarantinedFile, RepairReport};
+pub use memory::{InMemoryStorage, InMemoryStorageBuilder};
+pub use snapshot::{EntityDiff, Snapshot, SnapshotDiff};
 
 /// Generates a storage trait (async or blocking) with all entity methods.
 ///
@@ -27,6 +37,7 @@ macro_rules! define_storage {
         #[doc = $trait_doc]
         pub trait $trait_name: core::fmt::Debug + Send + Sync {
             define_storage!(@methods async_mode);
+            define_storage!(@provided async_mode);
         }
     };
     (
@@ -37,6 +48,7 @@ macro_rules! define_storage {
         #[doc = $trait_doc]
         pub trait $trait_name: core::fmt::Debug + Send + Sync {
             define_storage!(@methods blocking);
+            define_storage!(@provided blocking);
         }
     };
 
@@ -161,6 +173,336 @@ macro_rules! define_storage {
             -> Result<()>);
     };
 
+    // ── Provided methods (default implementations) ──────────────────
+    (@provided blocking) => {
+        /// Returns transactions whose payee, original payee, or comment
+        /// contains `query` (case-insensitive).
+        ///
+        /// The default implementation is a linear scan over
+        /// [`Self::transactions`]. Backends with a native full-text index
+        /// should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to read.
+        #[inline]
+        fn search_transactions(&self, query: &str) -> Result<Vec<Transaction>> {
+            let needle = query.to_lowercase();
+            Ok(self
+                .transactions()?
+                .into_iter()
+                .filter(|tx| super::matches_search_query(tx, &needle))
+                .collect())
+        }
+
+        /// Returns transactions dated within `[from, to]`, inclusive.
+        ///
+        /// Deleted transactions are included. The default implementation
+        /// filters [`Self::transactions`]; backends that can query by date
+        /// directly should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to read.
+        #[inline]
+        fn transactions_in_range(
+            &self,
+            from: NaiveDate,
+            to: NaiveDate,
+        ) -> Result<Vec<Transaction>> {
+            Ok(self
+                .transactions()?
+                .into_iter()
+                .filter(|tx| (from..=to).contains(&tx.date))
+                .collect())
+        }
+
+        /// Returns up to `limit` transactions ordered by `(date, id)` that
+        /// come strictly after `cursor`, or from the start if `cursor` is
+        /// `None`.
+        ///
+        /// Pass the date and ID of the last transaction of a page as the
+        /// cursor for the next one; unlike offsets, cursors stay stable
+        /// when earlier transactions are added or removed. Deleted
+        /// transactions are included. The default implementation sorts
+        /// [`Self::transactions`]; backends with an index on `(date, id)`
+        /// should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to read.
+        #[inline]
+        fn transactions_after(
+            &self,
+            cursor: Option<(NaiveDate, TransactionId)>,
+            limit: usize,
+        ) -> Result<Vec<Transaction>> {
+            Ok(super::transactions_page_after(
+                self.transactions()?,
+                cursor.as_ref(),
+                limit,
+            ))
+        }
+
+        /// Returns `true` if nothing has been stored yet: no server
+        /// timestamp and no entities of any type.
+        ///
+        /// The default implementation reads each collection in turn and
+        /// stops at the first non-empty one. Backends that can answer
+        /// more cheaply should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to read.
+        #[inline]
+        fn is_empty(&self) -> Result<bool> {
+            Ok(self.server_timestamp()?.is_none()
+                && self.accounts()?.is_empty()
+                && self.transactions()?.is_empty()
+                && self.tags()?.is_empty()
+                && self.merchants()?.is_empty()
+                && self.instruments()?.is_empty()
+                && self.companies()?.is_empty()
+                && self.countries()?.is_empty()
+                && self.users()?.is_empty()
+                && self.reminders()?.is_empty()
+                && self.reminder_markers()?.is_empty()
+                && self.budgets()?.is_empty())
+        }
+
+        /// Inserts or updates every entity in `batch`, each matched by ID
+        /// within its type.
+        ///
+        /// `batch.server_timestamp` is ignored. The default implementation
+        /// calls the per-type upsert methods in turn; backends that can
+        /// apply all types under one lock or transaction should override
+        /// it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to write.
+        #[inline]
+        fn upsert_batch(&self, batch: Snapshot) -> Result<()> {
+            self.upsert_accounts(batch.accounts)?;
+            self.upsert_transactions(batch.transactions)?;
+            self.upsert_tags(batch.tags)?;
+            self.upsert_merchants(batch.merchants)?;
+            self.upsert_instruments(batch.instruments)?;
+            self.upsert_companies(batch.companies)?;
+            self.upsert_countries(batch.countries)?;
+            self.upsert_users(batch.users)?;
+            self.upsert_reminders(batch.reminders)?;
+            self.upsert_reminder_markers(batch.reminder_markers)?;
+            self.upsert_budgets(batch.budgets)
+        }
+
+        /// Replaces all stored data with `snapshot`, including the server
+        /// timestamp.
+        ///
+        /// The default implementation clears storage and then upserts each
+        /// collection in turn, so concurrent readers may observe
+        /// intermediate states. Backends that can swap everything under one
+        /// lock or transaction should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to write.
+        #[inline]
+        fn apply_snapshot(&self, snapshot: Snapshot) -> Result<()> {
+            self.clear()?;
+            self.upsert_accounts(snapshot.accounts)?;
+            self.upsert_transactions(snapshot.transactions)?;
+            self.upsert_tags(snapshot.tags)?;
+            self.upsert_merchants(snapshot.merchants)?;
+            self.upsert_instruments(snapshot.instruments)?;
+            self.upsert_companies(snapshot.companies)?;
+            self.upsert_countries(snapshot.countries)?;
+            self.upsert_users(snapshot.users)?;
+            self.upsert_reminders(snapshot.reminders)?;
+            self.upsert_reminder_markers(snapshot.reminder_markers)?;
+            self.upsert_budgets(snapshot.budgets)?;
+            if let Some(timestamp) = snapshot.server_timestamp {
+                self.set_server_timestamp(timestamp)?;
+            }
+            Ok(())
+        }
+    };
+    (@provided async_mode) => {
+        /// Returns transactions whose payee, original payee, or comment
+        /// contains `query` (case-insensitive).
+        ///
+        /// The default implementation is a linear scan over
+        /// [`Self::transactions`]. Backends with a native full-text index
+        /// should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to read.
+        #[inline]
+        fn search_transactions(&self, query: &str)
+            -> impl core::future::Future<Output = Result<Vec<Transaction>>> + Send {
+            let needle = query.to_lowercase();
+            let transactions = self.transactions();
+            async move {
+                Ok(transactions
+                    .await?
+                    .into_iter()
+                    .filter(|tx| super::matches_search_query(tx, &needle))
+                    .collect())
+            }
+        }
+
+        /// Returns transactions dated within `[from, to]`, inclusive.
+        ///
+        /// Deleted transactions are included. The default implementation
+        /// filters [`Self::transactions`]; backends that can query by date
+        /// directly should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to read.
+        #[inline]
+        fn transactions_in_range(
+            &self,
+            from: NaiveDate,
+            to: NaiveDate,
+        ) -> impl core::future::Future<Output = Result<Vec<Transaction>>> + Send {
+            let transactions = self.transactions();
+            async move {
+                Ok(transactions
+                    .await?
+                    .into_iter()
+                    .filter(|tx| (from..=to).contains(&tx.date))
+                    .collect())
+            }
+        }
+
+        /// Returns up to `limit` transactions ordered by `(date, id)` that
+        /// come strictly after `cursor`, or from the start if `cursor` is
+        /// `None`.
+        ///
+        /// Pass the date and ID of the last transaction of a page as the
+        /// cursor for the next one; unlike offsets, cursors stay stable
+        /// when earlier transactions are added or removed. Deleted
+        /// transactions are included. The default implementation sorts
+        /// [`Self::transactions`]; backends with an index on `(date, id)`
+        /// should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to read.
+        #[inline]
+        fn transactions_after(
+            &self,
+            cursor: Option<(NaiveDate, TransactionId)>,
+            limit: usize,
+        ) -> impl core::future::Future<Output = Result<Vec<Transaction>>> + Send {
+            let transactions = self.transactions();
+            async move {
+                Ok(super::transactions_page_after(
+                    transactions.await?,
+                    cursor.as_ref(),
+                    limit,
+                ))
+            }
+        }
+
+        /// Returns `true` if nothing has been stored yet: no server
+        /// timestamp and no entities of any type.
+        ///
+        /// The default implementation reads each collection in turn and
+        /// stops at the first non-empty one. Backends that can answer
+        /// more cheaply should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to read.
+        #[inline]
+        fn is_empty(&self) -> impl core::future::Future<Output = Result<bool>> + Send {
+            async move {
+                Ok(self.server_timestamp().await?.is_none()
+                    && self.accounts().await?.is_empty()
+                    && self.transactions().await?.is_empty()
+                    && self.tags().await?.is_empty()
+                    && self.merchants().await?.is_empty()
+                    && self.instruments().await?.is_empty()
+                    && self.companies().await?.is_empty()
+                    && self.countries().await?.is_empty()
+                    && self.users().await?.is_empty()
+                    && self.reminders().await?.is_empty()
+                    && self.reminder_markers().await?.is_empty()
+                    && self.budgets().await?.is_empty())
+            }
+        }
+
+        /// Inserts or updates every entity in `batch`, each matched by ID
+        /// within its type.
+        ///
+        /// `batch.server_timestamp` is ignored. The default implementation
+        /// calls the per-type upsert methods in turn; backends that can
+        /// apply all types under one lock or transaction should override
+        /// it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to write.
+        #[inline]
+        fn upsert_batch(
+            &self,
+            batch: Snapshot,
+        ) -> impl core::future::Future<Output = Result<()>> + Send {
+            async move {
+                self.upsert_accounts(batch.accounts).await?;
+                self.upsert_transactions(batch.transactions).await?;
+                self.upsert_tags(batch.tags).await?;
+                self.upsert_merchants(batch.merchants).await?;
+                self.upsert_instruments(batch.instruments).await?;
+                self.upsert_companies(batch.companies).await?;
+                self.upsert_countries(batch.countries).await?;
+                self.upsert_users(batch.users).await?;
+                self.upsert_reminders(batch.reminders).await?;
+                self.upsert_reminder_markers(batch.reminder_markers).await?;
+                self.upsert_budgets(batch.budgets).await
+            }
+        }
+
+        /// Replaces all stored data with `snapshot`, including the server
+        /// timestamp.
+        ///
+        /// The default implementation clears storage and then upserts each
+        /// collection in turn, so concurrent readers may observe
+        /// intermediate states. Backends that can swap everything under one
+        /// lock or transaction should override it.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the storage backend fails to write.
+        #[inline]
+        fn apply_snapshot(
+            &self,
+            snapshot: Snapshot,
+        ) -> impl core::future::Future<Output = Result<()>> + Send {
+            async move {
+                self.clear().await?;
+                self.upsert_accounts(snapshot.accounts).await?;
+                self.upsert_transactions(snapshot.transactions).await?;
+                self.upsert_tags(snapshot.tags).await?;
+                self.upsert_merchants(snapshot.merchants).await?;
+                self.upsert_instruments(snapshot.instruments).await?;
+                self.upsert_companies(snapshot.companies).await?;
+                self.upsert_countries(snapshot.countries).await?;
+                self.upsert_users(snapshot.users).await?;
+                self.upsert_reminders(snapshot.reminders).await?;
+                self.upsert_reminder_markers(snapshot.reminder_markers).await?;
+                self.upsert_budgets(snapshot.budgets).await?;
+                if let Some(timestamp) = snapshot.server_timestamp {
+                    self.set_server_timestamp(timestamp).await?;
+                }
+                Ok(())
+            }
+        }
+    };
+
     // ── Blocking method renderer ────────────────────────────────────
     (@method blocking, $name:ident, $doc:expr,
      $($param:ident: $param_ty:ty,)* -> $ret:ty) => {
@@ -177,6 +519,30 @@ macro_rules! define_storage {
     };
 }
 
+/// Returns `true` if the transaction's payee, original payee, or comment
+/// contains `needle`, which must already be lowercased.
+fn matches_search_query(tx: &crate::models::Transaction, needle: &str) -> bool {
+    [&tx.payee, &tx.original_payee, &tx.comment]
+        .into_iter()
+        .flatten()
+        .any(|text| text.to_lowercase().contains(needle))
+}
+
+/// Sorts `transactions` by `(date, id)` and keeps the first `limit` that
+/// come strictly after `cursor`.
+fn transactions_page_after(
+    mut transactions: Vec<crate::models::Transaction>,
+    cursor: Option<&(crate::models::NaiveDate, crate::models::TransactionId)>,
+    limit: usize,
+) -> Vec<crate::models::Transaction> {
+    if let Some(after) = cursor {
+        transactions.retain(|tx| (tx.date, &tx.id) > (after.0, &after.1));
+    }
+    transactions.sort_unstable_by(|a, b| (a.date, &a.id).cmp(&(b.date, &b.id)));
+    transactions.truncate(limit);
+    transactions
+}
+
 #[cfg(feature = "async")]
 mod async_storage {
     //! Async storage trait definition.
@@ -184,11 +550,13 @@ mod async_storage {
     use crate::error::Result;
     use crate::models::{
         Account, AccountId, Budget, Company, CompanyId, Country, Instrument, InstrumentId,
-        Merchant, MerchantId, Reminder, ReminderId, ReminderMarker, ReminderMarkerId, Tag, TagId,
-        Transaction, TransactionId, User, UserId,
+        Merchant, MerchantId, NaiveDate, Reminder, ReminderId, ReminderMarker, ReminderMarkerId,
+        Tag, TagId, Transaction, TransactionId, User, UserId,
     };
     use chrono::{DateTime, Utc};
 
+    use super::Snapshot;
+
     define_storage! {
         trait_name: Storage,
         trait_doc: "Async storage backend for persisting synced ZenMoney data.\n\nAll methods take `&self` — implementations should use interior mutability\n(e.g. `Mutex`) for thread-safe mutation.",
@@ -203,11 +571,13 @@ mod blocking_storage {
     use crate::error::Result;
     use crate::models::{
         Account, AccountId, Budget, Company, CompanyId, Country, Instrument, InstrumentId,
-        Merchant, MerchantId, Reminder, ReminderId, ReminderMarker, ReminderMarkerId, Tag, TagId,
-        Transaction, TransactionId, User, UserId,
+        Merchant, MerchantId, NaiveDate, Reminder, ReminderId, ReminderMarker, ReminderMarkerId,
+        Tag, TagId, Transaction, TransactionId, User, UserId,
     };
     use chrono::{DateTime, Utc};
 
+    use super::Snapshot;
+
     define_storage! {
         trait_name: BlockingStorage,
         trait_doc: "Blocking storage backend for persisting synced ZenMoney data.\n\nAll methods take `&self` — implementations should use interior mutability\n(e.g. `Mutex`) for thread-safe mutation.",
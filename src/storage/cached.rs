@@ -0,0 +1,527 @@
+//! Read-through caching wrapper for storage backends.
+//!
+//! Provides [`CachedStorage`], which keeps each entity collection in
+//! memory after its first read so that repeated reads between syncs hit
+//! the backend only once.
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+
+use super::Snapshot;
+use crate::error::{Result, ZenMoneyError};
+use crate::models::{
+    Account, AccountId, Budget, Company, CompanyId, Country, Instrument, InstrumentId, Merchant,
+    MerchantId, Reminder, ReminderId, ReminderMarker, ReminderMarkerId, Tag, TagId, Transaction,
+    TransactionId, User, UserId,
+};
+
+/// Storage wrapper that caches every read collection in memory.
+///
+/// Each entity type is cached independently. A write (upsert or remove)
+/// to one type invalidates only that type's cache; [`clear`] and
+/// `apply_snapshot` drop all of them. Writes always go straight to the wrapped backend.
+///
+/// This type implements [`super::Storage`] (async) and
+/// [`super::BlockingStorage`] (blocking) whenever the wrapped backend
+/// does, so it can be passed to the high-level client builders in place
+/// of the backend itself.
+///
+/// Changes made to the backend behind the wrapper's back (for example by
+/// another process writing the same files) are not seen until
+/// [`CachedStorage::invalidate`] is called.
+///
+/// [`clear`]: super::Storage::clear
+///
+/// # Example
+///
+/// ```rust
+/// use zenmoney_rs::storage::{CachedStorage, InMemoryStorage};
+///
+/// let storage = CachedStorage::new(InMemoryStorage::new());
+/// // ZenMoneyBlocking::builder().storage(storage).token("...").build()
+/// ```
+#[derive(Debug)]
+pub struct CachedStorage<S> {
+    /// Wrapped backend.
+    inner: S,
+    /// Cached collections; `None` means not loaded yet.
+    cache: Mutex<Cache>,
+}
+
+/// Per-entity cache slots.
+#[derive(Debug, Default)]
+struct Cache {
+    /// Bumped on every eviction, so a read that raced with a write can
+    /// tell that the value it fetched may already be stale.
+    generation: u64,
+    /// Cached server timestamp.
+    #[allow(
+        clippy::option_option,
+        reason = "outer None means not loaded, inner None means never synced"
+    )]
+    server_timestamp: Option<Option<DateTime<Utc>>>,
+    /// Cached accounts.
+    accounts: Option<Vec<Account>>,
+    /// Cached transactions.
+    transactions: Option<Vec<Transaction>>,
+    /// Cached tags.
+    tags: Option<Vec<Tag>>,
+    /// Cached merchants.
+    merchants: Option<Vec<Merchant>>,
+    /// Cached instruments.
+    instruments: Option<Vec<Instrument>>,
+    /// Cached companies.
+    companies: Option<Vec<Company>>,
+    /// Cached countries.
+    countries: Option<Vec<Country>>,
+    /// Cached users.
+    users: Option<Vec<User>>,
+    /// Cached reminders.
+    reminders: Option<Vec<Reminder>>,
+    /// Cached reminder markers.
+    reminder_markers: Option<Vec<ReminderMarker>>,
+    /// Cached budgets.
+    budgets: Option<Vec<Budget>>,
+}
+
+impl Cache {
+    /// Creates a cache with nothing loaded.
+    const fn new() -> Self {
+        Self {
+            generation: 0,
+            server_timestamp: None,
+            accounts: None,
+            transactions: None,
+            tags: None,
+            merchants: None,
+            instruments: None,
+            companies: None,
+            countries: None,
+            users: None,
+            reminders: None,
+            reminder_markers: None,
+            budgets: None,
+        }
+    }
+}
+
+/// Selects one cache slot.
+type Slot<T> = fn(&mut Cache) -> &mut Option<T>;
+
+impl<S> CachedStorage<S> {
+    /// Wraps `inner` with an initially empty cache.
+    #[inline]
+    #[must_use]
+    pub const fn new(inner: S) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(Cache::new()),
+        }
+    }
+
+    /// Returns a reference to the wrapped backend.
+    ///
+    /// Writes made through this reference bypass the cache; call
+    /// [`Self::invalidate`] afterwards.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Unwraps the backend, discarding the cache.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Drops every cached collection so the next reads hit the backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZenMoneyError::Storage`] if the cache lock is poisoned.
+    #[inline]
+    pub fn invalidate(&self) -> Result<()> {
+        self.with_cache(|cache| {
+            let generation = cache.generation.wrapping_add(1);
+            *cache = Cache {
+                generation,
+                ..Cache::new()
+            };
+        })
+    }
+
+    /// Acquires the cache lock and applies a closure.
+    fn with_cache<R>(&self, f: impl FnOnce(&mut Cache) -> R) -> Result<R> {
+        let mut cache = self
+            .cache
+            .lock()
+            .map_err(|err| ZenMoneyError::Storage(err.to_string().into()))?;
+        Ok(f(&mut cache))
+    }
+
+    /// Returns a copy of the cached value in `slot`, if loaded, together
+    /// with the current cache generation.
+    fn cached<T: Clone>(&self, slot: Slot<T>) -> Result<(Option<T>, u64)> {
+        self.with_cache(|cache| (slot(cache).clone(), cache.generation))
+    }
+
+    /// Stores a value read from the backend in `slot`.
+    ///
+    /// `generation` is the one returned by the [`Self::cached`] miss that
+    /// preceded the read. If anything was evicted since, a write may have
+    /// landed after the read, so the value is dropped instead of cached.
+    fn fill<T>(&self, slot: Slot<T>, value: T, generation: u64) -> Result<()> {
+        self.with_cache(|cache| {
+            if cache.generation == generation {
+                *slot(cache) = Some(value);
+            }
+        })
+    }
+
+    /// Marks `slot` as not loaded.
+    fn evict<T>(&self, slot: Slot<T>) -> Result<()> {
+        self.with_cache(|cache| {
+            cache.generation = cache.generation.wrapping_add(1);
+            *slot(cache) = None;
+        })
+    }
+}
+
+/// Generates the cached read, upsert, and remove methods for each entity
+/// type in either blocking or async style.
+#[allow(unused_macro_rules, reason = "rules are feature-gated at call sites")]
+macro_rules! cached_entity_methods {
+    (blocking; $($field:ident: $ty:ty, $upsert:ident, $remove:ident, $id:ty;)+) => {
+        $(
+            #[inline]
+            fn $field(&self) -> Result<Vec<$ty>> {
+                let (cached, generation) = self.cached(|cache| &mut cache.$field)?;
+                if let Some(hit) = cached {
+                    return Ok(hit);
+                }
+                let fresh = self.inner.$field()?;
+                self.fill(|cache| &mut cache.$field, fresh.clone(), generation)?;
+                Ok(fresh)
+            }
+
+            #[inline]
+            fn $upsert(&self, items: Vec<$ty>) -> Result<()> {
+                self.inner.$upsert(items)?;
+                self.evict(|cache| &mut cache.$field)
+            }
+
+            #[inline]
+            fn $remove(&self, ids: &[$id]) -> Result<()> {
+                self.inner.$remove(ids)?;
+                self.evict(|cache| &mut cache.$field)
+            }
+        )+
+    };
+    (async_mode; $($field:ident: $ty:ty, $upsert:ident, $remove:ident, $id:ty;)+) => {
+        $(
+            #[inline]
+            async fn $field(&self) -> Result<Vec<$ty>> {
+                let (cached, generation) = self.cached(|cache| &mut cache.$field)?;
+                if let Some(hit) = cached {
+                    return Ok(hit);
+                }
+                let fresh = self.inner.$field().await?;
+                self.fill(|cache| &mut cache.$field, fresh.clone(), generation)?;
+                Ok(fresh)
+            }
+
+            #[inline]
+            async fn $upsert(&self, items: Vec<$ty>) -> Result<()> {
+                self.inner.$upsert(items).await?;
+                self.evict(|cache| &mut cache.$field)
+            }
+
+            #[inline]
+            async fn $remove(&self, ids: &[$id]) -> Result<()> {
+                self.inner.$remove(ids).await?;
+                self.evict(|cache| &mut cache.$field)
+            }
+        )+
+    };
+}
+
+// ── BlockingStorage implementation ──────────────────────────────────────
+
+#[cfg(feature = "blocking")]
+impl<S: super::BlockingStorage> super::BlockingStorage for CachedStorage<S> {
+    #[inline]
+    fn server_timestamp(&self) -> Result<Option<DateTime<Utc>>> {
+        let (cached, generation) = self.cached(|cache| &mut cache.server_timestamp)?;
+        if let Some(hit) = cached {
+            return Ok(hit);
+        }
+        let fresh = self.inner.server_timestamp()?;
+        self.fill(|cache| &mut cache.server_timestamp, fresh, generation)?;
+        Ok(fresh)
+    }
+
+    #[inline]
+    fn set_server_timestamp(&self, timestamp: DateTime<Utc>) -> Result<()> {
+        self.inner.set_server_timestamp(timestamp)?;
+        self.evict(|cache| &mut cache.server_timestamp)
+    }
+
+    cached_entity_methods! {
+        blocking;
+        accounts: Account, upsert_accounts, remove_accounts, AccountId;
+        transactions: Transaction, upsert_transactions, remove_transactions, TransactionId;
+        tags: Tag, upsert_tags, remove_tags, TagId;
+        merchants: Merchant, upsert_merchants, remove_merchants, MerchantId;
+        instruments: Instrument, upsert_instruments, remove_instruments, InstrumentId;
+        companies: Company, upsert_companies, remove_companies, CompanyId;
+        countries: Country, upsert_countries, remove_countries, i32;
+        users: User, upsert_users, remove_users, UserId;
+        reminders: Reminder, upsert_reminders, remove_reminders, ReminderId;
+        reminder_markers: ReminderMarker, upsert_reminder_markers, remove_reminder_markers, ReminderMarkerId;
+        budgets: Budget, upsert_budgets, remove_budgets, String;
+    }
+
+    #[inline]
+    fn clear(&self) -> Result<()> {
+        self.inner.clear()?;
+        self.invalidate()
+    }
+
+    #[inline]
+    fn apply_snapshot(&self, snapshot: Snapshot) -> Result<()> {
+        self.inner.apply_snapshot(snapshot)?;
+        self.invalidate()
+    }
+
+    #[inline]
+    fn upsert_batch(&self, batch: Snapshot) -> Result<()> {
+        self.inner.upsert_batch(batch)?;
+        self.invalidate()
+    }
+}
+
+// ── Storage (async) implementation ──────────────────────────────────────
+
+#[cfg(feature = "async")]
+impl<S: super::Storage> super::Storage for CachedStorage<S> {
+    #[inline]
+    async fn server_timestamp(&self) -> Result<Option<DateTime<Utc>>> {
+        let (cached, generation) = self.cached(|cache| &mut cache.server_timestamp)?;
+        if let Some(hit) = cached {
+            return Ok(hit);
+        }
+        let fresh = self.inner.server_timestamp().await?;
+        self.fill(|cache| &mut cache.server_timestamp, fresh, generation)?;
+        Ok(fresh)
+    }
+
+    #[inline]
+    async fn set_server_timestamp(&self, timestamp: DateTime<Utc>) -> Result<()> {
+        self.inner.set_server_timestamp(timestamp).await?;
+        self.evict(|cache| &mut cache.server_timestamp)
+    }
+
+    cached_entity_methods! {
+        async_mode;
+        accounts: Account, upsert_accounts, remove_accounts, AccountId;
+        transactions: Transaction, upsert_transactions, remove_transactions, TransactionId;
+        tags: Tag, upsert_tags, remove_tags, TagId;
+        merchants: Merchant, upsert_merchants, remove_merchants, MerchantId;
+        instruments: Instrument, upsert_instruments, remove_instruments, InstrumentId;
+        companies: Company, upsert_companies, remove_companies, CompanyId;
+        countries: Country, upsert_countries, remove_countries, i32;
+        users: User, upsert_users, remove_users, UserId;
+        reminders: Reminder, upsert_reminders, remove_reminders, ReminderId;
+        reminder_markers: ReminderMarker, upsert_reminder_markers, remove_reminder_markers, ReminderMarkerId;
+        budgets: Budget, upsert_budgets, remove_budgets, String;
+    }
+
+    #[inline]
+    async fn clear(&self) -> Result<()> {
+        self.inner.clear().await?;
+        self.invalidate()
+    }
+
+    #[inline]
+    async fn apply_snapshot(&self, snapshot: Snapshot) -> Result<()> {
+        self.inner.apply_snapshot(snapshot).await?;
+        self.invalidate()
+    }
+
+    #[inline]
+    async fn upsert_batch(&self, batch: Snapshot) -> Result<()> {
+        self.inner.upsert_batch(batch).await?;
+        self.invalidate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::InMemoryStorage;
+
+    fn ts() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    fn test_tag(id: &str) -> Tag {
+        Tag {
+            id: TagId::new(id.to_owned()),
+            changed: ts(),
+            user: UserId::new(1_i64),
+            title: format!("Tag {id}"),
+            parent: None,
+            icon: None,
+            picture: None,
+            color: None,
+            show_income: true,
+            show_outcome: true,
+            budget_income: false,
+            budget_outcome: false,
+            required: None,
+            static_id: None,
+            archive: None,
+        }
+    }
+
+    fn test_merchant(id: &str) -> Merchant {
+        Merchant {
+            id: MerchantId::new(id.to_owned()),
+            changed: ts(),
+            user: UserId::new(1_i64),
+            title: format!("Merchant {id}"),
+        }
+    }
+
+    #[test]
+    fn fill_after_eviction_is_discarded() {
+        let storage = CachedStorage::new(InMemoryStorage::new());
+        let (hit, generation) = storage.cached(|cache| &mut cache.tags).unwrap();
+        assert!(hit.is_none());
+
+        // A write lands between the backend read and the fill.
+        storage.evict(|cache| &mut cache.tags).unwrap();
+        storage
+            .fill(|cache| &mut cache.tags, vec![test_tag("stale")], generation)
+            .unwrap();
+        let (hit, generation) = storage.cached(|cache| &mut cache.tags).unwrap();
+        assert!(hit.is_none());
+
+        storage
+            .fill(|cache| &mut cache.tags, vec![test_tag("fresh")], generation)
+            .unwrap();
+        let (hit, _) = storage.cached(|cache| &mut cache.tags).unwrap();
+        assert_eq!(hit.unwrap()[0].id, TagId::new("fresh".to_owned()));
+    }
+
+    #[cfg(feature = "blocking")]
+    mod blocking {
+        use super::*;
+        use crate::storage::BlockingStorage;
+
+        #[test]
+        fn write_invalidates_only_its_own_collection() {
+            let storage = CachedStorage::new(InMemoryStorage::new());
+            assert!(storage.tags().unwrap().is_empty());
+            assert!(storage.merchants().unwrap().is_empty());
+
+            // Written behind the cache's back: invisible while tags are cached.
+            storage
+                .get_ref()
+                .upsert_tags(vec![test_tag("t-1")])
+                .unwrap();
+
+            storage
+                .upsert_merchants(vec![test_merchant("m-1")])
+                .unwrap();
+            assert_eq!(storage.merchants().unwrap().len(), 1);
+            assert!(storage.tags().unwrap().is_empty());
+
+            storage.upsert_tags(vec![test_tag("t-2")]).unwrap();
+            assert_eq!(storage.tags().unwrap().len(), 2);
+        }
+
+        #[test]
+        fn clear_and_invalidate_drop_cached_data() {
+            let storage = CachedStorage::new(InMemoryStorage::new());
+            storage.upsert_tags(vec![test_tag("t-1")]).unwrap();
+            assert_eq!(storage.tags().unwrap().len(), 1);
+            storage.clear().unwrap();
+            assert!(storage.tags().unwrap().is_empty());
+
+            storage
+                .get_ref()
+                .upsert_tags(vec![test_tag("t-2")])
+                .unwrap();
+            assert!(storage.tags().unwrap().is_empty());
+            storage.invalidate().unwrap();
+            assert_eq!(storage.tags().unwrap().len(), 1);
+        }
+
+        #[test]
+        fn server_timestamp_is_cached_and_invalidated() {
+            let storage = CachedStorage::new(InMemoryStorage::new());
+            assert!(storage.server_timestamp().unwrap().is_none());
+            storage.set_server_timestamp(ts()).unwrap();
+            assert_eq!(storage.server_timestamp().unwrap(), Some(ts()));
+        }
+
+        #[test]
+        fn apply_snapshot_replaces_cached_data() {
+            let storage = CachedStorage::new(InMemoryStorage::new());
+            storage.upsert_tags(vec![test_tag("t-1")]).unwrap();
+            assert_eq!(storage.tags().unwrap().len(), 1);
+
+            let snapshot = Snapshot {
+                merchants: vec![test_merchant("m-1")],
+                server_timestamp: Some(ts()),
+                ..Snapshot::default()
+            };
+            storage.apply_snapshot(snapshot).unwrap();
+            assert!(storage.tags().unwrap().is_empty());
+            assert_eq!(storage.merchants().unwrap().len(), 1);
+            assert_eq!(storage.server_timestamp().unwrap(), Some(ts()));
+        }
+    }
+
+    #[cfg(feature = "async")]
+    mod async_tests {
+        use super::*;
+        use crate::storage::Storage;
+
+        #[tokio::test]
+        async fn write_invalidates_only_its_own_collection() {
+            let storage = CachedStorage::new(InMemoryStorage::new());
+            assert!(storage.tags().await.unwrap().is_empty());
+            assert!(storage.merchants().await.unwrap().is_empty());
+
+            storage
+                .get_ref()
+                .upsert_tags(vec![test_tag("t-1")])
+                .await
+                .unwrap();
+
+            storage
+                .upsert_merchants(vec![test_merchant("m-1")])
+                .await
+                .unwrap();
+            assert_eq!(storage.merchants().await.unwrap().len(), 1);
+            assert!(storage.tags().await.unwrap().is_empty());
+
+            storage.upsert_tags(vec![test_tag("t-2")]).await.unwrap();
+            assert_eq!(storage.tags().await.unwrap().len(), 2);
+        }
+
+        #[tokio::test]
+        async fn clear_drops_cached_data() {
+            let storage = CachedStorage::new(InMemoryStorage::new());
+            storage.upsert_tags(vec![test_tag("t-1")]).await.unwrap();
+            assert_eq!(storage.tags().await.unwrap().len(), 1);
+            storage.clear().await.unwrap();
+            assert!(storage.tags().await.unwrap().is_empty());
+        }
+    }
+}
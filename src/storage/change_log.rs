@@ -0,0 +1,96 @@
+//! Bounded log of recent storage mutations.
+//!
+//! Backends that support it record one [`Change`] per upserted or removed
+//! entity so that a watching UI can apply deltas after a sync instead of
+//! re-reading every collection.
+
+use alloc::collections::VecDeque;
+
+/// Entity collection touched by a [`Change`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    /// An [`Account`](crate::models::Account).
+    Account,
+    /// A [`Transaction`](crate::models::Transaction).
+    Transaction,
+    /// A [`Tag`](crate::models::Tag).
+    Tag,
+    /// A [`Merchant`](crate::models::Merchant).
+    Merchant,
+    /// An [`Instrument`](crate::models::Instrument).
+    Instrument,
+    /// A [`Company`](crate::models::Company).
+    Company,
+    /// A [`Country`](crate::models::Country).
+    Country,
+    /// A [`User`](crate::models::User).
+    User,
+    /// A [`Reminder`](crate::models::Reminder).
+    Reminder,
+    /// A [`ReminderMarker`](crate::models::ReminderMarker).
+    ReminderMarker,
+    /// A [`Budget`](crate::models::Budget).
+    Budget,
+}
+
+/// Kind of mutation recorded in a [`Change`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeOperation {
+    /// The entity was inserted or replaced.
+    Upsert,
+    /// The entity was removed.
+    Remove,
+}
+
+/// A single recorded mutation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Change {
+    /// Collection the entity belongs to.
+    pub kind: EntityKind,
+    /// What happened to the entity.
+    pub operation: ChangeOperation,
+    /// Entity identifier in its string form.
+    pub id: String,
+}
+
+/// Ring buffer keeping the last `capacity` changes.
+///
+/// A capacity of zero disables recording.
+#[derive(Debug, Default)]
+pub(super) struct ChangeLog {
+    /// Maximum number of retained changes.
+    capacity: usize,
+    /// Retained changes, oldest first.
+    entries: VecDeque<Change>,
+}
+
+impl ChangeLog {
+    /// Creates a log that retains up to `capacity` changes.
+    pub(super) const fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Records a change, evicting the oldest one when full.
+    pub(super) fn record(&mut self, kind: EntityKind, operation: ChangeOperation, id: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            let _evicted = self.entries.pop_front();
+        }
+        self.entries.push_back(Change {
+            kind,
+            operation,
+            id,
+        });
+    }
+
+    /// Returns up to `count` most recent changes, oldest first.
+    pub(super) fn recent(&self, count: usize) -> Vec<Change> {
+        let skip = self.entries.len().saturating_sub(count);
+        self.entries.iter().skip(skip).cloned().collect()
+    }
+}
@@ -0,0 +1,217 @@
+//! Dyn-compatible counterpart of the async [`Storage`] trait.
+//!
+//! [`Storage`] returns `impl Future`, so it cannot be used as a trait
+//! object. [`DynStorage`] mirrors every method with boxed futures and is
+//! implemented for every [`Storage`] backend, so backends can be chosen at
+//! runtime and held as `Box<dyn DynStorage>`. That box implements
+//! [`Storage`] again and can be passed to the high-level client.
+
+use core::future::Future;
+use core::pin::Pin;
+
+use chrono::{DateTime, Utc};
+
+use super::{Snapshot, Storage};
+use crate::error::Result;
+use crate::models::{
+    Account, AccountId, Budget, Company, CompanyId, Country, Instrument, InstrumentId, Merchant,
+    MerchantId, NaiveDate, Reminder, ReminderId, ReminderMarker, ReminderMarkerId, Tag, TagId,
+    Transaction, TransactionId, User, UserId,
+};
+
+/// Boxed, `Send` future returned by [`DynStorage`] methods.
+pub type BoxFuture<'call, T> = Pin<Box<dyn Future<Output = T> + Send + 'call>>;
+
+/// Generates [`DynStorage`], its blanket implementation for [`Storage`]
+/// backends, and the [`Storage`] implementation for `Box<dyn DynStorage>`.
+///
+/// `owned` methods take only owned arguments; `borrowed` methods take a
+/// single reference that the returned future may hold on to.
+macro_rules! define_dyn_storage {
+    (
+        owned { $(fn $name:ident($($param:ident: $param_ty:ty),*) -> $ret:ty;)+ }
+        borrowed { $(fn $b_name:ident($b_param:ident: &$b_ty:ty) -> $b_ret:ty;)+ }
+    ) => {
+        /// Dyn-compatible form of [`Storage`], for choosing a backend at
+        /// runtime.
+        ///
+        /// Implemented for every [`Storage`] backend. Each method forwards
+        /// to the [`Storage`] method of the same name, including any
+        /// backend overrides of provided methods.
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use zenmoney_rs::storage::{DynStorage, InMemoryStorage};
+        ///
+        /// let storage: Box<dyn DynStorage> = Box::new(InMemoryStorage::new());
+        /// // ZenMoney::builder().storage(storage).token("...").build()
+        /// ```
+        pub trait DynStorage: core::fmt::Debug + Send + Sync {
+            $(
+                #[doc = concat!("Boxed form of [`Storage::", stringify!($name), "`].")]
+                ///
+                /// # Errors
+                ///
+                /// Returns an error if the storage backend fails.
+                fn $name(&self $(, $param: $param_ty)*) -> BoxFuture<'_, $ret>;
+            )+
+            $(
+                #[doc = concat!("Boxed form of [`Storage::", stringify!($b_name), "`].")]
+                ///
+                /// # Errors
+                ///
+                /// Returns an error if the storage backend fails.
+                fn $b_name<'call>(&'call self, $b_param: &'call $b_ty)
+                    -> BoxFuture<'call, $b_ret>;
+            )+
+        }
+
+        impl<S: Storage> DynStorage for S {
+            $(
+                #[inline]
+                fn $name(&self $(, $param: $param_ty)*) -> BoxFuture<'_, $ret> {
+                    Box::pin(Storage::$name(self $(, $param)*))
+                }
+            )+
+            $(
+                #[inline]
+                fn $b_name<'call>(&'call self, $b_param: &'call $b_ty)
+                    -> BoxFuture<'call, $b_ret> {
+                    Box::pin(Storage::$b_name(self, $b_param))
+                }
+            )+
+        }
+
+        impl Storage for Box<dyn DynStorage> {
+            $(
+                #[inline]
+                async fn $name(&self $(, $param: $param_ty)*) -> $ret {
+                    DynStorage::$name(&**self $(, $param)*).await
+                }
+            )+
+            $(
+                #[inline]
+                async fn $b_name(&self, $b_param: &$b_ty) -> $b_ret {
+                    DynStorage::$b_name(&**self, $b_param).await
+                }
+            )+
+        }
+    };
+}
+
+define_dyn_storage! {
+    owned {
+        fn server_timestamp() -> Result<Option<DateTime<Utc>>>;
+        fn set_server_timestamp(timestamp: DateTime<Utc>) -> Result<()>;
+        fn accounts() -> Result<Vec<Account>>;
+        fn transactions() -> Result<Vec<Transaction>>;
+        fn tags() -> Result<Vec<Tag>>;
+        fn merchants() -> Result<Vec<Merchant>>;
+        fn instruments() -> Result<Vec<Instrument>>;
+        fn companies() -> Result<Vec<Company>>;
+        fn countries() -> Result<Vec<Country>>;
+        fn users() -> Result<Vec<User>>;
+        fn reminders() -> Result<Vec<Reminder>>;
+        fn reminder_markers() -> Result<Vec<ReminderMarker>>;
+        fn budgets() -> Result<Vec<Budget>>;
+        fn upsert_accounts(items: Vec<Account>) -> Result<()>;
+        fn upsert_transactions(items: Vec<Transaction>) -> Result<()>;
+        fn upsert_tags(items: Vec<Tag>) -> Result<()>;
+        fn upsert_merchants(items: Vec<Merchant>) -> Result<()>;
+        fn upsert_instruments(items: Vec<Instrument>) -> Result<()>;
+        fn upsert_companies(items: Vec<Company>) -> Result<()>;
+        fn upsert_countries(items: Vec<Country>) -> Result<()>;
+        fn upsert_users(items: Vec<User>) -> Result<()>;
+        fn upsert_reminders(items: Vec<Reminder>) -> Result<()>;
+        fn upsert_reminder_markers(items: Vec<ReminderMarker>) -> Result<()>;
+        fn upsert_budgets(items: Vec<Budget>) -> Result<()>;
+        fn clear() -> Result<()>;
+        fn transactions_in_range(from: NaiveDate, to: NaiveDate) -> Result<Vec<Transaction>>;
+        fn transactions_after(
+            cursor: Option<(NaiveDate, TransactionId)>,
+            limit: usize
+        ) -> Result<Vec<Transaction>>;
+        fn is_empty() -> Result<bool>;
+        fn upsert_batch(batch: Snapshot) -> Result<()>;
+        fn apply_snapshot(snapshot: Snapshot) -> Result<()>;
+    }
+    borrowed {
+        fn remove_accounts(ids: &[AccountId]) -> Result<()>;
+        fn remove_transactions(ids: &[TransactionId]) -> Result<()>;
+        fn remove_tags(ids: &[TagId]) -> Result<()>;
+        fn remove_merchants(ids: &[MerchantId]) -> Result<()>;
+        fn remove_instruments(ids: &[InstrumentId]) -> Result<()>;
+        fn remove_companies(ids: &[CompanyId]) -> Result<()>;
+        fn remove_countries(ids: &[i32]) -> Result<()>;
+        fn remove_users(ids: &[UserId]) -> Result<()>;
+        fn remove_reminders(ids: &[ReminderId]) -> Result<()>;
+        fn remove_reminder_markers(ids: &[ReminderMarkerId]) -> Result<()>;
+        fn remove_budgets(ids: &[String]) -> Result<()>;
+        fn search_transactions(query: &str) -> Result<Vec<Transaction>>;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{CachedStorage, InMemoryStorage};
+
+    /// Creates a minimal test tag.
+    fn test_tag(id: &str) -> Tag {
+        Tag {
+            id: TagId::new(id.to_owned()),
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            user: UserId::new(1_i64),
+            title: id.to_owned(),
+            parent: None,
+            icon: None,
+            picture: None,
+            color: None,
+            show_income: true,
+            show_outcome: true,
+            budget_income: false,
+            budget_outcome: false,
+            required: None,
+            static_id: None,
+            archive: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn backends_are_interchangeable_behind_trait_object() {
+        let backends: Vec<Box<dyn DynStorage>> = vec![
+            Box::new(InMemoryStorage::new()),
+            Box::new(CachedStorage::new(InMemoryStorage::new())),
+        ];
+        for storage in &backends {
+            let storage: &dyn DynStorage = storage.as_ref();
+            assert!(storage.is_empty().await.unwrap());
+            storage
+                .upsert_tags(vec![test_tag("t-1"), test_tag("t-2")])
+                .await
+                .unwrap();
+            storage
+                .remove_tags(&[TagId::new("t-1".to_owned())])
+                .await
+                .unwrap();
+            let tags = storage.tags().await.unwrap();
+            assert_eq!(tags.len(), 1);
+            assert_eq!(tags[0].id, TagId::new("t-2".to_owned()));
+        }
+    }
+
+    #[tokio::test]
+    async fn boxed_backend_implements_storage() {
+        /// Reads tags through the static [`Storage`] trait.
+        async fn tag_count<S: Storage>(storage: &S) -> usize {
+            Storage::tags(storage).await.unwrap().len()
+        }
+
+        let storage: Box<dyn DynStorage> = Box::new(InMemoryStorage::new());
+        Storage::upsert_tags(&storage, vec![test_tag("t-1")])
+            .await
+            .unwrap();
+        assert_eq!(tag_count(&storage).await, 1);
+    }
+}
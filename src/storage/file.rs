@@ -4,14 +4,26 @@
 //! directory (default: `$XDG_DATA_HOME/zenmoney-rs/`).
 
 use core::hash::Hash;
+use core::time::Duration;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+#[cfg(feature = "encryption")]
+use std::io::Cursor;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use self::coalesce::{Slot, WriteBuffer, append_batch};
+#[cfg(feature = "encryption")]
+use self::crypto::FileCipher;
+#[cfg(feature = "storage-events")]
+use self::events::TransactionEvent;
+use self::stream::{ArrayStream, SharedLock};
+use super::Snapshot;
 use crate::error::{Result, ZenMoneyError};
 use crate::models::{
     Account, AccountId, Budget, Company, CompanyId, Country, Instrument, InstrumentId, Merchant,
@@ -19,6 +31,15 @@ use crate::models::{
     Transaction, TransactionId, User, UserId,
 };
 
+mod coalesce;
+#[cfg(feature = "encryption")]
+mod crypto;
+#[cfg(feature = "storage-events")]
+mod events;
+#[cfg(feature = "compression")]
+mod gzip;
+mod stream;
+
 /// Application name used for the XDG data directory.
 const APP_NAME: &str = "zenmoney-rs";
 
@@ -46,8 +67,38 @@ const REMINDERS_FILE: &str = "reminders.json";
 const REMINDER_MARKERS_FILE: &str = "reminder_markers.json";
 /// File name for budgets.
 const BUDGETS_FILE: &str = "budgets.json";
+/// Suffix of gzip-compressed entity files.
+#[cfg(feature = "compression")]
+const GZIP_SUFFIX: &str = ".gz";
+/// Append-only log of transaction mutations.
+#[cfg(feature = "storage-events")]
+const TRANSACTION_EVENTS_FILE: &str = "transactions_events.jsonl";
 /// Sentinel file used for cross-process file locking.
 const LOCK_FILE: &str = "storage.lock";
+/// Subdirectory holding automatic pre-clear backups.
+const BACKUP_DIR: &str = "backup";
+/// Subdirectory holding files moved aside by [`FileStorage::repair`].
+const CORRUPT_DIR: &str = "corrupt";
+/// Subdirectory staging the files of an in-progress
+/// [`FileStorage::restore`].
+const RESTORE_DIR: &str = "restoring";
+/// Every data file managed by the storage (entities plus metadata).
+const DATA_FILES: [&str; 12] = [
+    META_FILE,
+    ACCOUNTS_FILE,
+    TRANSACTIONS_FILE,
+    TAGS_FILE,
+    MERCHANTS_FILE,
+    INSTRUMENTS_FILE,
+    COMPANIES_FILE,
+    COUNTRIES_FILE,
+    USERS_FILE,
+    REMINDERS_FILE,
+    REMINDER_MARKERS_FILE,
+    BUDGETS_FILE,
+];
+/// Pause between `try_lock` attempts when a lock timeout is configured.
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Metadata stored alongside entity files.
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -57,6 +108,34 @@ struct Meta {
     server_timestamp: Option<i64>,
 }
 
+/// A data file that failed to parse and was moved aside by
+/// [`FileStorage::repair`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuarantinedFile {
+    /// Name of the data file (e.g. `transactions.json`).
+    pub file: String,
+    /// Where the unreadable contents were moved to.
+    pub moved_to: PathBuf,
+    /// Parse error that caused the quarantine.
+    pub error: String,
+}
+
+/// Outcome of [`FileStorage::repair`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// Data files that could not be parsed, in layout order.
+    pub quarantined: Vec<QuarantinedFile>,
+}
+
+impl RepairReport {
+    /// Returns `true` if every data file parsed and nothing was moved.
+    #[inline]
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.quarantined.is_empty()
+    }
+}
+
 /// File-backed storage that persists synced data as JSON files.
 ///
 /// Each entity type is stored in a separate `.json` file. A `meta.json`
@@ -70,13 +149,57 @@ struct Meta {
 /// [`std::fs::File::lock_shared`]).
 ///
 /// Read operations acquire a shared lock (allowing concurrent readers),
-/// while write operations acquire an exclusive lock.
+/// while write operations acquire an exclusive lock. By default a
+/// contended file lock blocks indefinitely; use
+/// [`FileStorage::lock_timeout`] to bound the wait.
+///
+/// # Write coalescing
+///
+/// With [`FileStorage::coalesce_writes`], upserts are buffered in memory
+/// and written together instead of rewriting a file per call. Reads
+/// through this instance see the buffered items; other processes and
+/// other instances do not until the buffer is flushed. Buffered upserts
+/// are lost if the process crashes or is killed before a flush.
+///
+/// # Transaction events
+///
+/// With the `storage-events` feature and
+/// `FileStorage::record_transaction_events`, every write to the
+/// transactions is also appended to `transactions_events.jsonl`, and
+/// `FileStorage::replay_transactions` rebuilds the collection from that
+/// log. The log is not a data file: `clear` and backups leave it alone.
+///
+/// # Encryption at rest
+///
+/// With the `encryption` feature, `FileStorage::new_encrypted` seals
+/// `meta.json` and every entity file with ChaCha20-Poly1305, each under a
+/// fresh random nonce stored at the start of the file. Backups and
+/// quarantined files stay encrypted. The `storage.lock` sentinel and the
+/// transaction event log are written in plaintext.
+///
+/// # Compression
+///
+/// With the `compression` feature, `FileStorage::with_compression`
+/// stores entity files gzip-compressed as `<name>.json.gz`. Whether a file
+/// is compressed is told by its extension, so stores written either way
+/// stay readable.
+///
+/// # Backups
+///
+/// [`FileStorage::backup`] copies the data files into a timestamped
+/// directory of your choice, and [`FileStorage::restore`] brings such a
+/// copy back, e.g. to undo a full sync that returned unexpected data.
+/// [`FileStorage::backup_before_clear`] does the same automatically under
+/// `backup/` whenever the storage is cleared.
 ///
 /// # File layout
 ///
 /// ```text
 /// <dir>/
 ///   storage.lock          (cross-process lock sentinel)
+///   backup/<id>/          (pre-clear backups, if enabled)
+///   corrupt/<id>/         (unparseable files moved aside by `repair`)
+///   restoring/            (staged files of an interrupted `restore`)
 ///   meta.json
 ///   accounts.json
 ///   transactions.json
@@ -89,6 +212,7 @@ struct Meta {
 ///   reminders.json
 ///   reminder_markers.json
 ///   budgets.json
+///   transactions_events.jsonl  (transaction event log, if enabled)
 /// ```
 #[derive(Debug)]
 pub struct FileStorage {
@@ -98,6 +222,21 @@ pub struct FileStorage {
     lock: Mutex<()>,
     /// Sentinel file for cross-process advisory locking.
     lock_file: fs::File,
+    /// Maximum wait for the file lock, or `None` to block indefinitely.
+    lock_timeout: Option<Duration>,
+    /// Whether `clear` copies the data files to `backup/` first.
+    backup_before_clear: bool,
+    /// Upserts buffered by write coalescing.
+    writes: WriteBuffer,
+    /// Whether transaction writes are appended to the event log.
+    #[cfg(feature = "storage-events")]
+    events_enabled: bool,
+    /// Cipher sealing the data files, or `None` to store plaintext JSON.
+    #[cfg(feature = "encryption")]
+    cipher: Option<FileCipher>,
+    /// Whether entity files are written gzip-compressed.
+    #[cfg(feature = "compression")]
+    compress: bool,
 }
 
 impl FileStorage {
@@ -125,9 +264,344 @@ impl FileStorage {
             dir,
             lock: Mutex::new(()),
             lock_file,
+            lock_timeout: None,
+            backup_before_clear: false,
+            writes: WriteBuffer::new(),
+            #[cfg(feature = "storage-events")]
+            events_enabled: false,
+            #[cfg(feature = "encryption")]
+            cipher: None,
+            #[cfg(feature = "compression")]
+            compress: false,
         })
     }
 
+    /// Creates a file storage that encrypts its data files with `key`.
+    ///
+    /// Every file written is sealed with ChaCha20-Poly1305 and every file
+    /// read is decrypted. The same key must be used to open the storage
+    /// again; existing plaintext files are not converted and fail to read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created or the lock
+    /// file cannot be opened.
+    #[cfg(feature = "encryption")]
+    #[inline]
+    pub fn new_encrypted(dir: PathBuf, key: [u8; 32]) -> Result<Self> {
+        let mut storage = Self::new(dir)?;
+        storage.cipher = Some(FileCipher::new(&key));
+        Ok(storage)
+    }
+
+    /// Makes `clear` (and therefore a full sync) copy the current data
+    /// files to `backup/<id>/` before deleting them.
+    ///
+    /// Use [`FileStorage::backups`] to list the saved backups and
+    /// [`FileStorage::restore_backup`] to bring one back.
+    #[inline]
+    #[must_use]
+    pub const fn backup_before_clear(mut self, enabled: bool) -> Self {
+        self.backup_before_clear = enabled;
+        self
+    }
+
+    /// Writes entity files gzip-compressed, as `<name>.json.gz`.
+    ///
+    /// Files are read in whichever form is on disk, preferring the one
+    /// matching this setting, so flipping it keeps existing data readable;
+    /// the next write of a file converts it and deletes the other form.
+    /// `meta.json` is never compressed.
+    #[cfg(feature = "compression")]
+    #[inline]
+    #[must_use]
+    pub const fn with_compression(mut self, enabled: bool) -> Self {
+        self.compress = enabled;
+        self
+    }
+
+    /// Buffers upserts in memory instead of writing them immediately.
+    ///
+    /// The buffer is written in one batch by [`FileStorage::flush`], when
+    /// the storage is dropped, and by the first upsert arriving
+    /// `max_delay` or more after the oldest buffered one. There is no
+    /// background timer: an idle buffer stays in memory until one of those
+    /// happens. Removals, `set_server_timestamp`, and streaming flush the
+    /// buffer first, so the stored server timestamp is never ahead of
+    /// the data on disk; `clear` and `apply_snapshot` discard it.
+    ///
+    /// Buffered upserts are not durable: they are lost if the process
+    /// exits without dropping the storage or crashes, and other processes
+    /// do not see them until they are flushed.
+    #[inline]
+    #[must_use]
+    pub const fn coalesce_writes(mut self, max_delay: Duration) -> Self {
+        self.writes.enable(max_delay);
+        self
+    }
+
+    /// Appends every write to the transactions to
+    /// `transactions_events.jsonl`.
+    ///
+    /// On an encrypted storage each line of the log is sealed on its own
+    /// with the storage key.
+    ///
+    /// Upserts, removals, `clear`, `apply_snapshot`,
+    /// [`FileStorage::restore_backup`], and a [`FileStorage::repair`] that
+    /// quarantines the transactions each append their events under the
+    /// same lock as the write itself. Coalesced upserts are logged when
+    /// they are flushed. Writes made before recording was enabled are not
+    /// in the log.
+    #[cfg(feature = "storage-events")]
+    #[inline]
+    #[must_use]
+    pub const fn record_transaction_events(mut self, enabled: bool) -> Self {
+        self.events_enabled = enabled;
+        self
+    }
+
+    /// Rebuilds the transactions by replaying `transactions_events.jsonl`
+    /// from the start, in no particular order.
+    ///
+    /// Buffered upserts are flushed first, so with
+    /// [`FileStorage::record_transaction_events`] enabled from the
+    /// beginning the result matches the stored transactions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock cannot be acquired, the log cannot be
+    /// read, or a line of it does not parse.
+    #[cfg(feature = "storage-events")]
+    #[inline]
+    pub fn replay_transactions(&self) -> Result<Vec<Transaction>> {
+        self.flush_pending()?;
+        self.with_shared_lock(|| {
+            events::replay(&self.path(TRANSACTION_EVENTS_FILE), |line| {
+                self.open_event_line(line)
+            })
+        })
+    }
+
+    /// Writes any upserts buffered by [`FileStorage::coalesce_writes`].
+    ///
+    /// Does nothing when coalescing is off or the buffer is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock cannot be acquired or a file cannot be
+    /// written. The buffered upserts are dropped in that case.
+    #[inline]
+    pub fn flush(&self) -> Result<()> {
+        self.flush_pending()
+    }
+
+    /// Streams stored transactions one at a time instead of loading the
+    /// whole file into memory.
+    ///
+    /// The shared lock is held until the iterator is dropped, so writers
+    /// (and any other call on this storage from the same thread) wait for
+    /// it. Parsing stops after the first error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock cannot be acquired or the file cannot
+    /// be opened. Malformed contents surface as items of the iterator.
+    #[inline]
+    pub fn transactions_streaming(&self) -> Result<impl Iterator<Item = Result<Transaction>> + '_> {
+        self.flush_pending()?;
+        let guard = self.lock.lock().map_err(|err| lock_poison_error(&err))?;
+        self.acquire_file_lock(LockMode::Shared)?;
+        let lock = SharedLock::new(guard, &self.lock_file);
+        Ok(ArrayStream::new(self.open_stream(TRANSACTIONS_FILE)?, lock))
+    }
+
+    /// Lists the IDs of the pre-clear backups, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup directory cannot be read.
+    #[inline]
+    pub fn backups(&self) -> Result<Vec<String>> {
+        self.with_shared_lock(|| {
+            let entries = match fs::read_dir(self.path(BACKUP_DIR)) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(err) => return Err(storage_io_error(err)),
+            };
+            let mut ids = Vec::new();
+            for maybe_entry in entries {
+                let entry = maybe_entry.map_err(storage_io_error)?;
+                if entry.file_type().map_err(storage_io_error)?.is_dir() {
+                    ids.push(entry.file_name().to_string_lossy().into_owned());
+                }
+            }
+            ids.sort_unstable();
+            Ok(ids)
+        })
+    }
+
+    /// Replaces the current data with the pre-clear backup `id`.
+    ///
+    /// Behaves like [`FileStorage::restore`] on `backup/<id>/`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `id` is not a known backup, a file in it does
+    /// not parse, or a file cannot be copied.
+    #[inline]
+    pub fn restore_backup(&self, id: &str) -> Result<()> {
+        let source = self.backup_path(id)?;
+        if !source.is_dir() {
+            return Err(ZenMoneyError::Storage(
+                format!("backup not found: {id}").into(),
+            ));
+        }
+        self.restore(&source)
+    }
+
+    /// Copies the data files into a new timestamped subdirectory of
+    /// `dest` and returns its path.
+    ///
+    /// Buffered upserts are flushed first, and the copy is taken under the
+    /// exclusive lock so it is a consistent snapshot. The `storage.lock`
+    /// sentinel, the transaction event log and earlier backups are not
+    /// copied. Encrypted or compressed files are copied as stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock cannot be acquired or a file cannot be
+    /// copied.
+    #[inline]
+    pub fn backup(&self, dest: &Path) -> Result<PathBuf> {
+        self.flush_pending()?;
+        self.with_exclusive_lock(|| {
+            let target = create_timestamped_dir(dest)?;
+            copy_data_files(&self.dir, &target)?;
+            tracing::debug!(backup = %target.display(), "backed up storage");
+            Ok(target)
+        })
+    }
+
+    /// Replaces the current data with the files in `src`, typically a
+    /// directory returned by [`FileStorage::backup`].
+    ///
+    /// Every file in `src` is checked to parse (and decrypt, for an
+    /// encrypted storage) before anything is overwritten. The files are
+    /// then staged under `restoring/` and renamed into place under the
+    /// exclusive lock, so other readers see either the old or the restored
+    /// data. Data files absent from `src` are removed, and buffered upserts
+    /// are discarded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `src` is not a directory, a file in it does not
+    /// parse, or a file cannot be copied. The current data is left
+    /// untouched if validation or staging fails.
+    #[inline]
+    pub fn restore(&self, src: &Path) -> Result<()> {
+        self.discard_pending()?;
+        self.with_exclusive_lock(|| {
+            if !src.is_dir() {
+                return Err(ZenMoneyError::Storage(
+                    format!("backup not found: {}", src.display()).into(),
+                ));
+            }
+            for (name, check) in self.check_files(src) {
+                check.map_err(|err| {
+                    ZenMoneyError::Storage(
+                        format!("cannot restore {name} from {}: {err}", src.display()).into(),
+                    )
+                })?;
+            }
+            let staging = self.path(RESTORE_DIR);
+            match fs::remove_dir_all(&staging) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(storage_io_error(err)),
+            }
+            fs::create_dir_all(&staging).map_err(storage_io_error)?;
+            copy_data_files(src, &staging)?;
+            self.remove_data_files()?;
+            move_data_files(&staging, &self.dir)?;
+            fs::remove_dir(&staging).map_err(storage_io_error)?;
+            #[cfg(feature = "storage-events")]
+            self.record_reset(self.read_entities(TRANSACTIONS_FILE)?)?;
+            Ok(())
+        })
+    }
+
+    /// Checks that every data file parses, moving unreadable ones to
+    /// `corrupt/<id>/` so the storage becomes usable again.
+    ///
+    /// A quarantined file is treated as empty from then on. Because its
+    /// entities are lost, the stored server timestamp is discarded as
+    /// well whenever anything was quarantined, so the next sync is a full
+    /// one and restores the data from the server. The returned report
+    /// lists what was moved and why.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read or moved. Parse failures
+    /// are reported, not returned.
+    #[inline]
+    pub fn repair(&self) -> Result<RepairReport> {
+        self.with_exclusive_lock(|| {
+            let checks = self.check_files(&self.dir);
+            let mut quarantine_dir: Option<PathBuf> = None;
+            let mut report = RepairReport::default();
+            for (name, check) in checks {
+                match check {
+                    Ok(()) => {}
+                    Err(ZenMoneyError::Serialization(err)) => {
+                        let dir = match quarantine_dir.take() {
+                            Some(dir) => dir,
+                            None => create_timestamped_dir(&self.path(CORRUPT_DIR))?,
+                        };
+                        let stored = self
+                            .stored_names(name)
+                            .into_iter()
+                            .find(|file| self.path(file).exists())
+                            .unwrap_or_else(|| name.to_owned());
+                        let moved_to = dir.join(&stored);
+                        quarantine_dir = Some(dir);
+                        fs::rename(self.path(&stored), &moved_to).map_err(storage_io_error)?;
+                        tracing::warn!(file = name, error = %err, "quarantined corrupt storage file");
+                        #[cfg(feature = "storage-events")]
+                        if name == TRANSACTIONS_FILE {
+                            self.record_events([TransactionEvent::Clear])?;
+                        }
+                        report.quarantined.push(QuarantinedFile {
+                            file: name.to_owned(),
+                            moved_to,
+                            error: err.to_string(),
+                        });
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            if !report.is_clean() {
+                match fs::remove_file(self.path(META_FILE)) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(storage_io_error(err)),
+                }
+            }
+            Ok(report)
+        })
+    }
+
+    /// Bounds how long operations wait for the cross-process file lock.
+    ///
+    /// Instead of blocking, the lock is polled with `try_lock` until
+    /// `timeout` elapses, after which the operation fails with
+    /// [`ZenMoneyError::LockTimeout`].
+    #[inline]
+    #[must_use]
+    pub const fn lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = Some(timeout);
+        self
+    }
+
     /// Returns the default XDG-compliant data directory for this application.
     ///
     /// On Linux: `$XDG_DATA_HOME/zenmoney-rs/` (typically
@@ -156,7 +630,7 @@ impl FileStorage {
     /// executes `op`, then releases the file lock.
     fn with_shared_lock<R, F: FnOnce() -> Result<R>>(&self, op: F) -> Result<R> {
         let _guard: MutexGuard<'_, ()> = self.lock.lock().map_err(|err| lock_poison_error(&err))?;
-        self.lock_file.lock_shared().map_err(storage_io_error)?;
+        self.acquire_file_lock(LockMode::Shared)?;
         let result = op();
         // Only surface the unlock error when the operation succeeded;
         // otherwise the original error is more useful.
@@ -172,7 +646,7 @@ impl FileStorage {
     /// lock, executes `op`, then releases the file lock.
     fn with_exclusive_lock<R, F: FnOnce() -> Result<R>>(&self, op: F) -> Result<R> {
         let _guard: MutexGuard<'_, ()> = self.lock.lock().map_err(|err| lock_poison_error(&err))?;
-        self.lock_file.lock().map_err(storage_io_error)?;
+        self.acquire_file_lock(LockMode::Exclusive)?;
         let result = op();
         if let Err(err) = self.lock_file.unlock()
             && result.is_ok()
@@ -182,49 +656,219 @@ impl FileStorage {
         result
     }
 
+    /// Acquires the cross-process file lock, honouring `lock_timeout`.
+    fn acquire_file_lock(&self, mode: LockMode) -> Result<()> {
+        let Some(timeout) = self.lock_timeout else {
+            return match mode {
+                LockMode::Shared => self.lock_file.lock_shared(),
+                LockMode::Exclusive => self.lock_file.lock(),
+            }
+            .map_err(storage_io_error);
+        };
+        let deadline = Instant::now() + timeout;
+        loop {
+            let attempt = match mode {
+                LockMode::Shared => self.lock_file.try_lock_shared(),
+                LockMode::Exclusive => self.lock_file.try_lock(),
+            };
+            match attempt {
+                Ok(()) => return Ok(()),
+                Err(fs::TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    std::thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(fs::TryLockError::WouldBlock) => {
+                    return Err(ZenMoneyError::LockTimeout { timeout });
+                }
+                Err(fs::TryLockError::Error(err)) => return Err(storage_io_error(err)),
+            }
+        }
+    }
+
     /// Reads and deserializes a JSON file. Returns an empty `Vec` if the
     /// file does not exist.
     fn read_entities<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<Vec<T>> {
-        let path = self.path(name);
-        match fs::read_to_string(&path) {
-            Ok(contents) => serde_json::from_str(&contents).map_err(ZenMoneyError::from),
-            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
-            Err(err) => Err(storage_io_error(err)),
-        }
+        self.read_data(name)?.map_or_else(
+            || Ok(Vec::new()),
+            |contents| serde_json::from_slice(&contents).map_err(ZenMoneyError::from),
+        )
+    }
+
+    /// Checks that the entity file `name` in `dir` is absent or parses as
+    /// a list of `T`.
+    fn parses<T: serde::de::DeserializeOwned>(&self, dir: &Path, name: &str) -> Result<()> {
+        self.read_data_in(dir, name)?.map_or(Ok(()), |contents| {
+            serde_json::from_slice::<Vec<T>>(&contents)
+                .map(drop)
+                .map_err(ZenMoneyError::from)
+        })
+    }
+
+    /// Checks that every data file in `dir` is absent or parses, pairing
+    /// each file name with the outcome.
+    fn check_files(&self, dir: &Path) -> [(&'static str, Result<()>); 12] {
+        [
+            (META_FILE, self.read_meta_in(dir).map(drop)),
+            (ACCOUNTS_FILE, self.parses::<Account>(dir, ACCOUNTS_FILE)),
+            (
+                TRANSACTIONS_FILE,
+                self.parses::<Transaction>(dir, TRANSACTIONS_FILE),
+            ),
+            (TAGS_FILE, self.parses::<Tag>(dir, TAGS_FILE)),
+            (MERCHANTS_FILE, self.parses::<Merchant>(dir, MERCHANTS_FILE)),
+            (
+                INSTRUMENTS_FILE,
+                self.parses::<Instrument>(dir, INSTRUMENTS_FILE),
+            ),
+            (COMPANIES_FILE, self.parses::<Company>(dir, COMPANIES_FILE)),
+            (COUNTRIES_FILE, self.parses::<Country>(dir, COUNTRIES_FILE)),
+            (USERS_FILE, self.parses::<User>(dir, USERS_FILE)),
+            (REMINDERS_FILE, self.parses::<Reminder>(dir, REMINDERS_FILE)),
+            (
+                REMINDER_MARKERS_FILE,
+                self.parses::<ReminderMarker>(dir, REMINDER_MARKERS_FILE),
+            ),
+            (BUDGETS_FILE, self.parses::<Budget>(dir, BUDGETS_FILE)),
+        ]
     }
 
     /// Atomically writes a serialized JSON file (write-to-tmp then rename).
     fn write_entities<T: Serialize>(&self, name: &str, items: &[T]) -> Result<()> {
-        let path = self.path(name);
-        let tmp_path = self.path(&format!("{name}.tmp"));
-        let json = serde_json::to_string_pretty(items).map_err(ZenMoneyError::from)?;
-        fs::write(&tmp_path, json).map_err(storage_io_error)?;
-        fs::rename(&tmp_path, &path).map_err(storage_io_error)?;
-        Ok(())
+        let json = serde_json::to_vec_pretty(items).map_err(ZenMoneyError::from)?;
+        self.write_data(name, &json)
     }
 
     /// Reads the metadata file.
     fn read_meta(&self) -> Result<Meta> {
-        let path = self.path(META_FILE);
-        match fs::read_to_string(&path) {
-            Ok(contents) => serde_json::from_str(&contents).map_err(ZenMoneyError::from),
-            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Meta::default()),
-            Err(err) => Err(storage_io_error(err)),
-        }
+        self.read_meta_in(&self.dir)
+    }
+
+    /// Reads the metadata file in `dir`.
+    fn read_meta_in(&self, dir: &Path) -> Result<Meta> {
+        self.read_raw_in(dir, META_FILE)?.map_or_else(
+            || Ok(Meta::default()),
+            |contents| serde_json::from_slice(&contents).map_err(ZenMoneyError::from),
+        )
     }
 
     /// Atomically writes the metadata file.
     fn write_meta(&self, meta: &Meta) -> Result<()> {
-        let path = self.path(META_FILE);
-        let tmp_path = self.path(&format!("{META_FILE}.tmp"));
-        let json = serde_json::to_string_pretty(meta).map_err(ZenMoneyError::from)?;
-        fs::write(&tmp_path, json).map_err(storage_io_error)?;
-        fs::rename(&tmp_path, &path).map_err(storage_io_error)?;
+        let json = serde_json::to_vec_pretty(meta).map_err(ZenMoneyError::from)?;
+        self.write_raw(META_FILE, &json)
+    }
+
+    /// Reads an entity file in whichever stored form exists first,
+    /// decompressing it if needed. Returns `None` if no form exists.
+    fn read_data(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        self.read_data_in(&self.dir, name)
+    }
+
+    /// Like [`Self::read_data`], for the copy of the file in `dir`.
+    fn read_data_in(&self, dir: &Path, name: &str) -> Result<Option<Vec<u8>>> {
+        for file in self.stored_names(name) {
+            if let Some(contents) = self.read_raw_in(dir, &file)? {
+                return decode(&file, contents).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Writes an entity file in the configured form, then deletes the
+    /// other stored form so it cannot shadow the new contents.
+    fn write_data(&self, name: &str, contents: &[u8]) -> Result<()> {
+        let names = self.stored_names(name);
+        let Some((target, stale)) = names.split_first() else {
+            return Ok(());
+        };
+        self.write_raw(target, &encode(target, contents)?)?;
+        for file in stale {
+            remove_if_exists(&self.path(file))?;
+        }
         Ok(())
     }
 
-    /// Merges new items into an entity file by key (insert-or-replace).
-    fn upsert_file<T, K>(&self, name: &str, new_items: Vec<T>, key_fn: fn(&T) -> K) -> Result<()>
+    /// Opens an entity file for streaming in whichever stored form exists
+    /// first. Encrypted files are decrypted into memory as a whole.
+    fn open_stream(&self, name: &str) -> Result<Option<Box<dyn BufRead>>> {
+        #[cfg(feature = "encryption")]
+        if self.cipher.is_some() {
+            let contents = self.read_data(name)?;
+            return Ok(contents.map(|bytes| -> Box<dyn BufRead> { Box::new(Cursor::new(bytes)) }));
+        }
+        for file in self.stored_names(name) {
+            match fs::File::open(self.path(&file)) {
+                Ok(handle) => return Ok(Some(stream_reader(&file, handle))),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(storage_io_error(err)),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the names an entity file may be stored under, the one
+    /// matching the compression setting first.
+    #[cfg(feature = "compression")]
+    fn stored_names(&self, name: &str) -> Vec<String> {
+        let mut names = stored_variants(name);
+        if self.compress {
+            names.reverse();
+        }
+        names
+    }
+
+    /// Returns the names an entity file may be stored under.
+    #[cfg(not(feature = "compression"))]
+    #[expect(clippy::unused_self, reason = "mirrors the compression variant")]
+    fn stored_names(&self, name: &str) -> Vec<String> {
+        stored_variants(name)
+    }
+
+    /// Reads the file `name` in `dir` as stored, decrypting it when the
+    /// storage is encrypted. Returns `None` if the file does not exist.
+    #[cfg_attr(
+        not(feature = "encryption"),
+        expect(clippy::unused_self, reason = "only the cipher is read from `self`")
+    )]
+    fn read_raw_in(&self, dir: &Path, name: &str) -> Result<Option<Vec<u8>>> {
+        let contents = match fs::read(dir.join(name)) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(storage_io_error(err)),
+        };
+        #[cfg(feature = "encryption")]
+        if let Some(cipher) = self.cipher.as_ref() {
+            return cipher.open(name, &contents).map(Some);
+        }
+        Ok(Some(contents))
+    }
+
+    /// Atomically writes a file as stored (write-to-tmp then rename),
+    /// encrypting it when the storage is encrypted.
+    fn write_raw(&self, name: &str, contents: &[u8]) -> Result<()> {
+        #[cfg(feature = "encryption")]
+        let sealed = self
+            .cipher
+            .as_ref()
+            .map(|cipher| cipher.seal(name, contents))
+            .transpose()?;
+        #[cfg(feature = "encryption")]
+        let bytes = sealed.as_deref().unwrap_or(contents);
+        #[cfg(not(feature = "encryption"))]
+        let bytes = contents;
+        let tmp_path = self.path(&format!("{name}.tmp"));
+        fs::write(&tmp_path, bytes).map_err(storage_io_error)?;
+        fs::rename(&tmp_path, self.path(name)).map_err(storage_io_error)?;
+        Ok(())
+    }
+
+    /// Merges new items into an entity file by key (insert-or-replace),
+    /// or buffers them into `slot` when coalescing writes.
+    fn upsert_file<T, K>(
+        &self,
+        name: &str,
+        new_items: Vec<T>,
+        key_fn: fn(&T) -> K,
+        slot: Slot<T>,
+    ) -> Result<()>
     where
         T: Serialize + serde::de::DeserializeOwned,
         K: Hash + Eq,
@@ -232,10 +876,91 @@ impl FileStorage {
         if new_items.is_empty() {
             return Ok(());
         }
+        if self.writes.is_enabled() {
+            return self.writes.push(
+                |batch| slot(batch).extend(new_items),
+                |batch| self.write_batch(batch),
+            );
+        }
+        self.with_exclusive_lock(|| self.merge_into_file(name, new_items, key_fn))
+    }
+
+    /// Writes any buffered upserts.
+    fn flush_pending(&self) -> Result<()> {
+        self.writes.flush(|batch| self.write_batch(batch))
+    }
+
+    /// Drops any buffered upserts without writing them.
+    fn discard_pending(&self) -> Result<()> {
+        self.writes.discard()
+    }
+
+    /// Reads an entity file with the buffered upserts of that type
+    /// applied on top.
+    fn read_file<T, K>(&self, name: &str, key_fn: fn(&T) -> K, slot: Slot<T>) -> Result<Vec<T>>
+    where
+        T: Clone + serde::de::DeserializeOwned,
+        K: Hash + Eq,
+    {
+        self.writes.read(slot, key_fn, || {
+            self.with_shared_lock(|| self.read_entities(name))
+        })
+    }
+
+    /// Writes `batch` with [`Self::write_batch`], or buffers it when
+    /// coalescing writes.
+    fn upsert_batch_or_buffer(&self, batch: Snapshot) -> Result<()> {
+        if self.writes.is_enabled() {
+            return self.writes.push(
+                |pending| append_batch(pending, batch),
+                |pending| self.write_batch(pending),
+            );
+        }
+        self.write_batch(batch)
+    }
+
+    /// Merges new items into an entity file by key without locking; the
+    /// caller must hold the exclusive lock. Skips the file entirely when
+    /// there is nothing to merge.
+    fn merge_into_file<T, K>(
+        &self,
+        name: &str,
+        new_items: Vec<T>,
+        key_fn: fn(&T) -> K,
+    ) -> Result<()>
+    where
+        T: Serialize + serde::de::DeserializeOwned,
+        K: Hash + Eq,
+    {
+        if new_items.is_empty() {
+            return Ok(());
+        }
+        let existing: Vec<T> = self.read_entities(name)?;
+        let merged = upsert_by_key(existing, new_items, key_fn);
+        self.write_entities(name, &merged)
+    }
+
+    /// Merges every collection of `batch` into its entity file under a
+    /// single exclusive lock, rewriting only files that receive items.
+    fn write_batch(&self, batch: Snapshot) -> Result<()> {
         self.with_exclusive_lock(|| {
-            let existing: Vec<T> = self.read_entities(name)?;
-            let merged = upsert_by_key(existing, new_items, key_fn);
-            self.write_entities(name, &merged)
+            self.merge_into_file(ACCOUNTS_FILE, batch.accounts, account_key)?;
+            #[cfg(feature = "storage-events")]
+            self.record_upserts(&batch.transactions)?;
+            self.merge_into_file(TRANSACTIONS_FILE, batch.transactions, transaction_key)?;
+            self.merge_into_file(TAGS_FILE, batch.tags, tag_key)?;
+            self.merge_into_file(MERCHANTS_FILE, batch.merchants, merchant_key)?;
+            self.merge_into_file(INSTRUMENTS_FILE, batch.instruments, instrument_key)?;
+            self.merge_into_file(COMPANIES_FILE, batch.companies, company_key)?;
+            self.merge_into_file(COUNTRIES_FILE, batch.countries, country_key)?;
+            self.merge_into_file(USERS_FILE, batch.users, user_key)?;
+            self.merge_into_file(REMINDERS_FILE, batch.reminders, reminder_key)?;
+            self.merge_into_file(
+                REMINDER_MARKERS_FILE,
+                batch.reminder_markers,
+                reminder_marker_key,
+            )?;
+            self.merge_into_file(BUDGETS_FILE, batch.budgets, budget_key)
         })
     }
 
@@ -248,6 +973,7 @@ impl FileStorage {
         if ids.is_empty() {
             return Ok(());
         }
+        self.flush_pending()?;
         self.with_exclusive_lock(|| {
             let existing: Vec<T> = self.read_entities(name)?;
             let filtered = remove_by_key(existing, ids, key_fn);
@@ -267,6 +993,7 @@ impl FileStorage {
 
     /// Writes `server_timestamp` to meta (with lock).
     fn write_server_timestamp(&self, timestamp: DateTime<Utc>) -> Result<()> {
+        self.flush_pending()?;
         self.with_exclusive_lock(|| {
             let mut meta = self.read_meta()?;
             meta.server_timestamp = Some(timestamp.timestamp());
@@ -274,41 +1001,306 @@ impl FileStorage {
         })
     }
 
-    /// Deletes all entity files and metadata.
+    /// Deletes all entity files and metadata, backing them up first if
+    /// [`FileStorage::backup_before_clear`] is enabled.
     ///
-    /// The `storage.lock` sentinel is intentionally preserved — it is
-    /// infrastructure, not data.
+    /// The `storage.lock` sentinel and existing backups are intentionally
+    /// preserved — they are infrastructure, not data.
     fn clear_all(&self) -> Result<()> {
+        self.discard_pending()?;
         self.with_exclusive_lock(|| {
-            let files = [
-                META_FILE,
-                ACCOUNTS_FILE,
-                TRANSACTIONS_FILE,
-                TAGS_FILE,
-                MERCHANTS_FILE,
-                INSTRUMENTS_FILE,
-                COMPANIES_FILE,
-                COUNTRIES_FILE,
-                USERS_FILE,
-                REMINDERS_FILE,
-                REMINDER_MARKERS_FILE,
-                BUDGETS_FILE,
-            ];
-            for name in files {
-                let path = self.path(name);
-                match fs::remove_file(&path) {
-                    Ok(()) => {}
-                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
-                    Err(err) => return Err(storage_io_error(err)),
-                }
+            if self.backup_before_clear {
+                let dest = create_timestamped_dir(&self.path(BACKUP_DIR))?;
+                copy_data_files(&self.dir, &dest)?;
+                tracing::debug!(backup = %dest.display(), "backed up storage before clear");
             }
-            Ok(())
+            #[cfg(feature = "storage-events")]
+            self.record_events([TransactionEvent::Clear])?;
+            self.remove_data_files()
         })
     }
+
+    /// Writes every entity file and the metadata from `snapshot` under a
+    /// single exclusive lock, so readers see either the old or the new
+    /// contents.
+    fn write_snapshot(&self, snapshot: &Snapshot) -> Result<()> {
+        self.discard_pending()?;
+        self.with_exclusive_lock(|| {
+            self.write_entities(ACCOUNTS_FILE, &snapshot.accounts)?;
+            self.write_entities(TRANSACTIONS_FILE, &snapshot.transactions)?;
+            #[cfg(feature = "storage-events")]
+            self.record_reset(snapshot.transactions.clone())?;
+            self.write_entities(TAGS_FILE, &snapshot.tags)?;
+            self.write_entities(MERCHANTS_FILE, &snapshot.merchants)?;
+            self.write_entities(INSTRUMENTS_FILE, &snapshot.instruments)?;
+            self.write_entities(COMPANIES_FILE, &snapshot.companies)?;
+            self.write_entities(COUNTRIES_FILE, &snapshot.countries)?;
+            self.write_entities(USERS_FILE, &snapshot.users)?;
+            self.write_entities(REMINDERS_FILE, &snapshot.reminders)?;
+            self.write_entities(REMINDER_MARKERS_FILE, &snapshot.reminder_markers)?;
+            self.write_entities(BUDGETS_FILE, &snapshot.budgets)?;
+            self.write_meta(&Meta {
+                server_timestamp: snapshot.server_timestamp.map(|ts| ts.timestamp()),
+            })
+        })
+    }
+
+    /// Stores upserted transactions, appending their events when
+    /// recording is on.
+    fn upsert_transaction_file(&self, items: Vec<Transaction>) -> Result<()> {
+        #[cfg(feature = "storage-events")]
+        if self.events_enabled && !self.writes.is_enabled() && !items.is_empty() {
+            return self.with_exclusive_lock(|| {
+                self.record_upserts(&items)?;
+                self.merge_into_file(TRANSACTIONS_FILE, items, transaction_key)
+            });
+        }
+        self.upsert_file(TRANSACTIONS_FILE, items, transaction_key, |batch| {
+            &mut batch.transactions
+        })
+    }
+
+    /// Removes transactions, appending their events when recording is on.
+    fn remove_transaction_file(&self, ids: &[TransactionId]) -> Result<()> {
+        #[cfg(feature = "storage-events")]
+        if self.events_enabled && !ids.is_empty() {
+            self.flush_pending()?;
+            return self.with_exclusive_lock(|| {
+                self.record_events(
+                    ids.iter()
+                        .map(|id| TransactionEvent::Remove { id: id.clone() }),
+                )?;
+                let existing: Vec<Transaction> = self.read_entities(TRANSACTIONS_FILE)?;
+                let filtered = remove_by_key(existing, ids, transaction_key);
+                self.write_entities(TRANSACTIONS_FILE, &filtered)
+            });
+        }
+        self.remove_file(TRANSACTIONS_FILE, ids, transaction_key)
+    }
+
+    /// Appends `events` to the transaction log if recording is on. The
+    /// caller must hold the exclusive lock.
+    #[cfg(feature = "storage-events")]
+    fn record_events<I: IntoIterator<Item = TransactionEvent>>(&self, events: I) -> Result<()> {
+        if !self.events_enabled {
+            return Ok(());
+        }
+        events::append(&self.path(TRANSACTION_EVENTS_FILE), events, |line| {
+            self.seal_event_line(line)
+        })
+    }
+
+    /// Encrypts one event-log line when the storage is encrypted.
+    #[cfg(feature = "storage-events")]
+    #[cfg_attr(
+        not(feature = "encryption"),
+        expect(clippy::unused_self, reason = "only the cipher is read from `self`"),
+        expect(
+            clippy::unnecessary_wraps,
+            reason = "sealing can fail with encryption on"
+        ),
+        expect(clippy::missing_const_for_fn, reason = "sealing is not const")
+    )]
+    fn seal_event_line(&self, line: String) -> Result<String> {
+        #[cfg(feature = "encryption")]
+        if let Some(cipher) = self.cipher.as_ref() {
+            return cipher.seal_line(TRANSACTION_EVENTS_FILE, &line);
+        }
+        Ok(line)
+    }
+
+    /// Decrypts one event-log line when the storage is encrypted.
+    #[cfg(feature = "storage-events")]
+    #[cfg_attr(
+        not(feature = "encryption"),
+        expect(clippy::unused_self, reason = "only the cipher is read from `self`"),
+        expect(
+            clippy::unnecessary_wraps,
+            reason = "opening can fail with encryption on"
+        )
+    )]
+    fn open_event_line(&self, line: &str) -> Result<String> {
+        #[cfg(feature = "encryption")]
+        if let Some(cipher) = self.cipher.as_ref() {
+            return cipher.open_line(TRANSACTION_EVENTS_FILE, line);
+        }
+        Ok(line.to_owned())
+    }
+
+    /// Logs an upsert event per transaction.
+    #[cfg(feature = "storage-events")]
+    fn record_upserts(&self, items: &[Transaction]) -> Result<()> {
+        self.record_events(items.iter().map(|transaction| TransactionEvent::Upsert {
+            transaction: Box::new(transaction.clone()),
+        }))
+    }
+
+    /// Logs a clear followed by an upsert per transaction in `items`.
+    #[cfg(feature = "storage-events")]
+    fn record_reset(&self, items: Vec<Transaction>) -> Result<()> {
+        self.record_events(
+            core::iter::once(TransactionEvent::Clear).chain(items.into_iter().map(|transaction| {
+                TransactionEvent::Upsert {
+                    transaction: Box::new(transaction),
+                }
+            })),
+        )
+    }
+
+    /// Removes every data file, ignoring ones that do not exist.
+    fn remove_data_files(&self) -> Result<()> {
+        for file in DATA_FILES.into_iter().flat_map(stored_variants) {
+            remove_if_exists(&self.path(&file))?;
+        }
+        Ok(())
+    }
+
+    /// Resolves a backup ID to its directory, rejecting path traversal.
+    fn backup_path(&self, id: &str) -> Result<PathBuf> {
+        if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+            return Err(ZenMoneyError::Storage(
+                format!("invalid backup id: {id}").into(),
+            ));
+        }
+        Ok(self.path(BACKUP_DIR).join(id))
+    }
 }
 
 // ── Free-standing helpers ───────────────────────────────────────────────
 
+/// Kind of cross-process file lock to acquire.
+#[derive(Debug, Clone, Copy)]
+enum LockMode {
+    /// Shared lock for readers.
+    Shared,
+    /// Exclusive lock for writers.
+    Exclusive,
+}
+
+/// Copies every existing data file from `from` into `to`.
+fn copy_data_files(from: &Path, to: &Path) -> Result<()> {
+    for file in DATA_FILES.into_iter().flat_map(stored_variants) {
+        match fs::copy(from.join(&file), to.join(&file)) {
+            Ok(_bytes) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(storage_io_error(err)),
+        }
+    }
+    Ok(())
+}
+
+/// Moves every existing data file from `from` into `to`.
+fn move_data_files(from: &Path, to: &Path) -> Result<()> {
+    for file in DATA_FILES.into_iter().flat_map(stored_variants) {
+        match fs::rename(from.join(&file), to.join(&file)) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(storage_io_error(err)),
+        }
+    }
+    Ok(())
+}
+
+/// Creates a new directory under `parent` named after the current time
+/// and returns its path.
+///
+/// Names sort chronologically. If a directory from the same millisecond
+/// already exists, a `-1`, `-2`, … suffix is appended, so an earlier
+/// backup is never reused or overwritten.
+#[expect(
+    clippy::create_dir,
+    reason = "creation must fail when the directory already exists"
+)]
+fn create_timestamped_dir(parent: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(parent).map_err(storage_io_error)?;
+    let stamp = Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let mut attempt = 0_u32;
+    loop {
+        let name = if attempt == 0 {
+            stamp.clone()
+        } else {
+            format!("{stamp}-{attempt}")
+        };
+        let dir = parent.join(name);
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(err) => return Err(storage_io_error(err)),
+        }
+    }
+}
+
+/// Deletes `path`, ignoring a file that does not exist.
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(storage_io_error(err)),
+    }
+}
+
+/// Returns every name a data file may be stored under, plain first.
+#[cfg(feature = "compression")]
+fn stored_variants(name: &str) -> Vec<String> {
+    vec![name.to_owned(), format!("{name}{GZIP_SUFFIX}")]
+}
+
+/// Returns every name a data file may be stored under.
+#[cfg(not(feature = "compression"))]
+fn stored_variants(name: &str) -> Vec<String> {
+    vec![name.to_owned()]
+}
+
+/// Turns the stored bytes of `file` into JSON, decompressing `.gz` files.
+#[cfg(feature = "compression")]
+fn decode(file: &str, contents: Vec<u8>) -> Result<Vec<u8>> {
+    if file.ends_with(GZIP_SUFFIX) {
+        gzip::decompress(&contents)
+    } else {
+        Ok(contents)
+    }
+}
+
+/// Turns the stored bytes of a file into JSON.
+#[cfg(not(feature = "compression"))]
+#[expect(clippy::unnecessary_wraps, reason = "mirrors the compression variant")]
+const fn decode(_file: &str, contents: Vec<u8>) -> Result<Vec<u8>> {
+    Ok(contents)
+}
+
+/// Turns JSON into the bytes stored as `file`, compressing `.gz` files.
+#[cfg(feature = "compression")]
+fn encode(file: &str, contents: &[u8]) -> Result<Vec<u8>> {
+    if file.ends_with(GZIP_SUFFIX) {
+        gzip::compress(contents)
+    } else {
+        Ok(contents.to_vec())
+    }
+}
+
+/// Turns JSON into the bytes stored for a file.
+#[cfg(not(feature = "compression"))]
+#[expect(clippy::unnecessary_wraps, reason = "mirrors the compression variant")]
+fn encode(_file: &str, contents: &[u8]) -> Result<Vec<u8>> {
+    Ok(contents.to_vec())
+}
+
+/// Wraps an opened `file` in a buffered reader, decompressing `.gz` files.
+#[cfg(feature = "compression")]
+fn stream_reader(file: &str, handle: fs::File) -> Box<dyn BufRead> {
+    if file.ends_with(GZIP_SUFFIX) {
+        gzip::reader(handle)
+    } else {
+        Box::new(BufReader::new(handle))
+    }
+}
+
+/// Wraps an opened file in a buffered reader.
+#[cfg(not(feature = "compression"))]
+fn stream_reader(_file: &str, handle: fs::File) -> Box<dyn BufRead> {
+    Box::new(BufReader::new(handle))
+}
+
 /// Wraps an I/O error into a [`ZenMoneyError::Storage`].
 fn storage_io_error(err: std::io::Error) -> ZenMoneyError {
     ZenMoneyError::Storage(Box::new(err))
@@ -405,6 +1397,15 @@ fn reminder_marker_key(item: &ReminderMarker) -> ReminderMarkerId {
     item.id.clone()
 }
 
+impl Drop for FileStorage {
+    #[inline]
+    fn drop(&mut self) {
+        if let Err(err) = self.flush_pending() {
+            tracing::warn!(error = %err, "failed to flush coalesced writes on drop");
+        }
+    }
+}
+
 // ── BlockingStorage implementation ──────────────────────────────────────
 
 #[cfg(feature = "blocking")]
@@ -421,112 +1422,132 @@ impl super::BlockingStorage for FileStorage {
 
     #[inline]
     fn accounts(&self) -> Result<Vec<Account>> {
-        self.with_shared_lock(|| self.read_entities(ACCOUNTS_FILE))
+        self.read_file(ACCOUNTS_FILE, account_key, |batch| &mut batch.accounts)
     }
 
     #[inline]
     fn transactions(&self) -> Result<Vec<Transaction>> {
-        self.with_shared_lock(|| self.read_entities(TRANSACTIONS_FILE))
+        self.read_file(TRANSACTIONS_FILE, transaction_key, |batch| {
+            &mut batch.transactions
+        })
     }
 
     #[inline]
     fn tags(&self) -> Result<Vec<Tag>> {
-        self.with_shared_lock(|| self.read_entities(TAGS_FILE))
+        self.read_file(TAGS_FILE, tag_key, |batch| &mut batch.tags)
     }
 
     #[inline]
     fn merchants(&self) -> Result<Vec<Merchant>> {
-        self.with_shared_lock(|| self.read_entities(MERCHANTS_FILE))
+        self.read_file(MERCHANTS_FILE, merchant_key, |batch| &mut batch.merchants)
     }
 
     #[inline]
     fn instruments(&self) -> Result<Vec<Instrument>> {
-        self.with_shared_lock(|| self.read_entities(INSTRUMENTS_FILE))
+        self.read_file(INSTRUMENTS_FILE, instrument_key, |batch| {
+            &mut batch.instruments
+        })
     }
 
     #[inline]
     fn companies(&self) -> Result<Vec<Company>> {
-        self.with_shared_lock(|| self.read_entities(COMPANIES_FILE))
+        self.read_file(COMPANIES_FILE, company_key, |batch| &mut batch.companies)
     }
 
     #[inline]
     fn countries(&self) -> Result<Vec<Country>> {
-        self.with_shared_lock(|| self.read_entities(COUNTRIES_FILE))
+        self.read_file(COUNTRIES_FILE, country_key, |batch| &mut batch.countries)
     }
 
     #[inline]
     fn users(&self) -> Result<Vec<User>> {
-        self.with_shared_lock(|| self.read_entities(USERS_FILE))
+        self.read_file(USERS_FILE, user_key, |batch| &mut batch.users)
     }
 
     #[inline]
     fn reminders(&self) -> Result<Vec<Reminder>> {
-        self.with_shared_lock(|| self.read_entities(REMINDERS_FILE))
+        self.read_file(REMINDERS_FILE, reminder_key, |batch| &mut batch.reminders)
     }
 
     #[inline]
     fn reminder_markers(&self) -> Result<Vec<ReminderMarker>> {
-        self.with_shared_lock(|| self.read_entities(REMINDER_MARKERS_FILE))
+        self.read_file(REMINDER_MARKERS_FILE, reminder_marker_key, |batch| {
+            &mut batch.reminder_markers
+        })
     }
 
     #[inline]
     fn budgets(&self) -> Result<Vec<Budget>> {
-        self.with_shared_lock(|| self.read_entities(BUDGETS_FILE))
+        self.read_file(BUDGETS_FILE, budget_key, |batch| &mut batch.budgets)
     }
 
     #[inline]
     fn upsert_accounts(&self, items: Vec<Account>) -> Result<()> {
-        self.upsert_file(ACCOUNTS_FILE, items, account_key)
+        self.upsert_file(ACCOUNTS_FILE, items, account_key, |batch| {
+            &mut batch.accounts
+        })
     }
 
     #[inline]
     fn upsert_transactions(&self, items: Vec<Transaction>) -> Result<()> {
-        self.upsert_file(TRANSACTIONS_FILE, items, transaction_key)
+        self.upsert_transaction_file(items)
     }
 
     #[inline]
     fn upsert_tags(&self, items: Vec<Tag>) -> Result<()> {
-        self.upsert_file(TAGS_FILE, items, tag_key)
+        self.upsert_file(TAGS_FILE, items, tag_key, |batch| &mut batch.tags)
     }
 
     #[inline]
     fn upsert_merchants(&self, items: Vec<Merchant>) -> Result<()> {
-        self.upsert_file(MERCHANTS_FILE, items, merchant_key)
+        self.upsert_file(MERCHANTS_FILE, items, merchant_key, |batch| {
+            &mut batch.merchants
+        })
     }
 
     #[inline]
     fn upsert_instruments(&self, items: Vec<Instrument>) -> Result<()> {
-        self.upsert_file(INSTRUMENTS_FILE, items, instrument_key)
+        self.upsert_file(INSTRUMENTS_FILE, items, instrument_key, |batch| {
+            &mut batch.instruments
+        })
     }
 
     #[inline]
     fn upsert_companies(&self, items: Vec<Company>) -> Result<()> {
-        self.upsert_file(COMPANIES_FILE, items, company_key)
+        self.upsert_file(COMPANIES_FILE, items, company_key, |batch| {
+            &mut batch.companies
+        })
     }
 
     #[inline]
     fn upsert_countries(&self, items: Vec<Country>) -> Result<()> {
-        self.upsert_file(COUNTRIES_FILE, items, country_key)
+        self.upsert_file(COUNTRIES_FILE, items, country_key, |batch| {
+            &mut batch.countries
+        })
     }
 
     #[inline]
     fn upsert_users(&self, items: Vec<User>) -> Result<()> {
-        self.upsert_file(USERS_FILE, items, user_key)
+        self.upsert_file(USERS_FILE, items, user_key, |batch| &mut batch.users)
     }
 
     #[inline]
     fn upsert_reminders(&self, items: Vec<Reminder>) -> Result<()> {
-        self.upsert_file(REMINDERS_FILE, items, reminder_key)
+        self.upsert_file(REMINDERS_FILE, items, reminder_key, |batch| {
+            &mut batch.reminders
+        })
     }
 
     #[inline]
     fn upsert_reminder_markers(&self, items: Vec<ReminderMarker>) -> Result<()> {
-        self.upsert_file(REMINDER_MARKERS_FILE, items, reminder_marker_key)
+        self.upsert_file(REMINDER_MARKERS_FILE, items, reminder_marker_key, |batch| {
+            &mut batch.reminder_markers
+        })
     }
 
     #[inline]
     fn upsert_budgets(&self, items: Vec<Budget>) -> Result<()> {
-        self.upsert_file(BUDGETS_FILE, items, budget_key)
+        self.upsert_file(BUDGETS_FILE, items, budget_key, |batch| &mut batch.budgets)
     }
 
     #[inline]
@@ -536,7 +1557,7 @@ impl super::BlockingStorage for FileStorage {
 
     #[inline]
     fn remove_transactions(&self, ids: &[TransactionId]) -> Result<()> {
-        self.remove_file(TRANSACTIONS_FILE, ids, transaction_key)
+        self.remove_transaction_file(ids)
     }
 
     #[inline]
@@ -590,6 +1611,16 @@ impl super::BlockingStorage for FileStorage {
     fn clear(&self) -> Result<()> {
         self.clear_all()
     }
+
+    #[inline]
+    fn apply_snapshot(&self, snapshot: Snapshot) -> Result<()> {
+        self.write_snapshot(&snapshot)
+    }
+
+    #[inline]
+    fn upsert_batch(&self, batch: Snapshot) -> Result<()> {
+        self.upsert_batch_or_buffer(batch)
+    }
 }
 
 // ── Storage (async) implementation ──────────────────────────────────────
@@ -611,62 +1642,82 @@ impl super::Storage for FileStorage {
 
     #[inline]
     fn accounts(&self) -> impl Future<Output = Result<Vec<Account>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(ACCOUNTS_FILE)))
+        core::future::ready(self.read_file(ACCOUNTS_FILE, account_key, |batch| &mut batch.accounts))
     }
 
     #[inline]
     fn transactions(&self) -> impl Future<Output = Result<Vec<Transaction>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(TRANSACTIONS_FILE)))
+        core::future::ready(self.read_file(TRANSACTIONS_FILE, transaction_key, |batch| {
+            &mut batch.transactions
+        }))
     }
 
     #[inline]
     fn tags(&self) -> impl Future<Output = Result<Vec<Tag>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(TAGS_FILE)))
+        core::future::ready(self.read_file(TAGS_FILE, tag_key, |batch| &mut batch.tags))
     }
 
     #[inline]
     fn merchants(&self) -> impl Future<Output = Result<Vec<Merchant>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(MERCHANTS_FILE)))
+        core::future::ready(
+            self.read_file(MERCHANTS_FILE, merchant_key, |batch| &mut batch.merchants),
+        )
     }
 
     #[inline]
     fn instruments(&self) -> impl Future<Output = Result<Vec<Instrument>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(INSTRUMENTS_FILE)))
+        core::future::ready(self.read_file(INSTRUMENTS_FILE, instrument_key, |batch| {
+            &mut batch.instruments
+        }))
     }
 
     #[inline]
     fn companies(&self) -> impl Future<Output = Result<Vec<Company>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(COMPANIES_FILE)))
+        core::future::ready(
+            self.read_file(COMPANIES_FILE, company_key, |batch| &mut batch.companies),
+        )
     }
 
     #[inline]
     fn countries(&self) -> impl Future<Output = Result<Vec<Country>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(COUNTRIES_FILE)))
+        core::future::ready(
+            self.read_file(COUNTRIES_FILE, country_key, |batch| &mut batch.countries),
+        )
     }
 
     #[inline]
     fn users(&self) -> impl Future<Output = Result<Vec<User>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(USERS_FILE)))
+        core::future::ready(self.read_file(USERS_FILE, user_key, |batch| &mut batch.users))
     }
 
     #[inline]
     fn reminders(&self) -> impl Future<Output = Result<Vec<Reminder>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(REMINDERS_FILE)))
+        core::future::ready(
+            self.read_file(REMINDERS_FILE, reminder_key, |batch| &mut batch.reminders),
+        )
     }
 
     #[inline]
     fn reminder_markers(&self) -> impl Future<Output = Result<Vec<ReminderMarker>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(REMINDER_MARKERS_FILE)))
+        core::future::ready(
+            self.read_file(REMINDER_MARKERS_FILE, reminder_marker_key, |batch| {
+                &mut batch.reminder_markers
+            }),
+        )
     }
 
     #[inline]
     fn budgets(&self) -> impl Future<Output = Result<Vec<Budget>>> + Send {
-        core::future::ready(self.with_shared_lock(|| self.read_entities(BUDGETS_FILE)))
+        core::future::ready(self.read_file(BUDGETS_FILE, budget_key, |batch| &mut batch.budgets))
     }
 
     #[inline]
     fn upsert_accounts(&self, items: Vec<Account>) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(ACCOUNTS_FILE, items, account_key))
+        core::future::ready(
+            self.upsert_file(ACCOUNTS_FILE, items, account_key, |batch| {
+                &mut batch.accounts
+            }),
+        )
     }
 
     #[inline]
@@ -674,17 +1725,21 @@ impl super::Storage for FileStorage {
         &self,
         items: Vec<Transaction>,
     ) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(TRANSACTIONS_FILE, items, transaction_key))
+        core::future::ready(self.upsert_transaction_file(items))
     }
 
     #[inline]
     fn upsert_tags(&self, items: Vec<Tag>) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(TAGS_FILE, items, tag_key))
+        core::future::ready(self.upsert_file(TAGS_FILE, items, tag_key, |batch| &mut batch.tags))
     }
 
     #[inline]
     fn upsert_merchants(&self, items: Vec<Merchant>) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(MERCHANTS_FILE, items, merchant_key))
+        core::future::ready(
+            self.upsert_file(MERCHANTS_FILE, items, merchant_key, |batch| {
+                &mut batch.merchants
+            }),
+        )
     }
 
     #[inline]
@@ -692,27 +1747,43 @@ impl super::Storage for FileStorage {
         &self,
         items: Vec<Instrument>,
     ) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(INSTRUMENTS_FILE, items, instrument_key))
+        core::future::ready(
+            self.upsert_file(INSTRUMENTS_FILE, items, instrument_key, |batch| {
+                &mut batch.instruments
+            }),
+        )
     }
 
     #[inline]
     fn upsert_companies(&self, items: Vec<Company>) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(COMPANIES_FILE, items, company_key))
+        core::future::ready(
+            self.upsert_file(COMPANIES_FILE, items, company_key, |batch| {
+                &mut batch.companies
+            }),
+        )
     }
 
     #[inline]
     fn upsert_countries(&self, items: Vec<Country>) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(COUNTRIES_FILE, items, country_key))
+        core::future::ready(
+            self.upsert_file(COUNTRIES_FILE, items, country_key, |batch| {
+                &mut batch.countries
+            }),
+        )
     }
 
     #[inline]
     fn upsert_users(&self, items: Vec<User>) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(USERS_FILE, items, user_key))
+        core::future::ready(self.upsert_file(USERS_FILE, items, user_key, |batch| &mut batch.users))
     }
 
     #[inline]
     fn upsert_reminders(&self, items: Vec<Reminder>) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(REMINDERS_FILE, items, reminder_key))
+        core::future::ready(
+            self.upsert_file(REMINDERS_FILE, items, reminder_key, |batch| {
+                &mut batch.reminders
+            }),
+        )
     }
 
     #[inline]
@@ -720,12 +1791,19 @@ impl super::Storage for FileStorage {
         &self,
         items: Vec<ReminderMarker>,
     ) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(REMINDER_MARKERS_FILE, items, reminder_marker_key))
+        core::future::ready(self.upsert_file(
+            REMINDER_MARKERS_FILE,
+            items,
+            reminder_marker_key,
+            |batch| &mut batch.reminder_markers,
+        ))
     }
 
     #[inline]
     fn upsert_budgets(&self, items: Vec<Budget>) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.upsert_file(BUDGETS_FILE, items, budget_key))
+        core::future::ready(
+            self.upsert_file(BUDGETS_FILE, items, budget_key, |batch| &mut batch.budgets),
+        )
     }
 
     #[inline]
@@ -738,7 +1816,7 @@ impl super::Storage for FileStorage {
         &self,
         ids: &[TransactionId],
     ) -> impl Future<Output = Result<()>> + Send {
-        core::future::ready(self.remove_file(TRANSACTIONS_FILE, ids, transaction_key))
+        core::future::ready(self.remove_transaction_file(ids))
     }
 
     #[inline]
@@ -793,6 +1871,16 @@ impl super::Storage for FileStorage {
     fn clear(&self) -> impl Future<Output = Result<()>> + Send {
         core::future::ready(self.clear_all())
     }
+
+    #[inline]
+    fn apply_snapshot(&self, snapshot: Snapshot) -> impl Future<Output = Result<()>> + Send {
+        core::future::ready(self.write_snapshot(&snapshot))
+    }
+
+    #[inline]
+    fn upsert_batch(&self, batch: Snapshot) -> impl Future<Output = Result<()>> + Send {
+        core::future::ready(self.upsert_batch_or_buffer(batch))
+    }
 }
 
 #[cfg(test)]
@@ -1048,6 +2136,112 @@ mod tests {
             assert!(storage.server_timestamp().unwrap().is_none());
         }
 
+        #[test]
+        fn coalesced_upserts_are_written_once_on_flush() {
+            let (storage, dir) = temp_storage();
+            let storage = storage.coalesce_writes(Duration::MAX);
+            let path = dir.path().join(TRANSACTIONS_FILE);
+            storage
+                .upsert_transactions(vec![test_transaction("tx-1", "acc-1")])
+                .unwrap();
+            storage
+                .upsert_transactions(vec![test_transaction("tx-2", "acc-1")])
+                .unwrap();
+            storage
+                .upsert_batch(Snapshot {
+                    transactions: vec![test_transaction("tx-3", "acc-1")],
+                    ..Snapshot::default()
+                })
+                .unwrap();
+
+            assert!(!path.exists());
+            assert_eq!(storage.transactions().unwrap().len(), 3);
+
+            storage.flush().unwrap();
+            let written: Vec<Transaction> = storage.read_entities(TRANSACTIONS_FILE).unwrap();
+            assert_eq!(written.len(), 3);
+
+            storage.upsert_tags(vec![test_tag("t-1", "Food")]).unwrap();
+            drop(storage);
+            let reopened = FileStorage::new(dir.path().to_path_buf()).unwrap();
+            assert_eq!(reopened.tags().unwrap().len(), 1);
+        }
+
+        #[test]
+        fn upsert_batch_merges_every_type_and_skips_empty_ones() {
+            let (storage, dir) = temp_storage();
+            storage
+                .upsert_accounts(vec![test_account("a-1", "Old")])
+                .unwrap();
+            storage
+                .upsert_batch(Snapshot {
+                    accounts: vec![test_account("a-1", "Renamed"), test_account("a-2", "New")],
+                    transactions: vec![test_transaction("tx-1", "a-2")],
+                    users: vec![test_user(1)],
+                    server_timestamp: Some(DateTime::from_timestamp(1_700_000_000, 0).unwrap()),
+                    ..Snapshot::default()
+                })
+                .unwrap();
+
+            let mut titles: Vec<String> = storage
+                .accounts()
+                .unwrap()
+                .into_iter()
+                .map(|account| account.title)
+                .collect();
+            titles.sort();
+            assert_eq!(titles, ["New", "Renamed"]);
+            assert_eq!(storage.transactions().unwrap().len(), 1);
+            assert_eq!(storage.users().unwrap().len(), 1);
+            assert!(!dir.path().join(TAGS_FILE).exists());
+            assert!(storage.server_timestamp().unwrap().is_none());
+        }
+
+        #[test]
+        fn apply_snapshot_is_atomic_for_readers() {
+            let snapshot_of = |count: usize| Snapshot {
+                accounts: (0..count)
+                    .map(|i| test_account(&format!("a-{i}"), "Account"))
+                    .collect(),
+                transactions: (0..count)
+                    .map(|i| test_transaction(&format!("tx-{i}"), &format!("a-{i}")))
+                    .collect(),
+                server_timestamp: Some(DateTime::from_timestamp(1_700_000_000, 0).unwrap()),
+                ..Snapshot::default()
+            };
+            let (storage, _dir) = temp_storage();
+            storage.apply_snapshot(snapshot_of(3)).unwrap();
+
+            std::thread::scope(|scope| {
+                let _writer = scope.spawn(|| {
+                    for round in 0..50_usize {
+                        let count = if round % 2 == 0 { 5 } else { 3 };
+                        storage.apply_snapshot(snapshot_of(count)).unwrap();
+                    }
+                });
+                for _ in 0..50_usize {
+                    let (accounts, transactions) = storage
+                        .with_shared_lock(|| {
+                            Ok((
+                                storage.read_entities::<Account>(ACCOUNTS_FILE)?.len(),
+                                storage
+                                    .read_entities::<Transaction>(TRANSACTIONS_FILE)?
+                                    .len(),
+                            ))
+                        })
+                        .unwrap();
+                    assert!(
+                        accounts == transactions && (accounts == 3 || accounts == 5),
+                        "observed partial state: {accounts} accounts, {transactions} transactions"
+                    );
+                }
+            });
+            assert_eq!(
+                storage.server_timestamp().unwrap(),
+                DateTime::from_timestamp(1_700_000_000, 0)
+            );
+        }
+
         #[test]
         fn set_and_get_server_timestamp() {
             let (storage, _dir) = temp_storage();
@@ -1346,6 +2540,409 @@ mod tests {
         assert!(storage.path(LOCK_FILE).exists());
     }
 
+    #[test]
+    fn timestamped_dirs_never_collide() {
+        let dir = tempfile::tempdir().unwrap();
+        let created: Vec<PathBuf> = (0..5)
+            .map(|_| create_timestamped_dir(dir.path()).unwrap())
+            .collect();
+        let mut names: Vec<_> = created
+            .iter()
+            .map(|path| path.file_name().unwrap())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), 5);
+        assert!(created.iter().all(|path| path.is_dir()));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn clear_backs_up_and_restore_brings_data_back() {
+        use crate::storage::BlockingStorage;
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorage::new(dir.path().to_path_buf())
+            .unwrap()
+            .backup_before_clear(true);
+        storage
+            .upsert_accounts(vec![test_account("a-1", "Savings")])
+            .unwrap();
+        storage
+            .set_server_timestamp(DateTime::from_timestamp(100, 0).unwrap())
+            .unwrap();
+
+        storage.clear().unwrap();
+        assert!(storage.accounts().unwrap().is_empty());
+        let backups = storage.backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert!(dir.path().join(BACKUP_DIR).join(&backups[0]).is_dir());
+
+        storage.restore_backup(&backups[0]).unwrap();
+        assert_eq!(storage.accounts().unwrap()[0].title, "Savings");
+        assert_eq!(
+            storage.server_timestamp().unwrap(),
+            Some(DateTime::from_timestamp(100, 0).unwrap())
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn backup_then_restore_undoes_later_writes() {
+        use crate::storage::BlockingStorage;
+
+        let (storage, dir) = temp_storage();
+        let dest = tempfile::tempdir().unwrap();
+        storage
+            .upsert_accounts(vec![test_account("a-1", "Savings")])
+            .unwrap();
+        storage
+            .set_server_timestamp(DateTime::from_timestamp(100, 0).unwrap())
+            .unwrap();
+
+        let snapshot = storage.backup(dest.path()).unwrap();
+        assert!(snapshot.starts_with(dest.path()));
+        assert!(snapshot.join(ACCOUNTS_FILE).exists());
+        assert!(!snapshot.join(LOCK_FILE).exists());
+
+        storage.clear().unwrap();
+        storage.upsert_tags(vec![test_tag("t-1", "Food")]).unwrap();
+        storage.restore(&snapshot).unwrap();
+
+        assert_eq!(storage.accounts().unwrap()[0].title, "Savings");
+        assert!(storage.tags().unwrap().is_empty());
+        assert_eq!(
+            storage.server_timestamp().unwrap(),
+            Some(DateTime::from_timestamp(100, 0).unwrap())
+        );
+        assert!(!dir.path().join(RESTORE_DIR).exists());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn restore_rejects_unparseable_source_without_touching_data() {
+        use crate::storage::BlockingStorage;
+
+        let (storage, _dir) = temp_storage();
+        let dest = tempfile::tempdir().unwrap();
+        storage
+            .upsert_accounts(vec![test_account("a-1", "Savings")])
+            .unwrap();
+        let snapshot = storage.backup(dest.path()).unwrap();
+        fs::write(snapshot.join(TAGS_FILE), b"not json").unwrap();
+        storage
+            .upsert_accounts(vec![test_account("a-2", "Card")])
+            .unwrap();
+
+        assert!(matches!(
+            storage.restore(&snapshot),
+            Err(ZenMoneyError::Storage(_))
+        ));
+        assert!(storage.restore(&dest.path().join("missing")).is_err());
+        assert_eq!(storage.accounts().unwrap().len(), 2);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn is_empty_uses_default_scan() {
+        use crate::storage::BlockingStorage;
+
+        let (storage, _dir) = temp_storage();
+        assert!(storage.is_empty().unwrap());
+        storage
+            .upsert_accounts(vec![test_account("a-1", "Test")])
+            .unwrap();
+        assert!(!storage.is_empty().unwrap());
+    }
+
+    #[cfg(all(feature = "blocking", feature = "storage-events"))]
+    #[test]
+    fn replay_transactions_reproduces_stored_transactions() {
+        use crate::storage::BlockingStorage;
+
+        /// Sorts transactions by ID so collections compare regardless of
+        /// storage order.
+        fn sorted(mut items: Vec<Transaction>) -> Vec<Transaction> {
+            items.sort_by(|lhs, rhs| lhs.id.as_inner().cmp(rhs.id.as_inner()));
+            items
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorage::new(dir.path().to_path_buf())
+            .unwrap()
+            .record_transaction_events(true);
+        storage
+            .upsert_transactions(vec![
+                test_transaction("tx-1", "a-1"),
+                test_transaction("tx-2", "a-1"),
+            ])
+            .unwrap();
+        storage.clear().unwrap();
+        storage
+            .upsert_transactions(vec![
+                test_transaction("tx-3", "a-1"),
+                test_transaction("tx-4", "a-1"),
+            ])
+            .unwrap();
+        let mut edited = test_transaction("tx-3", "a-1");
+        edited.comment = Some("edited".to_owned());
+        storage
+            .upsert_batch(Snapshot {
+                transactions: vec![edited, test_transaction("tx-5", "a-2")],
+                ..Snapshot::default()
+            })
+            .unwrap();
+        storage
+            .remove_transactions(&[TransactionId::new("tx-4".to_owned())])
+            .unwrap();
+
+        let replayed = sorted(storage.replay_transactions().unwrap());
+        assert_eq!(replayed, sorted(storage.transactions().unwrap()));
+        let ids: Vec<&str> = replayed.iter().map(|tx| tx.id.as_inner()).collect();
+        assert_eq!(ids, ["tx-3", "tx-5"]);
+        assert_eq!(replayed[0].comment.as_deref(), Some("edited"));
+        assert!(dir.path().join(TRANSACTION_EVENTS_FILE).is_file());
+    }
+
+    #[cfg(all(feature = "blocking", feature = "encryption"))]
+    #[test]
+    fn encrypted_storage_round_trips_and_rejects_wrong_key() {
+        use crate::storage::BlockingStorage;
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorage::new_encrypted(dir.path().to_path_buf(), [7; 32]).unwrap();
+        storage
+            .upsert_accounts(vec![test_account("a-1", "Secret savings")])
+            .unwrap();
+        storage
+            .upsert_transactions(vec![test_transaction("tx-1", "a-1")])
+            .unwrap();
+        storage
+            .set_server_timestamp(DateTime::from_timestamp(100, 0).unwrap())
+            .unwrap();
+
+        for name in [META_FILE, ACCOUNTS_FILE, TRANSACTIONS_FILE] {
+            let raw = fs::read(dir.path().join(name)).unwrap();
+            assert!(serde_json::from_slice::<serde_json::Value>(&raw).is_err());
+            assert!(!String::from_utf8_lossy(&raw).contains("Secret savings"));
+        }
+        assert_eq!(storage.accounts().unwrap()[0].title, "Secret savings");
+        assert_eq!(
+            storage.server_timestamp().unwrap(),
+            Some(DateTime::from_timestamp(100, 0).unwrap())
+        );
+        assert_eq!(storage.transactions_streaming().unwrap().count(), 1);
+
+        let wrong_key = FileStorage::new_encrypted(dir.path().to_path_buf(), [8; 32]).unwrap();
+        assert!(matches!(
+            wrong_key.accounts(),
+            Err(ZenMoneyError::Storage(_))
+        ));
+        assert!(matches!(
+            wrong_key.server_timestamp(),
+            Err(ZenMoneyError::Storage(_))
+        ));
+    }
+
+    #[cfg(all(
+        feature = "blocking",
+        feature = "storage-events",
+        feature = "encryption"
+    ))]
+    #[test]
+    fn encrypted_storage_seals_each_event_line() {
+        use crate::storage::BlockingStorage;
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorage::new_encrypted(dir.path().to_path_buf(), [7; 32])
+            .unwrap()
+            .record_transaction_events(true);
+        let mut secret = test_transaction("tx-1", "a-1");
+        secret.comment = Some("Secret gift".to_owned());
+        storage.upsert_transactions(vec![secret]).unwrap();
+        storage
+            .upsert_transactions(vec![test_transaction("tx-2", "a-1")])
+            .unwrap();
+
+        let log = fs::read_to_string(dir.path().join(TRANSACTION_EVENTS_FILE)).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_ne!(lines[0], lines[1]);
+        for line in &lines {
+            assert!(line.bytes().all(|byte| byte.is_ascii_hexdigit()));
+        }
+        assert!(!log.contains("Secret gift"));
+        assert_eq!(storage.replay_transactions().unwrap().len(), 2);
+
+        let wrong_key = FileStorage::new_encrypted(dir.path().to_path_buf(), [8; 32])
+            .unwrap()
+            .record_transaction_events(true);
+        assert!(matches!(
+            wrong_key.replay_transactions(),
+            Err(ZenMoneyError::Storage(_))
+        ));
+    }
+
+    #[cfg(all(feature = "blocking", feature = "compression"))]
+    #[test]
+    fn compressed_storage_reads_either_form_and_converts_on_write() {
+        use crate::storage::BlockingStorage;
+
+        let dir = tempfile::tempdir().unwrap();
+        let gz_name = format!("{TRANSACTIONS_FILE}{GZIP_SUFFIX}");
+        let compressed = FileStorage::new(dir.path().to_path_buf())
+            .unwrap()
+            .with_compression(true);
+        compressed
+            .upsert_transactions(vec![test_transaction("tx-1", "a-1")])
+            .unwrap();
+        assert!(dir.path().join(&gz_name).exists());
+        assert!(!dir.path().join(TRANSACTIONS_FILE).exists());
+        assert_eq!(compressed.transactions().unwrap().len(), 1);
+        assert_eq!(compressed.transactions_streaming().unwrap().count(), 1);
+
+        let plain = FileStorage::new(dir.path().to_path_buf()).unwrap();
+        assert_eq!(plain.transactions().unwrap().len(), 1);
+        assert_eq!(plain.transactions_streaming().unwrap().count(), 1);
+        plain
+            .upsert_transactions(vec![test_transaction("tx-2", "a-1")])
+            .unwrap();
+        assert!(dir.path().join(TRANSACTIONS_FILE).exists());
+        assert!(!dir.path().join(&gz_name).exists());
+        assert_eq!(compressed.transactions().unwrap().len(), 2);
+    }
+
+    #[cfg(all(feature = "blocking", feature = "compression"))]
+    #[test]
+    fn repair_quarantines_corrupt_compressed_file() {
+        use crate::storage::BlockingStorage;
+
+        let dir = tempfile::tempdir().unwrap();
+        let gz_name = format!("{TRANSACTIONS_FILE}{GZIP_SUFFIX}");
+        let storage = FileStorage::new(dir.path().to_path_buf())
+            .unwrap()
+            .with_compression(true);
+        storage
+            .upsert_transactions(vec![test_transaction("tx-1", "a-1")])
+            .unwrap();
+        assert!(storage.repair().unwrap().is_clean());
+
+        fs::write(dir.path().join(&gz_name), b"\x1f\x8b\x08not gzip").unwrap();
+        assert!(storage.transactions().is_err());
+
+        let report = storage.repair().unwrap();
+        assert_eq!(report.quarantined.len(), 1);
+        let quarantined = &report.quarantined[0];
+        assert_eq!(quarantined.file, TRANSACTIONS_FILE);
+        assert!(quarantined.moved_to.ends_with(&gz_name));
+        assert!(quarantined.moved_to.is_file());
+        assert!(!dir.path().join(&gz_name).exists());
+        assert!(storage.transactions().unwrap().is_empty());
+        assert!(storage.repair().unwrap().is_clean());
+    }
+
+    #[test]
+    fn restore_backup_rejects_unknown_and_traversal_ids() {
+        let (storage, _dir) = temp_storage();
+        assert!(storage.restore_backup("missing").is_err());
+        assert!(storage.restore_backup("../etc").is_err());
+        assert!(storage.backups().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn repair_quarantines_corrupt_file_and_resets_timestamp() {
+        use crate::storage::BlockingStorage;
+
+        let (storage, dir) = temp_storage();
+        storage
+            .upsert_accounts(vec![test_account("a-1", "Card")])
+            .unwrap();
+        storage
+            .upsert_transactions(vec![test_transaction("tx-1", "a-1")])
+            .unwrap();
+        storage
+            .set_server_timestamp(DateTime::from_timestamp(1_700_000_000, 0).unwrap())
+            .unwrap();
+        assert!(storage.repair().unwrap().is_clean());
+
+        fs::write(
+            dir.path().join(TRANSACTIONS_FILE),
+            "[{\"id\": \"tx-1\", \"chan",
+        )
+        .unwrap();
+        assert!(storage.transactions().is_err());
+
+        let report = storage.repair().unwrap();
+        assert_eq!(report.quarantined.len(), 1);
+        let quarantined = &report.quarantined[0];
+        assert_eq!(quarantined.file, TRANSACTIONS_FILE);
+        assert!(
+            quarantined
+                .moved_to
+                .starts_with(dir.path().join(CORRUPT_DIR))
+        );
+        assert!(quarantined.moved_to.is_file());
+        assert!(!quarantined.error.is_empty());
+
+        assert!(storage.transactions().unwrap().is_empty());
+        assert_eq!(storage.accounts().unwrap().len(), 1);
+        assert_eq!(storage.server_timestamp().unwrap(), None);
+        assert!(storage.repair().unwrap().is_clean());
+    }
+
+    #[test]
+    fn lock_timeout_expires_while_other_handle_holds_lock() {
+        let (holder, dir) = temp_storage();
+        let waiter = FileStorage::new(dir.path().to_path_buf())
+            .unwrap()
+            .lock_timeout(Duration::from_millis(50));
+        holder.lock_file.lock().unwrap();
+
+        let err = waiter.read_server_timestamp().unwrap_err();
+        assert!(matches!(err, ZenMoneyError::LockTimeout { .. }));
+
+        holder.lock_file.unlock().unwrap();
+        assert!(waiter.read_server_timestamp().unwrap().is_none());
+    }
+
+    #[test]
+    fn transactions_streaming_yields_each_item_and_holds_lock() {
+        let (storage, dir) = temp_storage();
+        assert_eq!(storage.transactions_streaming().unwrap().count(), 0);
+
+        let stored = vec![
+            test_transaction("tx-1", "acc-1"),
+            test_transaction("tx-2", "acc-1"),
+        ];
+        storage.write_entities(TRANSACTIONS_FILE, &stored).unwrap();
+        let writer = FileStorage::new(dir.path().to_path_buf())
+            .unwrap()
+            .lock_timeout(Duration::from_millis(50));
+
+        let mut stream = storage.transactions_streaming().unwrap();
+        assert_eq!(stream.next().unwrap().unwrap(), stored[0]);
+        let err = writer.write_server_timestamp(Utc::now()).unwrap_err();
+        assert!(matches!(err, ZenMoneyError::LockTimeout { .. }));
+        assert_eq!(stream.next().unwrap().unwrap(), stored[1]);
+        assert!(stream.next().is_none());
+        drop(stream);
+
+        writer.write_server_timestamp(Utc::now()).unwrap();
+    }
+
+    #[test]
+    fn transactions_streaming_stops_at_malformed_contents() {
+        let (storage, dir) = temp_storage();
+        let valid = serde_json::to_string(&test_transaction("tx-1", "acc-1")).unwrap();
+        fs::write(dir.path().join(TRANSACTIONS_FILE), format!("[{valid}, 42]")).unwrap();
+
+        let mut stream = storage.transactions_streaming().unwrap();
+        assert!(stream.next().unwrap().is_ok());
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+    }
+
     #[cfg(feature = "blocking")]
     #[test]
     fn concurrent_upserts_are_safe() {
@@ -0,0 +1,156 @@
+//! Write coalescing: buffering upserts in memory between flushes.
+
+use core::hash::Hash;
+use core::time::Duration;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+use super::{lock_poison_error, upsert_by_key};
+use crate::error::Result;
+use crate::storage::Snapshot;
+
+/// Selects the collection of one entity type within a batch.
+pub(super) type Slot<T> = fn(&mut Snapshot) -> &mut Vec<T>;
+
+/// Upserts buffered since the last flush.
+#[derive(Debug)]
+struct PendingWrites {
+    /// When the oldest buffered upsert arrived.
+    since: Instant,
+    /// Buffered items, in arrival order per entity type.
+    batch: Snapshot,
+}
+
+/// In-memory buffer of upserts for a [`FileStorage`](super::FileStorage).
+///
+/// Writing is left to the caller, which passes the function that stores a
+/// batch. The buffer lock is held while that function runs, so flushes
+/// never overtake each other.
+#[derive(Debug)]
+pub(super) struct WriteBuffer {
+    /// Maximum age of buffered upserts, or `None` to write through.
+    max_delay: Option<Duration>,
+    /// Buffered upserts, or `None` when empty.
+    pending: Mutex<Option<PendingWrites>>,
+}
+
+impl WriteBuffer {
+    /// Creates a disabled buffer.
+    pub(super) const fn new() -> Self {
+        Self {
+            max_delay: None,
+            pending: Mutex::new(None),
+        }
+    }
+
+    /// Enables buffering with the given maximum age.
+    pub(super) const fn enable(&mut self, max_delay: Duration) {
+        self.max_delay = Some(max_delay);
+    }
+
+    /// Whether upserts should be buffered instead of written through.
+    pub(super) const fn is_enabled(&self) -> bool {
+        self.max_delay.is_some()
+    }
+
+    /// Locks the buffer.
+    ///
+    /// Lock order: the buffer is always locked before the storage lock.
+    fn lock(&self) -> Result<MutexGuard<'_, Option<PendingWrites>>> {
+        self.pending.lock().map_err(|err| lock_poison_error(&err))
+    }
+
+    /// Passes the buffered upserts, if any, to `write` and empties the
+    /// buffer.
+    pub(super) fn flush(&self, write: impl FnOnce(Snapshot) -> Result<()>) -> Result<()> {
+        let mut pending = self.lock()?;
+        let result = flush_locked(&mut pending, write);
+        drop(pending);
+        result
+    }
+
+    /// Drops the buffered upserts without writing them.
+    pub(super) fn discard(&self) -> Result<()> {
+        let _discarded = self.lock()?.take();
+        Ok(())
+    }
+
+    /// Adds upserts to the buffer, flushing it through `write` once the
+    /// oldest buffered upsert is older than the maximum delay.
+    pub(super) fn push(
+        &self,
+        fill: impl FnOnce(&mut Snapshot),
+        write: impl FnOnce(Snapshot) -> Result<()>,
+    ) -> Result<()> {
+        let mut pending = self.lock()?;
+        let writes = pending.get_or_insert_with(|| PendingWrites {
+            since: Instant::now(),
+            batch: Snapshot::new(),
+        });
+        fill(&mut writes.batch);
+        let due = self
+            .max_delay
+            .is_some_and(|max_delay| writes.since.elapsed() >= max_delay);
+        let result = if due {
+            flush_locked(&mut pending, write)
+        } else {
+            Ok(())
+        };
+        drop(pending);
+        result
+    }
+
+    /// Returns the items from `read` with the buffered upserts in `slot`
+    /// applied on top.
+    pub(super) fn read<T, K>(
+        &self,
+        slot: Slot<T>,
+        key_fn: fn(&T) -> K,
+        read: impl FnOnce() -> Result<Vec<T>>,
+    ) -> Result<Vec<T>>
+    where
+        T: Clone,
+        K: Hash + Eq,
+    {
+        let mut pending = self.lock()?;
+        let read_result = read();
+        let buffered = pending
+            .as_mut()
+            .map(|writes| slot(&mut writes.batch).clone())
+            .unwrap_or_default();
+        drop(pending);
+        let stored = read_result?;
+        if buffered.is_empty() {
+            return Ok(stored);
+        }
+        Ok(upsert_by_key(stored, buffered, key_fn))
+    }
+}
+
+/// Passes the contents of an already locked buffer to `write`.
+fn flush_locked(
+    pending: &mut Option<PendingWrites>,
+    write: impl FnOnce(Snapshot) -> Result<()>,
+) -> Result<()> {
+    let Some(writes) = pending.take() else {
+        return Ok(());
+    };
+    tracing::debug!("flushing coalesced writes");
+    write(writes.batch)
+}
+
+/// Moves every item of `batch` to the end of the matching collection of
+/// `into`.
+pub(super) fn append_batch(into: &mut Snapshot, mut batch: Snapshot) {
+    into.accounts.append(&mut batch.accounts);
+    into.transactions.append(&mut batch.transactions);
+    into.tags.append(&mut batch.tags);
+    into.merchants.append(&mut batch.merchants);
+    into.instruments.append(&mut batch.instruments);
+    into.companies.append(&mut batch.companies);
+    into.countries.append(&mut batch.countries);
+    into.users.append(&mut batch.users);
+    into.reminders.append(&mut batch.reminders);
+    into.reminder_markers.append(&mut batch.reminder_markers);
+    into.budgets.append(&mut batch.budgets);
+}
@@ -0,0 +1,117 @@
+//! Encryption at rest for storage files.
+//!
+//! An encrypted file holds a random 96-bit nonce followed by the
+//! ChaCha20-Poly1305 ciphertext of the original contents. The file name is
+//! bound as associated data, so a file renamed to another entity's name
+//! fails to decrypt instead of being read as the wrong collection.
+//!
+//! Append-only files are sealed line by line instead: each line carries
+//! its own nonce and ciphertext, hex-encoded so it stays a single line.
+
+use core::fmt;
+#[cfg(feature = "storage-events")]
+use core::fmt::Write as _;
+
+use chacha20poly1305::aead::{Aead as _, AeadCore as _, KeyInit as _, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::error::{Result, ZenMoneyError};
+
+/// Length of the nonce prefix of an encrypted file.
+const NONCE_LEN: usize = 12;
+
+/// Symmetric cipher sealing the contents of storage files.
+pub(super) struct FileCipher(ChaCha20Poly1305);
+
+impl FileCipher {
+    /// Creates a cipher from a 256-bit key.
+    pub(super) fn new(key: &[u8; 32]) -> Self {
+        Self(ChaCha20Poly1305::new(Key::from_slice(key)))
+    }
+
+    /// Encrypts the contents of the file `name` under a fresh nonce.
+    pub(super) fn seal(&self, name: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .0
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_err| ZenMoneyError::Storage(format!("failed to encrypt {name}").into()))?;
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Decrypts the contents of the file `name`.
+    ///
+    /// Fails with [`ZenMoneyError::Storage`] if the file is too short,
+    /// was written with another key or under another name, or was
+    /// modified.
+    pub(super) fn open(&self, name: &str, sealed: &[u8]) -> Result<Vec<u8>> {
+        let undecryptable = || {
+            ZenMoneyError::Storage(
+                format!("cannot decrypt {name}: wrong key or corrupted file").into(),
+            )
+        };
+        let (nonce, ciphertext) = sealed
+            .split_at_checked(NONCE_LEN)
+            .ok_or_else(undecryptable)?;
+        self.0
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_err| undecryptable())
+    }
+
+    /// Encrypts one line of the append-only file `name` under a fresh
+    /// nonce and hex-encodes the result.
+    #[cfg(feature = "storage-events")]
+    pub(super) fn seal_line(&self, name: &str, line: &str) -> Result<String> {
+        let sealed = self.seal(name, line.as_bytes())?;
+        let mut encoded = String::with_capacity(sealed.len().saturating_mul(2));
+        for byte in sealed {
+            write!(encoded, "{byte:02x}").map_err(|err| ZenMoneyError::Storage(err.into()))?;
+        }
+        Ok(encoded)
+    }
+
+    /// Decrypts one line written by [`FileCipher::seal_line`].
+    ///
+    /// Fails with [`ZenMoneyError::Storage`] if the line is not valid hex,
+    /// was sealed with another key or for another file, or was modified.
+    #[cfg(feature = "storage-events")]
+    pub(super) fn open_line(&self, name: &str, line: &str) -> Result<String> {
+        let malformed = || ZenMoneyError::Storage(format!("malformed line in {name}").into());
+        let pairs = line.as_bytes().chunks_exact(2);
+        if !pairs.remainder().is_empty() {
+            return Err(malformed());
+        }
+        let sealed: Vec<u8> = pairs
+            .map(|pair| {
+                core::str::from_utf8(pair)
+                    .ok()
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(malformed)?;
+        let plaintext = self.open(name, &sealed)?;
+        String::from_utf8(plaintext).map_err(|err| ZenMoneyError::Storage(err.into()))
+    }
+}
+
+impl fmt::Debug for FileCipher {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FileCipher(..)")
+    }
+}
@@ -0,0 +1,107 @@
+//! Append-only log of transaction mutations.
+//!
+//! Each line of the log is one JSON-encoded [`EventRecord`], passed
+//! through a line encoder that seals it when the storage is encrypted.
+//! Replaying the lines in order rebuilds the transaction collection as it
+//! was after the last recorded write.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::storage_io_error;
+use crate::error::{Result, ZenMoneyError};
+use crate::models::{Transaction, TransactionId};
+
+/// A single mutation of the transaction collection.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub(super) enum TransactionEvent {
+    /// The transaction was inserted or replaced.
+    Upsert {
+        /// Stored transaction after the write.
+        transaction: Box<Transaction>,
+    },
+    /// The transaction was removed.
+    Remove {
+        /// ID of the removed transaction.
+        id: TransactionId,
+    },
+    /// Every transaction was removed.
+    Clear,
+}
+
+/// A logged event with the time it was recorded.
+#[derive(Debug, Serialize, Deserialize)]
+struct EventRecord {
+    /// When the event was appended.
+    at: DateTime<Utc>,
+    /// What happened.
+    #[serde(flatten)]
+    event: TransactionEvent,
+}
+
+/// Appends `events` to the log at `path`, creating it if needed.
+///
+/// Each JSON line goes through `encode` before it is written. All events
+/// share one timestamp and are written with a single `write` call so a
+/// batch is never interleaved with another one.
+pub(super) fn append<I, E>(path: &Path, events: I, encode: E) -> Result<()>
+where
+    I: IntoIterator<Item = TransactionEvent>,
+    E: Fn(String) -> Result<String>,
+{
+    let at = Utc::now();
+    let mut lines = String::new();
+    for event in events {
+        lines.push_str(&encode(serde_json::to_string(&EventRecord { at, event })?)?);
+        lines.push('\n');
+    }
+    if lines.is_empty() {
+        return Ok(());
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(storage_io_error)?;
+    file.write_all(lines.as_bytes()).map_err(storage_io_error)
+}
+
+/// Rebuilds the transaction collection from the log at `path`, turning
+/// each line back into JSON with `decode`.
+///
+/// A missing log replays to an empty collection. Blank lines are skipped.
+pub(super) fn replay<D>(path: &Path, decode: D) -> Result<Vec<Transaction>>
+where
+    D: Fn(&str) -> Result<String>,
+{
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(storage_io_error(err)),
+    };
+    let mut state: HashMap<TransactionId, Transaction> = HashMap::new();
+    for maybe_line in BufReader::new(file).lines() {
+        let line = maybe_line.map_err(storage_io_error)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: EventRecord =
+            serde_json::from_str(&decode(&line)?).map_err(ZenMoneyError::from)?;
+        match record.event {
+            TransactionEvent::Upsert { transaction } => {
+                let _old = state.insert(transaction.id.clone(), *transaction);
+            }
+            TransactionEvent::Remove { id } => {
+                let _old = state.remove(&id);
+            }
+            TransactionEvent::Clear => state.clear(),
+        }
+    }
+    Ok(state.into_values().collect())
+}
@@ -0,0 +1,36 @@
+//! Gzip compression of entity files.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read as _, Write as _};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use super::storage_io_error;
+use crate::error::Result;
+
+/// Compresses `contents` into a gzip stream.
+pub(super) fn compress(contents: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(contents).map_err(storage_io_error)?;
+    encoder.finish().map_err(storage_io_error)
+}
+
+/// Decompresses a whole gzip stream.
+///
+/// The input is already in memory, so a failure here means the file is
+/// corrupt. It is reported as a serialization error, the same way the
+/// streaming reader surfaces it, so that `repair` quarantines the file.
+pub(super) fn decompress(contents: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let _len = GzDecoder::new(contents)
+        .read_to_end(&mut out)
+        .map_err(serde_json::Error::io)?;
+    Ok(out)
+}
+
+/// Wraps a compressed file in a buffered, decompressing reader.
+pub(super) fn reader(file: fs::File) -> Box<dyn BufRead> {
+    Box::new(BufReader::new(GzDecoder::new(file)))
+}
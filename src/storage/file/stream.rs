@@ -0,0 +1,159 @@
+//! Incremental reading of JSON array files.
+
+use core::marker::PhantomData;
+use std::fs;
+use std::io::BufRead;
+use std::sync::MutexGuard;
+
+use serde::de::DeserializeOwned;
+
+use super::storage_io_error;
+use crate::error::{Result, ZenMoneyError};
+
+/// Keeps the in-process mutex and the shared file lock of a
+/// [`FileStorage`](super::FileStorage) until dropped.
+pub(super) struct SharedLock<'storage> {
+    /// In-process guard, released after the file lock.
+    _guard: MutexGuard<'storage, ()>,
+    /// Sentinel file holding the shared advisory lock.
+    lock_file: &'storage fs::File,
+}
+
+impl<'storage> SharedLock<'storage> {
+    /// Wraps an already acquired mutex guard and shared file lock.
+    pub(super) const fn new(
+        guard: MutexGuard<'storage, ()>,
+        lock_file: &'storage fs::File,
+    ) -> Self {
+        Self {
+            _guard: guard,
+            lock_file,
+        }
+    }
+}
+
+impl Drop for SharedLock<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        // Nothing useful can be done with an unlock failure here; the lock
+        // is released when the sentinel file handle is closed anyway.
+        drop(self.lock_file.unlock());
+    }
+}
+
+/// Yields the elements of a JSON array file one at a time.
+///
+/// Only the element currently being parsed is held in memory. After the
+/// first error the stream is exhausted.
+pub(super) struct ArrayStream<'storage, T> {
+    /// Buffered file contents, or `None` once the stream is finished.
+    reader: Option<Box<dyn BufRead>>,
+    /// Whether the opening `[` has been consumed.
+    started: bool,
+    /// Whether at least one element has been yielded.
+    seen_element: bool,
+    /// Lock held for as long as the stream is alive.
+    _lock: SharedLock<'storage>,
+    /// Element type.
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'storage, T: DeserializeOwned> ArrayStream<'storage, T> {
+    /// Streams the array read from `reader`, or nothing if `reader` is
+    /// `None`.
+    pub(super) fn new(reader: Option<Box<dyn BufRead>>, lock: SharedLock<'storage>) -> Self {
+        Self {
+            reader,
+            started: false,
+            seen_element: false,
+            _lock: lock,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Advances past the next separator and parses one element, returning
+    /// `None` at the closing `]`.
+    fn read_next(reader: &mut dyn BufRead, state: (bool, bool)) -> Result<Option<T>> {
+        let (started, seen_element) = state;
+        if !started {
+            expect_byte(reader, b'[')?;
+        }
+        match peek_token(reader)? {
+            Some(b']') => {
+                reader.consume(1);
+                return Ok(None);
+            }
+            Some(b',') if seen_element => reader.consume(1),
+            Some(_) if !seen_element => {}
+            Some(other) => return Err(unexpected(other)),
+            None => return Err(truncated()),
+        }
+        let mut de = serde_json::Deserializer::from_reader(reader);
+        T::deserialize(&mut de)
+            .map(Some)
+            .map_err(ZenMoneyError::from)
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for ArrayStream<'_, T> {
+    type Item = Result<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let reader = self.reader.as_mut()?;
+        let result = Self::read_next(reader, (self.started, self.seen_element));
+        self.started = true;
+        match result {
+            Ok(Some(item)) => {
+                self.seen_element = true;
+                Some(Ok(item))
+            }
+            Ok(None) => {
+                self.reader = None;
+                None
+            }
+            Err(err) => {
+                self.reader = None;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Skips whitespace and returns the next byte without consuming it.
+fn peek_token(reader: &mut dyn BufRead) -> Result<Option<u8>> {
+    loop {
+        let buf = reader.fill_buf().map_err(storage_io_error)?;
+        let Some(&byte) = buf.first() else {
+            return Ok(None);
+        };
+        if !byte.is_ascii_whitespace() {
+            return Ok(Some(byte));
+        }
+        reader.consume(1);
+    }
+}
+
+/// Skips whitespace and consumes `expected`.
+fn expect_byte(reader: &mut dyn BufRead, expected: u8) -> Result<()> {
+    match peek_token(reader)? {
+        Some(byte) if byte == expected => {
+            reader.consume(1);
+            Ok(())
+        }
+        Some(other) => Err(unexpected(other)),
+        None => Err(truncated()),
+    }
+}
+
+/// Error for a byte that does not fit the array syntax.
+fn unexpected(byte: u8) -> ZenMoneyError {
+    ZenMoneyError::Storage(
+        format!("unexpected character '{}' in JSON array", char::from(byte)).into(),
+    )
+}
+
+/// Error for a file that ends before the closing `]`.
+fn truncated() -> ZenMoneyError {
+    ZenMoneyError::Storage("JSON array ends unexpectedly".into())
+}
@@ -5,7 +5,7 @@
 //! is undesirable.
 
 use core::hash::Hash;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 
 use chrono::{DateTime, Utc};
@@ -13,6 +13,9 @@ use chrono::{DateTime, Utc};
 #[cfg(feature = "async")]
 use core::future::{self, Future};
 
+use super::Snapshot;
+use super::change_log::{Change, ChangeLog, ChangeOperation, EntityKind};
+use super::snapshot::budget_key;
 use crate::error::{Result, ZenMoneyError};
 use crate::models::{
     Account, AccountId, Budget, Company, CompanyId, Country, Instrument, InstrumentId, Merchant,
@@ -53,30 +56,10 @@ pub struct InMemoryStorage {
 /// Inner mutable state.
 #[derive(Debug, Default)]
 struct Inner {
-    /// Server timestamp.
-    server_timestamp: Option<DateTime<Utc>>,
-    /// Stored accounts.
-    accounts: Vec<Account>,
-    /// Stored transactions.
-    transactions: Vec<Transaction>,
-    /// Stored tags.
-    tags: Vec<Tag>,
-    /// Stored merchants.
-    merchants: Vec<Merchant>,
-    /// Stored instruments.
-    instruments: Vec<Instrument>,
-    /// Stored companies.
-    companies: Vec<Company>,
-    /// Stored countries.
-    countries: Vec<Country>,
-    /// Stored users.
-    users: Vec<User>,
-    /// Stored reminders.
-    reminders: Vec<Reminder>,
-    /// Stored reminder markers.
-    reminder_markers: Vec<ReminderMarker>,
-    /// Stored budgets.
-    budgets: Vec<Budget>,
+    /// Stored entities and server timestamp.
+    data: Snapshot,
+    /// Recent mutations, disabled unless a capacity is configured.
+    changes: ChangeLog,
 }
 
 impl InMemoryStorage {
@@ -87,11 +70,296 @@ impl InMemoryStorage {
         Self::default()
     }
 
+    /// Creates an in-memory storage pre-populated with the given snapshot.
+    ///
+    /// Useful for test fixtures that need a storage in a known state
+    /// without going through a sync.
+    #[inline]
+    #[must_use]
+    pub const fn with_data(snapshot: Snapshot) -> Self {
+        Self::from_parts(snapshot, 0)
+    }
+
+    /// Creates an empty in-memory storage that records its last
+    /// `capacity` mutations, readable via [`Self::recent_changes`].
+    #[inline]
+    #[must_use]
+    pub const fn with_change_log(capacity: usize) -> Self {
+        Self::from_parts(Snapshot::new(), capacity)
+    }
+
+    /// Returns a builder for assembling a pre-populated storage one entity
+    /// type at a time.
+    #[inline]
+    #[must_use]
+    pub fn builder() -> InMemoryStorageBuilder {
+        InMemoryStorageBuilder::default()
+    }
+
+    /// Returns up to `count` most recent mutations, oldest first.
+    ///
+    /// Each upserted or removed entity yields one [`Change`]; removing an
+    /// ID that is not stored records nothing. [`apply_snapshot`] records a
+    /// removal for every entity it drops and an upsert for every entity it
+    /// stores. Always empty unless the storage was created with a
+    /// change-log capacity. [`clear`] is not recorded.
+    ///
+    /// [`apply_snapshot`]: super::BlockingStorage::apply_snapshot
+    /// [`clear`]: super::BlockingStorage::clear
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the internal lock is poisoned.
+    #[inline]
+    pub fn recent_changes(&self, count: usize) -> Result<Vec<Change>> {
+        self.with_lock(|inner| inner.changes.recent(count))
+    }
+
+    /// Returns `true` if the snapshot holds no timestamp and no entities.
+    fn data_is_empty(&self) -> Result<bool> {
+        self.with_lock(|inner| inner.data == Snapshot::new())
+    }
+
+    /// Creates a storage from its data and change-log capacity.
+    const fn from_parts(data: Snapshot, change_log_capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                data,
+                changes: ChangeLog::new(change_log_capacity),
+            }),
+        }
+    }
+
     /// Acquires the inner lock and applies a closure.
     fn with_lock<R>(&self, f: impl FnOnce(&mut Inner) -> R) -> Result<R> {
         let mut inner = self.inner.lock().map_err(|err| lock_error(&err))?;
         Ok(f(&mut inner))
     }
+
+    /// Upserts `items` into the collection selected by `field`, logging
+    /// each one.
+    fn upsert_logged<T, K>(
+        &self,
+        kind: EntityKind,
+        items: Vec<T>,
+        field: fn(&mut Snapshot) -> &mut Vec<T>,
+        key_fn: fn(&T) -> K,
+    ) -> Result<()>
+    where
+        K: Hash + Eq + ToString,
+    {
+        self.with_lock(|inner| {
+            let ids: Vec<String> = items.iter().map(|item| key_fn(item).to_string()).collect();
+            upsert_by_key(field(&mut inner.data), items, key_fn);
+            for id in ids {
+                inner.changes.record(kind, ChangeOperation::Upsert, id);
+            }
+        })
+    }
+
+    /// Removes `ids` from the collection selected by `field`, logging
+    /// each ID that was actually stored.
+    fn remove_logged<T, K>(
+        &self,
+        kind: EntityKind,
+        ids: &[K],
+        field: fn(&mut Snapshot) -> &mut Vec<T>,
+        key_fn: fn(&T) -> K,
+    ) -> Result<()>
+    where
+        K: Hash + Eq + ToString,
+    {
+        self.with_lock(|inner| {
+            let existing = field(&mut inner.data);
+            let present: HashSet<K> = existing.iter().map(key_fn).collect();
+            remove_by_key(existing, ids, key_fn);
+            for id in ids.iter().filter(|id| present.contains(id)) {
+                inner
+                    .changes
+                    .record(kind, ChangeOperation::Remove, id.to_string());
+            }
+        })
+    }
+
+    /// Replaces all data with `snapshot`, logging the entities it drops
+    /// and stores.
+    fn replace_logged(&self, snapshot: Snapshot) -> Result<()> {
+        self.with_lock(|inner| {
+            let old = core::mem::replace(&mut inner.data, snapshot);
+            let (new, log) = (&inner.data, &mut inner.changes);
+            /// Logs the replacement of one collection.
+            macro_rules! replaced {
+                ($kind:ident, $field:ident, $key:expr) => {
+                    log_replacement(log, EntityKind::$kind, &old.$field, &new.$field, $key)
+                };
+            }
+            replaced!(Account, accounts, |a| a.id.clone());
+            replaced!(Transaction, transactions, |t| t.id.clone());
+            replaced!(Tag, tags, |t| t.id.clone());
+            replaced!(Merchant, merchants, |m| m.id.clone());
+            replaced!(Instrument, instruments, |i| i.id);
+            replaced!(Company, companies, |c| c.id);
+            replaced!(Country, countries, |c| c.id);
+            replaced!(User, users, |u| u.id);
+            replaced!(Reminder, reminders, |r| r.id.clone());
+            replaced!(ReminderMarker, reminder_markers, |r| r.id.clone());
+            replaced!(Budget, budgets, budget_key);
+        })
+    }
+}
+
+/// Logs the removal of every `old` item missing from `new`, then the
+/// upsert of every `new` item.
+fn log_replacement<T, K>(
+    log: &mut ChangeLog,
+    kind: EntityKind,
+    old: &[T],
+    new: &[T],
+    key_fn: fn(&T) -> K,
+) where
+    K: Hash + Eq + ToString,
+{
+    let kept: HashSet<K> = new.iter().map(key_fn).collect();
+    for id in old.iter().map(key_fn).filter(|id| !kept.contains(id)) {
+        log.record(kind, ChangeOperation::Remove, id.to_string());
+    }
+    for id in new.iter().map(key_fn) {
+        log.record(kind, ChangeOperation::Upsert, id.to_string());
+    }
+}
+
+/// Builder for a pre-populated [`InMemoryStorage`].
+///
+/// # Example
+///
+/// ```rust
+/// use zenmoney_rs::storage::InMemoryStorage;
+///
+/// let storage = InMemoryStorage::builder()
+///     .accounts(Vec::new())
+///     .transactions(Vec::new())
+///     .build();
+/// # drop(storage);
+/// ```
+#[derive(Debug, Default)]
+pub struct InMemoryStorageBuilder {
+    /// Snapshot accumulated so far.
+    snapshot: Snapshot,
+    /// Change-log capacity; zero disables the log.
+    change_log_capacity: usize,
+}
+
+impl InMemoryStorageBuilder {
+    /// Enables the change log, retaining the last `capacity` mutations.
+    #[inline]
+    #[must_use]
+    pub const fn change_log(mut self, capacity: usize) -> Self {
+        self.change_log_capacity = capacity;
+        self
+    }
+
+    /// Sets the stored server timestamp.
+    #[inline]
+    #[must_use]
+    pub const fn server_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.snapshot.server_timestamp = Some(timestamp);
+        self
+    }
+
+    /// Sets the stored accounts.
+    #[inline]
+    #[must_use]
+    pub fn accounts(mut self, items: Vec<Account>) -> Self {
+        self.snapshot.accounts = items;
+        self
+    }
+
+    /// Sets the stored transactions.
+    #[inline]
+    #[must_use]
+    pub fn transactions(mut self, items: Vec<Transaction>) -> Self {
+        self.snapshot.transactions = items;
+        self
+    }
+
+    /// Sets the stored tags.
+    #[inline]
+    #[must_use]
+    pub fn tags(mut self, items: Vec<Tag>) -> Self {
+        self.snapshot.tags = items;
+        self
+    }
+
+    /// Sets the stored merchants.
+    #[inline]
+    #[must_use]
+    pub fn merchants(mut self, items: Vec<Merchant>) -> Self {
+        self.snapshot.merchants = items;
+        self
+    }
+
+    /// Sets the stored instruments.
+    #[inline]
+    #[must_use]
+    pub fn instruments(mut self, items: Vec<Instrument>) -> Self {
+        self.snapshot.instruments = items;
+        self
+    }
+
+    /// Sets the stored companies.
+    #[inline]
+    #[must_use]
+    pub fn companies(mut self, items: Vec<Company>) -> Self {
+        self.snapshot.companies = items;
+        self
+    }
+
+    /// Sets the stored countries.
+    #[inline]
+    #[must_use]
+    pub fn countries(mut self, items: Vec<Country>) -> Self {
+        self.snapshot.countries = items;
+        self
+    }
+
+    /// Sets the stored users.
+    #[inline]
+    #[must_use]
+    pub fn users(mut self, items: Vec<User>) -> Self {
+        self.snapshot.users = items;
+        self
+    }
+
+    /// Sets the stored reminders.
+    #[inline]
+    #[must_use]
+    pub fn reminders(mut self, items: Vec<Reminder>) -> Self {
+        self.snapshot.reminders = items;
+        self
+    }
+
+    /// Sets the stored reminder markers.
+    #[inline]
+    #[must_use]
+    pub fn reminder_markers(mut self, items: Vec<ReminderMarker>) -> Self {
+        self.snapshot.reminder_markers = items;
+        self
+    }
+
+    /// Sets the stored budgets.
+    #[inline]
+    #[must_use]
+    pub fn budgets(mut self, items: Vec<Budget>) -> Self {
+        self.snapshot.budgets = items;
+        self
+    }
+
+    /// Builds the storage.
+    #[inline]
+    #[must_use]
+    pub fn build(self) -> InMemoryStorage {
+        InMemoryStorage::from_parts(self.snapshot, self.change_log_capacity)
+    }
 }
 
 /// Merges `new_items` into `existing` by key, replacing duplicates.
@@ -116,192 +384,295 @@ fn remove_by_key<T, K>(existing: &mut Vec<T>, ids: &[K], key_fn: fn(&T) -> K)
 where
     K: Hash + Eq,
 {
-    let id_set: std::collections::HashSet<&K> = ids.iter().collect();
+    let id_set: HashSet<&K> = ids.iter().collect();
     existing.retain(|item| !id_set.contains(&key_fn(item)));
 }
 
+/// Clones the transactions dated within `[from, to]`.
+fn transactions_dated(
+    transactions: &[Transaction],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<Transaction> {
+    transactions
+        .iter()
+        .filter(|tx| (from..=to).contains(&tx.date))
+        .cloned()
+        .collect()
+}
+
 /// Wraps a mutex poison error.
 fn lock_error<T>(err: &std::sync::PoisonError<T>) -> ZenMoneyError {
     ZenMoneyError::Storage(err.to_string().into())
 }
 
-/// Extracts the budget composite key.
-fn budget_key(budget: &Budget) -> (UserId, Option<TagId>, NaiveDate) {
-    (budget.user, budget.tag.clone(), budget.date)
-}
-
 // ── BlockingStorage implementation ──────────────────────────────────────
 
 #[cfg(feature = "blocking")]
 impl super::BlockingStorage for InMemoryStorage {
     #[inline]
     fn server_timestamp(&self) -> Result<Option<DateTime<Utc>>> {
-        self.with_lock(|inner| inner.server_timestamp)
+        self.with_lock(|inner| inner.data.server_timestamp)
     }
 
     #[inline]
     fn set_server_timestamp(&self, timestamp: DateTime<Utc>) -> Result<()> {
-        self.with_lock(|inner| inner.server_timestamp = Some(timestamp))
+        self.with_lock(|inner| inner.data.server_timestamp = Some(timestamp))
     }
 
     #[inline]
     fn accounts(&self) -> Result<Vec<Account>> {
-        self.with_lock(|inner| inner.accounts.clone())
+        self.with_lock(|inner| inner.data.accounts.clone())
     }
 
     #[inline]
     fn transactions(&self) -> Result<Vec<Transaction>> {
-        self.with_lock(|inner| inner.transactions.clone())
+        self.with_lock(|inner| inner.data.transactions.clone())
     }
 
     #[inline]
     fn tags(&self) -> Result<Vec<Tag>> {
-        self.with_lock(|inner| inner.tags.clone())
+        self.with_lock(|inner| inner.data.tags.clone())
     }
 
     #[inline]
     fn merchants(&self) -> Result<Vec<Merchant>> {
-        self.with_lock(|inner| inner.merchants.clone())
+        self.with_lock(|inner| inner.data.merchants.clone())
     }
 
     #[inline]
     fn instruments(&self) -> Result<Vec<Instrument>> {
-        self.with_lock(|inner| inner.instruments.clone())
+        self.with_lock(|inner| inner.data.instruments.clone())
     }
 
     #[inline]
     fn companies(&self) -> Result<Vec<Company>> {
-        self.with_lock(|inner| inner.companies.clone())
+        self.with_lock(|inner| inner.data.companies.clone())
     }
 
     #[inline]
     fn countries(&self) -> Result<Vec<Country>> {
-        self.with_lock(|inner| inner.countries.clone())
+        self.with_lock(|inner| inner.data.countries.clone())
     }
 
     #[inline]
     fn users(&self) -> Result<Vec<User>> {
-        self.with_lock(|inner| inner.users.clone())
+        self.with_lock(|inner| inner.data.users.clone())
     }
 
     #[inline]
     fn reminders(&self) -> Result<Vec<Reminder>> {
-        self.with_lock(|inner| inner.reminders.clone())
+        self.with_lock(|inner| inner.data.reminders.clone())
     }
 
     #[inline]
     fn reminder_markers(&self) -> Result<Vec<ReminderMarker>> {
-        self.with_lock(|inner| inner.reminder_markers.clone())
+        self.with_lock(|inner| inner.data.reminder_markers.clone())
     }
 
     #[inline]
     fn budgets(&self) -> Result<Vec<Budget>> {
-        self.with_lock(|inner| inner.budgets.clone())
+        self.with_lock(|inner| inner.data.budgets.clone())
     }
 
     #[inline]
     fn upsert_accounts(&self, items: Vec<Account>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.accounts, items, |a| a.id.clone()))
+        self.upsert_logged(
+            EntityKind::Account,
+            items,
+            |data| &mut data.accounts,
+            |a| a.id.clone(),
+        )
     }
 
     #[inline]
     fn upsert_transactions(&self, items: Vec<Transaction>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.transactions, items, |t| t.id.clone()))
+        self.upsert_logged(
+            EntityKind::Transaction,
+            items,
+            |data| &mut data.transactions,
+            |t| t.id.clone(),
+        )
     }
 
     #[inline]
     fn upsert_tags(&self, items: Vec<Tag>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.tags, items, |t| t.id.clone()))
+        self.upsert_logged(
+            EntityKind::Tag,
+            items,
+            |data| &mut data.tags,
+            |t| t.id.clone(),
+        )
     }
 
     #[inline]
     fn upsert_merchants(&self, items: Vec<Merchant>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.merchants, items, |m| m.id.clone()))
+        self.upsert_logged(
+            EntityKind::Merchant,
+            items,
+            |data| &mut data.merchants,
+            |m| m.id.clone(),
+        )
     }
 
     #[inline]
     fn upsert_instruments(&self, items: Vec<Instrument>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.instruments, items, |i| i.id))
+        self.upsert_logged(
+            EntityKind::Instrument,
+            items,
+            |data| &mut data.instruments,
+            |i| i.id,
+        )
     }
 
     #[inline]
     fn upsert_companies(&self, items: Vec<Company>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.companies, items, |c| c.id))
+        self.upsert_logged(
+            EntityKind::Company,
+            items,
+            |data| &mut data.companies,
+            |c| c.id,
+        )
     }
 
     #[inline]
     fn upsert_countries(&self, items: Vec<Country>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.countries, items, |c| c.id))
+        self.upsert_logged(
+            EntityKind::Country,
+            items,
+            |data| &mut data.countries,
+            |c| c.id,
+        )
     }
 
     #[inline]
     fn upsert_users(&self, items: Vec<User>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.users, items, |u| u.id))
+        self.upsert_logged(EntityKind::User, items, |data| &mut data.users, |u| u.id)
     }
 
     #[inline]
     fn upsert_reminders(&self, items: Vec<Reminder>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.reminders, items, |r| r.id.clone()))
+        self.upsert_logged(
+            EntityKind::Reminder,
+            items,
+            |data| &mut data.reminders,
+            |r| r.id.clone(),
+        )
     }
 
     #[inline]
     fn upsert_reminder_markers(&self, items: Vec<ReminderMarker>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.reminder_markers, items, |r| r.id.clone()))
+        self.upsert_logged(
+            EntityKind::ReminderMarker,
+            items,
+            |data| &mut data.reminder_markers,
+            |r| r.id.clone(),
+        )
     }
 
     #[inline]
     fn upsert_budgets(&self, items: Vec<Budget>) -> Result<()> {
-        self.with_lock(|inner| upsert_by_key(&mut inner.budgets, items, budget_key))
+        self.upsert_logged(
+            EntityKind::Budget,
+            items,
+            |data| &mut data.budgets,
+            budget_key,
+        )
     }
 
     #[inline]
     fn remove_accounts(&self, ids: &[AccountId]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.accounts, ids, |a| a.id.clone()))
+        self.remove_logged(
+            EntityKind::Account,
+            ids,
+            |data| &mut data.accounts,
+            |a| a.id.clone(),
+        )
     }
 
     #[inline]
     fn remove_transactions(&self, ids: &[TransactionId]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.transactions, ids, |t| t.id.clone()))
+        self.remove_logged(
+            EntityKind::Transaction,
+            ids,
+            |data| &mut data.transactions,
+            |t| t.id.clone(),
+        )
     }
 
     #[inline]
     fn remove_tags(&self, ids: &[TagId]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.tags, ids, |t| t.id.clone()))
+        self.remove_logged(
+            EntityKind::Tag,
+            ids,
+            |data| &mut data.tags,
+            |t| t.id.clone(),
+        )
     }
 
     #[inline]
     fn remove_merchants(&self, ids: &[MerchantId]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.merchants, ids, |m| m.id.clone()))
+        self.remove_logged(
+            EntityKind::Merchant,
+            ids,
+            |data| &mut data.merchants,
+            |m| m.id.clone(),
+        )
     }
 
     #[inline]
     fn remove_instruments(&self, ids: &[InstrumentId]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.instruments, ids, |i| i.id))
+        self.remove_logged(
+            EntityKind::Instrument,
+            ids,
+            |data| &mut data.instruments,
+            |i| i.id,
+        )
     }
 
     #[inline]
     fn remove_companies(&self, ids: &[CompanyId]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.companies, ids, |c| c.id))
+        self.remove_logged(
+            EntityKind::Company,
+            ids,
+            |data| &mut data.companies,
+            |c| c.id,
+        )
     }
 
     #[inline]
     fn remove_countries(&self, ids: &[i32]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.countries, ids, |c| c.id))
+        self.remove_logged(
+            EntityKind::Country,
+            ids,
+            |data| &mut data.countries,
+            |c| c.id,
+        )
     }
 
     #[inline]
     fn remove_users(&self, ids: &[UserId]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.users, ids, |u| u.id))
+        self.remove_logged(EntityKind::User, ids, |data| &mut data.users, |u| u.id)
     }
 
     #[inline]
     fn remove_reminders(&self, ids: &[ReminderId]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.reminders, ids, |r| r.id.clone()))
+        self.remove_logged(
+            EntityKind::Reminder,
+            ids,
+            |data| &mut data.reminders,
+            |r| r.id.clone(),
+        )
     }
 
     #[inline]
     fn remove_reminder_markers(&self, ids: &[ReminderMarkerId]) -> Result<()> {
-        self.with_lock(|inner| remove_by_key(&mut inner.reminder_markers, ids, |r| r.id.clone()))
+        self.remove_logged(
+            EntityKind::ReminderMarker,
+            ids,
+            |data| &mut data.reminder_markers,
+            |r| r.id.clone(),
+        )
     }
 
     #[inline]
@@ -311,9 +682,24 @@ impl super::BlockingStorage for InMemoryStorage {
         Ok(())
     }
 
+    #[inline]
+    fn is_empty(&self) -> Result<bool> {
+        self.data_is_empty()
+    }
+
     #[inline]
     fn clear(&self) -> Result<()> {
-        self.with_lock(|inner| *inner = Inner::default())
+        self.with_lock(|inner| inner.data = Snapshot::default())
+    }
+
+    #[inline]
+    fn apply_snapshot(&self, snapshot: Snapshot) -> Result<()> {
+        self.replace_logged(snapshot)
+    }
+
+    #[inline]
+    fn transactions_in_range(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<Transaction>> {
+        self.with_lock(|inner| transactions_dated(&inner.data.transactions, from, to))
     }
 }
 
@@ -323,7 +709,7 @@ impl super::BlockingStorage for InMemoryStorage {
 impl super::Storage for InMemoryStorage {
     #[inline]
     fn server_timestamp(&self) -> impl Future<Output = Result<Option<DateTime<Utc>>>> + Send {
-        future::ready(self.with_lock(|inner| inner.server_timestamp))
+        future::ready(self.with_lock(|inner| inner.data.server_timestamp))
     }
 
     #[inline]
@@ -331,69 +717,72 @@ impl super::Storage for InMemoryStorage {
         &self,
         timestamp: DateTime<Utc>,
     ) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| inner.server_timestamp = Some(timestamp)))
+        future::ready(self.with_lock(|inner| inner.data.server_timestamp = Some(timestamp)))
     }
 
     #[inline]
     fn accounts(&self) -> impl Future<Output = Result<Vec<Account>>> + Send {
-        future::ready(self.with_lock(|inner| inner.accounts.clone()))
+        future::ready(self.with_lock(|inner| inner.data.accounts.clone()))
     }
 
     #[inline]
     fn transactions(&self) -> impl Future<Output = Result<Vec<Transaction>>> + Send {
-        future::ready(self.with_lock(|inner| inner.transactions.clone()))
+        future::ready(self.with_lock(|inner| inner.data.transactions.clone()))
     }
 
     #[inline]
     fn tags(&self) -> impl Future<Output = Result<Vec<Tag>>> + Send {
-        future::ready(self.with_lock(|inner| inner.tags.clone()))
+        future::ready(self.with_lock(|inner| inner.data.tags.clone()))
     }
 
     #[inline]
     fn merchants(&self) -> impl Future<Output = Result<Vec<Merchant>>> + Send {
-        future::ready(self.with_lock(|inner| inner.merchants.clone()))
+        future::ready(self.with_lock(|inner| inner.data.merchants.clone()))
     }
 
     #[inline]
     fn instruments(&self) -> impl Future<Output = Result<Vec<Instrument>>> + Send {
-        future::ready(self.with_lock(|inner| inner.instruments.clone()))
+        future::ready(self.with_lock(|inner| inner.data.instruments.clone()))
     }
 
     #[inline]
     fn companies(&self) -> impl Future<Output = Result<Vec<Company>>> + Send {
-        future::ready(self.with_lock(|inner| inner.companies.clone()))
+        future::ready(self.with_lock(|inner| inner.data.companies.clone()))
     }
 
     #[inline]
     fn countries(&self) -> impl Future<Output = Result<Vec<Country>>> + Send {
-        future::ready(self.with_lock(|inner| inner.countries.clone()))
+        future::ready(self.with_lock(|inner| inner.data.countries.clone()))
     }
 
     #[inline]
     fn users(&self) -> impl Future<Output = Result<Vec<User>>> + Send {
-        future::ready(self.with_lock(|inner| inner.users.clone()))
+        future::ready(self.with_lock(|inner| inner.data.users.clone()))
     }
 
     #[inline]
     fn reminders(&self) -> impl Future<Output = Result<Vec<Reminder>>> + Send {
-        future::ready(self.with_lock(|inner| inner.reminders.clone()))
+        future::ready(self.with_lock(|inner| inner.data.reminders.clone()))
     }
 
     #[inline]
     fn reminder_markers(&self) -> impl Future<Output = Result<Vec<ReminderMarker>>> + Send {
-        future::ready(self.with_lock(|inner| inner.reminder_markers.clone()))
+        future::ready(self.with_lock(|inner| inner.data.reminder_markers.clone()))
     }
 
     #[inline]
     fn budgets(&self) -> impl Future<Output = Result<Vec<Budget>>> + Send {
-        future::ready(self.with_lock(|inner| inner.budgets.clone()))
+        future::ready(self.with_lock(|inner| inner.data.budgets.clone()))
     }
 
     #[inline]
     fn upsert_accounts(&self, items: Vec<Account>) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| upsert_by_key(&mut inner.accounts, items, |a| a.id.clone())),
-        )
+        future::ready(self.upsert_logged(
+            EntityKind::Account,
+            items,
+            |data| &mut data.accounts,
+            |a| a.id.clone(),
+        ))
     }
 
     #[inline]
@@ -401,23 +790,32 @@ impl super::Storage for InMemoryStorage {
         &self,
         items: Vec<Transaction>,
     ) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| upsert_by_key(&mut inner.transactions, items, |t| t.id.clone())),
-        )
+        future::ready(self.upsert_logged(
+            EntityKind::Transaction,
+            items,
+            |data| &mut data.transactions,
+            |t| t.id.clone(),
+        ))
     }
 
     #[inline]
     fn upsert_tags(&self, items: Vec<Tag>) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| upsert_by_key(&mut inner.tags, items, |t| t.id.clone())),
-        )
+        future::ready(self.upsert_logged(
+            EntityKind::Tag,
+            items,
+            |data| &mut data.tags,
+            |t| t.id.clone(),
+        ))
     }
 
     #[inline]
     fn upsert_merchants(&self, items: Vec<Merchant>) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| upsert_by_key(&mut inner.merchants, items, |m| m.id.clone())),
-        )
+        future::ready(self.upsert_logged(
+            EntityKind::Merchant,
+            items,
+            |data| &mut data.merchants,
+            |m| m.id.clone(),
+        ))
     }
 
     #[inline]
@@ -425,31 +823,47 @@ impl super::Storage for InMemoryStorage {
         &self,
         items: Vec<Instrument>,
     ) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| upsert_by_key(&mut inner.instruments, items, |i| i.id)),
-        )
+        future::ready(self.upsert_logged(
+            EntityKind::Instrument,
+            items,
+            |data| &mut data.instruments,
+            |i| i.id,
+        ))
     }
 
     #[inline]
     fn upsert_companies(&self, items: Vec<Company>) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| upsert_by_key(&mut inner.companies, items, |c| c.id)))
+        future::ready(self.upsert_logged(
+            EntityKind::Company,
+            items,
+            |data| &mut data.companies,
+            |c| c.id,
+        ))
     }
 
     #[inline]
     fn upsert_countries(&self, items: Vec<Country>) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| upsert_by_key(&mut inner.countries, items, |c| c.id)))
+        future::ready(self.upsert_logged(
+            EntityKind::Country,
+            items,
+            |data| &mut data.countries,
+            |c| c.id,
+        ))
     }
 
     #[inline]
     fn upsert_users(&self, items: Vec<User>) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| upsert_by_key(&mut inner.users, items, |u| u.id)))
+        future::ready(self.upsert_logged(EntityKind::User, items, |data| &mut data.users, |u| u.id))
     }
 
     #[inline]
     fn upsert_reminders(&self, items: Vec<Reminder>) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| upsert_by_key(&mut inner.reminders, items, |r| r.id.clone())),
-        )
+        future::ready(self.upsert_logged(
+            EntityKind::Reminder,
+            items,
+            |data| &mut data.reminders,
+            |r| r.id.clone(),
+        ))
     }
 
     #[inline]
@@ -457,21 +871,32 @@ impl super::Storage for InMemoryStorage {
         &self,
         items: Vec<ReminderMarker>,
     ) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| {
-            upsert_by_key(&mut inner.reminder_markers, items, |r| r.id.clone());
-        }))
+        future::ready(self.upsert_logged(
+            EntityKind::ReminderMarker,
+            items,
+            |data| &mut data.reminder_markers,
+            |r| r.id.clone(),
+        ))
     }
 
     #[inline]
     fn upsert_budgets(&self, items: Vec<Budget>) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| upsert_by_key(&mut inner.budgets, items, budget_key)))
+        future::ready(self.upsert_logged(
+            EntityKind::Budget,
+            items,
+            |data| &mut data.budgets,
+            budget_key,
+        ))
     }
 
     #[inline]
     fn remove_accounts(&self, ids: &[AccountId]) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| remove_by_key(&mut inner.accounts, ids, |a| a.id.clone())),
-        )
+        future::ready(self.remove_logged(
+            EntityKind::Account,
+            ids,
+            |data| &mut data.accounts,
+            |a| a.id.clone(),
+        ))
     }
 
     #[inline]
@@ -479,48 +904,77 @@ impl super::Storage for InMemoryStorage {
         &self,
         ids: &[TransactionId],
     ) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| remove_by_key(&mut inner.transactions, ids, |t| t.id.clone())),
-        )
+        future::ready(self.remove_logged(
+            EntityKind::Transaction,
+            ids,
+            |data| &mut data.transactions,
+            |t| t.id.clone(),
+        ))
     }
 
     #[inline]
     fn remove_tags(&self, ids: &[TagId]) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| remove_by_key(&mut inner.tags, ids, |t| t.id.clone())))
+        future::ready(self.remove_logged(
+            EntityKind::Tag,
+            ids,
+            |data| &mut data.tags,
+            |t| t.id.clone(),
+        ))
     }
 
     #[inline]
     fn remove_merchants(&self, ids: &[MerchantId]) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| remove_by_key(&mut inner.merchants, ids, |m| m.id.clone())),
-        )
+        future::ready(self.remove_logged(
+            EntityKind::Merchant,
+            ids,
+            |data| &mut data.merchants,
+            |m| m.id.clone(),
+        ))
     }
 
     #[inline]
     fn remove_instruments(&self, ids: &[InstrumentId]) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| remove_by_key(&mut inner.instruments, ids, |i| i.id)))
+        future::ready(self.remove_logged(
+            EntityKind::Instrument,
+            ids,
+            |data| &mut data.instruments,
+            |i| i.id,
+        ))
     }
 
     #[inline]
     fn remove_companies(&self, ids: &[CompanyId]) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| remove_by_key(&mut inner.companies, ids, |c| c.id)))
+        future::ready(self.remove_logged(
+            EntityKind::Company,
+            ids,
+            |data| &mut data.companies,
+            |c| c.id,
+        ))
     }
 
     #[inline]
     fn remove_countries(&self, ids: &[i32]) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| remove_by_key(&mut inner.countries, ids, |c| c.id)))
+        future::ready(self.remove_logged(
+            EntityKind::Country,
+            ids,
+            |data| &mut data.countries,
+            |c| c.id,
+        ))
     }
 
     #[inline]
     fn remove_users(&self, ids: &[UserId]) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| remove_by_key(&mut inner.users, ids, |u| u.id)))
+        future::ready(self.remove_logged(EntityKind::User, ids, |data| &mut data.users, |u| u.id))
     }
 
     #[inline]
     fn remove_reminders(&self, ids: &[ReminderId]) -> impl Future<Output = Result<()>> + Send {
-        future::ready(
-            self.with_lock(|inner| remove_by_key(&mut inner.reminders, ids, |r| r.id.clone())),
-        )
+        future::ready(self.remove_logged(
+            EntityKind::Reminder,
+            ids,
+            |data| &mut data.reminders,
+            |r| r.id.clone(),
+        ))
     }
 
     #[inline]
@@ -528,9 +982,12 @@ impl super::Storage for InMemoryStorage {
         &self,
         ids: &[ReminderMarkerId],
     ) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| {
-            remove_by_key(&mut inner.reminder_markers, ids, |r| r.id.clone());
-        }))
+        future::ready(self.remove_logged(
+            EntityKind::ReminderMarker,
+            ids,
+            |data| &mut data.reminder_markers,
+            |r| r.id.clone(),
+        ))
     }
 
     #[inline]
@@ -538,16 +995,37 @@ impl super::Storage for InMemoryStorage {
         future::ready(Ok(()))
     }
 
+    #[inline]
+    fn is_empty(&self) -> impl Future<Output = Result<bool>> + Send {
+        future::ready(self.data_is_empty())
+    }
+
     #[inline]
     fn clear(&self) -> impl Future<Output = Result<()>> + Send {
-        future::ready(self.with_lock(|inner| *inner = Inner::default()))
+        future::ready(self.with_lock(|inner| inner.data = Snapshot::default()))
+    }
+
+    #[inline]
+    fn apply_snapshot(&self, snapshot: Snapshot) -> impl Future<Output = Result<()>> + Send {
+        future::ready(self.replace_logged(snapshot))
+    }
+
+    #[inline]
+    fn transactions_in_range(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<Transaction>>> + Send {
+        future::ready(
+            self.with_lock(|inner| transactions_dated(&inner.data.transactions, from, to)),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{AccountType, Interval, ReminderMarkerState};
+    use crate::models::{AccountType, Interval, NaiveDate, ReminderMarkerState};
 
     // ── Test helpers ───────────────────────────────────────────────────
 
@@ -781,6 +1259,33 @@ mod tests {
             assert_eq!(s.server_timestamp().unwrap(), Some(ts()));
         }
 
+        #[test]
+        fn apply_snapshot_is_atomic_for_readers() {
+            let snapshot_of = |count: usize| Snapshot {
+                accounts: (0..count)
+                    .map(|i| test_account(&format!("a-{i}")))
+                    .collect(),
+                server_timestamp: Some(ts()),
+                ..Snapshot::default()
+            };
+            let s = InMemoryStorage::new();
+            s.apply_snapshot(snapshot_of(3)).unwrap();
+
+            std::thread::scope(|scope| {
+                let _writer = scope.spawn(|| {
+                    for round in 0..200_usize {
+                        let count = if round % 2 == 0 { 5 } else { 3 };
+                        s.apply_snapshot(snapshot_of(count)).unwrap();
+                    }
+                });
+                for _ in 0..200_usize {
+                    let len = s.accounts().unwrap().len();
+                    assert!(len == 3 || len == 5, "observed partial state: {len}");
+                }
+            });
+            assert_eq!(s.server_timestamp().unwrap(), Some(ts()));
+        }
+
         #[test]
         fn upsert_and_read_accounts() {
             let s = InMemoryStorage::new();
@@ -812,6 +1317,189 @@ mod tests {
             assert!(s.transactions().unwrap().is_empty());
         }
 
+        #[test]
+        fn transactions_after_pages_through_stable_boundary() {
+            let s = InMemoryStorage::new();
+            let dated = |id: &str, day: u32| {
+                let mut tx = test_transaction(id);
+                tx.date = NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+                tx
+            };
+            s.upsert_transactions(vec![
+                dated("e", 3),
+                dated("b", 2),
+                dated("a", 1),
+                dated("d", 2),
+                dated("c", 2),
+            ])
+            .unwrap();
+            let ids = |page: &[Transaction]| -> Vec<String> {
+                page.iter().map(|tx| tx.id.to_string()).collect()
+            };
+
+            let first = s.transactions_after(None, 3).unwrap();
+            assert_eq!(ids(&first), ["a", "b", "c"]);
+            let last = first.last().unwrap();
+            let cursor = (last.date, last.id.clone());
+
+            // A transaction sorting before the cursor does not shift the
+            // next page.
+            s.upsert_transactions(vec![dated("aa", 1)]).unwrap();
+            let second = s.transactions_after(Some(cursor.clone()), 3).unwrap();
+            assert_eq!(ids(&second), ["d", "e"]);
+            let last = second.last().unwrap();
+            assert!(
+                s.transactions_after(Some((last.date, last.id.clone())), 3)
+                    .unwrap()
+                    .is_empty()
+            );
+            assert!(s.transactions_after(Some(cursor), 0).unwrap().is_empty());
+        }
+
+        #[test]
+        fn transactions_in_range_includes_boundaries() {
+            let s = InMemoryStorage::new();
+            let dated = |id: &str, day: u32| {
+                let mut tx = test_transaction(id);
+                tx.date = NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+                tx
+            };
+            s.upsert_transactions(vec![
+                dated("before", 1),
+                dated("first", 2),
+                dated("middle", 15),
+                dated("last", 31),
+            ])
+            .unwrap();
+
+            let mut ids: Vec<String> = s
+                .transactions_in_range(
+                    NaiveDate::from_ymd_opt(2024, 3, 2).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|tx| tx.id.to_string())
+                .collect();
+            ids.sort();
+            assert_eq!(ids, ["first", "last", "middle"]);
+        }
+
+        #[test]
+        fn search_transactions_matches_comment_word() {
+            let s = InMemoryStorage::new();
+            let mut coffee = test_transaction("t-1");
+            coffee.comment = Some("Morning Coffee with team".to_owned());
+            let mut rent = test_transaction("t-2");
+            rent.payee = Some("Landlord".to_owned());
+            s.upsert_transactions(vec![coffee, rent]).unwrap();
+
+            let found = s.search_transactions("coffee").unwrap();
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id, TransactionId::new("t-1".to_owned()));
+            assert_eq!(s.search_transactions("LANDLORD").unwrap().len(), 1);
+            assert!(s.search_transactions("groceries").unwrap().is_empty());
+        }
+
+        #[test]
+        fn with_data_serves_snapshot_contents() {
+            let snapshot = Snapshot {
+                server_timestamp: Some(ts()),
+                accounts: vec![test_account("a-1")],
+                tags: vec![test_tag("tag-1")],
+                ..Snapshot::default()
+            };
+            let s = InMemoryStorage::with_data(snapshot);
+            assert_eq!(s.server_timestamp().unwrap(), Some(ts()));
+            assert_eq!(s.accounts().unwrap().len(), 1);
+            assert_eq!(s.tags().unwrap().len(), 1);
+            assert!(s.transactions().unwrap().is_empty());
+        }
+
+        #[test]
+        fn change_log_keeps_most_recent_mutations() {
+            use crate::storage::{ChangeOperation, EntityKind};
+
+            let s = InMemoryStorage::with_change_log(2);
+            s.upsert_accounts(vec![test_account("a-1")]).unwrap();
+            s.upsert_tags(vec![test_tag("tag-1")]).unwrap();
+            s.remove_accounts(&[
+                AccountId::new("a-1".to_owned()),
+                AccountId::new("missing".to_owned()),
+            ])
+            .unwrap();
+
+            let changes = s.recent_changes(10).unwrap();
+            assert_eq!(changes.len(), 2);
+            assert_eq!(changes[0].kind, EntityKind::Tag);
+            assert_eq!(changes[1].kind, EntityKind::Account);
+            assert_eq!(changes[1].operation, ChangeOperation::Remove);
+            assert_eq!(changes[1].id, "a-1");
+            assert_eq!(s.recent_changes(1).unwrap().len(), 1);
+        }
+
+        #[test]
+        fn apply_snapshot_logs_dropped_and_stored_entities() {
+            use crate::storage::{ChangeOperation, EntityKind};
+
+            let s = InMemoryStorage::with_change_log(10);
+            s.upsert_accounts(vec![test_account("a-1"), test_account("a-2")])
+                .unwrap();
+            s.apply_snapshot(Snapshot {
+                accounts: vec![test_account("a-2")],
+                tags: vec![test_tag("tag-1")],
+                ..Snapshot::default()
+            })
+            .unwrap();
+
+            let changes = s.recent_changes(3).unwrap();
+            let summary: Vec<(EntityKind, ChangeOperation, &str)> = changes
+                .iter()
+                .map(|change| (change.kind, change.operation, change.id.as_str()))
+                .collect();
+            assert_eq!(
+                summary,
+                [
+                    (EntityKind::Account, ChangeOperation::Remove, "a-1"),
+                    (EntityKind::Account, ChangeOperation::Upsert, "a-2"),
+                    (EntityKind::Tag, ChangeOperation::Upsert, "tag-1"),
+                ]
+            );
+        }
+
+        #[test]
+        fn is_empty_tracks_timestamp_and_entities() {
+            let s = InMemoryStorage::new();
+            assert!(s.is_empty().unwrap());
+            s.upsert_tags(vec![test_tag("tag-1")]).unwrap();
+            assert!(!s.is_empty().unwrap());
+            s.clear().unwrap();
+            s.set_server_timestamp(ts()).unwrap();
+            assert!(!s.is_empty().unwrap());
+        }
+
+        #[test]
+        fn change_log_disabled_by_default() {
+            let s = InMemoryStorage::new();
+            s.upsert_accounts(vec![test_account("a-1")]).unwrap();
+            assert!(s.recent_changes(10).unwrap().is_empty());
+        }
+
+        #[test]
+        fn builder_populates_each_entity_type() {
+            let s = InMemoryStorage::builder()
+                .server_timestamp(ts())
+                .accounts(vec![test_account("a-1"), test_account("a-2")])
+                .transactions(vec![test_transaction("t-1")])
+                .merchants(vec![test_merchant("m-1")])
+                .build();
+            assert_eq!(s.server_timestamp().unwrap(), Some(ts()));
+            assert_eq!(s.accounts().unwrap().len(), 2);
+            assert_eq!(s.transactions().unwrap().len(), 1);
+            assert_eq!(s.merchants().unwrap().len(), 1);
+            assert!(s.tags().unwrap().is_empty());
+        }
+
         #[test]
         fn upsert_and_remove_tags() {
             let s = InMemoryStorage::new();
@@ -921,6 +1609,23 @@ mod tests {
         use super::*;
         use crate::storage::Storage;
 
+        #[tokio::test]
+        async fn apply_snapshot_replaces_everything() {
+            let s = InMemoryStorage::new();
+            s.upsert_accounts(vec![test_account("old")]).await.unwrap();
+            let snapshot = Snapshot {
+                accounts: vec![test_account("new")],
+                server_timestamp: Some(ts()),
+                ..Snapshot::default()
+            };
+            s.apply_snapshot(snapshot).await.unwrap();
+
+            let accounts = s.accounts().await.unwrap();
+            assert_eq!(accounts.len(), 1);
+            assert_eq!(accounts[0].id, AccountId::new("new".to_owned()));
+            assert_eq!(s.server_timestamp().await.unwrap(), Some(ts()));
+        }
+
         #[tokio::test]
         async fn server_timestamp_lifecycle() {
             let s = InMemoryStorage::new();
@@ -961,6 +1666,60 @@ mod tests {
             assert!(s.transactions().await.unwrap().is_empty());
         }
 
+        #[tokio::test]
+        async fn is_empty_tracks_timestamp_and_entities() {
+            let s = InMemoryStorage::new();
+            assert!(s.is_empty().await.unwrap());
+            s.upsert_accounts(vec![test_account("a-1")]).await.unwrap();
+            assert!(!s.is_empty().await.unwrap());
+        }
+
+        #[tokio::test]
+        async fn transactions_after_pages_by_date_and_id() {
+            let s = InMemoryStorage::new();
+            let day = |day: u32| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+            let mut first = test_transaction("t-1");
+            first.date = day(1);
+            let mut second = test_transaction("t-2");
+            second.date = day(2);
+            s.upsert_transactions(vec![second, first]).await.unwrap();
+
+            let page = s.transactions_after(None, 1).await.unwrap();
+            assert_eq!(page[0].id, TransactionId::new("t-1".to_owned()));
+            let cursor = Some((page[0].date, page[0].id.clone()));
+            let page = s.transactions_after(cursor, 1).await.unwrap();
+            assert_eq!(page[0].id, TransactionId::new("t-2".to_owned()));
+        }
+
+        #[tokio::test]
+        async fn transactions_in_range_includes_boundaries() {
+            let s = InMemoryStorage::new();
+            let day = |day: u32| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+            let mut inside = test_transaction("inside");
+            inside.date = day(10);
+            let mut outside = test_transaction("outside");
+            outside.date = day(11);
+            s.upsert_transactions(vec![inside, outside]).await.unwrap();
+
+            let found = s.transactions_in_range(day(10), day(10)).await.unwrap();
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id, TransactionId::new("inside".to_owned()));
+        }
+
+        #[tokio::test]
+        async fn search_transactions_matches_comment_word() {
+            let s = InMemoryStorage::new();
+            let mut coffee = test_transaction("t-1");
+            coffee.comment = Some("Morning Coffee with team".to_owned());
+            s.upsert_transactions(vec![coffee, test_transaction("t-2")])
+                .await
+                .unwrap();
+
+            let found = s.search_transactions("coffee").await.unwrap();
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id, TransactionId::new("t-1".to_owned()));
+        }
+
         #[tokio::test]
         async fn upsert_and_remove_tags() {
             let s = InMemoryStorage::new();
@@ -0,0 +1,380 @@
+//! Point-in-time copy of every entity held by a storage backend.
+//!
+//! [`Snapshot`] is the plain-data form of a storage's contents: one `Vec`
+//! per entity type plus the last server timestamp. It is used to seed
+//! [`super::InMemoryStorage`] and as the unit of backup and restore.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    Account, Budget, Company, Country, Instrument, Merchant, Reminder, ReminderMarker, Tag,
+    Transaction, User,
+};
+
+/// All entities of a storage backend at a single point in time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Last server timestamp, or `None` if never synced.
+    #[serde(default)]
+    pub server_timestamp: Option<DateTime<Utc>>,
+    /// Stored accounts.
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+    /// Stored transactions.
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+    /// Stored tags.
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    /// Stored merchants.
+    #[serde(default)]
+    pub merchants: Vec<Merchant>,
+    /// Stored instruments.
+    #[serde(default)]
+    pub instruments: Vec<Instrument>,
+    /// Stored companies.
+    #[serde(default)]
+    pub companies: Vec<Company>,
+    /// Stored countries.
+    #[serde(default)]
+    pub countries: Vec<Country>,
+    /// Stored users.
+    #[serde(default)]
+    pub users: Vec<User>,
+    /// Stored reminders.
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    /// Stored reminder markers.
+    #[serde(default)]
+    pub reminder_markers: Vec<ReminderMarker>,
+    /// Stored budgets.
+    #[serde(default)]
+    pub budgets: Vec<Budget>,
+}
+
+impl Snapshot {
+    /// Creates an empty snapshot that has never been synced.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            server_timestamp: None,
+            accounts: Vec::new(),
+            transactions: Vec::new(),
+            tags: Vec::new(),
+            merchants: Vec::new(),
+            instruments: Vec::new(),
+            companies: Vec::new(),
+            countries: Vec::new(),
+            users: Vec::new(),
+            reminders: Vec::new(),
+            reminder_markers: Vec::new(),
+            budgets: Vec::new(),
+        }
+    }
+
+    /// Returns a copy safe to share in bug reports.
+    ///
+    /// Transactions are replaced by [`Transaction::anonymized`]. Reminder
+    /// and marker payees and comments, account bank sync IDs, and user
+    /// logins and emails are cleared as well. Merchant and account titles
+    /// are blanked too, since they repeat payee names and often name a
+    /// bank or a person. Tag titles are kept, as are all IDs, amounts, and
+    /// dates.
+    #[inline]
+    #[must_use]
+    pub fn anonymized(&self) -> Self {
+        let mut snapshot = self.clone();
+        snapshot.transactions = self
+            .transactions
+            .iter()
+            .map(Transaction::anonymized)
+            .collect();
+        for account in &mut snapshot.accounts {
+            account.title.clear();
+            account.sync_id = None;
+        }
+        for merchant in &mut snapshot.merchants {
+            merchant.title.clear();
+        }
+        for user in &mut snapshot.users {
+            user.login = None;
+            user.email = None;
+        }
+        for reminder in &mut snapshot.reminders {
+            reminder.payee = None;
+            reminder.comment = None;
+        }
+        for marker in &mut snapshot.reminder_markers {
+            marker.payee = None;
+            marker.comment = None;
+        }
+        snapshot
+    }
+
+    /// Compares this snapshot (the older one) with `other` (the newer one).
+    ///
+    /// For every entity type, reports the IDs present only in `other`
+    /// (added), only in `self` (removed), and in both but with different
+    /// contents (modified). A bumped `changed` timestamp alone counts as a
+    /// modification. Budgets are keyed as `user/tag/date`.
+    #[inline]
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> SnapshotDiff {
+        SnapshotDiff {
+            accounts: EntityDiff::between(&self.accounts, &other.accounts, |a| a.id.to_string()),
+            transactions: EntityDiff::between(&self.transactions, &other.transactions, |t| {
+                t.id.to_string()
+            }),
+            tags: EntityDiff::between(&self.tags, &other.tags, |t| t.id.to_string()),
+            merchants: EntityDiff::between(&self.merchants, &other.merchants, |m| m.id.to_string()),
+            instruments: EntityDiff::between(&self.instruments, &other.instruments, |i| {
+                i.id.to_string()
+            }),
+            companies: EntityDiff::between(&self.companies, &other.companies, |c| c.id.to_string()),
+            countries: EntityDiff::between(&self.countries, &other.countries, |c| c.id.to_string()),
+            users: EntityDiff::between(&self.users, &other.users, |u| u.id.to_string()),
+            reminders: EntityDiff::between(&self.reminders, &other.reminders, |r| r.id.to_string()),
+            reminder_markers: EntityDiff::between(
+                &self.reminder_markers,
+                &other.reminder_markers,
+                |m| m.id.to_string(),
+            ),
+            budgets: EntityDiff::between(&self.budgets, &other.budgets, budget_key),
+        }
+    }
+}
+
+/// Added, removed, and modified IDs of one entity type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntityDiff {
+    /// IDs present only in the newer snapshot.
+    pub added: Vec<String>,
+    /// IDs present only in the older snapshot.
+    pub removed: Vec<String>,
+    /// IDs present in both snapshots with different contents.
+    pub modified: Vec<String>,
+}
+
+impl EntityDiff {
+    /// Compares two collections keyed by `key`.
+    ///
+    /// Added and modified IDs follow the order of `new`; removed IDs
+    /// follow the order of `old`.
+    fn between<T: PartialEq>(old: &[T], new: &[T], key: fn(&T) -> String) -> Self {
+        let old_by_key: HashMap<String, &T> = old.iter().map(|item| (key(item), item)).collect();
+        let new_by_key: HashMap<String, &T> = new.iter().map(|item| (key(item), item)).collect();
+        let mut diff = Self::default();
+        for item in new {
+            let id = key(item);
+            match old_by_key.get(&id) {
+                None => diff.added.push(id),
+                Some(previous) if *previous != item => diff.modified.push(id),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .iter()
+            .map(key)
+            .filter(|id| !new_by_key.contains_key(id))
+            .collect();
+        diff
+    }
+
+    /// Returns `true` if nothing was added, removed, or modified.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Per-entity differences between two [`Snapshot`]s.
+///
+/// Produced by [`Snapshot::diff`]. The server timestamp is not compared.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// Account differences.
+    pub accounts: EntityDiff,
+    /// Transaction differences.
+    pub transactions: EntityDiff,
+    /// Tag differences.
+    pub tags: EntityDiff,
+    /// Merchant differences.
+    pub merchants: EntityDiff,
+    /// Instrument differences.
+    pub instruments: EntityDiff,
+    /// Company differences.
+    pub companies: EntityDiff,
+    /// Country differences.
+    pub countries: EntityDiff,
+    /// User differences.
+    pub users: EntityDiff,
+    /// Reminder differences.
+    pub reminders: EntityDiff,
+    /// Reminder marker differences.
+    pub reminder_markers: EntityDiff,
+    /// Budget differences, keyed as `user/tag/date`.
+    pub budgets: EntityDiff,
+}
+
+impl SnapshotDiff {
+    /// Returns `true` if the two snapshots hold the same entities.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+            && self.transactions.is_empty()
+            && self.tags.is_empty()
+            && self.merchants.is_empty()
+            && self.instruments.is_empty()
+            && self.companies.is_empty()
+            && self.countries.is_empty()
+            && self.users.is_empty()
+            && self.reminders.is_empty()
+            && self.reminder_markers.is_empty()
+            && self.budgets.is_empty()
+    }
+}
+
+/// Extracts the budget composite key as `user/tag/date`.
+pub(super) fn budget_key(budget: &Budget) -> String {
+    let tag = budget
+        .tag
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    format!("{}/{tag}/{}", budget.user, budget.date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_transaction(id: &str, outcome: f64) -> Transaction {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "changed": 1_700_000_000,
+            "created": 1_700_000_000,
+            "user": 1,
+            "deleted": false,
+            "incomeInstrument": 1,
+            "incomeAccount": "a-1",
+            "income": 0.0,
+            "outcomeInstrument": 1,
+            "outcomeAccount": "a-1",
+            "outcome": outcome,
+            "date": "2024-01-01",
+        }))
+        .unwrap()
+    }
+
+    fn snapshot_with(transactions: Vec<Transaction>) -> Snapshot {
+        Snapshot {
+            transactions,
+            ..Snapshot::new()
+        }
+    }
+
+    #[test]
+    fn anonymized_clears_transaction_details() {
+        let mut tx = test_transaction("tx-1", 42.5);
+        tx.payee = Some("Corner Cafe".to_owned());
+        tx.original_payee = Some("CORNER CAFE LLC".to_owned());
+        tx.comment = Some("lunch with Bob".to_owned());
+        tx.latitude = Some(55.75);
+        tx.longitude = Some(37.62);
+        tx.income_bank_id = Some("in-123".to_owned());
+        tx.outcome_bank_id = Some("out-456".to_owned());
+        let merchant: Merchant = serde_json::from_value(serde_json::json!({
+            "id": "m-1", "changed": 1_700_000_000, "user": 1, "title": "Corner Cafe"
+        }))
+        .unwrap();
+        let account: Account = serde_json::from_value(serde_json::json!({
+            "id": "a-1", "changed": 1_700_000_000, "user": 1, "role": null,
+            "instrument": 1, "company": null, "type": "ccard", "title": "Bob's card",
+            "syncID": ["1234"], "balance": 0.0, "startBalance": 0.0, "creditLimit": 0.0,
+            "inBalance": true, "savings": false, "enableCorrection": false,
+            "enableSMS": false, "archive": false, "private": false
+        }))
+        .unwrap();
+        let snapshot = Snapshot {
+            merchants: vec![merchant.clone()],
+            accounts: vec![account.clone()],
+            ..snapshot_with(vec![tx.clone()])
+        };
+
+        let anonymized = snapshot.anonymized();
+        assert_eq!(anonymized.merchants[0].title, "");
+        assert_eq!(anonymized.merchants[0].id, merchant.id);
+        assert_eq!(anonymized.accounts[0].title, "");
+        assert_eq!(anonymized.accounts[0].sync_id, None);
+        assert_eq!(anonymized.accounts[0].id, account.id);
+        let cleaned = &anonymized.transactions[0];
+        assert_eq!(cleaned.payee, None);
+        assert_eq!(cleaned.original_payee, None);
+        assert_eq!(cleaned.comment, None);
+        assert_eq!(cleaned.latitude, None);
+        assert_eq!(cleaned.longitude, None);
+        assert_eq!(cleaned.income_bank_id, None);
+        assert_eq!(cleaned.outcome_bank_id, None);
+        assert_eq!(cleaned.id, tx.id);
+        assert_eq!(cleaned.outcome, 42.5);
+        assert_eq!(cleaned.outcome_account, tx.outcome_account);
+        assert_eq!(cleaned.date, tx.date);
+        assert_eq!(*cleaned, tx.anonymized());
+    }
+
+    #[test]
+    fn diff_of_identical_snapshots_is_empty() {
+        let snapshot = snapshot_with(vec![test_transaction("tx-1", 10.0)]);
+        assert!(snapshot.diff(&snapshot.clone()).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_modified_transactions() {
+        let yesterday = snapshot_with(vec![
+            test_transaction("tx-1", 10.0),
+            test_transaction("tx-2", 20.0),
+        ]);
+        let today = snapshot_with(vec![
+            test_transaction("tx-1", 15.0),
+            test_transaction("tx-3", 30.0),
+        ]);
+
+        let diff = yesterday.diff(&today);
+        assert_eq!(diff.transactions.added, ["tx-3"]);
+        assert_eq!(diff.transactions.removed, ["tx-2"]);
+        assert_eq!(diff.transactions.modified, ["tx-1"]);
+        assert!(diff.accounts.is_empty());
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn diff_by_one_transaction() {
+        let before = snapshot_with(vec![test_transaction("tx-1", 10.0)]);
+        let after = snapshot_with(vec![
+            test_transaction("tx-1", 10.0),
+            test_transaction("tx-2", 5.0),
+        ]);
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.transactions,
+            EntityDiff {
+                added: vec!["tx-2".to_owned()],
+                removed: Vec::new(),
+                modified: Vec::new(),
+            }
+        );
+        assert_eq!(
+            SnapshotDiff {
+                transactions: EntityDiff::default(),
+                ..diff
+            },
+            SnapshotDiff::default()
+        );
+    }
+}
@@ -4,11 +4,43 @@
 //! [`BlockingStorage`] backend to provide automatic incremental sync
 //! and convenient query methods.
 
+use alloc::collections::BTreeMap;
+use core::cmp::Ordering;
+use core::time::Duration;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Datelike as _, Months, Utc};
+
 use crate::error::{Result, ZenMoneyError};
 use crate::models::{
-    AccountId, CompanyId, DiffResponse, InstrumentId, MerchantId, NaiveDate, ReminderId,
-    ReminderMarkerId, TagId, Transaction, TransactionId, UserId,
+    Account, AccountId, Budget, CompanyId, DiffResponse, Instrument, InstrumentId, Merchant,
+    MerchantId, NaiveDate, Reminder, ReminderId, ReminderMarker, ReminderMarkerId,
+    ReminderMarkerState, SuggestResponse, Tag, TagId, Transaction, TransactionId, User, UserId,
 };
+use crate::storage::Snapshot;
+
+mod dashboard;
+mod references;
+mod stats;
+mod suggestion;
+mod telemetry;
+
+pub use dashboard::AccountSummary;
+pub use references::{IntegrityIssue, ReferenceError, ReferenceField};
+pub use stats::{EntityStats, StorageStats};
+pub use suggestion::{Resolved, ResolvedSuggestion};
+
+/// Which side of a transaction the amount criteria apply to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Match on the `income` amount only.
+    Income,
+    /// Match on the `outcome` amount only.
+    Outcome,
+    /// Match if either side satisfies the criteria.
+    #[default]
+    Either,
+}
 
 /// Composable filter for querying transactions from storage.
 ///
@@ -37,16 +69,49 @@ pub struct TransactionFilter {
     pub date_to: Option<NaiveDate>,
     /// Account ID (matches `income_account` or `outcome_account`).
     pub account: Option<AccountId>,
-    /// Tag ID (matches if the transaction's tag list contains it).
-    pub tag: Option<TagId>,
+    /// Accepted tag IDs (matches if the transaction's tag list contains
+    /// any of them; empty means no tag criterion).
+    pub tags: Vec<TagId>,
     /// Payee substring (case-insensitive).
     pub payee: Option<String>,
+    /// Comment substring (case-insensitive).
+    pub comment: Option<String>,
     /// Merchant ID.
     pub merchant: Option<MerchantId>,
+    /// Exact merchant category code.
+    pub mcc: Option<i32>,
+    /// Set of accepted merchant category codes.
+    pub mcc_in: Option<Vec<i32>>,
     /// Minimum amount (matches if income >= val OR outcome >= val).
     pub min_amount: Option<f64>,
     /// Maximum amount (matches if income <= val AND outcome <= val).
     pub max_amount: Option<f64>,
+    /// Minimum net amount, `income - outcome` (inclusive).
+    pub min_net_amount: Option<f64>,
+    /// Maximum net amount, `income - outcome` (inclusive).
+    pub max_net_amount: Option<f64>,
+    /// Side of the transaction the amount criteria apply to.
+    pub direction: Direction,
+    /// Whether the transaction must (`true`) or must not (`false`) carry
+    /// QR code data.
+    pub has_qr: Option<bool>,
+    /// Whether the transaction must (`true`) or must not (`false`) carry
+    /// both coordinates.
+    pub has_location: Option<bool>,
+    /// Whether the transaction must (`true`) or must not (`false`) have a
+    /// merchant.
+    pub has_merchant: Option<bool>,
+    /// Whether the transaction must (`true`) or must not (`false`) carry
+    /// at least one tag.
+    pub has_tag: Option<bool>,
+    /// Whether the transaction must (`true`) or must not (`false`) be a
+    /// transfer (see [`Self::transfer`]).
+    pub transfer: Option<bool>,
+    /// Whether the payee must (`true`) or must not (`false`) differ from
+    /// the original payee (see [`Self::payee_differs_from_original`]).
+    pub payee_differs: Option<bool>,
+    /// Whether the combined criteria are inverted (see [`Self::negate`]).
+    pub negated: bool,
 }
 
 impl TransactionFilter {
@@ -57,6 +122,41 @@ impl TransactionFilter {
         Self::default()
     }
 
+    /// Matches transactions dated in the calendar month containing
+    /// `today`.
+    #[inline]
+    #[must_use]
+    pub fn this_month(today: NaiveDate) -> Self {
+        Self::new().month(today.year(), today.month())
+    }
+
+    /// Matches expenses: transactions with a positive outcome that are
+    /// not transfers.
+    #[inline]
+    #[must_use]
+    pub fn expenses() -> Self {
+        Self::new()
+            .direction(Direction::Outcome)
+            .min_amount(f64::MIN_POSITIVE)
+            .transfer(false)
+    }
+
+    /// Matches transactions whose income or outcome is at least
+    /// `threshold`.
+    #[inline]
+    #[must_use]
+    pub fn large(threshold: f64) -> Self {
+        Self::new().min_amount(threshold)
+    }
+
+    /// Matches transactions without any tag, leaving out transfers, which
+    /// are never categorized.
+    #[inline]
+    #[must_use]
+    pub fn uncategorized() -> Self {
+        Self::new().has_tag(false).transfer(false)
+    }
+
     /// Restricts to transactions within the given date range (inclusive).
     #[inline]
     #[must_use]
@@ -66,6 +166,24 @@ impl TransactionFilter {
         self
     }
 
+    /// Restricts to transactions dated in the given calendar month, from
+    /// its first to its last day.
+    ///
+    /// An invalid `month` (outside `1..=12`) or out-of-range `year`
+    /// yields an empty range that matches nothing.
+    #[inline]
+    #[must_use]
+    pub fn month(self, year: i32, month: u32) -> Self {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return self.date_range(NaiveDate::MAX, NaiveDate::MIN);
+        };
+        let last = first
+            .checked_add_months(Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(NaiveDate::MAX);
+        self.date_range(first, last)
+    }
+
     /// Restricts to transactions involving the given account.
     #[inline]
     #[must_use]
@@ -75,10 +193,26 @@ impl TransactionFilter {
     }
 
     /// Restricts to transactions tagged with the given tag.
+    ///
+    /// Adds `id` to the accepted tags, so repeated calls behave like
+    /// [`Self::tags_any`].
     #[inline]
     #[must_use]
     pub fn tag(mut self, id: TagId) -> Self {
-        self.tag = Some(id);
+        self.tags.push(id);
+        self
+    }
+
+    /// Restricts to transactions tagged with any of the given tags.
+    ///
+    /// The tags are OR-combined with each other: a transaction passes
+    /// the tag criterion if it carries at least one of them. The tag
+    /// criterion as a whole is still AND-combined with the date,
+    /// account, amount, and other criteria.
+    #[inline]
+    #[must_use]
+    pub fn tags_any(mut self, ids: Vec<TagId>) -> Self {
+        self.tags.extend(ids);
         self
     }
 
@@ -91,6 +225,17 @@ impl TransactionFilter {
         self
     }
 
+    /// Restricts to transactions whose comment contains the given
+    /// substring (case-insensitive).
+    ///
+    /// An empty string matches every transaction that has a comment.
+    #[inline]
+    #[must_use]
+    pub fn comment<T: Into<String>>(mut self, text: T) -> Self {
+        self.comment = Some(text.into());
+        self
+    }
+
     /// Restricts to transactions with the given merchant.
     #[inline]
     #[must_use]
@@ -99,6 +244,27 @@ impl TransactionFilter {
         self
     }
 
+    /// Restricts to transactions with the given merchant category code.
+    ///
+    /// Transactions without an MCC never match.
+    #[inline]
+    #[must_use]
+    pub const fn mcc(mut self, code: i32) -> Self {
+        self.mcc = Some(code);
+        self
+    }
+
+    /// Restricts to transactions whose merchant category code is one of
+    /// `codes`, e.g. `(5811..=5814).collect()` for all restaurant codes.
+    ///
+    /// Transactions without an MCC never match.
+    #[inline]
+    #[must_use]
+    pub fn mcc_in(mut self, codes: Vec<i32>) -> Self {
+        self.mcc_in = Some(codes);
+        self
+    }
+
     /// Restricts to transactions with amounts in the given range.
     ///
     /// A transaction matches if its income or outcome falls within
@@ -111,15 +277,136 @@ impl TransactionFilter {
         self
     }
 
-    /// Returns `true` if the transaction satisfies all set criteria.
+    /// Restricts to transactions with an amount of at least `min`, with
+    /// no upper bound.
+    #[inline]
+    #[must_use]
+    pub const fn min_amount(mut self, min: f64) -> Self {
+        self.min_amount = Some(min);
+        self
+    }
+
+    /// Restricts to transactions whose net effect, `income - outcome`,
+    /// falls within `[min, max]`.
+    ///
+    /// Expenses have a negative net amount, so
+    /// `net_amount_range(f64::MIN, -1000.0)` keeps net outflows of at
+    /// least 1000. This criterion is independent of
+    /// [`Self::amount_range`]; when both are set, a transaction must
+    /// satisfy both.
+    #[inline]
+    #[must_use]
+    pub const fn net_amount_range(mut self, min: f64, max: f64) -> Self {
+        self.min_net_amount = Some(min);
+        self.max_net_amount = Some(max);
+        self
+    }
+
+    /// Applies the amount criteria to one side of the transaction only.
+    ///
+    /// With [`Direction::Outcome`], `amount_range(50.0, 100.0)` matches
+    /// expenses between 50 and 100. Defaults to [`Direction::Either`].
+    #[inline]
+    #[must_use]
+    pub const fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Restricts to transactions with (`true`) or without (`false`) QR
+    /// code data.
+    #[inline]
+    #[must_use]
+    pub const fn has_qr(mut self, present: bool) -> Self {
+        self.has_qr = Some(present);
+        self
+    }
+
+    /// Restricts to transactions with (`true`) or without (`false`)
+    /// coordinates. A transaction has a location only if both latitude
+    /// and longitude are set.
+    #[inline]
+    #[must_use]
+    pub const fn has_location(mut self, present: bool) -> Self {
+        self.has_location = Some(present);
+        self
+    }
+
+    /// Restricts to transactions with (`true`) or without (`false`) a
+    /// merchant.
+    #[inline]
+    #[must_use]
+    pub const fn has_merchant(mut self, present: bool) -> Self {
+        self.has_merchant = Some(present);
+        self
+    }
+
+    /// Restricts to transactions with (`true`) or without (`false`) at
+    /// least one tag.
+    #[inline]
+    #[must_use]
+    pub const fn has_tag(mut self, present: bool) -> Self {
+        self.has_tag = Some(present);
+        self
+    }
+
+    /// Restricts to transfers (`true`) or to everything else (`false`).
+    ///
+    /// A transfer moves money between two different accounts, so both
+    /// its income and outcome are positive.
+    #[inline]
+    #[must_use]
+    pub const fn transfer(mut self, yes: bool) -> Self {
+        self.transfer = Some(yes);
+        self
+    }
+
+    /// Restricts to transactions whose payee was (`true`) or was not
+    /// (`false`) renamed from the original bank payee.
+    ///
+    /// A transaction counts as renamed only if both `payee` and
+    /// `original_payee` are set and differ case-insensitively.
+    #[inline]
+    #[must_use]
+    pub const fn payee_differs_from_original(mut self, yes: bool) -> Self {
+        self.payee_differs = Some(yes);
+        self
+    }
+
+    /// Inverts the filter so it matches exactly the transactions the
+    /// criteria reject, e.g. `TransactionFilter::new().tag(food).negate()`
+    /// for everything not tagged as food.
+    ///
+    /// The inversion covers all criteria, including ones added after this
+    /// call; negating twice restores the original filter. Deleted
+    /// transactions stay excluded by the client either way.
+    #[inline]
+    #[must_use]
+    pub const fn negate(mut self) -> Self {
+        self.negated = !self.negated;
+        self
+    }
+
+    /// Returns `true` if the transaction satisfies all set criteria, or
+    /// fails at least one of them when the filter is negated.
     #[inline]
     pub(crate) fn matches(&self, tx: &Transaction) -> bool {
+        self.matches_criteria(tx) != self.negated
+    }
+
+    /// Checks every criterion, ignoring negation.
+    fn matches_criteria(&self, tx: &Transaction) -> bool {
         self.matches_date(tx)
             && self.matches_account(tx)
             && self.matches_tag(tx)
             && self.matches_payee(tx)
+            && self.matches_payee_differs(tx)
+            && self.matches_comment(tx)
             && self.matches_merchant(tx)
+            && self.matches_mcc(tx)
             && self.matches_amount(tx)
+            && self.matches_net_amount(tx)
+            && self.matches_presence(tx)
     }
 
     /// Checks date range criteria.
@@ -137,9 +424,11 @@ impl TransactionFilter {
 
     /// Checks tag criteria.
     fn matches_tag(&self, tx: &Transaction) -> bool {
-        self.tag
-            .as_ref()
-            .is_none_or(|tag_id| tx.tag.as_ref().is_some_and(|tags| tags.contains(tag_id)))
+        self.tags.is_empty()
+            || tx
+                .tag
+                .as_ref()
+                .is_some_and(|tags| tags.iter().any(|tag_id| self.tags.contains(tag_id)))
     }
 
     /// Checks payee criteria.
@@ -152,6 +441,28 @@ impl TransactionFilter {
         })
     }
 
+    /// Checks the renamed-payee criterion.
+    fn matches_payee_differs(&self, tx: &Transaction) -> bool {
+        self.payee_differs.is_none_or(|want| {
+            let differs = tx
+                .payee
+                .as_ref()
+                .zip(tx.original_payee.as_ref())
+                .is_some_and(|(payee, original)| payee.to_lowercase() != original.to_lowercase());
+            differs == want
+        })
+    }
+
+    /// Checks comment criteria.
+    fn matches_comment(&self, tx: &Transaction) -> bool {
+        self.comment.as_ref().is_none_or(|comment| {
+            let comment_lower = comment.to_lowercase();
+            tx.comment
+                .as_ref()
+                .is_some_and(|c| c.to_lowercase().contains(&comment_lower))
+        })
+    }
+
     /// Checks merchant criteria.
     fn matches_merchant(&self, tx: &Transaction) -> bool {
         self.merchant
@@ -159,13 +470,162 @@ impl TransactionFilter {
             .is_none_or(|merchant_id| tx.merchant.as_ref().is_some_and(|m| m == merchant_id))
     }
 
+    /// Checks merchant category code criteria.
+    fn matches_mcc(&self, tx: &Transaction) -> bool {
+        self.mcc.is_none_or(|code| tx.mcc == Some(code))
+            && self
+                .mcc_in
+                .as_ref()
+                .is_none_or(|codes| tx.mcc.is_some_and(|mcc| codes.contains(&mcc)))
+    }
+
     /// Checks amount criteria.
     fn matches_amount(&self, tx: &Transaction) -> bool {
-        self.min_amount
-            .is_none_or(|min| tx.income >= min || tx.outcome >= min)
+        match self.direction {
+            Direction::Income => self.amount_in_range(tx.income),
+            Direction::Outcome => self.amount_in_range(tx.outcome),
+            Direction::Either => {
+                self.min_amount
+                    .is_none_or(|min| tx.income >= min || tx.outcome >= min)
+                    && self
+                        .max_amount
+                        .is_none_or(|max| tx.income <= max && tx.outcome <= max)
+            }
+        }
+    }
+
+    /// Checks net amount criteria.
+    fn matches_net_amount(&self, tx: &Transaction) -> bool {
+        let net = tx.income - tx.outcome;
+        self.min_net_amount.is_none_or(|min| net >= min)
+            && self.max_net_amount.is_none_or(|max| net <= max)
+    }
+
+    /// Checks a single amount against the min/max bounds.
+    fn amount_in_range(&self, amount: f64) -> bool {
+        self.min_amount.is_none_or(|min| amount >= min)
+            && self.max_amount.is_none_or(|max| amount <= max)
+    }
+
+    /// Checks QR, location, merchant, tag, and transfer criteria.
+    fn matches_presence(&self, tx: &Transaction) -> bool {
+        let has_location = tx.latitude.is_some() && tx.longitude.is_some();
+        let has_tag = tx.tag.as_ref().is_some_and(|tags| !tags.is_empty());
+        let transfer =
+            tx.income_account != tx.outcome_account && tx.income > 0.0_f64 && tx.outcome > 0.0_f64;
+        self.has_qr.is_none_or(|want| tx.qr_code.is_some() == want)
+            && self.has_location.is_none_or(|want| has_location == want)
             && self
-                .max_amount
-                .is_none_or(|max| tx.income <= max && tx.outcome <= max)
+                .has_merchant
+                .is_none_or(|want| tx.merchant.is_some() == want)
+            && self.has_tag.is_none_or(|want| has_tag == want)
+            && self.transfer.is_none_or(|want| transfer == want)
+    }
+}
+
+/// Ordering applied by the high-level clients' `transactions_sorted`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionSort {
+    /// Oldest transaction date first; ties broken by `created`.
+    DateAsc,
+    /// Newest transaction date first; ties broken by `created`.
+    #[default]
+    DateDesc,
+    /// Least recently changed first.
+    ChangedAsc,
+    /// Most recently changed first.
+    ChangedDesc,
+    /// Largest outcome amount first; `NaN` amounts last.
+    OutcomeDesc,
+    /// Largest income amount first; `NaN` amounts last.
+    IncomeDesc,
+}
+
+impl TransactionSort {
+    /// Compares two transactions according to this ordering.
+    fn compare(self, a: &Transaction, b: &Transaction) -> Ordering {
+        match self {
+            Self::DateAsc => a.date.cmp(&b.date).then(a.created.cmp(&b.created)),
+            Self::DateDesc => b.date.cmp(&a.date).then(b.created.cmp(&a.created)),
+            Self::ChangedAsc => a.changed.cmp(&b.changed),
+            Self::ChangedDesc => b.changed.cmp(&a.changed),
+            Self::OutcomeDesc => descending_nan_last(a.outcome, b.outcome),
+            Self::IncomeDesc => descending_nan_last(a.income, b.income),
+        }
+    }
+}
+
+/// Orders amounts from largest to smallest, placing `NaN` after all
+/// other values.
+fn descending_nan_last(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.total_cmp(&a),
+    }
+}
+
+/// A window into an ordered result set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    /// Number of leading items to skip.
+    pub offset: usize,
+    /// Maximum number of items to return.
+    pub limit: usize,
+}
+
+impl Page {
+    /// Creates a page starting at `offset` with at most `limit` items.
+    #[inline]
+    #[must_use]
+    pub const fn new(offset: usize, limit: usize) -> Self {
+        Self { offset, limit }
+    }
+}
+
+/// One page of results along with the size of the full result set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PagedResult<T> {
+    /// Items within the requested page.
+    pub items: Vec<T>,
+    /// Number of items matching the query before paging.
+    pub total: usize,
+}
+
+impl<T> PagedResult<T> {
+    /// Cuts `page` out of the full result set.
+    ///
+    /// An offset past the end or a zero limit yields no items.
+    fn paginate(all: Vec<T>, page: Page) -> Self {
+        let total = all.len();
+        let items = all.into_iter().skip(page.offset).take(page.limit).collect();
+        Self { items, total }
+    }
+}
+
+/// Picks the base instrument: the one with a rate of `1.0`.
+///
+/// When several instruments qualify, the currency of the main user (the
+/// one without a parent) wins. Without any qualifying instrument, the main
+/// user's currency is returned if it is stored.
+fn pick_base_instrument(instruments: Vec<Instrument>, users: &[User]) -> Option<Instrument> {
+    let user_currency = users
+        .iter()
+        .find(|user| user.parent.is_none())
+        .or_else(|| users.first())
+        .map(|user| user.currency);
+    let (base, others): (Vec<Instrument>, Vec<Instrument>) = instruments
+        .into_iter()
+        .partition(|instr| (instr.rate - 1.0).abs() < f64::EPSILON);
+    let is_user_currency = |instr: &Instrument| Some(instr.id) == user_currency;
+    if let Some(preferred) = base.iter().position(is_user_currency) {
+        return base.into_iter().nth(preferred);
+    }
+    if base.is_empty() {
+        others.into_iter().find(is_user_currency)
+    } else {
+        base.into_iter().next()
     }
 }
 
@@ -191,13 +651,47 @@ mod entity_type {
     pub(super) const REMINDER: &str = "reminder";
     /// Reminder marker entity type.
     pub(super) const REMINDER_MARKER: &str = "reminderMarker";
+    /// Budget entity type.
+    pub(super) const BUDGET: &str = "budget";
+
+    /// Every entity type above.
+    pub(super) const ALL: [&str; 11] = [
+        ACCOUNT,
+        TRANSACTION,
+        TAG,
+        MERCHANT,
+        INSTRUMENT,
+        COMPANY,
+        COUNTRY,
+        USER,
+        REMINDER,
+        REMINDER_MARKER,
+        BUDGET,
+    ];
+}
+
+/// Checks that every name is a known entity type and converts the names
+/// for [`DiffRequestBuilder::force_fetch_kinds`](crate::models::DiffRequestBuilder::force_fetch_kinds).
+fn force_fetch_kinds(entities: &[&str]) -> Result<Vec<String>> {
+    entities
+        .iter()
+        .map(|&name| {
+            if entity_type::ALL.contains(&name) {
+                Ok(name.to_owned())
+            } else {
+                Err(ZenMoneyError::Validation(format!(
+                    "unknown entity type for force fetch: {name}"
+                )))
+            }
+        })
+        .collect()
 }
 
 /// Groups [`Deletion`] records by entity type for batch processing.
 ///
 /// Numeric IDs (`instrument`, `company`, `country`, `user`) are parsed
-/// from the string representation. Unknown entity types are silently
-/// skipped with a tracing warning.
+/// from the string representation. Unknown entity types are skipped
+/// with a tracing warning and counted in `unknown`.
 struct GroupedDeletions {
     /// Account IDs to remove.
     accounts: Vec<AccountId>,
@@ -219,6 +713,10 @@ struct GroupedDeletions {
     reminders: Vec<ReminderId>,
     /// Reminder marker IDs to remove.
     reminder_markers: Vec<ReminderMarkerId>,
+    /// Budget IDs to remove.
+    budgets: Vec<String>,
+    /// Number of deletions of unknown entity types.
+    unknown: usize,
 }
 
 impl GroupedDeletions {
@@ -238,6 +736,8 @@ impl GroupedDeletions {
             users: Vec::new(),
             reminders: Vec::new(),
             reminder_markers: Vec::new(),
+            budgets: Vec::new(),
+            unknown: 0,
         };
         for deletion in &response.deletion {
             result.push_deletion(&deletion.object, &deletion.id)?;
@@ -262,96 +762,838 @@ impl GroupedDeletions {
             entity_type::REMINDER_MARKER => self
                 .reminder_markers
                 .push(ReminderMarkerId::new(id.to_owned())),
-            other => tracing::warn!(object = %other, id = %id, "unknown deletion type"),
+            entity_type::BUDGET => self.budgets.push(id.to_owned()),
+            other => {
+                tracing::warn!(object = %other, id = %id, "unknown deletion type");
+                self.unknown = self.unknown.saturating_add(1);
+            }
         }
         Ok(())
     }
 }
 
-/// Parses a numeric ID from a string, wrapping parse errors.
-fn parse_numeric_id<T: core::str::FromStr>(raw: &str) -> Result<T>
-where
-    T::Err: core::error::Error + Send + Sync + 'static,
-{
-    raw.parse::<T>()
-        .map_err(|err| ZenMoneyError::Storage(Box::new(err)))
+/// Collects the entities a diff response creates or updates into a
+/// storage batch.
+fn upserts_of(response: &DiffResponse) -> Snapshot {
+    Snapshot {
+        server_timestamp: None,
+        accounts: response.account.clone(),
+        transactions: response.transaction.clone(),
+        tags: response.tag.clone(),
+        merchants: response.merchant.clone(),
+        instruments: response.instrument.clone(),
+        companies: response.company.clone(),
+        countries: response.country.clone(),
+        users: response.user.clone(),
+        reminders: response.reminder.clone(),
+        reminder_markers: response.reminder_marker.clone(),
+        budgets: response.budget.clone(),
+    }
 }
 
-/// Generates a high-level ZenMoney client (async or blocking).
-macro_rules! define_zen_money {
-    (
-        client_name: $client:ident,
-        builder_name: $builder:ident,
-        http_client: $http_client:ty,
-        storage_trait: $storage_trait:ident,
-        client_doc: $client_doc:expr,
-        builder_doc: $builder_doc:expr,
-        $(async_kw: $async_kw:tt,)?
-        $(await_kw: $await_ext:tt,)?
-        $(send_bound: $send_bound:tt,)?
-    ) => {
-        #[doc = $builder_doc]
-        #[derive(Debug)]
-        pub struct $builder<S: $storage_trait> {
-            /// API token.
-            token: Option<String>,
-            /// Base URL override (for testing).
-            base_url: Option<String>,
-            /// Storage backend.
-            storage: Option<S>,
-        }
-
-        impl<S: $storage_trait> $builder<S> {
-            /// Sets the access token for API authentication.
-            #[inline]
-            #[must_use]
-            pub fn token<T: Into<String>>(mut self, token: T) -> Self {
-                self.token = Some(token.into());
-                self
-            }
+/// Entities whose `changed` timestamp must be bumped before pushing.
+///
+/// The server ignores an update whose `changed` is not newer than the
+/// stored one, so every mutating helper calls [`Touch::touch`] on the
+/// entities it modifies.
+trait Touch {
+    /// Sets `changed` to `now`.
+    fn touch(&mut self, now: DateTime<Utc>);
+}
 
-            /// Overrides the base URL (useful for testing with a mock server).
-            #[inline]
-            #[must_use]
-            pub fn base_url<T: Into<String>>(mut self, url: T) -> Self {
-                self.base_url = Some(url.into());
-                self
+/// Implements [`Touch`] for entity types with a `changed` field.
+macro_rules! impl_touch {
+    ($($entity:ty),+ $(,)?) => {
+        $(
+            impl Touch for $entity {
+                fn touch(&mut self, now: DateTime<Utc>) {
+                    self.changed = now;
+                }
             }
+        )+
+    };
+}
 
-            /// Sets the storage backend.
-            #[inline]
-            #[must_use]
-            pub fn storage(mut self, storage: S) -> Self {
-                self.storage = Some(storage);
-                self
-            }
+impl_touch!(
+    Account,
+    Transaction,
+    Tag,
+    Merchant,
+    Reminder,
+    ReminderMarker,
+    Budget
+);
+
+/// Verifies that the server did not move its timestamp backwards.
+///
+/// A request sent from [`DateTime::UNIX_EPOCH`] (initial or full sync) is
+/// always accepted.
+///
+/// # Errors
+///
+/// Returns [`ZenMoneyError::TimestampRegression`] if `received` is older
+/// than `sent`.
+fn check_timestamp_monotonic(sent: DateTime<Utc>, received: DateTime<Utc>) -> Result<()> {
+    if sent == DateTime::<Utc>::UNIX_EPOCH || received >= sent {
+        return Ok(());
+    }
+    tracing::warn!(sent = %sent, received = %received, "server timestamp regressed");
+    Err(ZenMoneyError::TimestampRegression { sent, received })
+}
 
-            /// Builds the high-level client.
-            ///
-            /// # Errors
-            ///
-            /// Returns [`ZenMoneyError::TokenExpired`] if no token was provided.
-            /// Returns [`ZenMoneyError::Storage`] if no storage was provided.
-            /// Returns [`ZenMoneyError::Http`] if the HTTP client fails to build.
-            #[inline]
-            pub fn build(self) -> Result<$client<S>> {
-                let storage = self.storage.ok_or_else(|| {
-                    ZenMoneyError::Storage("storage backend is required".into())
-                })?;
+/// Splits an account's balance into cleared and pending parts.
+///
+/// The cleared balance is `start_balance` plus every settled, non-deleted
+/// transaction touching the account; the pending total sums only the
+/// transactions on hold.
+fn split_cleared_and_pending(
+    start_balance: f64,
+    transactions: &[Transaction],
+    account_id: &AccountId,
+) -> (f64, f64) {
+    let mut cleared = start_balance;
+    let mut pending = 0.0_f64;
+    for tx in transactions.iter().filter(|tx| !tx.deleted) {
+        let delta = balance_delta(tx, account_id);
+        if tx.hold == Some(true) {
+            pending += delta;
+        } else {
+            cleared += delta;
+        }
+    }
+    (cleared, pending)
+}
 
-                let mut http_builder = <$http_client>::builder().token(
-                    self.token
-                        .ok_or(ZenMoneyError::TokenExpired)?,
-                );
-                if let Some(url) = self.base_url {
-                    http_builder = http_builder.base_url(url);
-                }
-                let client = http_builder.build()?;
+/// Returns how much `tx` changes the balance of `account_id`.
+fn balance_delta(tx: &Transaction, account_id: &AccountId) -> f64 {
+    let mut delta = 0.0_f64;
+    if tx.income_account == *account_id {
+        delta += tx.income;
+    }
+    if tx.outcome_account == *account_id {
+        delta -= tx.outcome;
+    }
+    delta
+}
 
-                Ok($client { client, storage })
-            }
+/// Builds a dense day-by-day balance series for `account_id`.
+///
+/// The balance at the end of each day in `from..=to` is `start_balance`
+/// plus every non-deleted transaction touching the account up to and
+/// including that day. Returns an empty series if `from` is after `to`.
+fn balance_series(
+    start_balance: f64,
+    transactions: &[Transaction],
+    account_id: &AccountId,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<(NaiveDate, f64)> {
+    let mut opening = start_balance;
+    let mut daily: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for tx in transactions
+        .iter()
+        .filter(|tx| !tx.deleted && tx.date <= to)
+    {
+        let delta = balance_delta(tx, account_id);
+        if tx.date < from {
+            opening += delta;
+        } else {
+            *daily.entry(tx.date).or_insert(0.0_f64) += delta;
         }
-
+    }
+    let mut balance = opening;
+    from.iter_days()
+        .take_while(|day| *day <= to)
+        .map(|day| {
+            balance += daily.get(&day).copied().unwrap_or(0.0_f64);
+            (day, balance)
+        })
+        .collect()
+}
+
+/// Replaces tag `from` with `into` in the tag list of each item that
+/// carries it.
+///
+/// Returns only the rewritten items, with `into` kept once and `changed`
+/// set to `now`.
+fn repoint_tag<T: Touch>(
+    items: Vec<T>,
+    tags: fn(&mut T) -> Option<&mut Vec<TagId>>,
+    from: &TagId,
+    into: &TagId,
+    now: DateTime<Utc>,
+) -> Vec<T> {
+    items
+        .into_iter()
+        .filter_map(|mut item| {
+            let item_tags = tags(&mut item)?;
+            if !item_tags.contains(from) {
+                return None;
+            }
+            let mut merged: Vec<TagId> = Vec::with_capacity(item_tags.len());
+            for tag in item_tags.drain(..) {
+                let target = if tag == *from { into.clone() } else { tag };
+                if !merged.contains(&target) {
+                    merged.push(target);
+                }
+            }
+            *item_tags = merged;
+            item.touch(now);
+            Some(item)
+        })
+        .collect()
+}
+
+/// Moves the budgets of tag `from` onto tag `into`.
+///
+/// A budget is skipped when `into` already has one for the same user and
+/// month, so existing limits on the target tag are never overwritten.
+/// Returns only the rewritten budgets, with `changed` set to `now`.
+fn repoint_budgets(
+    budgets: Vec<Budget>,
+    from: &TagId,
+    into: &TagId,
+    now: DateTime<Utc>,
+) -> Vec<Budget> {
+    let taken: HashSet<(UserId, NaiveDate)> = budgets
+        .iter()
+        .filter(|budget| budget.tag.as_ref() == Some(into))
+        .map(|budget| (budget.user, budget.date))
+        .collect();
+    budgets
+        .into_iter()
+        .filter(|budget| {
+            budget.tag.as_ref() == Some(from) && !taken.contains(&(budget.user, budget.date))
+        })
+        .map(|mut budget| {
+            budget.tag = Some(into.clone());
+            budget.touch(now);
+            budget
+        })
+        .collect()
+}
+
+/// Replaces account `from` with `into` on every side of each item that
+/// references it.
+///
+/// Returns only the rewritten items, with `changed` set to `now`.
+fn repoint_account<T: Touch>(
+    items: Vec<T>,
+    sides: fn(&mut T) -> [&mut AccountId; 2],
+    from: &AccountId,
+    into: &AccountId,
+    now: DateTime<Utc>,
+) -> Vec<T> {
+    items
+        .into_iter()
+        .filter_map(|mut item| {
+            let mut rewritten = false;
+            for account in sides(&mut item) {
+                if *account == *from {
+                    *account = into.clone();
+                    rewritten = true;
+                }
+            }
+            rewritten.then(|| {
+                item.touch(now);
+                item
+            })
+        })
+        .collect()
+}
+
+/// Instrument rates relative to the base currency, set up for
+/// converting amounts into one target instrument.
+struct RateTable {
+    /// Rate of every known instrument.
+    rates: HashMap<InstrumentId, f64>,
+    /// Rate of the target instrument; never zero.
+    target_rate: f64,
+}
+
+impl RateTable {
+    /// Builds a table converting into `target`.
+    ///
+    /// Fails with [`ZenMoneyError::NotFound`] if `target` is not among
+    /// `instruments`, or [`ZenMoneyError::Validation`] if its rate is
+    /// zero.
+    fn new(instruments: &[Instrument], target: InstrumentId) -> Result<Self> {
+        let rates: HashMap<InstrumentId, f64> = instruments
+            .iter()
+            .map(|instr| (instr.id, instr.rate))
+            .collect();
+        let target_rate = *rates
+            .get(&target)
+            .ok_or_else(|| missing_instrument(target))?;
+        if target_rate == 0.0_f64 {
+            return Err(ZenMoneyError::Validation(format!(
+                "instrument {target} has a zero rate"
+            )));
+        }
+        Ok(Self { rates, target_rate })
+    }
+
+    /// Converts `amount` from instrument `from` into the target, or
+    /// returns `None` if `from` has no known rate.
+    fn try_convert(&self, amount: f64, from: InstrumentId) -> Option<f64> {
+        self.rates
+            .get(&from)
+            .map(|rate| amount * rate / self.target_rate)
+    }
+
+    /// Converts `amount` from instrument `from` into the target, failing
+    /// with [`ZenMoneyError::NotFound`] if `from` has no known rate.
+    fn convert(&self, amount: f64, from: InstrumentId) -> Result<f64> {
+        self.try_convert(amount, from)
+            .ok_or_else(|| missing_instrument(from))
+    }
+}
+
+/// Builds the error for an instrument missing from storage.
+fn missing_instrument(id: InstrumentId) -> ZenMoneyError {
+    ZenMoneyError::NotFound {
+        entity: entity_type::INSTRUMENT,
+        id: id.to_string(),
+    }
+}
+
+/// Converts `amount` from one instrument to another via their rates
+/// relative to the base currency.
+fn convert_with_rates(
+    instruments: &[Instrument],
+    amount: f64,
+    from: InstrumentId,
+    to: InstrumentId,
+) -> Result<f64> {
+    RateTable::new(instruments, to)?.convert(amount, from)
+}
+
+/// Pairs each account with its balance converted to `base`.
+///
+/// Accounts without an instrument, or whose instrument is not in
+/// `instruments`, are skipped; a missing balance counts as zero.
+fn balances_in_base(
+    accounts: Vec<Account>,
+    instruments: &[Instrument],
+    base: &Instrument,
+) -> Result<Vec<(Account, f64)>> {
+    let table = RateTable::new(instruments, base.id)?;
+    Ok(accounts
+        .into_iter()
+        .filter_map(|account| {
+            let balance = account.balance.unwrap_or(0.0_f64);
+            let Some(converted) = account
+                .instrument
+                .and_then(|id| table.try_convert(balance, id))
+            else {
+                tracing::debug!(account = %account.id, "skipping account without known instrument");
+                return None;
+            };
+            Some((account, converted))
+        })
+        .collect())
+}
+
+/// Returns `true` if `last` is unset or more than `min_interval` before
+/// `now`. An interval too large for chrono is never exceeded.
+fn is_stale(last: Option<DateTime<Utc>>, now: DateTime<Utc>, min_interval: Duration) -> bool {
+    last.is_none_or(|synced| {
+        chrono::TimeDelta::from_std(min_interval)
+            .is_ok_and(|interval| now.signed_duration_since(synced) > interval)
+    })
+}
+
+/// Sums `outcome` per tag; untagged transactions go under `None`.
+///
+/// A transaction with several tags adds its full outcome to each of them.
+fn sum_outcome_by_tag(transactions: &[Transaction]) -> HashMap<Option<TagId>, f64> {
+    let mut totals: HashMap<Option<TagId>, f64> = HashMap::new();
+    for tx in transactions {
+        match tx.tag.as_deref() {
+            Some(tags) if !tags.is_empty() => {
+                for tag in tags {
+                    *totals.entry(Some(tag.clone())).or_default() += tx.outcome;
+                }
+            }
+            _ => *totals.entry(None).or_default() += tx.outcome,
+        }
+    }
+    totals
+}
+
+/// Sums `outcome` per tag after converting it into `currency`.
+///
+/// Untagged transactions are skipped, so their instruments are never
+/// looked up. A transaction with several tags adds its full converted
+/// outcome to each of them.
+fn sum_outcome_by_tag_in(
+    transactions: &[Transaction],
+    instruments: &[Instrument],
+    currency: InstrumentId,
+) -> Result<HashMap<TagId, f64>> {
+    let table = RateTable::new(instruments, currency)?;
+    let mut totals: HashMap<TagId, f64> = HashMap::new();
+    for tx in transactions {
+        let tags = tx.tag.as_deref().unwrap_or_default();
+        if tags.is_empty() || tx.outcome == 0.0_f64 {
+            continue;
+        }
+        let amount = table.convert(tx.outcome, tx.outcome_instrument)?;
+        for tag in tags {
+            *totals.entry(tag.clone()).or_default() += amount;
+        }
+    }
+    Ok(totals)
+}
+
+/// Drops transactions whose income and outcome accounts are both
+/// archived.
+///
+/// A transfer between an active and an archived account is kept.
+fn retain_active(transactions: Vec<Transaction>, accounts: &[Account]) -> Vec<Transaction> {
+    let archived: HashSet<&AccountId> = accounts
+        .iter()
+        .filter(|account| account.archive)
+        .map(|account| &account.id)
+        .collect();
+    transactions
+        .into_iter()
+        .filter(|tx| {
+            !(archived.contains(&tx.income_account) && archived.contains(&tx.outcome_account))
+        })
+        .collect()
+}
+
+/// Expands reminders into planned forecast markers within `[from, to]`.
+///
+/// Occurrences that already have a marker for the same reminder and date
+/// are skipped. Generated markers get a fresh UUID and `changed` set to
+/// `now`.
+fn plan_markers(
+    reminders: &[Reminder],
+    existing: &[ReminderMarker],
+    from: NaiveDate,
+    to: NaiveDate,
+    now: DateTime<Utc>,
+) -> Vec<ReminderMarker> {
+    let taken: HashSet<(&ReminderId, NaiveDate)> = existing
+        .iter()
+        .map(|marker| (&marker.reminder, marker.date))
+        .collect();
+    reminders
+        .iter()
+        .flat_map(|reminder| {
+            reminder
+                .occurrences(from, to)
+                .into_iter()
+                .filter(|&date| !taken.contains(&(&reminder.id, date)))
+                .map(move |date| ReminderMarker {
+                    id: ReminderMarkerId::new(uuid::Uuid::new_v4().to_string()),
+                    changed: now,
+                    user: reminder.user,
+                    income_instrument: reminder.income_instrument,
+                    income_account: reminder.income_account.clone(),
+                    income: reminder.income,
+                    outcome_instrument: reminder.outcome_instrument,
+                    outcome_account: reminder.outcome_account.clone(),
+                    outcome: reminder.outcome,
+                    tag: reminder.tag.clone(),
+                    merchant: reminder.merchant.clone(),
+                    payee: reminder.payee.clone(),
+                    comment: reminder.comment.clone(),
+                    date,
+                    reminder: reminder.id.clone(),
+                    state: ReminderMarkerState::Planned,
+                    notify: reminder.notify,
+                    is_forecast: Some(true),
+                })
+        })
+        .collect()
+}
+
+/// Returns `true` if `err` means the server could not answer (transport
+/// failure, timeout or `5xx`), as opposed to rejecting the request.
+const fn is_unavailable(err: &ZenMoneyError) -> bool {
+    matches!(
+        *err,
+        ZenMoneyError::Http(_)
+            | ZenMoneyError::Timeout(_)
+            | ZenMoneyError::Api {
+                status: 500..=599,
+                ..
+            }
+    )
+}
+
+/// Builds a suggestion from past transactions with the same payee.
+///
+/// Matches `payee` case-insensitively against each non-deleted
+/// transaction's payee or original payee, then picks the most frequent
+/// payee spelling, tag set, and merchant among the matches.
+fn suggest_from_history(transactions: &[Transaction], payee: &str) -> SuggestResponse {
+    let needle = payee.to_lowercase();
+    let matches: Vec<&Transaction> = transactions
+        .iter()
+        .filter(|tx| !tx.deleted)
+        .filter(|tx| {
+            [tx.payee.as_deref(), tx.original_payee.as_deref()]
+                .into_iter()
+                .flatten()
+                .any(|name| name.to_lowercase() == needle)
+        })
+        .collect();
+    SuggestResponse {
+        payee: most_frequent(matches.iter().filter_map(|tx| tx.payee.clone())),
+        merchant: most_frequent(matches.iter().filter_map(|tx| tx.merchant.clone())),
+        tag: most_frequent(
+            matches
+                .iter()
+                .filter_map(|tx| tx.tag.clone())
+                .filter(|tags| !tags.is_empty()),
+        ),
+    }
+}
+
+/// Returns the most frequent item; ties go to the earliest occurrence.
+fn most_frequent<T: PartialEq>(items: impl Iterator<Item = T>) -> Option<T> {
+    let mut counts: Vec<(T, usize)> = Vec::new();
+    for item in items {
+        if let Some(entry) = counts.iter_mut().find(|entry| entry.0 == item) {
+            entry.1 = entry.1.saturating_add(1);
+        } else {
+            counts.push((item, 1));
+        }
+    }
+    counts
+        .into_iter()
+        .reduce(|best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .map(|(item, _)| item)
+}
+
+/// Parses a numeric ID from a string, wrapping parse errors.
+fn parse_numeric_id<T: core::str::FromStr>(raw: &str) -> Result<T>
+where
+    T::Err: core::error::Error + Send + Sync + 'static,
+{
+    raw.parse::<T>()
+        .map_err(|err| ZenMoneyError::Storage(Box::new(err)))
+}
+
+/// Generates `push_*` methods, each with a `push_*_dry` twin that only
+/// builds the request, in either blocking or async style.
+macro_rules! push_methods {
+    ([$($async_kw:tt)?] [$($await_ext:tt)?]) => {};
+    (
+        [$($async_kw:tt)?] [$($await_ext:tt)?]
+        $(#[doc = $doc:literal])*
+        $push:ident / $dry:ident($items:ident: $ty:ty);
+        $($rest:tt)*
+    ) => {
+        $(#[doc = $doc])*
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the request fails validation, or the HTTP
+        /// request or storage update fails.
+        pub $($async_kw)? fn $push(&self, $items: Vec<$ty>) -> Result<DiffResponse> {
+            let request = self.$dry($items) $( .$await_ext )? ?;
+            self.send_diff(&request) $( .$await_ext )?
+        }
+
+        #[doc = concat!("Builds the request [`Self::", stringify!($push), "`] would send, without")]
+        /// sending it or touching storage.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the request fails validation or the
+        /// storage backend fails to read.
+        #[inline]
+        pub $($async_kw)? fn $dry(&self, $items: Vec<$ty>) -> Result<DiffRequest> {
+            self.base_diff_request() $( .$await_ext )? ?
+                .$items($items)
+                .build()
+        }
+
+        push_methods! { [$($async_kw)?] [$($await_ext)?] $($rest)* }
+    };
+}
+
+/// Generates `delete_*` methods, each with a `delete_*_dry` twin that only
+/// builds the request, in either blocking or async style.
+macro_rules! delete_methods {
+    ([$($async_kw:tt)?] [$($await_ext:tt)?]) => {};
+    (
+        [$($async_kw:tt)?] [$($await_ext:tt)?]
+        $(#[doc = $doc:literal])*
+        $delete:ident / $dry:ident($id:ty, $object:expr) => $remove:ident;
+        $($rest:tt)*
+    ) => {
+        $(#[doc = $doc])*
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the request fails validation, or the HTTP
+        /// request or storage update fails.
+        pub $($async_kw)? fn $delete(&self, ids: &[$id]) -> Result<DiffResponse> {
+            let request = self.$dry(ids) $( .$await_ext )? ?;
+            let response = self.send_diff(&request) $( .$await_ext )? ?;
+            self.storage.$remove(ids) $( .$await_ext )? ?;
+            Ok(response)
+        }
+
+        #[doc = concat!("Builds the request [`Self::", stringify!($delete), "`] would send, without")]
+        /// sending it or touching storage.
+        ///
+        /// Deletion stamps are taken at call time, so they differ from
+        /// those of a later real call.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the request fails validation or the
+        /// storage backend fails to read.
+        #[inline]
+        pub $($async_kw)? fn $dry(&self, ids: &[$id]) -> Result<DiffRequest> {
+            self.deletion_request(ids, $object) $( .$await_ext )?
+        }
+
+        delete_methods! { [$($async_kw)?] [$($await_ext)?] $($rest)* }
+    };
+}
+
+/// Generates a high-level ZenMoney client (async or blocking).
+macro_rules! define_zen_money {
+    (
+        client_name: $client:ident,
+        builder_name: $builder:ident,
+        http_client: $http_client:ty,
+        storage_trait: $storage_trait:ident,
+        client_doc: $client_doc:expr,
+        builder_doc: $builder_doc:expr,
+        $(async_kw: $async_kw:tt,)?
+        $(await_kw: $await_ext:tt,)?
+        $(send_bound: $send_bound:tt,)?
+    ) => {
+        #[doc = $builder_doc]
+        #[derive(Debug)]
+        pub struct $builder<S: $storage_trait> {
+            /// API token.
+            token: Option<String>,
+            /// Base URL override (for testing).
+            base_url: Option<String>,
+            /// Optional request-signing callback.
+            signer: Option<RequestSigner>,
+            /// Header name override for the request signature.
+            signature_header: Option<String>,
+            /// Retry behaviour for transiently failing requests.
+            retry_policy: Option<RetryPolicy>,
+            /// Per-request HTTP timeout override.
+            timeout: Option<Duration>,
+            /// Extra headers sent with every request.
+            headers: Vec<(String, String)>,
+            /// `User-Agent` override.
+            user_agent: Option<String>,
+            /// HTTP protocol version selection.
+            http_version: HttpVersion,
+            /// Whether sync force-fetches missing reference data.
+            auto_bootstrap: bool,
+            /// Storage backend.
+            storage: Option<S>,
+        }
+
+        impl<S: $storage_trait> $builder<S> {
+            /// Sets the access token for API authentication.
+            #[inline]
+            #[must_use]
+            pub fn token<T: Into<String>>(mut self, token: T) -> Self {
+                self.token = Some(token.into());
+                self
+            }
+
+            /// Overrides the base URL (useful for testing with a mock server).
+            #[inline]
+            #[must_use]
+            pub fn base_url<T: Into<String>>(mut self, url: T) -> Self {
+                self.base_url = Some(url.into());
+                self
+            }
+
+            /// Signs every request for gateways that require it.
+            ///
+            /// `signer` is called with the endpoint path and the exact
+            /// JSON body bytes sent; see the low-level client builder's
+            /// `request_signer` for details.
+            #[inline]
+            #[must_use]
+            pub fn request_signer<F>(mut self, signer: F) -> Self
+            where
+                F: Fn(&str, &[u8]) -> String + Send + Sync + 'static,
+            {
+                self.signer = Some(RequestSigner::new(signer));
+                self
+            }
+
+            /// Overrides the header carrying the request signature
+            /// (`X-Signature` by default).
+            #[inline]
+            #[must_use]
+            pub fn signature_header<T: Into<String>>(mut self, name: T) -> Self {
+                self.signature_header = Some(name.into());
+                self
+            }
+
+            /// Sets how requests failing transiently are retried.
+            ///
+            /// Applies to every endpoint. Defaults to no retries.
+            #[inline]
+            #[must_use]
+            pub const fn retry(mut self, policy: RetryPolicy) -> Self {
+                self.retry_policy = Some(policy);
+                self
+            }
+
+            /// Adds a header sent with every request.
+            ///
+            /// See the low-level client builder's `header` for how custom
+            /// headers interact with the ones the client sets itself.
+            #[inline]
+            #[must_use]
+            pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+                self.headers.push((name.into(), value.into()));
+                self
+            }
+
+            /// Sets the `User-Agent` header identifying the application.
+            ///
+            /// Defaults to `zenmoney-rs/<version>`.
+            #[inline]
+            #[must_use]
+            pub fn user_agent<T: Into<String>>(mut self, user_agent: T) -> Self {
+                self.user_agent = Some(user_agent.into());
+                self
+            }
+
+            /// Limits how long each HTTP request may take.
+            ///
+            /// Defaults to 30 seconds; a request exceeding it fails with
+            /// [`ZenMoneyError::Timeout`].
+            #[inline]
+            #[must_use]
+            pub const fn timeout(mut self, timeout: Duration) -> Self {
+                self.timeout = Some(timeout);
+                self
+            }
+
+            /// Restricts the client to HTTP/1.1; see the low-level client
+            /// builder's `http1_only`.
+            #[inline]
+            #[must_use]
+            pub const fn http1_only(mut self, enabled: bool) -> Self {
+                self.http_version = if enabled {
+                    HttpVersion::Http1Only
+                } else {
+                    HttpVersion::Auto
+                };
+                self
+            }
+
+            /// Speaks HTTP/2 without negotiating it; see the low-level
+            /// client builder's `http2_prior_knowledge`.
+            #[inline]
+            #[must_use]
+            pub const fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+                self.http_version = if enabled {
+                    HttpVersion::Http2PriorKnowledge
+                } else {
+                    HttpVersion::Auto
+                };
+                self
+            }
+
+            /// Makes `sync` force-fetch reference data
+            /// (instruments, companies, countries, and users) once when
+            /// `needs_full_sync` still holds afterwards.
+            ///
+            /// Off by default.
+            #[inline]
+            #[must_use]
+            pub const fn auto_bootstrap(mut self, enabled: bool) -> Self {
+                self.auto_bootstrap = enabled;
+                self
+            }
+
+            /// Sets the storage backend.
+            #[inline]
+            #[must_use]
+            pub fn storage(mut self, storage: S) -> Self {
+                self.storage = Some(storage);
+                self
+            }
+
+            /// Builds the high-level client.
+            ///
+            /// # Errors
+            ///
+            /// Returns [`ZenMoneyError::TokenExpired`] if no token was provided.
+            /// Returns [`ZenMoneyError::Storage`] if no storage was provided.
+            /// Returns [`ZenMoneyError::Validation`] if a custom header is
+            /// invalid.
+            /// Returns [`ZenMoneyError::Http`] if the HTTP client fails to build.
+            #[inline]
+            pub fn build(self) -> Result<$client<S>> {
+                let storage = self.storage.ok_or_else(|| {
+                    ZenMoneyError::Storage("storage backend is required".into())
+                })?;
+
+                let mut http_builder = <$http_client>::builder().token(
+                    self.token
+                        .ok_or(ZenMoneyError::TokenExpired)?,
+                );
+                if let Some(url) = self.base_url {
+                    http_builder = http_builder.base_url(url);
+                }
+                if let Some(signer) = self.signer {
+                    http_builder =
+                        http_builder.request_signer(move |path, body| signer.sign(path, body));
+                }
+                if let Some(header) = self.signature_header {
+                    http_builder = http_builder.signature_header(header);
+                }
+                if let Some(policy) = self.retry_policy {
+                    http_builder = http_builder.retry(policy);
+                }
+                if let Some(timeout) = self.timeout {
+                    http_builder = http_builder.timeout(timeout);
+                }
+                for (name, value) in self.headers {
+                    http_builder = http_builder.header(name, value);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    http_builder = http_builder.user_agent(user_agent);
+                }
+                match self.http_version {
+                    HttpVersion::Auto => {}
+                    HttpVersion::Http1Only => http_builder = http_builder.http1_only(true),
+                    HttpVersion::Http2PriorKnowledge => {
+                        http_builder = http_builder.http2_prior_knowledge(true);
+                    }
+                }
+                let client = http_builder.build()?;
+
+                Ok($client {
+                    client,
+                    storage,
+                    auto_bootstrap: self.auto_bootstrap,
+                    drift: AtomicBool::new(false),
+                })
+            }
+        }
+
         #[doc = $client_doc]
         #[derive(Debug)]
         pub struct $client<S: $storage_trait> {
@@ -359,6 +1601,11 @@ macro_rules! define_zen_money {
             client: $http_client,
             /// Storage backend.
             storage: S,
+            /// Whether sync force-fetches missing reference data.
+            auto_bootstrap: bool,
+            /// Set when a sync skipped deletions of unknown entity types;
+            /// cleared by a full sync.
+            drift: AtomicBool,
         }
 
         impl<S: $storage_trait> $client<S> {
@@ -369,6 +1616,14 @@ macro_rules! define_zen_money {
                 $builder {
                     token: None,
                     base_url: None,
+                    signer: None,
+                    signature_header: None,
+                    retry_policy: None,
+                    timeout: None,
+                    headers: Vec::new(),
+                    user_agent: None,
+                    http_version: HttpVersion::Auto,
+                    auto_bootstrap: false,
                     storage: None,
                 }
             }
@@ -385,6 +1640,26 @@ macro_rules! define_zen_money {
             /// or deletion ID parsing fails.
             #[tracing::instrument(skip_all)]
             pub $($async_kw)? fn sync(&self) -> Result<DiffResponse> {
+                let timer = SyncTimer::start();
+                let result = self.sync_once() $( .$await_ext )?;
+                timer.finish(&result);
+                result
+            }
+
+            /// Runs one incremental sync; [`Self::sync`] wraps it with
+            /// metrics reporting.
+            $($async_kw)? fn sync_once(&self) -> Result<DiffResponse> {
+                let response = self.pull_changes() $( .$await_ext )? ?;
+                if self.auto_bootstrap && self.needs_full_sync() $( .$await_ext )? ? {
+                    self.bootstrap_reference_data(response.server_timestamp)
+                        $( .$await_ext )? ?;
+                }
+                Ok(response)
+            }
+
+            /// Sends a request carrying no local changes and applies the
+            /// server's changes since the stored timestamp.
+            $($async_kw)? fn pull_changes(&self) -> Result<DiffResponse> {
                 let ts = self.storage.server_timestamp()
                     $( .$await_ext )?
                     ?
@@ -392,15 +1667,122 @@ macro_rules! define_zen_money {
                 tracing::debug!(server_timestamp = %ts, "starting incremental sync");
                 let request = DiffRequest::sync_only(ts, Utc::now());
                 let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
+                self.apply_diff(&request, &response) $( .$await_ext )? ?;
                 Ok(response)
             }
 
-            /// Performs a full sync: clears all stored data, then syncs
-            /// from epoch.
-            ///
-            /// Returns the diff response for inspection.
-            ///
+            /// Force-fetches instruments, companies, countries, and users
+            /// and applies them to storage.
+            $($async_kw)? fn bootstrap_reference_data(
+                &self,
+                server_timestamp: DateTime<Utc>,
+            ) -> Result<()> {
+                tracing::debug!("force-fetching missing reference data");
+                let request = DiffRequest::builder()
+                    .server_timestamp(server_timestamp)
+                    .force_fetch_kinds(
+                        [
+                            entity_type::INSTRUMENT,
+                            entity_type::COMPANY,
+                            entity_type::COUNTRY,
+                            entity_type::USER,
+                        ]
+                        .map(str::to_owned)
+                        .to_vec(),
+                    )
+                    .build()?;
+                let response = self.client.diff(&request) $( .$await_ext )? ?;
+                self.apply_diff(&request, &response) $( .$await_ext )?
+            }
+
+            /// Performs an incremental sync that also asks the server to
+            /// resend every entity of the given types.
+            ///
+            /// Use it to repair a damaged local table (e.g. `"instrument"`)
+            /// without clearing everything via `full_sync`. Names are the
+            /// server's entity type strings, such as `"account"`, `"tag"`,
+            /// or `"reminderMarker"`.
+            ///
+            /// # Errors
+            ///
+            /// Returns [`ZenMoneyError::Validation`] if a name is not a
+            /// known entity type, or an error if the HTTP request or
+            /// storage read/write fails.
+            #[inline]
+            #[tracing::instrument(skip_all)]
+            pub $($async_kw)? fn sync_force(&self, entities: &[&str]) -> Result<DiffResponse> {
+                let timer = SyncTimer::start();
+                let result = self.sync_force_once(entities) $( .$await_ext )?;
+                timer.finish(&result);
+                result
+            }
+
+            /// Runs one forced sync; [`Self::sync_force`] wraps it with
+            /// metrics reporting.
+            $($async_kw)? fn sync_force_once(&self, entities: &[&str]) -> Result<DiffResponse> {
+                let kinds = force_fetch_kinds(entities)?;
+                let ts = self.storage.server_timestamp()
+                    $( .$await_ext )?
+                    ?
+                    .unwrap_or(DateTime::<Utc>::UNIX_EPOCH);
+                tracing::debug!(server_timestamp = %ts, kinds = ?kinds, "starting forced sync");
+                let request = DiffRequest::builder()
+                    .server_timestamp(ts)
+                    .force_fetch_kinds(kinds)
+                    .build()?;
+                let response = self.client.diff(&request) $( .$await_ext )? ?;
+                self.apply_diff(&request, &response) $( .$await_ext )? ?;
+                Ok(response)
+            }
+
+            /// Pulls server changes without ever sending local data.
+            ///
+            /// Behaves like [`Self::sync`] but is guaranteed to send a
+            /// request with no entities, deletions, or force-fetch entries,
+            /// which makes it safe for strictly read-only mirrors. Unlike
+            /// `sync`, it never bootstraps missing reference data.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the HTTP request, storage read/write,
+            /// or deletion ID parsing fails.
+            #[inline]
+            #[tracing::instrument(skip_all)]
+            pub $($async_kw)? fn pull_only(&self) -> Result<DiffResponse> {
+                let timer = SyncTimer::start();
+                let result = self.pull_changes() $( .$await_ext )?;
+                timer.finish(&result);
+                result
+            }
+
+            /// Syncs only if more than `min_interval` has passed since the
+            /// last sync, returning `None` when the sync was skipped.
+            ///
+            /// The last sync time is the stored server timestamp, so the
+            /// throttle survives restarts; storage that has never been synced
+            /// is always stale.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if reading the stored timestamp or the sync
+            /// itself fails.
+            #[inline]
+            pub $($async_kw)? fn sync_if_stale(
+                &self,
+                min_interval: Duration,
+            ) -> Result<Option<DiffResponse>> {
+                let last = self.storage.server_timestamp() $( .$await_ext )? ?;
+                if !is_stale(last, Utc::now(), min_interval) {
+                    return Ok(None);
+                }
+                self.sync() $( .$await_ext )? .map(Some)
+            }
+
+            /// Performs a full sync: clears all stored data, then syncs
+            /// from epoch.
+            ///
+            /// Returns the diff response for inspection.
+            ///
             /// # Errors
             ///
             /// Returns an error if clearing storage, the HTTP request,
@@ -409,9 +1791,82 @@ macro_rules! define_zen_money {
             pub $($async_kw)? fn full_sync(&self) -> Result<DiffResponse> {
                 tracing::debug!("starting full sync");
                 self.storage.clear() $( .$await_ext )? ?;
+                self.drift.store(false, Ordering::Relaxed);
                 self.sync() $( .$await_ext )?
             }
 
+            /// Like [`Self::full_sync`], but restores the previous storage
+            /// contents if the sync fails.
+            ///
+            /// Everything in storage is copied into memory before clearing.
+            /// If the sync errors, the copy is written back with
+            /// `apply_snapshot` and the sync error is returned, so a flaky
+            /// connection does not leave the cache empty.
+            ///
+            /// # Errors
+            ///
+            /// Returns the sync error after restoring, or an error if
+            /// reading, clearing, or restoring storage fails.
+            #[inline]
+            #[tracing::instrument(skip_all)]
+            pub $($async_kw)? fn force_full_resync_preserving_backup(&self) -> Result<DiffResponse> {
+                let backup = self.snapshot() $( .$await_ext )? ?;
+                let drift = self.drift.load(Ordering::Relaxed);
+                match self.full_sync() $( .$await_ext )? {
+                    Ok(response) => Ok(response),
+                    Err(err) => {
+                        tracing::warn!(error = %err, "full sync failed, restoring backup");
+                        self.storage.apply_snapshot(backup) $( .$await_ext )? ?;
+                        self.drift.store(drift, Ordering::Relaxed);
+                        Err(err)
+                    }
+                }
+            }
+
+            /// Returns `true` when local data looks incomplete enough to
+            /// warrant a [`Self::full_sync`].
+            ///
+            /// That is the case if storage reports itself empty, has never
+            /// been synced (no server timestamp), or holds no users or no
+            /// instruments, which indicates an interrupted initial
+            /// bootstrap.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn needs_full_sync(&self) -> Result<bool> {
+                if self.storage.is_empty() $( .$await_ext )? ? {
+                    return Ok(true);
+                }
+                if self.storage.server_timestamp() $( .$await_ext )? ?.is_none() {
+                    return Ok(true);
+                }
+                if self.storage.users() $( .$await_ext )? ?.is_empty() {
+                    return Ok(true);
+                }
+                Ok(self.storage.instruments() $( .$await_ext )? ?.is_empty())
+            }
+
+            /// Returns `true` when local data has likely drifted from the
+            /// server and a [`Self::full_sync`] is advisable.
+            ///
+            /// Drift is reported if a sync since the last full sync
+            /// skipped deletions of entity types this client does not
+            /// know, or if stored transactions reference entities that
+            /// are not in storage (see [`Self::validate_references`]).
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn drift_detected(&self) -> Result<bool> {
+                if self.drift.load(Ordering::Relaxed) {
+                    return Ok(true);
+                }
+                Ok(!self.validate_references() $( .$await_ext )? ?.is_empty())
+            }
+
             /// Returns all accounts from storage.
             ///
             /// # Errors
@@ -462,6 +1917,54 @@ macro_rules! define_zen_money {
                 self.storage.instruments() $( .$await_ext )?
             }
 
+            /// Returns all accounts from storage keyed by ID, for repeated
+            /// lookups without rescanning.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn accounts_map(&self) -> Result<HashMap<AccountId, Account>> {
+                let all = self.storage.accounts() $( .$await_ext )? ?;
+                Ok(all.into_iter().map(|item| (item.id.clone(), item)).collect())
+            }
+
+            /// Returns all tags from storage keyed by ID, for repeated
+            /// lookups without rescanning.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn tags_map(&self) -> Result<HashMap<TagId, Tag>> {
+                let all = self.storage.tags() $( .$await_ext )? ?;
+                Ok(all.into_iter().map(|item| (item.id.clone(), item)).collect())
+            }
+
+            /// Returns all merchants from storage keyed by ID, for repeated
+            /// lookups without rescanning.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn merchants_map(&self) -> Result<HashMap<MerchantId, Merchant>> {
+                let all = self.storage.merchants() $( .$await_ext )? ?;
+                Ok(all.into_iter().map(|item| (item.id.clone(), item)).collect())
+            }
+
+            /// Returns all instruments from storage keyed by ID, for repeated
+            /// lookups without rescanning.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn instruments_map(&self) -> Result<HashMap<InstrumentId, Instrument>> {
+                let all = self.storage.instruments() $( .$await_ext )? ?;
+                Ok(all.into_iter().map(|item| (item.id, item)).collect())
+            }
+
             /// Returns all companies from storage.
             ///
             /// # Errors
@@ -537,9 +2040,112 @@ macro_rules! define_zen_money {
                 Ok(all.into_iter().filter(|tx| !tx.deleted && filter.matches(tx)).collect())
             }
 
-            /// Returns transactions within a date range (inclusive).
+            /// Returns one page of non-deleted transactions matching the
+            /// given filter, in storage order, along with the total number
+            /// of matches.
             ///
-            /// This is a convenience wrapper around [`Self::filter_transactions`].
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn filter_transactions_page(
+                &self,
+                filter: &TransactionFilter,
+                page: Page,
+            ) -> Result<PagedResult<Transaction>> {
+                let matching = self.filter_transactions(filter) $( .$await_ext )? ?;
+                Ok(PagedResult::paginate(matching, page))
+            }
+
+            /// Returns non-deleted transactions matching the given filter,
+            /// ordered by `sort`.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn transactions_sorted(
+                &self,
+                filter: &TransactionFilter,
+                sort: TransactionSort,
+            ) -> Result<Vec<Transaction>> {
+                let mut matching = self.filter_transactions(filter) $( .$await_ext )? ?;
+                matching.sort_by(|a, b| sort.compare(a, b));
+                Ok(matching)
+            }
+
+            /// Returns non-deleted transactions matching the given filter,
+            /// excluding those that only touch archived accounts.
+            ///
+            /// Accounts are loaded once. A transaction is dropped when both
+            /// its income and outcome accounts are archived, so transfers
+            /// between an active and an archived account are kept.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn filter_active_transactions(
+                &self,
+                filter: &TransactionFilter,
+            ) -> Result<Vec<Transaction>> {
+                let accounts = self.storage.accounts() $( .$await_ext )? ?;
+                let matching = self.filter_transactions(filter) $( .$await_ext )? ?;
+                Ok(retain_active(matching, &accounts))
+            }
+
+            /// Sums the `outcome` of non-deleted transactions matching the
+            /// filter, grouped by tag.
+            ///
+            /// Untagged transactions are collected under the `None` key. A
+            /// transaction with several tags contributes its full outcome to
+            /// each of them, so the per-tag sums can add up to more than the
+            /// total spent.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn spending_by_tag(
+                &self,
+                filter: &TransactionFilter,
+            ) -> Result<HashMap<Option<TagId>, f64>> {
+                let matching = self.filter_transactions(filter) $( .$await_ext )? ?;
+                Ok(sum_outcome_by_tag(&matching))
+            }
+
+            /// Sums the `outcome` of non-deleted transactions dated within
+            /// `[from, to]`, converted into `currency` and grouped by tag.
+            ///
+            /// Each outcome is converted with the stored instrument rates
+            /// before summing, so transactions in different currencies add
+            /// up to one figure. Untagged transactions are left out. A
+            /// transaction with several tags contributes its full outcome
+            /// to each of them.
+            ///
+            /// # Errors
+            ///
+            /// Returns [`ZenMoneyError::NotFound`] if `currency` or a
+            /// tagged transaction's instrument is not in storage,
+            /// [`ZenMoneyError::Validation`] if `currency` has a zero rate,
+            /// or an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn spending_by_tag_in(
+                &self,
+                from: NaiveDate,
+                to: NaiveDate,
+                currency: InstrumentId,
+            ) -> Result<HashMap<TagId, f64>> {
+                let instruments = self.storage.instruments() $( .$await_ext )? ?;
+                let in_range = self.transactions_by_date(from, to) $( .$await_ext )? ?;
+                sum_outcome_by_tag_in(&in_range, &instruments, currency)
+            }
+
+            /// Returns non-deleted transactions within a date range
+            /// (inclusive).
+            ///
+            /// Delegates to the storage backend's `transactions_in_range`,
+            /// so backends that can query by date avoid a full scan.
             ///
             /// # Errors
             ///
@@ -549,8 +2155,30 @@ macro_rules! define_zen_money {
                 from: NaiveDate,
                 to: NaiveDate,
             ) -> Result<Vec<Transaction>> {
-                self.filter_transactions(&TransactionFilter::new().date_range(from, to))
-                    $( .$await_ext )?
+                let in_range = self.storage.transactions_in_range(from, to) $( .$await_ext )? ?;
+                Ok(in_range.into_iter().filter(|tx| !tx.deleted).collect())
+            }
+
+            /// Returns an iterator over transactions within a date range
+            /// (inclusive).
+            ///
+            /// Unlike [`Self::transactions_by_date`], matching is lazy: the
+            /// iterator owns the stored transactions and filters them as it
+            /// is consumed, so adaptors can be chained without collecting
+            /// an intermediate `Vec`.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn transactions_between(
+                &self,
+                from: NaiveDate,
+                to: NaiveDate,
+            ) -> Result<impl Iterator<Item = Transaction>> {
+                let filter = TransactionFilter::new().date_range(from, to);
+                let all = self.storage.transactions() $( .$await_ext )? ?;
+                Ok(all.into_iter().filter(move |tx| !tx.deleted && filter.matches(tx)))
             }
 
             /// Returns transactions for a specific account (income or outcome).
@@ -569,6 +2197,79 @@ macro_rules! define_zen_money {
                 ) $( .$await_ext )?
             }
 
+            /// Returns non-deleted transactions whose payee, original payee,
+            /// or comment contains `query` (case-insensitive).
+            ///
+            /// Delegates to the storage backend's `search_transactions`,
+            /// so backends with a full-text index answer it natively.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn search_transactions(
+                &self,
+                query: &str,
+            ) -> Result<Vec<Transaction>> {
+                let found = self.storage.search_transactions(query) $( .$await_ext )? ?;
+                Ok(found.into_iter().filter(|tx| !tx.deleted).collect())
+            }
+
+            /// Returns `(cleared, pending)` balances for an account.
+            ///
+            /// The cleared balance starts from the account's
+            /// `start_balance` (zero if unset or the account is unknown)
+            /// and excludes transactions with `hold == Some(true)`; those
+            /// are summed into the pending total instead.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn cleared_and_pending_balance(
+                &self,
+                account_id: &AccountId,
+            ) -> Result<(f64, f64)> {
+                let accounts = self.storage.accounts() $( .$await_ext )? ?;
+                let start_balance = accounts
+                    .iter()
+                    .find(|acc| acc.id == *account_id)
+                    .and_then(|acc| acc.start_balance)
+                    .unwrap_or(0.0_f64);
+                let transactions = self.storage.transactions() $( .$await_ext )? ?;
+                Ok(split_cleared_and_pending(start_balance, &transactions, account_id))
+            }
+
+            /// Returns the account's balance at the end of each day from
+            /// `from` through `to`, inclusive.
+            ///
+            /// The series starts from the account's `start_balance` (zero
+            /// if unset or the account is unknown) plus every non-deleted
+            /// transaction before `from`, including those on hold. Days
+            /// without transactions repeat the previous day's balance, so
+            /// there is exactly one entry per day. The series is empty if
+            /// `from` is after `to`.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn balance_history(
+                &self,
+                account_id: &AccountId,
+                from: NaiveDate,
+                to: NaiveDate,
+            ) -> Result<Vec<(NaiveDate, f64)>> {
+                let accounts = self.storage.accounts() $( .$await_ext )? ?;
+                let start_balance = accounts
+                    .iter()
+                    .find(|acc| acc.id == *account_id)
+                    .and_then(|acc| acc.start_balance)
+                    .unwrap_or(0.0_f64);
+                let transactions = self.transactions_by_account(account_id) $( .$await_ext )? ?;
+                Ok(balance_series(start_balance, &transactions, account_id, from, to))
+            }
+
             /// Finds a tag by title (case-insensitive).
             ///
             /// # Errors
@@ -620,153 +2321,416 @@ macro_rules! define_zen_money {
                 Ok(all.into_iter().find(|instr| instr.id == id))
             }
 
-            /// Passes a suggest request through to the HTTP client.
+            /// Resolves an account's currency instrument.
+            ///
+            /// Returns `None` if the account has no instrument set or the
+            /// instrument is not in storage.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request fails.
+            /// Returns an error if the storage backend fails to read.
             #[inline]
-            pub $($async_kw)? fn suggest(
+            pub $($async_kw)? fn account_instrument(
                 &self,
-                request: &SuggestRequest,
-            ) -> Result<SuggestResponse> {
-                self.client.suggest(request) $( .$await_ext )?
-            }
-
-            // ── Push (create/update) methods ─────────────────────────
-
-            /// Helper: builds a [`DiffRequest`] pre-filled with sync timestamps.
-            $($async_kw)? fn base_diff_request(&self) -> Result<DiffRequest> {
-                let ts = self.storage.server_timestamp()
-                    $( .$await_ext )?
-                    ?
-                    .unwrap_or(DateTime::<Utc>::UNIX_EPOCH);
-                Ok(DiffRequest::sync_only(ts, Utc::now()))
-            }
-
-            /// Returns the user ID of the first stored user, or `0`
-            /// if no users have been synced yet.
-            $($async_kw)? fn current_user_id(&self) -> Result<i64> {
-                let users = self.storage.users() $( .$await_ext )? ?;
-                Ok(users.first().map_or(0, |u| u.id.into_inner()))
+                account: &Account,
+            ) -> Result<Option<Instrument>> {
+                let Some(id) = account.instrument else {
+                    return Ok(None);
+                };
+                self.instrument(id) $( .$await_ext )?
             }
 
-            /// Pushes accounts to the server (create or update).
+            /// Detects the base currency instrument of the account.
             ///
-            /// The server uses the `changed` timestamp for conflict
-            /// resolution. Returns the server's diff response after
-            /// applying any resulting changes to local storage.
+            /// By convention the base instrument has a rate of `1.0`. When
+            /// several instruments qualify, the user's configured currency
+            /// wins; without any, the user's currency is returned if stored.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn push_accounts(
-                &self,
-                accounts: Vec<Account>,
-            ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                request.account = accounts;
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                Ok(response)
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn base_instrument(&self) -> Result<Option<Instrument>> {
+                let instruments = self.storage.instruments() $( .$await_ext )? ?;
+                let users = self.storage.users() $( .$await_ext )? ?;
+                Ok(pick_base_instrument(instruments, &users))
             }
 
-            /// Pushes transactions to the server (create or update).
+            /// Pairs each active account with its balance converted to the
+            /// [base instrument](Self::base_instrument).
+            ///
+            /// Accounts with no instrument set, or whose instrument is not
+            /// in storage, are skipped. A missing balance counts as zero.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn push_transactions(
-                &self,
-                transactions: Vec<Transaction>,
-            ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                request.transaction = transactions;
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                Ok(response)
+            /// Returns [`ZenMoneyError::Validation`] if no base instrument
+            /// can be determined or it has a zero rate, or an error if the
+            /// storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn accounts_with_base_balance(&self) -> Result<Vec<(Account, f64)>> {
+                let instruments = self.storage.instruments() $( .$await_ext )? ?;
+                let users = self.storage.users() $( .$await_ext )? ?;
+                let base = pick_base_instrument(instruments.clone(), &users).ok_or_else(|| {
+                    ZenMoneyError::Validation("no base instrument in storage".to_owned())
+                })?;
+                let accounts = self.active_accounts() $( .$await_ext )? ?;
+                balances_in_base(accounts, &instruments, &base)
             }
 
-            /// Pushes tags to the server (create or update).
+            /// Returns every active account with its computed and
+            /// available balances, in display order.
+            ///
+            /// Storage is read once for all accounts; the balances are
+            /// derived as in [`Self::cleared_and_pending_balance`] (cleared
+            /// plus pending), and the order is that of
+            /// [`sort_accounts_for_display`].
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn push_tags(
-                &self,
-                tags: Vec<Tag>,
-            ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                request.tag = tags;
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                Ok(response)
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn dashboard_accounts(&self) -> Result<Vec<AccountSummary>> {
+                let mut accounts = self.active_accounts() $( .$await_ext )? ?;
+                sort_accounts_for_display(&mut accounts);
+                let transactions = self.storage.transactions() $( .$await_ext )? ?;
+                Ok(accounts
+                    .into_iter()
+                    .map(|account| AccountSummary::of(account, &transactions))
+                    .collect())
             }
 
-            /// Pushes merchants to the server (create or update).
+            /// Converts `amount` between currencies using the stored
+            /// instrument rates, as `amount * from.rate / to.rate`.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn push_merchants(
+            /// Returns [`ZenMoneyError::NotFound`] if either instrument is
+            /// not in storage, [`ZenMoneyError::Validation`] if `to` has a
+            /// zero rate, or an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn convert_amount(
                 &self,
-                merchants: Vec<Merchant>,
-            ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                request.merchant = merchants;
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                Ok(response)
+                amount: f64,
+                from: InstrumentId,
+                to: InstrumentId,
+            ) -> Result<f64> {
+                let instruments = self.storage.instruments() $( .$await_ext )? ?;
+                convert_with_rates(&instruments, amount, from, to)
             }
 
-            /// Pushes reminders to the server (create or update).
+            /// Passes a suggest request through to the HTTP client.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn push_reminders(
+            /// Returns an error if the HTTP request fails.
+            #[inline]
+            pub $($async_kw)? fn suggest(
                 &self,
-                reminders: Vec<Reminder>,
-            ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                request.reminder = reminders;
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                Ok(response)
+                request: &SuggestRequest,
+            ) -> Result<SuggestResponse> {
+                self.client.suggest(request) $( .$await_ext )?
+            }
+
+            /// Suggests a tag set and merchant from stored history.
+            ///
+            /// Looks up non-deleted transactions whose payee or original
+            /// payee equals `payee` (case-insensitive) and returns their
+            /// most frequent tags and merchant. Works offline; fields are
+            /// `None` when there is no matching history.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn suggest_local(&self, payee: &str) -> Result<SuggestResponse> {
+                let transactions = self.storage.transactions() $( .$await_ext )? ?;
+                Ok(suggest_from_history(&transactions, payee))
             }
 
-            /// Pushes reminder markers to the server (create or update).
+            /// Looks up the merchant and tags of a suggest response in
+            /// storage, so they can be shown by name.
+            ///
+            /// IDs missing from storage are returned as
+            /// [`Resolved::New`](crate::zen_money::Resolved::New).
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn push_reminder_markers(
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn resolve_suggestion(
                 &self,
-                markers: Vec<ReminderMarker>,
-            ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                request.reminder_marker = markers;
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                Ok(response)
+                response: &SuggestResponse,
+            ) -> Result<ResolvedSuggestion> {
+                let merchants = self.merchants_map() $( .$await_ext )? ?;
+                let tags = self.tags_map() $( .$await_ext )? ?;
+                Ok(ResolvedSuggestion::of(response, &merchants, &tags))
+            }
+
+            /// Summarizes the local storage: per-entity counts, the latest
+            /// `changed` timestamp of each entity type, and the server
+            /// timestamp of the last sync.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn storage_stats(&self) -> Result<StorageStats> {
+                Ok(StorageStats {
+                    server_timestamp: self.storage.server_timestamp() $( .$await_ext )? ?,
+                    accounts: EntityStats::of(
+                        &self.storage.accounts() $( .$await_ext )? ?,
+                        |account| account.changed,
+                    ),
+                    transactions: EntityStats::of(
+                        &self.storage.transactions() $( .$await_ext )? ?,
+                        |tx| tx.changed,
+                    ),
+                    tags: EntityStats::of(&self.storage.tags() $( .$await_ext )? ?, |tag| tag.changed),
+                    merchants: EntityStats::of(
+                        &self.storage.merchants() $( .$await_ext )? ?,
+                        |merchant| merchant.changed,
+                    ),
+                    instruments: EntityStats::of(
+                        &self.storage.instruments() $( .$await_ext )? ?,
+                        |instrument| instrument.changed,
+                    ),
+                    companies: EntityStats::of(
+                        &self.storage.companies() $( .$await_ext )? ?,
+                        |company| company.changed,
+                    ),
+                    countries: EntityStats::counted(&self.storage.countries() $( .$await_ext )? ?),
+                    users: EntityStats::of(&self.storage.users() $( .$await_ext )? ?, |user| user.changed),
+                    reminders: EntityStats::of(
+                        &self.storage.reminders() $( .$await_ext )? ?,
+                        |reminder| reminder.changed,
+                    ),
+                    reminder_markers: EntityStats::of(
+                        &self.storage.reminder_markers() $( .$await_ext )? ?,
+                        |marker| marker.changed,
+                    ),
+                    budgets: EntityStats::of(
+                        &self.storage.budgets() $( .$await_ext )? ?,
+                        |budget| budget.changed,
+                    ),
+                })
+            }
+
+            /// Reads every stored entity and the server timestamp into a
+            /// [`Snapshot`].
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn snapshot(&self) -> Result<Snapshot> {
+                Ok(Snapshot {
+                    server_timestamp: self.storage.server_timestamp() $( .$await_ext )? ?,
+                    accounts: self.storage.accounts() $( .$await_ext )? ?,
+                    transactions: self.storage.transactions() $( .$await_ext )? ?,
+                    tags: self.storage.tags() $( .$await_ext )? ?,
+                    merchants: self.storage.merchants() $( .$await_ext )? ?,
+                    instruments: self.storage.instruments() $( .$await_ext )? ?,
+                    companies: self.storage.companies() $( .$await_ext )? ?,
+                    countries: self.storage.countries() $( .$await_ext )? ?,
+                    users: self.storage.users() $( .$await_ext )? ?,
+                    reminders: self.storage.reminders() $( .$await_ext )? ?,
+                    reminder_markers: self.storage.reminder_markers() $( .$await_ext )? ?,
+                    budgets: self.storage.budgets() $( .$await_ext )? ?,
+                })
+            }
+
+            /// Returns the whole local storage as a [`DiffResponse`], the
+            /// shape the `/v8/diff/` endpoint answers with.
+            ///
+            /// `server_timestamp` is the timestamp of the last sync, or the
+            /// Unix epoch if storage was never synced, so applying the
+            /// document elsewhere resumes incremental sync from the same
+            /// point. `deletion` is always empty.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn export_diff(&self) -> Result<DiffResponse> {
+                let snapshot = self.snapshot() $( .$await_ext )? ?;
+                Ok(DiffResponse {
+                    server_timestamp: snapshot.server_timestamp.unwrap_or(DateTime::UNIX_EPOCH),
+                    instrument: snapshot.instruments,
+                    country: snapshot.countries,
+                    company: snapshot.companies,
+                    user: snapshot.users,
+                    account: snapshot.accounts,
+                    tag: snapshot.tags,
+                    merchant: snapshot.merchants,
+                    transaction: snapshot.transactions,
+                    reminder: snapshot.reminders,
+                    reminder_marker: snapshot.reminder_markers,
+                    budget: snapshot.budgets,
+                    deletion: Vec::new(),
+                })
+            }
+
+            /// Reports transaction references to entities missing from
+            /// storage.
+            ///
+            /// Scans non-deleted transactions for income/outcome accounts,
+            /// instruments, tags, and merchants with no stored entity of
+            /// that ID. An empty result means storage is consistent.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn validate_references(&self) -> Result<Vec<ReferenceError>> {
+                let transactions = self.storage.transactions() $( .$await_ext )? ?;
+                let accounts = self.storage.accounts() $( .$await_ext )? ?;
+                let instruments = self.storage.instruments() $( .$await_ext )? ?;
+                let tags = self.storage.tags() $( .$await_ext )? ?;
+                let merchants = self.storage.merchants() $( .$await_ext )? ?;
+                Ok(find_dangling_references(
+                    &transactions,
+                    &accounts,
+                    &instruments,
+                    &tags,
+                    &merchants,
+                ))
+            }
+
+            /// Checks referential integrity across all stored entities.
+            ///
+            /// Reports everything [`Self::validate_references`] does, plus
+            /// reminder markers whose reminder is missing and budgets whose
+            /// tag is missing. An empty result means storage is consistent.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn verify_integrity(&self) -> Result<Vec<IntegrityIssue>> {
+                let mut issues: Vec<IntegrityIssue> = self
+                    .validate_references()
+                    $( .$await_ext )? ?
+                    .into_iter()
+                    .map(IntegrityIssue::from)
+                    .collect();
+                issues.extend(find_orphaned_plans(
+                    &self.storage.reminder_markers() $( .$await_ext )? ?,
+                    &self.storage.reminders() $( .$await_ext )? ?,
+                    &self.storage.budgets() $( .$await_ext )? ?,
+                    &self.storage.tags() $( .$await_ext )? ?,
+                ));
+                Ok(issues)
+            }
+
+            /// Expands stored reminders into planned markers for the dates
+            /// they fall on within `[from, to]`.
+            ///
+            /// Dates that already have a marker for the same reminder, in
+            /// any state, are skipped. The generated markers are flagged as
+            /// forecasts and are neither stored nor pushed; see
+            /// [`Reminder::occurrences`] for how recurrence is expanded.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the storage backend fails to read.
+            #[inline]
+            pub $($async_kw)? fn generate_markers(
+                &self,
+                from: NaiveDate,
+                to: NaiveDate,
+            ) -> Result<Vec<ReminderMarker>> {
+                let reminders = self.storage.reminders() $( .$await_ext )? ?;
+                let existing = self.storage.reminder_markers() $( .$await_ext )? ?;
+                Ok(plan_markers(&reminders, &existing, from, to, Utc::now()))
             }
 
-            /// Pushes budgets to the server (create or update).
+            /// Asks the server for a suggestion, falling back to
+            /// [`Self::suggest_local`] when the server is unreachable or
+            /// gives an empty answer.
+            ///
+            /// A remote answer counts as empty when it carries neither
+            /// tags nor a merchant. Only transport failures, timeouts and
+            /// `5xx` responses trigger the fallback. The fallback uses the
+            /// request's payee.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn push_budgets(
+            /// Returns any other remote error, such as
+            /// [`ZenMoneyError::Unauthorized`] so the caller can refresh
+            /// the token. Also fails if the local fallback cannot read
+            /// storage.
+            #[inline]
+            pub $($async_kw)? fn suggest_or_local(
                 &self,
-                budgets: Vec<Budget>,
-            ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                request.budget = budgets;
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
+                request: &SuggestRequest,
+            ) -> Result<SuggestResponse> {
+                match self.client.suggest(request) $( .$await_ext )? {
+                    Ok(response)
+                        if response.merchant.is_some()
+                            || response.tag.as_ref().is_some_and(|tags| !tags.is_empty()) =>
+                    {
+                        return Ok(response);
+                    }
+                    Ok(_) => tracing::debug!("remote suggest returned nothing, using local history"),
+                    Err(err) if is_unavailable(&err) => {
+                        tracing::warn!(error = %err, "remote suggest failed, using local history");
+                    }
+                    Err(err) => return Err(err),
+                }
+                let payee = request.payee.as_deref().unwrap_or_default();
+                self.suggest_local(payee) $( .$await_ext )?
+            }
+
+            // ── Push (create/update) methods ─────────────────────────
+
+            /// Helper: starts a [`DiffRequest`] builder pre-filled with the
+            /// stored server timestamp.
+            $($async_kw)? fn base_diff_request(&self) -> Result<DiffRequestBuilder> {
+                let ts = self.storage.server_timestamp()
+                    $( .$await_ext )?
+                    ?
+                    .unwrap_or(DateTime::<Utc>::UNIX_EPOCH);
+                Ok(DiffRequest::builder().server_timestamp(ts))
+            }
+
+            /// Returns the user ID of the first stored user, or `0`
+            /// if no users have been synced yet.
+            $($async_kw)? fn current_user_id(&self) -> Result<i64> {
+                let users = self.storage.users() $( .$await_ext )? ?;
+                Ok(users.first().map_or(0, |u| u.id.into_inner()))
+            }
+
+            /// Helper: sends a prepared diff request and applies the
+            /// response to storage.
+            $($async_kw)? fn send_diff(&self, request: &DiffRequest) -> Result<DiffResponse> {
+                let response = self.client.diff(request) $( .$await_ext )? ?;
+                self.apply_diff(request, &response) $( .$await_ext )? ?;
                 Ok(response)
             }
 
+            push_methods! {
+                [$($async_kw)?] [$($await_ext)?]
+                /// Pushes accounts to the server (create or update).
+                ///
+                /// The server uses the `changed` timestamp for conflict
+                /// resolution. Returns the server's diff response after
+                /// applying any resulting changes to local storage.
+                push_accounts / push_accounts_dry(accounts: Account);
+                /// Pushes transactions to the server (create or update).
+                push_transactions / push_transactions_dry(transactions: Transaction);
+                /// Pushes tags to the server (create or update).
+                push_tags / push_tags_dry(tags: Tag);
+                /// Pushes merchants to the server (create or update).
+                push_merchants / push_merchants_dry(merchants: Merchant);
+                /// Pushes reminders to the server (create or update).
+                push_reminders / push_reminders_dry(reminders: Reminder);
+                /// Pushes reminder markers to the server (create or update).
+                push_reminder_markers / push_reminder_markers_dry(reminder_markers: ReminderMarker);
+                /// Pushes budgets to the server (create or update).
+                push_budgets / push_budgets_dry(budgets: Budget);
+            }
+
             // ── Delete methods ───────────────────────────────────────
 
             /// Helper: builds deletion records for the given IDs.
@@ -785,151 +2749,316 @@ macro_rules! define_zen_money {
                 .collect()
             }
 
-            /// Deletes accounts by ID.
-            ///
-            /// Constructs [`Deletion`] records and sends them via the diff
-            /// endpoint. Returns the server's response after applying
-            /// changes to local storage.
-            ///
-            /// # Errors
-            ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn delete_accounts(
+            /// Helper: builds a diff request deleting `ids` of the given
+            /// object type, stamped now by the current user.
+            $($async_kw)? fn deletion_request<Id: ToString + Sync>(
                 &self,
-                ids: &[AccountId],
-            ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
+                ids: &[Id],
+                object: &str,
+            ) -> Result<DiffRequest> {
                 let now = Utc::now();
                 let user = self.current_user_id() $( .$await_ext )? ?;
-                request.deletion = Self::build_deletions(
-                    ids.iter().map(ToString::to_string),
-                    entity_type::ACCOUNT,
-                    now,
-                    user,
-                );
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                self.storage.remove_accounts(ids) $( .$await_ext )? ?;
-                Ok(response)
+                self.base_diff_request() $( .$await_ext )? ?
+                    .deletions(Self::build_deletions(
+                        ids.iter().map(ToString::to_string),
+                        object,
+                        now,
+                        user,
+                    ))
+                    .build()
+            }
+
+            delete_methods! {
+                [$($async_kw)?] [$($await_ext)?]
+                /// Deletes accounts by ID.
+                ///
+                /// Constructs [`Deletion`] records and sends them via the diff
+                /// endpoint. Returns the server's response after applying
+                /// changes to local storage.
+                delete_accounts / delete_accounts_dry(AccountId, entity_type::ACCOUNT) => remove_accounts;
+                /// Deletes transactions by ID.
+                delete_transactions / delete_transactions_dry(TransactionId, entity_type::TRANSACTION) => remove_transactions;
+                /// Deletes tags by ID.
+                delete_tags / delete_tags_dry(TagId, entity_type::TAG) => remove_tags;
+                /// Deletes merchants by ID.
+                delete_merchants / delete_merchants_dry(MerchantId, entity_type::MERCHANT) => remove_merchants;
+                /// Deletes reminders by ID.
+                delete_reminders / delete_reminders_dry(ReminderId, entity_type::REMINDER) => remove_reminders;
+                /// Deletes reminder markers by ID.
+                delete_reminder_markers / delete_reminder_markers_dry(ReminderMarkerId, entity_type::REMINDER_MARKER) => remove_reminder_markers;
             }
 
-            /// Deletes transactions by ID.
+            /// Deletes transactions by ID, removing them locally before the
+            /// server confirms.
+            ///
+            /// The transactions disappear from storage immediately. If the
+            /// deletion cannot be sent or the server rejects it, the removed
+            /// transactions are re-inserted and the original error is
+            /// returned.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn delete_transactions(
+            /// Returns an error if the request fails validation, or the HTTP
+            /// request or storage update fails.
+            #[inline]
+            pub $($async_kw)? fn delete_transactions_optimistic(
                 &self,
                 ids: &[TransactionId],
             ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                let now = Utc::now();
-                let user = self.current_user_id() $( .$await_ext )? ?;
-                request.deletion = Self::build_deletions(
-                    ids.iter().map(ToString::to_string),
-                    entity_type::TRANSACTION,
-                    now,
-                    user,
-                );
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
+                let removed: Vec<Transaction> = self
+                    .storage
+                    .transactions() $( .$await_ext )? ?
+                    .into_iter()
+                    .filter(|tx| ids.contains(&tx.id))
+                    .collect();
                 self.storage.remove_transactions(ids) $( .$await_ext )? ?;
-                Ok(response)
+                match self.send_transaction_deletions(ids) $( .$await_ext )? {
+                    Ok((request, response)) => {
+                        self.apply_diff(&request, &response) $( .$await_ext )? ?;
+                        Ok(response)
+                    }
+                    Err(err) => {
+                        if let Err(rollback) =
+                            self.storage.upsert_transactions(removed) $( .$await_ext )?
+                        {
+                            tracing::warn!(
+                                error = %rollback,
+                                "failed to restore optimistically deleted transactions"
+                            );
+                        }
+                        Err(err)
+                    }
+                }
+            }
+
+            /// Helper: sends a diff deleting the given transactions, without
+            /// touching storage.
+            $($async_kw)? fn send_transaction_deletions(
+                &self,
+                ids: &[TransactionId],
+            ) -> Result<(DiffRequest, DiffResponse)> {
+                let request = self.delete_transactions_dry(ids) $( .$await_ext )? ?;
+                let response = self.client.diff(&request) $( .$await_ext )? ?;
+                Ok((request, response))
             }
 
-            /// Deletes tags by ID.
+            /// Archives an account.
+            ///
+            /// Sets the stored account's `archive` flag, bumps its
+            /// `changed` timestamp, and pushes it.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn delete_tags(
+            /// Returns [`ZenMoneyError::NotFound`] if the account is not in
+            /// storage, or an error if the HTTP request or storage update
+            /// fails.
+            #[inline]
+            pub $($async_kw)? fn archive_account(
                 &self,
-                ids: &[TagId],
+                id: &AccountId,
             ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                let now = Utc::now();
-                let user = self.current_user_id() $( .$await_ext )? ?;
-                request.deletion = Self::build_deletions(
-                    ids.iter().map(ToString::to_string),
-                    entity_type::TAG,
-                    now,
-                    user,
-                );
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                self.storage.remove_tags(ids) $( .$await_ext )? ?;
-                Ok(response)
+                let mut account = self
+                    .storage
+                    .accounts()
+                    $( .$await_ext )?
+                    ?
+                    .into_iter()
+                    .find(|account| account.id == *id)
+                    .ok_or_else(|| ZenMoneyError::NotFound {
+                        entity: entity_type::ACCOUNT,
+                        id: id.to_string(),
+                    })?;
+                account.archive = true;
+                account.touch(Utc::now());
+                self.push_accounts(vec![account]) $( .$await_ext )?
             }
 
-            /// Deletes merchants by ID.
+            /// Links a transaction to the reminder marker it realizes.
+            ///
+            /// Sets the stored transaction's `reminder_marker`, bumps its
+            /// `changed` timestamp, and pushes it.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn delete_merchants(
+            /// Returns [`ZenMoneyError::NotFound`] if the transaction or
+            /// marker is not in storage, or an error if the HTTP request
+            /// or storage update fails.
+            #[inline]
+            pub $($async_kw)? fn link_transaction_to_marker(
                 &self,
-                ids: &[MerchantId],
+                tx_id: &TransactionId,
+                marker_id: &ReminderMarkerId,
             ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
-                let now = Utc::now();
-                let user = self.current_user_id() $( .$await_ext )? ?;
-                request.deletion = Self::build_deletions(
-                    ids.iter().map(ToString::to_string),
-                    entity_type::MERCHANT,
-                    now,
-                    user,
-                );
-                let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                self.storage.remove_merchants(ids) $( .$await_ext )? ?;
-                Ok(response)
+                let markers = self.storage.reminder_markers() $( .$await_ext )? ?;
+                if !markers.iter().any(|marker| marker.id == *marker_id) {
+                    return Err(ZenMoneyError::NotFound {
+                        entity: entity_type::REMINDER_MARKER,
+                        id: marker_id.to_string(),
+                    });
+                }
+                let mut tx = self
+                    .storage
+                    .transactions()
+                    $( .$await_ext )?
+                    ?
+                    .into_iter()
+                    .find(|tx| tx.id == *tx_id)
+                    .ok_or_else(|| ZenMoneyError::NotFound {
+                        entity: entity_type::TRANSACTION,
+                        id: tx_id.to_string(),
+                    })?;
+                tx.reminder_marker = Some(marker_id.clone());
+                tx.touch(Utc::now());
+                self.push_transactions(vec![tx]) $( .$await_ext )?
             }
 
-            /// Deletes reminders by ID.
+            /// Merges tag `from` into tag `into`.
+            ///
+            /// Every stored non-deleted transaction, reminder, and reminder
+            /// marker tagged with `from` gets it replaced by `into` (without
+            /// duplicating `into`), and budgets of `from` move to `into`
+            /// unless `into` already has a budget for that month. The
+            /// rewritten entities are pushed together with the deletion of
+            /// `from` in a single diff request.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn delete_reminders(
+            /// Returns [`ZenMoneyError::Validation`] if `from` and `into`
+            /// are the same tag, or an error if the HTTP request or storage
+            /// update fails.
+            #[inline]
+            pub $($async_kw)? fn merge_tags(
                 &self,
-                ids: &[ReminderId],
+                from: &TagId,
+                into: &TagId,
             ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
+                if from == into {
+                    return Err(ZenMoneyError::Validation(format!(
+                        "cannot merge tag {from} into itself"
+                    )));
+                }
                 let now = Utc::now();
-                let user = self.current_user_id() $( .$await_ext )? ?;
-                request.deletion = Self::build_deletions(
-                    ids.iter().map(ToString::to_string),
-                    entity_type::REMINDER,
+                let live: Vec<Transaction> = self
+                    .storage
+                    .transactions() $( .$await_ext )? ?
+                    .into_iter()
+                    .filter(|tx| !tx.deleted)
+                    .collect();
+                let transactions = repoint_tag(live, |tx| tx.tag.as_mut(), from, into, now);
+                let reminders = repoint_tag(
+                    self.storage.reminders() $( .$await_ext )? ?,
+                    |reminder| reminder.tag.as_mut(),
+                    from,
+                    into,
+                    now,
+                );
+                let markers = repoint_tag(
+                    self.storage.reminder_markers() $( .$await_ext )? ?,
+                    |marker| marker.tag.as_mut(),
+                    from,
+                    into,
                     now,
-                    user,
                 );
+                let budgets = repoint_budgets(
+                    self.storage.budgets() $( .$await_ext )? ?,
+                    from,
+                    into,
+                    now,
+                );
+                let user = self.current_user_id() $( .$await_ext )? ?;
+                let request = self.base_diff_request() $( .$await_ext )? ?
+                    .transactions(transactions)
+                    .reminders(reminders)
+                    .reminder_markers(markers)
+                    .budgets(budgets)
+                    .deletions(Self::build_deletions(
+                        core::iter::once(from.to_string()),
+                        entity_type::TAG,
+                        now,
+                        user,
+                    ))
+                    .build()?;
                 let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                self.storage.remove_reminders(ids) $( .$await_ext )? ?;
+                self.apply_diff(&request, &response) $( .$await_ext )? ?;
+                self.storage.remove_tags(core::slice::from_ref(from)) $( .$await_ext )? ?;
                 Ok(response)
             }
 
-            /// Deletes reminder markers by ID.
+            /// Merges account `from` into account `into`.
+            ///
+            /// Every stored non-deleted transaction, reminder, and reminder
+            /// marker that uses `from` as its income or outcome account is
+            /// repointed to `into`, and the rewritten entities are pushed
+            /// together with the deletion of `from` in a single diff
+            /// request.
             ///
             /// # Errors
             ///
-            /// Returns an error if the HTTP request or storage update fails.
-            pub $($async_kw)? fn delete_reminder_markers(
+            /// Returns [`ZenMoneyError::Validation`] if `from` and `into`
+            /// are the same account, [`ZenMoneyError::NotFound`] if `into`
+            /// is not in storage, or an error if the HTTP request or
+            /// storage update fails.
+            #[inline]
+            pub $($async_kw)? fn merge_accounts(
                 &self,
-                ids: &[ReminderMarkerId],
+                from: &AccountId,
+                into: &AccountId,
             ) -> Result<DiffResponse> {
-                let mut request = self.base_diff_request() $( .$await_ext )? ?;
+                if from == into {
+                    return Err(ZenMoneyError::Validation(format!(
+                        "cannot merge account {from} into itself"
+                    )));
+                }
+                let accounts = self.storage.accounts() $( .$await_ext )? ?;
+                if !accounts.iter().any(|account| account.id == *into) {
+                    return Err(ZenMoneyError::NotFound {
+                        entity: entity_type::ACCOUNT,
+                        id: into.to_string(),
+                    });
+                }
                 let now = Utc::now();
-                let user = self.current_user_id() $( .$await_ext )? ?;
-                request.deletion = Self::build_deletions(
-                    ids.iter().map(ToString::to_string),
-                    entity_type::REMINDER_MARKER,
+                let live: Vec<Transaction> = self
+                    .storage
+                    .transactions() $( .$await_ext )? ?
+                    .into_iter()
+                    .filter(|tx| !tx.deleted)
+                    .collect();
+                let transactions = repoint_account(
+                    live,
+                    |tx| [&mut tx.income_account, &mut tx.outcome_account],
+                    from,
+                    into,
+                    now,
+                );
+                let reminders = repoint_account(
+                    self.storage.reminders() $( .$await_ext )? ?,
+                    |reminder| [&mut reminder.income_account, &mut reminder.outcome_account],
+                    from,
+                    into,
                     now,
-                    user,
                 );
+                let markers = repoint_account(
+                    self.storage.reminder_markers() $( .$await_ext )? ?,
+                    |marker| [&mut marker.income_account, &mut marker.outcome_account],
+                    from,
+                    into,
+                    now,
+                );
+                let user = self.current_user_id() $( .$await_ext )? ?;
+                let request = self.base_diff_request() $( .$await_ext )? ?
+                    .transactions(transactions)
+                    .reminders(reminders)
+                    .reminder_markers(markers)
+                    .deletions(Self::build_deletions(
+                        core::iter::once(from.to_string()),
+                        entity_type::ACCOUNT,
+                        now,
+                        user,
+                    ))
+                    .build()?;
                 let response = self.client.diff(&request) $( .$await_ext )? ?;
-                self.apply_diff(&response) $( .$await_ext )? ?;
-                self.storage.remove_reminder_markers(ids) $( .$await_ext )? ?;
+                self.apply_diff(&request, &response) $( .$await_ext )? ?;
+                self.storage.remove_accounts(core::slice::from_ref(from)) $( .$await_ext )? ?;
                 Ok(response)
             }
 
@@ -949,8 +3078,16 @@ macro_rules! define_zen_money {
 
             /// Applies upserts and deletions from a diff response to
             /// storage.
+            ///
+            /// Rejects responses whose server timestamp is older than the
+            /// one sent in `request` before touching storage.
             #[tracing::instrument(skip_all)]
-            $($async_kw)? fn apply_diff(&self, response: &DiffResponse) -> Result<()> {
+            $($async_kw)? fn apply_diff(
+                &self,
+                request: &DiffRequest,
+                response: &DiffResponse,
+            ) -> Result<()> {
+                check_timestamp_monotonic(request.server_timestamp, response.server_timestamp)?;
                 self.apply_upserts(response) $( .$await_ext )? ?;
                 self.apply_deletions(response) $( .$await_ext )? ?;
                 self.storage
@@ -963,42 +3100,10 @@ macro_rules! define_zen_money {
                 Ok(())
             }
 
-            /// Upserts all entity types from a diff response.
+            /// Upserts all entity types from a diff response in one
+            /// storage batch.
             $($async_kw)? fn apply_upserts(&self, response: &DiffResponse) -> Result<()> {
-                if !response.account.is_empty() {
-                    self.storage.upsert_accounts(response.account.clone()) $( .$await_ext )? ?;
-                }
-                if !response.transaction.is_empty() {
-                    self.storage.upsert_transactions(response.transaction.clone()) $( .$await_ext )? ?;
-                }
-                if !response.tag.is_empty() {
-                    self.storage.upsert_tags(response.tag.clone()) $( .$await_ext )? ?;
-                }
-                if !response.merchant.is_empty() {
-                    self.storage.upsert_merchants(response.merchant.clone()) $( .$await_ext )? ?;
-                }
-                if !response.instrument.is_empty() {
-                    self.storage.upsert_instruments(response.instrument.clone()) $( .$await_ext )? ?;
-                }
-                if !response.company.is_empty() {
-                    self.storage.upsert_companies(response.company.clone()) $( .$await_ext )? ?;
-                }
-                if !response.country.is_empty() {
-                    self.storage.upsert_countries(response.country.clone()) $( .$await_ext )? ?;
-                }
-                if !response.user.is_empty() {
-                    self.storage.upsert_users(response.user.clone()) $( .$await_ext )? ?;
-                }
-                if !response.reminder.is_empty() {
-                    self.storage.upsert_reminders(response.reminder.clone()) $( .$await_ext )? ?;
-                }
-                if !response.reminder_marker.is_empty() {
-                    self.storage.upsert_reminder_markers(response.reminder_marker.clone()) $( .$await_ext )? ?;
-                }
-                if !response.budget.is_empty() {
-                    self.storage.upsert_budgets(response.budget.clone()) $( .$await_ext )? ?;
-                }
-                Ok(())
+                self.storage.upsert_batch(upserts_of(response)) $( .$await_ext )?
             }
 
             /// Processes deletion records from a diff response.
@@ -1007,6 +3112,9 @@ macro_rules! define_zen_money {
                     return Ok(());
                 }
                 let groups = GroupedDeletions::from_response(response)?;
+                if groups.unknown > 0 {
+                    self.drift.store(true, Ordering::Relaxed);
+                }
                 if !groups.accounts.is_empty() {
                     self.storage.remove_accounts(&groups.accounts) $( .$await_ext )? ?;
                 }
@@ -1037,6 +3145,9 @@ macro_rules! define_zen_money {
                 if !groups.reminder_markers.is_empty() {
                     self.storage.remove_reminder_markers(&groups.reminder_markers) $( .$await_ext )? ?;
                 }
+                if !groups.budgets.is_empty() {
+                    self.storage.remove_budgets(&groups.budgets) $( .$await_ext )? ?;
+                }
                 Ok(())
             }
         }
@@ -1049,18 +3160,32 @@ macro_rules! define_zen_money {
 mod async_zen_money {
     //! Async high-level client.
 
-    use crate::client::ZenMoneyClient;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use core::time::Duration;
+    use std::collections::HashMap;
+
+    use crate::client::{HttpVersion, RequestSigner, RetryPolicy, ZenMoneyClient};
     use crate::error::{Result, ZenMoneyError};
     use crate::models::{
-        Account, AccountId, Budget, Company, Country, Deletion, DiffRequest, DiffResponse,
-        Instrument, InstrumentId, Merchant, MerchantId, NaiveDate, Reminder, ReminderId,
-        ReminderMarker, ReminderMarkerId, SuggestRequest, SuggestResponse, Tag, TagId, Transaction,
-        TransactionId, User,
+        Account, AccountId, Budget, Company, Country, Deletion, DiffRequest, DiffRequestBuilder,
+        DiffResponse, Instrument, InstrumentId, Merchant, MerchantId, NaiveDate, Reminder,
+        ReminderId, ReminderMarker, ReminderMarkerId, SuggestRequest, SuggestResponse, Tag, TagId,
+        Transaction, TransactionId, User, sort_accounts_for_display,
     };
-    use crate::storage::Storage;
+    use crate::storage::{Snapshot, Storage};
     use chrono::{DateTime, Utc};
 
-    use super::{GroupedDeletions, TransactionFilter, entity_type};
+    use super::references::{find_dangling_references, find_orphaned_plans};
+    use super::telemetry::SyncTimer;
+    use super::{
+        AccountSummary, EntityStats, GroupedDeletions, IntegrityIssue, Page, PagedResult,
+        ReferenceError, ResolvedSuggestion, StorageStats, Touch, TransactionFilter,
+        TransactionSort, balance_series, balances_in_base, check_timestamp_monotonic,
+        convert_with_rates, entity_type, force_fetch_kinds, is_stale, is_unavailable,
+        pick_base_instrument, plan_markers, repoint_account, repoint_budgets, repoint_tag,
+        retain_active, split_cleared_and_pending, suggest_from_history, sum_outcome_by_tag,
+        sum_outcome_by_tag_in, upserts_of,
+    };
 
     define_zen_money! {
         client_name: ZenMoney,
@@ -1081,18 +3206,32 @@ mod async_zen_money {
 mod blocking_zen_money {
     //! Blocking high-level client.
 
-    use crate::client::ZenMoneyBlockingClient;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use core::time::Duration;
+    use std::collections::HashMap;
+
+    use crate::client::{HttpVersion, RequestSigner, RetryPolicy, ZenMoneyBlockingClient};
     use crate::error::{Result, ZenMoneyError};
     use crate::models::{
-        Account, AccountId, Budget, Company, Country, Deletion, DiffRequest, DiffResponse,
-        Instrument, InstrumentId, Merchant, MerchantId, NaiveDate, Reminder, ReminderId,
-        ReminderMarker, ReminderMarkerId, SuggestRequest, SuggestResponse, Tag, TagId, Transaction,
-        TransactionId, User,
+        Account, AccountId, Budget, Company, Country, Deletion, DiffRequest, DiffRequestBuilder,
+        DiffResponse, Instrument, InstrumentId, Merchant, MerchantId, NaiveDate, Reminder,
+        ReminderId, ReminderMarker, ReminderMarkerId, SuggestRequest, SuggestResponse, Tag, TagId,
+        Transaction, TransactionId, User, sort_accounts_for_display,
     };
-    use crate::storage::BlockingStorage;
+    use crate::storage::{BlockingStorage, Snapshot};
     use chrono::{DateTime, Utc};
 
-    use super::{GroupedDeletions, TransactionFilter, entity_type};
+    use super::references::{find_dangling_references, find_orphaned_plans};
+    use super::telemetry::SyncTimer;
+    use super::{
+        AccountSummary, EntityStats, GroupedDeletions, IntegrityIssue, Page, PagedResult,
+        ReferenceError, ResolvedSuggestion, StorageStats, Touch, TransactionFilter,
+        TransactionSort, balance_series, balances_in_base, check_timestamp_monotonic,
+        convert_with_rates, entity_type, force_fetch_kinds, is_stale, is_unavailable,
+        pick_base_instrument, plan_markers, repoint_account, repoint_budgets, repoint_tag,
+        retain_active, split_cleared_and_pending, suggest_from_history, sum_outcome_by_tag,
+        sum_outcome_by_tag_in, upserts_of,
+    };
 
     define_zen_money! {
         client_name: ZenMoneyBlocking,
@@ -1115,11 +3254,52 @@ mod tests {
     use crate::models::{
         Account, AccountId, AccountType, Budget, Deletion, DiffResponse, Instrument, InstrumentId,
         Merchant, MerchantId, NaiveDate, Reminder, ReminderId, ReminderMarker, ReminderMarkerId,
-        Tag, TagId, Transaction, TransactionId, UserId,
+        Tag, TagId, Transaction, TransactionId, User, UserId,
     };
     use crate::storage::InMemoryStorage;
     use chrono::DateTime;
 
+    /// Asserts that a pushed entity's `changed` is within a second of now.
+    #[cfg(feature = "blocking")]
+    fn assert_recently_changed(entity: &serde_json::Value) {
+        let changed = entity["changed"].as_i64().unwrap();
+        let drift = (Utc::now().timestamp() - changed).abs();
+        assert!(drift <= 1, "changed is {drift}s away from now");
+    }
+
+    /// Creates a minimal test user.
+    fn test_user() -> User {
+        User {
+            id: UserId::new(1_i64),
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            login: None,
+            currency: InstrumentId::new(1_i32),
+            parent: None,
+            country: None,
+            country_code: None,
+            email: None,
+            is_forecast_enabled: None,
+            month_start_day: None,
+            paid_till: None,
+            plan_balance_mode: None,
+            plan_settings: None,
+            subscription: None,
+            subscription_renewal_date: None,
+        }
+    }
+
+    /// Creates a minimal test instrument.
+    fn test_instrument() -> Instrument {
+        Instrument {
+            id: InstrumentId::new(1_i32),
+            title: "Russian Ruble".to_owned(),
+            short_title: "RUB".to_owned(),
+            symbol: "₽".to_owned(),
+            rate: 1.0,
+            changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        }
+    }
+
     /// Creates a minimal test account.
     fn test_account(id: &str, title: &str, archive: bool) -> Account {
         Account {
@@ -1368,51 +3548,403 @@ mod tests {
     }
 
     #[test]
-    fn filter_merchant() {
-        let merchant_id = MerchantId::new("m-1".to_owned());
-        let filter = TransactionFilter::new().merchant(merchant_id.clone());
+    fn preset_this_month_spans_calendar_month() {
+        let filter = TransactionFilter::this_month(NaiveDate::from_ymd_opt(2024, 2, 17).unwrap());
+        assert_eq!(filter.date_from, NaiveDate::from_ymd_opt(2024, 2, 1));
+        assert_eq!(filter.date_to, NaiveDate::from_ymd_opt(2024, 2, 29));
+        let december =
+            TransactionFilter::this_month(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap());
+        assert_eq!(december.date_from, NaiveDate::from_ymd_opt(2023, 12, 1));
+        assert_eq!(december.date_to, NaiveDate::from_ymd_opt(2023, 12, 31));
+    }
 
-        let matching = test_transaction_full(
-            "t1",
-            "a-1",
-            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
-            0.0,
-            100.0,
-            None,
-            None,
-            Some(merchant_id),
-        );
-        let not_matching = test_transaction_full(
-            "t2",
-            "a-1",
-            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
-            0.0,
-            100.0,
-            None,
-            None,
-            None,
-        );
+    #[test]
+    fn month_handles_leap_february() {
+        let leap = TransactionFilter::new().month(2024, 2);
+        assert_eq!(leap.date_from, NaiveDate::from_ymd_opt(2024, 2, 1));
+        assert_eq!(leap.date_to, NaiveDate::from_ymd_opt(2024, 2, 29));
+        let common = TransactionFilter::new().month(2023, 2);
+        assert_eq!(common.date_from, NaiveDate::from_ymd_opt(2023, 2, 1));
+        assert_eq!(common.date_to, NaiveDate::from_ymd_opt(2023, 2, 28));
+    }
 
-        assert!(filter.matches(&matching));
-        assert!(!filter.matches(&not_matching));
+    #[test]
+    fn month_handles_december_and_invalid_months() {
+        let december = TransactionFilter::new().month(2023, 12);
+        assert_eq!(december.date_from, NaiveDate::from_ymd_opt(2023, 12, 1));
+        assert_eq!(december.date_to, NaiveDate::from_ymd_opt(2023, 12, 31));
+
+        let invalid = TransactionFilter::new().month(2023, 13);
+        let tx = test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        assert!(!invalid.matches(&tx));
     }
 
     #[test]
-    fn filter_amount_range() {
-        let filter = TransactionFilter::new().amount_range(50.0, 200.0);
+    fn preset_expenses_matches_positive_outcome() {
+        let filter = TransactionFilter::expenses();
+        assert_eq!(filter.direction, Direction::Outcome);
+        assert_eq!(filter.min_amount, Some(f64::MIN_POSITIVE));
+        assert!(filter.max_amount.is_none());
+        assert_eq!(filter.transfer, Some(false));
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let expense = test_transaction("t1", "a-1", date);
+        let mut income = test_transaction("t2", "a-1", date);
+        income.outcome = 0.0;
+        income.income = 100.0;
+        let mut transfer = test_transaction("t3", "a-1", date);
+        transfer.income_account = AccountId::new("a-2".to_owned());
+        transfer.income = transfer.outcome;
+        assert!(filter.matches(&expense));
+        assert!(!filter.matches(&income));
+        assert!(!filter.matches(&transfer));
+    }
 
-        let in_range = test_transaction_full(
-            "t1",
-            "a-1",
-            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
-            0.0,
-            100.0,
-            None,
-            None,
-            None,
-        );
-        let below_range = test_transaction_full(
-            "t2",
+    #[test]
+    fn force_fetch_kinds_accepts_every_entity_type() {
+        let kinds = force_fetch_kinds(&entity_type::ALL).unwrap();
+        assert!(kinds.contains(&entity_type::BUDGET.to_owned()));
+        assert!(matches!(
+            force_fetch_kinds(&["budgets"]),
+            Err(ZenMoneyError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn preset_uncategorized_skips_tagged_and_transfers() {
+        let filter = TransactionFilter::uncategorized();
+        assert_eq!(filter.has_tag, Some(false));
+        assert_eq!(filter.transfer, Some(false));
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let untagged = test_transaction("t1", "a-1", date);
+        let mut empty_tags = test_transaction("t2", "a-1", date);
+        empty_tags.tag = Some(Vec::new());
+        let mut tagged = test_transaction("t3", "a-1", date);
+        tagged.tag = Some(vec![TagId::new("tag-food".to_owned())]);
+        let mut transfer = test_transaction("t4", "a-1", date);
+        transfer.income_account = AccountId::new("a-2".to_owned());
+        transfer.income = transfer.outcome;
+        assert!(filter.matches(&untagged));
+        assert!(filter.matches(&empty_tags));
+        assert!(!filter.matches(&tagged));
+        assert!(!filter.matches(&transfer));
+    }
+
+    #[test]
+    fn preset_large_sets_min_amount() {
+        let filter = TransactionFilter::large(1000.0);
+        assert_eq!(filter.min_amount, Some(1000.0));
+        assert_eq!(filter.direction, Direction::Either);
+        assert_eq!(
+            filter,
+            TransactionFilter {
+                min_amount: Some(1000.0),
+                ..TransactionFilter::new()
+            }
+        );
+    }
+
+    #[test]
+    fn filter_tags_any_is_or_within_and_across_criteria() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let food = TagId::new("tag-food".to_owned());
+        let cafe = TagId::new("tag-cafe".to_owned());
+        let mut groceries = test_transaction("t1", "a-1", date);
+        groceries.tag = Some(vec![food.clone()]);
+        let mut coffee = test_transaction("t2", "a-1", date);
+        coffee.tag = Some(vec![TagId::new("tag-other".to_owned()), cafe.clone()]);
+        let mut rent = test_transaction("t3", "a-1", date);
+        rent.tag = Some(vec![TagId::new("tag-rent".to_owned())]);
+        let untagged = test_transaction("t4", "a-1", date);
+
+        let filter = TransactionFilter::new().tags_any(vec![food.clone(), cafe.clone()]);
+        assert!(filter.matches(&groceries));
+        assert!(filter.matches(&coffee));
+        assert!(!filter.matches(&rent));
+        assert!(!filter.matches(&untagged));
+
+        let chained = TransactionFilter::new().tag(food).tag(cafe);
+        assert_eq!(chained, filter);
+
+        let other_account = filter.account(AccountId::new("a-2".to_owned()));
+        assert!(!other_account.matches(&groceries));
+    }
+
+    #[test]
+    fn filter_by_net_amount() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut big_expense = test_transaction("t1", "a-1", date);
+        big_expense.outcome = 1500.0;
+        let mut small_expense = test_transaction("t2", "a-1", date);
+        small_expense.outcome = 200.0;
+        let mut refund = test_transaction("t3", "a-1", date);
+        refund.outcome = 0.0;
+        refund.income = 1500.0;
+
+        let outflows = TransactionFilter::new().net_amount_range(f64::MIN, -1000.0);
+        assert!(outflows.matches(&big_expense));
+        assert!(!outflows.matches(&small_expense));
+        assert!(!outflows.matches(&refund));
+
+        // Both criteria are AND-combined.
+        let both = outflows.amount_range(0.0, 1000.0);
+        assert!(!both.matches(&big_expense));
+    }
+
+    #[test]
+    fn convert_with_rates_uses_both_rates() {
+        let rub = test_instrument();
+        let mut usd = test_instrument();
+        usd.id = InstrumentId::new(840_i32);
+        usd.rate = 90.0;
+        let mut broken = test_instrument();
+        broken.id = InstrumentId::new(999_i32);
+        broken.rate = 0.0;
+        let instruments = [rub.clone(), usd.clone(), broken.clone()];
+
+        let in_rub = convert_with_rates(&instruments, 10.0, usd.id, rub.id).unwrap();
+        assert!((in_rub - 900.0).abs() < f64::EPSILON);
+        let in_usd = convert_with_rates(&instruments, 900.0, rub.id, usd.id).unwrap();
+        assert!((in_usd - 10.0).abs() < f64::EPSILON);
+        assert!(matches!(
+            convert_with_rates(&instruments, 1.0, rub.id, broken.id),
+            Err(ZenMoneyError::Validation(_))
+        ));
+        assert!(matches!(
+            convert_with_rates(&instruments, 1.0, InstrumentId::new(7_i32), rub.id),
+            Err(ZenMoneyError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn is_stale_compares_elapsed_time_with_interval() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let minute = Duration::from_secs(60);
+        assert!(is_stale(None, now, minute));
+        assert!(!is_stale(Some(now), now, minute));
+        let earlier = now - chrono::TimeDelta::seconds(61);
+        assert!(is_stale(Some(earlier), now, minute));
+        assert!(!is_stale(Some(earlier), now, Duration::MAX));
+    }
+
+    #[test]
+    fn sum_outcome_by_tag_counts_multi_tag_outcome_for_each_tag() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let food = TagId::new("tag-food".to_owned());
+        let cafe = TagId::new("tag-cafe".to_owned());
+        let mut groceries = test_transaction("t1", "a-1", date);
+        groceries.tag = Some(vec![food.clone()]);
+        let mut lunch = test_transaction("t2", "a-1", date);
+        lunch.tag = Some(vec![food.clone(), cafe.clone()]);
+        lunch.outcome = 40.0;
+        let untagged = test_transaction("t3", "a-1", date);
+        let mut empty_tags = test_transaction("t4", "a-1", date);
+        empty_tags.tag = Some(Vec::new());
+
+        let totals = sum_outcome_by_tag(&[groceries, lunch, untagged, empty_tags]);
+        assert_eq!(totals.len(), 3);
+        assert!((totals[&Some(food)] - 140.0).abs() < f64::EPSILON);
+        assert!((totals[&Some(cafe)] - 40.0).abs() < f64::EPSILON);
+        assert!((totals[&None] - 200.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn sum_outcome_by_tag_in_converts_each_currency() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let food = TagId::new("tag-food".to_owned());
+        let mut usd = test_instrument();
+        usd.id = InstrumentId::new(840_i32);
+        usd.rate = 90.0;
+        let mut rub_lunch = test_transaction("t1", "a-1", date);
+        rub_lunch.tag = Some(vec![food.clone()]);
+        rub_lunch.outcome = 900.0;
+        let mut usd_lunch = test_transaction("t2", "a-1", date);
+        usd_lunch.tag = Some(vec![food.clone()]);
+        usd_lunch.outcome_instrument = usd.id;
+        usd_lunch.outcome = 10.0;
+        let mut untagged = test_transaction("t3", "a-1", date);
+        untagged.outcome_instrument = InstrumentId::new(999_i32);
+        let instruments = [test_instrument(), usd.clone()];
+
+        let totals = sum_outcome_by_tag_in(
+            &[rub_lunch, usd_lunch.clone(), untagged],
+            &instruments,
+            usd.id,
+        )
+        .unwrap();
+        assert_eq!(totals.len(), 1);
+        assert!((totals[&food] - 20.0).abs() < f64::EPSILON);
+
+        let mut unknown = usd_lunch;
+        unknown.outcome_instrument = InstrumentId::new(999_i32);
+        assert!(matches!(
+            sum_outcome_by_tag_in(&[unknown], &instruments, usd.id),
+            Err(ZenMoneyError::NotFound { entity: "instrument", id }) if id == "999"
+        ));
+        assert!(matches!(
+            sum_outcome_by_tag_in(&[], &instruments, InstrumentId::new(7_i32)),
+            Err(ZenMoneyError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn negated_filter_excludes_exactly_the_matches() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let food = TagId::new("tag-food".to_owned());
+        let mut groceries = test_transaction("t1", "a-1", date);
+        groceries.tag = Some(vec![food.clone()]);
+        let mut rent = test_transaction("t2", "a-1", date);
+        rent.tag = Some(vec![TagId::new("tag-rent".to_owned())]);
+        let untagged = test_transaction("t3", "a-1", date);
+        let all = [groceries, rent, untagged];
+
+        let filter = TransactionFilter::new().tag(food);
+        let non_food = filter.clone().negate();
+        for tx in &all {
+            assert_ne!(filter.matches(tx), non_food.matches(tx), "{}", tx.id);
+        }
+        let kept: Vec<&str> = all
+            .iter()
+            .filter(|tx| non_food.matches(tx))
+            .map(|tx| tx.id.as_inner())
+            .collect();
+        assert_eq!(kept, ["t2", "t3"]);
+        assert_eq!(non_food.negate(), filter);
+    }
+
+    #[test]
+    fn filter_by_mcc() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut restaurant = test_transaction("t1", "a-1", date);
+        restaurant.mcc = Some(5812);
+        let mut grocery = test_transaction("t2", "a-1", date);
+        grocery.mcc = Some(5411);
+        let no_mcc = test_transaction("t3", "a-1", date);
+
+        let exact = TransactionFilter::new().mcc(5812);
+        assert!(exact.matches(&restaurant));
+        assert!(!exact.matches(&grocery));
+        assert!(!exact.matches(&no_mcc));
+
+        let dining = TransactionFilter::new().mcc_in((5811..=5814).collect());
+        assert!(dining.matches(&restaurant));
+        assert!(!dining.matches(&grocery));
+        assert!(!dining.matches(&no_mcc));
+
+        let combined = TransactionFilter::new()
+            .mcc(5812)
+            .date_range(date, date)
+            .amount_range(50.0, 150.0);
+        assert!(combined.matches(&restaurant));
+        let mut expensive = restaurant.clone();
+        expensive.outcome = 500.0;
+        assert!(!combined.matches(&expensive));
+    }
+
+    #[test]
+    fn filter_comment_case_insensitive() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut matching = test_transaction("t1", "a-1", date);
+        matching.comment = Some("Team lunch, REIMBURSABLE".to_owned());
+        let mut not_matching = test_transaction("t2", "a-1", date);
+        not_matching.comment = Some("Groceries".to_owned());
+        let no_comment = test_transaction("t3", "a-1", date);
+
+        let filter = TransactionFilter::new().comment("reimbursable");
+        assert!(filter.matches(&matching));
+        assert!(!filter.matches(&not_matching));
+        assert!(!filter.matches(&no_comment));
+
+        let any_comment = TransactionFilter::new().comment("");
+        assert!(any_comment.matches(&matching));
+        assert!(any_comment.matches(&not_matching));
+        assert!(!any_comment.matches(&no_comment));
+    }
+
+    #[test]
+    fn filter_merchant() {
+        let merchant_id = MerchantId::new("m-1".to_owned());
+        let filter = TransactionFilter::new().merchant(merchant_id.clone());
+
+        let matching = test_transaction_full(
+            "t1",
+            "a-1",
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            0.0,
+            100.0,
+            None,
+            None,
+            Some(merchant_id),
+        );
+        let not_matching = test_transaction_full(
+            "t2",
+            "a-1",
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            0.0,
+            100.0,
+            None,
+            None,
+            None,
+        );
+
+        assert!(filter.matches(&matching));
+        assert!(!filter.matches(&not_matching));
+    }
+
+    /// Returns an expense of 75 and an income of 75 for direction tests.
+    fn expense_and_income() -> (Transaction, Transaction) {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let expense = test_transaction_full("t1", "a-1", date, 0.0, 75.0, None, None, None);
+        let income = test_transaction_full("t2", "a-1", date, 75.0, 0.0, None, None, None);
+        (expense, income)
+    }
+
+    #[test]
+    fn filter_amount_direction_outcome() {
+        let (expense, income) = expense_and_income();
+        let filter = TransactionFilter::new()
+            .amount_range(50.0, 100.0)
+            .direction(Direction::Outcome);
+        assert!(filter.matches(&expense));
+        assert!(!filter.matches(&income));
+    }
+
+    #[test]
+    fn filter_amount_direction_income() {
+        let (expense, income) = expense_and_income();
+        let filter = TransactionFilter::new()
+            .amount_range(50.0, 100.0)
+            .direction(Direction::Income);
+        assert!(!filter.matches(&expense));
+        assert!(filter.matches(&income));
+    }
+
+    #[test]
+    fn filter_amount_direction_either_is_default() {
+        let (expense, income) = expense_and_income();
+        let filter = TransactionFilter::new().amount_range(50.0, 100.0);
+        assert_eq!(filter.direction, Direction::Either);
+        assert!(filter.matches(&expense));
+        assert!(filter.matches(&income));
+    }
+
+    #[test]
+    fn filter_amount_range() {
+        let filter = TransactionFilter::new().amount_range(50.0, 200.0);
+
+        let in_range = test_transaction_full(
+            "t1",
+            "a-1",
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            0.0,
+            100.0,
+            None,
+            None,
+            None,
+        );
+        let below_range = test_transaction_full(
+            "t2",
             "a-1",
             NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
             0.0,
@@ -1449,6 +3981,65 @@ mod tests {
         assert!(filter.matches(&income_in_range));
     }
 
+    #[test]
+    fn filter_by_qr_presence() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut with_qr = test_transaction("t1", "a-1", date);
+        with_qr.qr_code = Some("t=20240101T1200&s=100.00".to_owned());
+        let without_qr = test_transaction("t2", "a-1", date);
+
+        let want_qr = TransactionFilter::new().has_qr(true);
+        assert!(want_qr.matches(&with_qr));
+        assert!(!want_qr.matches(&without_qr));
+
+        let want_no_qr = TransactionFilter::new().has_qr(false);
+        assert!(!want_no_qr.matches(&with_qr));
+        assert!(want_no_qr.matches(&without_qr));
+    }
+
+    #[test]
+    fn filter_payee_differs_from_original() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut renamed = test_transaction("t1", "a-1", date);
+        renamed.payee = Some("Coffee Shop".to_owned());
+        renamed.original_payee = Some("COFFEESHOP*1234 MOSCOW".to_owned());
+        let mut same_but_case = test_transaction("t2", "a-1", date);
+        same_but_case.payee = Some("Coffee Shop".to_owned());
+        same_but_case.original_payee = Some("COFFEE SHOP".to_owned());
+        let mut no_original = test_transaction("t3", "a-1", date);
+        no_original.payee = Some("Coffee Shop".to_owned());
+
+        let want_renamed = TransactionFilter::new().payee_differs_from_original(true);
+        assert!(want_renamed.matches(&renamed));
+        assert!(!want_renamed.matches(&same_but_case));
+        assert!(!want_renamed.matches(&no_original));
+
+        let want_unchanged = TransactionFilter::new().payee_differs_from_original(false);
+        assert!(!want_unchanged.matches(&renamed));
+        assert!(want_unchanged.matches(&same_but_case));
+        assert!(want_unchanged.matches(&no_original));
+    }
+
+    #[test]
+    fn filter_by_location_presence() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut located = test_transaction("t1", "a-1", date);
+        located.latitude = Some(55.75);
+        located.longitude = Some(37.62);
+        let mut half_located = test_transaction("t2", "a-1", date);
+        half_located.latitude = Some(55.75);
+        let unlocated = test_transaction("t3", "a-1", date);
+
+        let want_location = TransactionFilter::new().has_location(true);
+        assert!(want_location.matches(&located));
+        assert!(!want_location.matches(&half_located));
+        assert!(!want_location.matches(&unlocated));
+
+        let want_no_location = TransactionFilter::new().has_location(false);
+        assert!(!want_no_location.matches(&located));
+        assert!(want_no_location.matches(&unlocated));
+    }
+
     #[test]
     fn filter_combined_criteria() {
         let filter = TransactionFilter::new()
@@ -1526,6 +4117,12 @@ mod tests {
                     stamp: DateTime::from_timestamp(100, 0).unwrap(),
                     user: 1_i64,
                 },
+                Deletion {
+                    id: "budget-1".to_owned(),
+                    object: "budget".to_owned(),
+                    stamp: DateTime::from_timestamp(100, 0).unwrap(),
+                    user: 1_i64,
+                },
                 Deletion {
                     id: "unknown-id".to_owned(),
                     object: "unknownType".to_owned(),
@@ -1539,6 +4136,8 @@ mod tests {
         assert_eq!(groups.accounts.len(), 1);
         assert_eq!(groups.instruments.len(), 1);
         assert_eq!(groups.instruments[0], InstrumentId::new(42_i32));
+        assert_eq!(groups.budgets, vec!["budget-1".to_owned()]);
+        assert_eq!(groups.unknown, 1);
     }
 
     #[test]
@@ -1567,6 +4166,19 @@ mod tests {
         assert!(GroupedDeletions::from_response(&response).is_err());
     }
 
+    #[test]
+    fn timestamp_check_accepts_forward_and_epoch() {
+        let older = DateTime::from_timestamp(100, 0).unwrap();
+        let newer = DateTime::from_timestamp(200, 0).unwrap();
+        check_timestamp_monotonic(older, newer).unwrap();
+        check_timestamp_monotonic(newer, newer).unwrap();
+        check_timestamp_monotonic(DateTime::UNIX_EPOCH, older).unwrap();
+        assert!(matches!(
+            check_timestamp_monotonic(newer, older),
+            Err(ZenMoneyError::TimestampRegression { .. })
+        ));
+    }
+
     #[cfg(feature = "blocking")]
     mod blocking {
         use super::*;
@@ -1787,192 +4399,1385 @@ mod tests {
         }
 
         #[test]
-        fn builder_succeeds_with_token_and_storage() {
-            let result = ZenMoneyBlocking::builder()
-                .token("test")
+        fn builder_succeeds_with_token_and_storage() {
+            let result = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(InMemoryStorage::new())
+                .build();
+            assert!(result.is_ok());
+        }
+
+        #[test]
+        fn accessor_methods_delegate_to_storage() {
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            assert!(client.accounts().unwrap().is_empty());
+            assert!(client.transactions().unwrap().is_empty());
+            assert!(client.tags().unwrap().is_empty());
+            assert!(client.merchants().unwrap().is_empty());
+            assert!(client.instruments().unwrap().is_empty());
+            assert!(client.companies().unwrap().is_empty());
+            assert!(client.countries().unwrap().is_empty());
+            assert!(client.users().unwrap().is_empty());
+            assert!(client.reminders().unwrap().is_empty());
+            assert!(client.reminder_markers().unwrap().is_empty());
+            assert!(client.budgets().unwrap().is_empty());
+        }
+
+        #[test]
+        fn active_accounts_filters_archived() {
+            let storage = InMemoryStorage::new();
+            storage
+                .upsert_accounts(vec![
+                    test_account("a-1", "Active", false),
+                    test_account("a-2", "Archived", true),
+                ])
+                .unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let active = client.active_accounts().unwrap();
+            assert_eq!(active.len(), 1);
+            assert_eq!(active[0].title, "Active");
+        }
+
+        #[test]
+        fn find_tag_by_title_case_insensitive() {
+            let storage = InMemoryStorage::new();
+            storage
+                .upsert_tags(vec![test_tag("t-1", "Groceries")])
+                .unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let found = client.find_tag_by_title("GROCERIES").unwrap();
+            assert!(found.is_some());
+            assert_eq!(found.unwrap().id, TagId::new("t-1".to_owned()));
+            assert!(client.find_tag_by_title("nonexistent").unwrap().is_none());
+        }
+
+        #[test]
+        fn find_account_by_title_case_insensitive() {
+            let storage = InMemoryStorage::new();
+            storage
+                .upsert_accounts(vec![test_account("a-1", "Checking", false)])
+                .unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let found = client.find_account_by_title("checking").unwrap();
+            assert!(found.is_some());
+            assert!(
+                client
+                    .find_account_by_title("nonexistent")
+                    .unwrap()
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn instrument_lookup() {
+            let storage = InMemoryStorage::new();
+            let instr = Instrument {
+                id: InstrumentId::new(840_i32),
+                title: "USD".to_owned(),
+                short_title: "USD".to_owned(),
+                symbol: "$".to_owned(),
+                rate: 1.0,
+                changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            };
+            storage.upsert_instruments(vec![instr]).unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let found = client.instrument(InstrumentId::new(840_i32)).unwrap();
+            assert!(found.is_some());
+            assert_eq!(found.unwrap().title, "USD");
+            assert!(
+                client
+                    .instrument(InstrumentId::new(999_i32))
+                    .unwrap()
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn base_instrument_prefers_user_currency() {
+            let mut usd = test_instrument();
+            usd.id = InstrumentId::new(840_i32);
+            usd.short_title = "USD".to_owned();
+            let mut eur = test_instrument();
+            eur.id = InstrumentId::new(978_i32);
+            eur.rate = 90.0;
+            let storage = InMemoryStorage::builder()
+                .instruments(vec![usd, test_instrument(), eur])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            // Two instruments have rate 1.0; without a user the first wins.
+            let base = client.base_instrument().unwrap().unwrap();
+            assert_eq!(base.id, InstrumentId::new(840_i32));
+
+            client.storage().upsert_users(vec![test_user()]).unwrap();
+            let base = client.base_instrument().unwrap().unwrap();
+            assert_eq!(base.id, InstrumentId::new(1_i32));
+        }
+
+        #[test]
+        fn accounts_with_base_balance_converts_each_currency() {
+            let mut usd = test_instrument();
+            usd.id = InstrumentId::new(840_i32);
+            usd.rate = 90.0;
+            let mut rub_card = test_account("a-rub", "Card", false);
+            rub_card.balance = Some(1000.0);
+            let mut usd_cash = test_account("a-usd", "Dollars", false);
+            usd_cash.instrument = Some(usd.id);
+            usd_cash.balance = Some(10.0);
+            let mut unknown = test_account("a-unknown", "Unknown", false);
+            unknown.instrument = Some(InstrumentId::new(999_i32));
+            let storage = InMemoryStorage::builder()
+                .instruments(vec![test_instrument(), usd])
+                .users(vec![test_user()])
+                .accounts(vec![
+                    rub_card,
+                    usd_cash,
+                    unknown,
+                    test_account("a-old", "Old", true),
+                ])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let balances: Vec<(String, f64)> = client
+                .accounts_with_base_balance()
+                .unwrap()
+                .into_iter()
+                .map(|(account, balance)| (account.title, balance))
+                .collect();
+            assert_eq!(
+                balances,
+                vec![("Card".to_owned(), 1000.0), ("Dollars".to_owned(), 900.0)]
+            );
+        }
+
+        #[test]
+        fn maps_key_entities_by_id() {
+            let mut usd = test_instrument();
+            usd.id = InstrumentId::new(840_i32);
+            usd.short_title = "USD".to_owned();
+            let storage = InMemoryStorage::builder()
+                .instruments(vec![test_instrument(), usd])
+                .accounts(vec![
+                    test_account("a-1", "Card", false),
+                    test_account("a-2", "Cash", false),
+                ])
+                .tags(vec![test_tag("t-1", "Food")])
+                .merchants(vec![test_merchant("m-1")])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+
+            let instruments = client.instruments_map().unwrap();
+            assert_eq!(instruments.len(), 2);
+            assert_eq!(instruments[&InstrumentId::new(840_i32)].short_title, "USD");
+            let accounts = client.accounts_map().unwrap();
+            assert_eq!(accounts[&AccountId::new("a-2".to_owned())].title, "Cash");
+            let tags = client.tags_map().unwrap();
+            assert_eq!(tags[&TagId::new("t-1".to_owned())].title, "Food");
+            let merchants = client.merchants_map().unwrap();
+            assert!(merchants.contains_key(&MerchantId::new("m-1".to_owned())));
+        }
+
+        #[test]
+        fn filter_active_transactions_drops_archived_only() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let mut transfer_out = test_transaction("tx-transfer", "a-active", date);
+            transfer_out.income_account = AccountId::new("a-old".to_owned());
+            let mut between_archived = test_transaction("tx-both", "a-old", date);
+            between_archived.income_account = AccountId::new("a-older".to_owned());
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![
+                    test_account("a-active", "Card", false),
+                    test_account("a-old", "Old card", true),
+                    test_account("a-older", "Older card", true),
+                ])
+                .transactions(vec![
+                    test_transaction("tx-active", "a-active", date),
+                    test_transaction("tx-archived", "a-old", date),
+                    transfer_out,
+                    between_archived,
+                ])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+
+            let mut ids: Vec<String> = client
+                .filter_active_transactions(&TransactionFilter::new())
+                .unwrap()
+                .into_iter()
+                .map(|tx| tx.id.to_string())
+                .collect();
+            ids.sort();
+            assert_eq!(ids, ["tx-active", "tx-transfer"]);
+        }
+
+        #[test]
+        fn transactions_sorted_orders_by_requested_key() {
+            let jan = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let feb = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+            let mut early = test_transaction("tx-early", "a-1", jan);
+            early.created = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+            early.changed = DateTime::from_timestamp(1_700_000_300, 0).unwrap();
+            early.outcome = 50.0;
+            let mut late_same_day = test_transaction("tx-late", "a-1", jan);
+            late_same_day.created = DateTime::from_timestamp(1_700_000_100, 0).unwrap();
+            late_same_day.changed = DateTime::from_timestamp(1_700_000_100, 0).unwrap();
+            late_same_day.outcome = f64::NAN;
+            let mut next_month = test_transaction("tx-feb", "a-1", feb);
+            next_month.created = DateTime::from_timestamp(1_700_000_050, 0).unwrap();
+            next_month.changed = DateTime::from_timestamp(1_700_000_200, 0).unwrap();
+            next_month.outcome = 200.0;
+            let storage = InMemoryStorage::builder()
+                .transactions(vec![late_same_day, next_month, early])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+
+            let sorted_ids = |sort: TransactionSort| -> Vec<String> {
+                client
+                    .transactions_sorted(&TransactionFilter::new(), sort)
+                    .unwrap()
+                    .into_iter()
+                    .map(|tx| tx.id.to_string())
+                    .collect()
+            };
+            assert_eq!(
+                sorted_ids(TransactionSort::DateAsc),
+                ["tx-early", "tx-late", "tx-feb"]
+            );
+            assert_eq!(
+                sorted_ids(TransactionSort::DateDesc),
+                ["tx-feb", "tx-late", "tx-early"]
+            );
+            assert_eq!(
+                sorted_ids(TransactionSort::ChangedAsc),
+                ["tx-late", "tx-feb", "tx-early"]
+            );
+            assert_eq!(
+                sorted_ids(TransactionSort::ChangedDesc),
+                ["tx-early", "tx-feb", "tx-late"]
+            );
+            assert_eq!(
+                sorted_ids(TransactionSort::OutcomeDesc),
+                ["tx-feb", "tx-early", "tx-late"]
+            );
+        }
+
+        #[test]
+        fn filter_transactions_page_slices_matches() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let storage = InMemoryStorage::builder()
+                .transactions(
+                    (1..=5)
+                        .map(|n| test_transaction(&format!("tx-{n}"), "a-1", date))
+                        .collect(),
+                )
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let filter = TransactionFilter::new();
+
+            let page = client
+                .filter_transactions_page(&filter, Page::new(1, 2))
+                .unwrap();
+            assert_eq!(page.total, 5);
+            let ids: Vec<String> = page.items.iter().map(|tx| tx.id.to_string()).collect();
+            assert_eq!(ids, ["tx-2", "tx-3"]);
+
+            let tail = client
+                .filter_transactions_page(&filter, Page::new(4, 10))
+                .unwrap();
+            assert_eq!(tail.items.len(), 1);
+            assert_eq!(tail.total, 5);
+
+            let past_end = client
+                .filter_transactions_page(&filter, Page::new(10, 10))
+                .unwrap();
+            assert!(past_end.items.is_empty());
+            assert_eq!(past_end.total, 5);
+
+            let empty = client
+                .filter_transactions_page(&filter, Page::new(0, 0))
+                .unwrap();
+            assert!(empty.items.is_empty());
+            assert_eq!(empty.total, 5);
+        }
+
+        #[test]
+        fn filter_transactions_excludes_deleted() {
+            let storage = InMemoryStorage::new();
+            let mut tx1 =
+                test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+            tx1.deleted = true;
+            let tx2 = test_transaction("tx-2", "a-1", NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+            storage.upsert_transactions(vec![tx1, tx2]).unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let results = client
+                .filter_transactions(&TransactionFilter::new())
+                .unwrap();
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].id, TransactionId::new("tx-2".to_owned()));
+        }
+
+        #[test]
+        fn search_transactions_excludes_deleted() {
+            let storage = InMemoryStorage::new();
+            let mut tx1 =
+                test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+            tx1.comment = Some("Birthday gift".to_owned());
+            tx1.deleted = true;
+            let mut tx2 =
+                test_transaction("tx-2", "a-1", NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+            tx2.comment = Some("Gift for mom".to_owned());
+            storage.upsert_transactions(vec![tx1, tx2]).unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let results = client.search_transactions("gift").unwrap();
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].id, TransactionId::new("tx-2".to_owned()));
+        }
+
+        #[test]
+        fn account_instrument_resolves_or_returns_none() {
+            let storage = InMemoryStorage::builder()
+                .instruments(vec![test_instrument()])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let mut account = test_account("a-1", "Card", false);
+            let found = client.account_instrument(&account).unwrap().unwrap();
+            assert_eq!(found.short_title, "RUB");
+
+            account.instrument = Some(InstrumentId::new(999_i32));
+            assert!(client.account_instrument(&account).unwrap().is_none());
+            account.instrument = None;
+            assert!(client.account_instrument(&account).unwrap().is_none());
+        }
+
+        #[test]
+        fn needs_full_sync_on_empty_storage() {
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            assert!(client.needs_full_sync().unwrap());
+        }
+
+        #[test]
+        fn needs_full_sync_when_bootstrap_incomplete() {
+            let storage = InMemoryStorage::builder()
+                .server_timestamp(DateTime::from_timestamp(1_700_000_000, 0).unwrap())
+                .users(vec![test_user()])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            assert!(client.needs_full_sync().unwrap());
+        }
+
+        #[test]
+        fn needs_full_sync_false_for_populated_storage() {
+            let storage = InMemoryStorage::builder()
+                .server_timestamp(DateTime::from_timestamp(1_700_000_000, 0).unwrap())
+                .users(vec![test_user()])
+                .instruments(vec![test_instrument()])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            assert!(!client.needs_full_sync().unwrap());
+        }
+
+        #[test]
+        fn cleared_and_pending_balance_splits_held_transactions() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let mut account = test_account("a-1", "Card", false);
+            account.start_balance = Some(1000.0);
+            let settled = test_transaction("tx-1", "a-1", date);
+            let mut held = test_transaction("tx-2", "a-1", date);
+            held.outcome = 40.0;
+            held.hold = Some(true);
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![account])
+                .transactions(vec![settled, held])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let (cleared, pending) = client
+                .cleared_and_pending_balance(&AccountId::new("a-1".to_owned()))
+                .unwrap();
+            assert!((cleared - 900.0).abs() < f64::EPSILON);
+            assert!((pending + 40.0).abs() < f64::EPSILON);
+        }
+
+        #[test]
+        fn dashboard_accounts_sorts_active_accounts_with_balances() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let mut wallet = test_account("a-wallet", "Wallet", false);
+            wallet.kind = AccountType::Cash;
+            wallet.start_balance = Some(500.0);
+            let mut card = test_account("a-card", "Card", false);
+            card.kind = AccountType::CreditCard;
+            card.start_balance = Some(1000.0);
+            card.credit_limit = Some(2000.0);
+            let mut held = test_transaction("tx-2", "a-card", date);
+            held.outcome = 40.0;
+            held.hold = Some(true);
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![card, test_account("a-old", "Old", true), wallet])
+                .transactions(vec![test_transaction("tx-1", "a-card", date), held])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let summaries: Vec<(String, f64, f64)> = client
+                .dashboard_accounts()
+                .unwrap()
+                .into_iter()
+                .map(|summary| {
+                    (
+                        summary.account.title,
+                        summary.computed_balance,
+                        summary.available_balance,
+                    )
+                })
+                .collect();
+            assert_eq!(
+                summaries,
+                vec![
+                    ("Wallet".to_owned(), 500.0, 500.0),
+                    ("Card".to_owned(), 860.0, 2860.0),
+                ]
+            );
+        }
+
+        #[test]
+        fn balance_history_carries_balance_over_empty_days() {
+            let day = |d: u32| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+            let mut account = test_account("a-1", "Card", false);
+            account.start_balance = Some(1000.0);
+            let before = test_transaction("tx-1", "a-1", day(1));
+            let mut income = test_transaction("tx-2", "a-1", day(3));
+            income.income_account = AccountId::new("a-1".to_owned());
+            income.outcome_account = AccountId::new("a-2".to_owned());
+            income.income = 50.0;
+            let after = test_transaction("tx-3", "a-1", day(9));
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![account])
+                .transactions(vec![before, income, after])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let history = client
+                .balance_history(&AccountId::new("a-1".to_owned()), day(2), day(4))
+                .unwrap();
+            assert_eq!(history, [(day(2), 900.0), (day(3), 950.0), (day(4), 950.0)]);
+        }
+
+        #[test]
+        fn transactions_by_date_delegates() {
+            let storage = InMemoryStorage::new();
+            let tx1 =
+                test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+            let tx2 =
+                test_transaction("tx-2", "a-1", NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+            storage.upsert_transactions(vec![tx1, tx2]).unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let results = client
+                .transactions_by_date(
+                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(),
+                )
+                .unwrap();
+            assert_eq!(results.len(), 1);
+        }
+
+        #[test]
+        fn transactions_between_iterates_range() {
+            let storage = InMemoryStorage::new();
+            let mut deleted =
+                test_transaction("tx-3", "a-1", NaiveDate::from_ymd_opt(2024, 1, 20).unwrap());
+            deleted.deleted = true;
+            storage
+                .upsert_transactions(vec![
+                    test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
+                    test_transaction("tx-2", "a-1", NaiveDate::from_ymd_opt(2024, 2, 10).unwrap()),
+                    test_transaction("tx-4", "a-1", NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()),
+                    deleted,
+                ])
+                .unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let count = client
+                .transactions_between(
+                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(),
+                )
+                .unwrap()
+                .count();
+            assert_eq!(count, 2);
+        }
+
+        #[test]
+        fn transactions_by_account_delegates() {
+            let storage = InMemoryStorage::new();
+            let tx1 = test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+            let tx2 = test_transaction("tx-2", "a-2", NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+            storage.upsert_transactions(vec![tx1, tx2]).unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let results = client
+                .transactions_by_account(&AccountId::new("a-1".to_owned()))
+                .unwrap();
+            assert_eq!(results.len(), 1);
+        }
+
+        #[test]
+        fn inner_client_and_storage_accessors() {
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            // Just verify they don't panic and return references.
+            let _http = client.inner_client();
+            let _storage = client.storage();
+        }
+
+        #[test]
+        fn sync_fetches_and_applies_diff() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            });
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            let response = client.sync().unwrap();
+            assert_eq!(
+                response.server_timestamp,
+                DateTime::from_timestamp(1_700_000_100, 0).unwrap()
+            );
+        }
+
+        #[test]
+        fn preserving_full_resync_restores_storage_when_fetch_fails() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(wiremock::ResponseTemplate::new(400))
+                    .mount(&mock_server)
+                    .await;
+            });
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![test_account("a-1", "Card", false)])
+                .transactions(vec![test_transaction("tx-1", "a-1", date)])
+                .build();
+            storage
+                .set_server_timestamp(DateTime::from_timestamp(1_700_000_000, 0).unwrap())
+                .unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(storage)
+                .build()
+                .unwrap();
+            let before = client.snapshot().unwrap();
+
+            assert!(client.force_full_resync_preserving_backup().is_err());
+            assert_eq!(client.snapshot().unwrap(), before);
+        }
+
+        #[test]
+        fn full_sync_clears_and_syncs() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            });
+            let storage = InMemoryStorage::new();
+            storage
+                .upsert_accounts(vec![test_account("a-1", "Old", false)])
+                .unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(storage)
+                .build()
+                .unwrap();
+            let _response = client.full_sync().unwrap();
+            // Storage should have been cleared before sync.
+            assert!(client.accounts().unwrap().is_empty());
+        }
+
+        #[test]
+        fn push_all_entity_types() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .expect(7_u64)
+                    .mount(&mock_server)
+                    .await;
+            });
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+
+            drop(
+                client
+                    .push_accounts(vec![test_account("a-1", "Test", false)])
+                    .unwrap(),
+            );
+            drop(
+                client
+                    .push_transactions(vec![test_transaction(
+                        "tx-1",
+                        "a-1",
+                        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                    )])
+                    .unwrap(),
+            );
+            drop(client.push_tags(vec![test_tag("t-1", "Food")]).unwrap());
+            drop(client.push_merchants(vec![test_merchant("m-1")]).unwrap());
+            drop(client.push_reminders(vec![test_reminder("r-1")]).unwrap());
+            drop(
+                client
+                    .push_reminder_markers(vec![test_reminder_marker("rm-1")])
+                    .unwrap(),
+            );
+            drop(client.push_budgets(vec![test_budget()]).unwrap());
+        }
+
+        #[test]
+        fn dry_runs_build_requests_without_sending() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .expect(0_u64)
+                    .mount(&mock_server)
+                    .await;
+            });
+            let synced = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+            let storage = InMemoryStorage::builder().users(vec![test_user()]).build();
+            storage.set_server_timestamp(synced).unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(storage)
+                .build()
+                .unwrap();
+
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let push = client
+                .push_transactions_dry(vec![test_transaction("tx-1", "a-1", date)])
+                .unwrap();
+            assert_eq!(push.server_timestamp, synced);
+            assert_eq!(push.transaction.len(), 1);
+            assert!(push.deletion.is_empty());
+
+            let delete = client
+                .delete_tags_dry(&[TagId::new("t-1".to_owned())])
+                .unwrap();
+            assert_eq!(delete.server_timestamp, synced);
+            assert_eq!(delete.deletion.len(), 1);
+            assert_eq!(delete.deletion[0].id, "t-1");
+            assert_eq!(delete.deletion[0].object, entity_type::TAG);
+            assert_eq!(delete.deletion[0].user, test_user().id.into_inner());
+            assert!(client.storage().transactions().unwrap().is_empty());
+        }
+
+        #[test]
+        fn delete_all_entity_types() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .expect(6_u64)
+                    .mount(&mock_server)
+                    .await;
+            });
+            let storage = InMemoryStorage::new();
+            storage
+                .upsert_accounts(vec![test_account("a-1", "Test", false)])
+                .unwrap();
+            storage
+                .upsert_transactions(vec![test_transaction(
+                    "tx-1",
+                    "a-1",
+                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                )])
+                .unwrap();
+            storage.upsert_tags(vec![test_tag("t-1", "Food")]).unwrap();
+            storage
+                .upsert_merchants(vec![test_merchant("m-1")])
+                .unwrap();
+            storage
+                .upsert_reminders(vec![test_reminder("r-1")])
+                .unwrap();
+            storage
+                .upsert_reminder_markers(vec![test_reminder_marker("rm-1")])
+                .unwrap();
+
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(storage)
+                .build()
+                .unwrap();
+
+            drop(
+                client
+                    .delete_accounts(&[AccountId::new("a-1".to_owned())])
+                    .unwrap(),
+            );
+            assert!(client.accounts().unwrap().is_empty());
+
+            drop(
+                client
+                    .delete_transactions(&[TransactionId::new("tx-1".to_owned())])
+                    .unwrap(),
+            );
+            assert!(client.transactions().unwrap().is_empty());
+
+            drop(client.delete_tags(&[TagId::new("t-1".to_owned())]).unwrap());
+            assert!(client.tags().unwrap().is_empty());
+
+            drop(
+                client
+                    .delete_merchants(&[MerchantId::new("m-1".to_owned())])
+                    .unwrap(),
+            );
+            assert!(client.merchants().unwrap().is_empty());
+
+            drop(
+                client
+                    .delete_reminders(&[ReminderId::new("r-1".to_owned())])
+                    .unwrap(),
+            );
+            assert!(client.reminders().unwrap().is_empty());
+
+            drop(
+                client
+                    .delete_reminder_markers(&[ReminderMarkerId::new("rm-1".to_owned())])
+                    .unwrap(),
+            );
+            assert!(client.reminder_markers().unwrap().is_empty());
+        }
+
+        #[test]
+        fn suggest_delegates_to_client() {
+            use crate::models::SuggestRequest;
+
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/suggest/"))
+                    .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(
+                        serde_json::json!({"payee": "Starbucks", "tag": null, "merchant": null}),
+                    ))
+                    .mount(&mock_server)
+                    .await;
+            });
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
                 .storage(InMemoryStorage::new())
-                .build();
-            assert!(result.is_ok());
+                .build()
+                .unwrap();
+            let resp = client
+                .suggest(&SuggestRequest {
+                    payee: Some("star".to_owned()),
+                    comment: None,
+                })
+                .unwrap();
+            assert_eq!(resp.payee.unwrap(), "Starbucks");
         }
 
         #[test]
-        fn accessor_methods_delegate_to_storage() {
+        fn suggest_local_uses_most_frequent_history() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let food = vec![TagId::new("tag-food".to_owned())];
+            let mut tx1 = test_transaction("tx-1", "a-1", date);
+            tx1.payee = Some("Starbucks".to_owned());
+            tx1.tag = Some(food.clone());
+            tx1.merchant = Some(MerchantId::new("m-sb".to_owned()));
+            let mut tx2 = test_transaction("tx-2", "a-1", date);
+            tx2.payee = Some("STARBUCKS".to_owned());
+            tx2.tag = Some(food.clone());
+            let mut tx3 = test_transaction("tx-3", "a-1", date);
+            tx3.payee = Some("Starbucks".to_owned());
+            tx3.tag = Some(vec![TagId::new("tag-gift".to_owned())]);
+            let mut other = test_transaction("tx-4", "a-1", date);
+            other.payee = Some("Landlord".to_owned());
+            other.tag = Some(vec![TagId::new("tag-rent".to_owned())]);
+            let storage = InMemoryStorage::builder()
+                .transactions(vec![tx1, tx2, tx3, other])
+                .build();
             let client = ZenMoneyBlocking::builder()
                 .token("test")
-                .storage(InMemoryStorage::new())
+                .storage(storage)
                 .build()
                 .unwrap();
-            assert!(client.accounts().unwrap().is_empty());
-            assert!(client.transactions().unwrap().is_empty());
-            assert!(client.tags().unwrap().is_empty());
-            assert!(client.merchants().unwrap().is_empty());
-            assert!(client.instruments().unwrap().is_empty());
-            assert!(client.companies().unwrap().is_empty());
-            assert!(client.countries().unwrap().is_empty());
-            assert!(client.users().unwrap().is_empty());
-            assert!(client.reminders().unwrap().is_empty());
-            assert!(client.reminder_markers().unwrap().is_empty());
-            assert!(client.budgets().unwrap().is_empty());
+            let resp = client.suggest_local("starbucks").unwrap();
+            assert_eq!(resp.tag, Some(food));
+            assert_eq!(resp.merchant, Some(MerchantId::new("m-sb".to_owned())));
+            assert_eq!(resp.payee.as_deref(), Some("Starbucks"));
+            let none = client.suggest_local("unknown").unwrap();
+            assert!(none.tag.is_none() && none.merchant.is_none());
         }
 
         #[test]
-        fn active_accounts_filters_archived() {
-            let storage = InMemoryStorage::new();
-            storage
-                .upsert_accounts(vec![
-                    test_account("a-1", "Active", false),
-                    test_account("a-2", "Archived", true),
-                ])
-                .unwrap();
+        fn resolve_suggestion_finds_stored_merchant_and_tags() {
+            let storage = InMemoryStorage::builder()
+                .merchants(vec![test_merchant("m-sb")])
+                .tags(vec![test_tag("tag-food", "Food")])
+                .build();
             let client = ZenMoneyBlocking::builder()
                 .token("test")
                 .storage(storage)
                 .build()
                 .unwrap();
-            let active = client.active_accounts().unwrap();
-            assert_eq!(active.len(), 1);
-            assert_eq!(active[0].title, "Active");
+            let response = SuggestResponse {
+                payee: Some("Starbucks".to_owned()),
+                merchant: Some(MerchantId::new("m-sb".to_owned())),
+                tag: Some(vec![
+                    TagId::new("tag-food".to_owned()),
+                    TagId::new("tag-new".to_owned()),
+                ]),
+            };
+            let resolved = client.resolve_suggestion(&response).unwrap();
+            assert_eq!(
+                resolved,
+                ResolvedSuggestion {
+                    payee: Some("Starbucks".to_owned()),
+                    merchant: Some(Resolved::Existing(test_merchant("m-sb"))),
+                    tags: vec![
+                        Resolved::Existing(test_tag("tag-food", "Food")),
+                        Resolved::New(TagId::new("tag-new".to_owned())),
+                    ],
+                }
+            );
         }
 
         #[test]
-        fn find_tag_by_title_case_insensitive() {
-            let storage = InMemoryStorage::new();
-            storage
-                .upsert_tags(vec![test_tag("t-1", "Groceries")])
-                .unwrap();
+        fn generate_markers_for_quarter_skips_existing() {
+            let reminder: Reminder = serde_json::from_value(serde_json::json!({
+                "id": "rem-rent", "changed": 1_700_000_000, "user": 1,
+                "incomeInstrument": 1, "incomeAccount": "a-1", "income": 0,
+                "outcomeInstrument": 1, "outcomeAccount": "a-1", "outcome": 5000.0,
+                "tag": null, "merchant": null, "payee": "Landlord", "comment": null,
+                "interval": "month", "step": 1, "points": [5],
+                "startDate": "2023-11-05", "endDate": null, "notify": true
+            }))
+            .unwrap();
+            let mut processed = plan_markers(
+                core::slice::from_ref(&reminder),
+                &[],
+                NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                Utc::now(),
+            )
+            .remove(0);
+            processed.state = ReminderMarkerState::Processed;
+            let storage = InMemoryStorage::builder()
+                .reminders(vec![reminder])
+                .reminder_markers(vec![processed])
+                .build();
             let client = ZenMoneyBlocking::builder()
                 .token("test")
                 .storage(storage)
                 .build()
                 .unwrap();
-            let found = client.find_tag_by_title("GROCERIES").unwrap();
-            assert!(found.is_some());
-            assert_eq!(found.unwrap().id, TagId::new("t-1".to_owned()));
-            assert!(client.find_tag_by_title("nonexistent").unwrap().is_none());
+
+            let markers = client
+                .generate_markers(
+                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+                )
+                .unwrap();
+            let dates: Vec<NaiveDate> = markers.iter().map(|marker| marker.date).collect();
+            assert_eq!(
+                dates,
+                vec![
+                    NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 3, 5).unwrap(),
+                ]
+            );
+            assert!(markers.iter().all(|marker| {
+                marker.state == ReminderMarkerState::Planned
+                    && marker.is_forecast == Some(true)
+                    && marker.reminder == ReminderId::new("rem-rent".to_owned())
+                    && marker.outcome == 5000.0
+            }));
+            assert!(markers.iter().all(|marker| marker.id.is_valid_uuid()));
+            assert_ne!(markers[0].id, markers[1].id);
         }
 
         #[test]
-        fn find_account_by_title_case_insensitive() {
-            let storage = InMemoryStorage::new();
-            storage
-                .upsert_accounts(vec![test_account("a-1", "Checking", false)])
-                .unwrap();
+        fn validate_references_reports_dangling_account() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let mut transfer = test_transaction("tx-2", "a-1", date);
+            transfer.income_account = AccountId::new("a-gone".to_owned());
+            let mut deleted = test_transaction("tx-3", "a-gone", date);
+            deleted.deleted = true;
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![test_account("a-1", "Cash", false)])
+                .instruments(vec![test_instrument()])
+                .transactions(vec![
+                    test_transaction("tx-1", "a-1", date),
+                    transfer,
+                    deleted,
+                ])
+                .build();
             let client = ZenMoneyBlocking::builder()
                 .token("test")
                 .storage(storage)
                 .build()
                 .unwrap();
-            let found = client.find_account_by_title("checking").unwrap();
-            assert!(found.is_some());
-            assert!(
-                client
-                    .find_account_by_title("nonexistent")
-                    .unwrap()
-                    .is_none()
+            let errors = client.validate_references().unwrap();
+            assert_eq!(
+                errors,
+                vec![ReferenceError {
+                    transaction: TransactionId::new("tx-2".to_owned()),
+                    field: ReferenceField::IncomeAccount,
+                    id: "a-gone".to_owned(),
+                }]
+            );
+            assert_eq!(
+                errors[0].to_string(),
+                "transaction tx-2 references missing income account a-gone"
             );
         }
 
         #[test]
-        fn instrument_lookup() {
-            let storage = InMemoryStorage::new();
-            let instr = Instrument {
-                id: InstrumentId::new(840_i32),
-                title: "USD".to_owned(),
-                short_title: "USD".to_owned(),
-                symbol: "$".to_owned(),
-                rate: 1.0,
-                changed: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
-            };
-            storage.upsert_instruments(vec![instr]).unwrap();
+        fn verify_integrity_reports_orphaned_markers_and_budgets() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let mut orphan = test_reminder_marker("rm-2");
+            orphan.reminder = ReminderId::new("r-gone".to_owned());
+            let mut tagged = test_budget();
+            tagged.tag = Some(TagId::new("t-gone".to_owned()));
+            let mut spending = test_transaction("tx-1", "a-1", date);
+            spending.tag = Some(vec![TagId::new("t-gone".to_owned())]);
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![test_account("a-1", "Cash", false)])
+                .instruments(vec![test_instrument()])
+                .transactions(vec![spending])
+                .reminders(vec![test_reminder("r-1")])
+                .reminder_markers(vec![test_reminder_marker("rm-1"), orphan])
+                .budgets(vec![test_budget(), tagged])
+                .build();
             let client = ZenMoneyBlocking::builder()
                 .token("test")
                 .storage(storage)
                 .build()
                 .unwrap();
-            let found = client.instrument(InstrumentId::new(840_i32)).unwrap();
-            assert!(found.is_some());
-            assert_eq!(found.unwrap().title, "USD");
-            assert!(
-                client
-                    .instrument(InstrumentId::new(999_i32))
-                    .unwrap()
-                    .is_none()
+            let issues = client.verify_integrity().unwrap();
+            assert_eq!(
+                issues,
+                vec![
+                    IntegrityIssue::Transaction(ReferenceError {
+                        transaction: TransactionId::new("tx-1".to_owned()),
+                        field: ReferenceField::Tag,
+                        id: "t-gone".to_owned(),
+                    }),
+                    IntegrityIssue::ReminderMarker {
+                        marker: ReminderMarkerId::new("rm-2".to_owned()),
+                        reminder: ReminderId::new("r-gone".to_owned()),
+                    },
+                    IntegrityIssue::Budget {
+                        date,
+                        tag: TagId::new("t-gone".to_owned()),
+                    },
+                ]
+            );
+            assert_eq!(
+                issues[1].to_string(),
+                "reminder marker rm-2 references missing reminder r-gone"
             );
         }
 
         #[test]
-        fn filter_transactions_excludes_deleted() {
-            let storage = InMemoryStorage::new();
-            let mut tx1 =
+        fn suggest_or_local_falls_back_on_remote_failure() {
+            use crate::models::SuggestRequest;
+
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/suggest/"))
+                    .respond_with(wiremock::ResponseTemplate::new(503))
+                    .mount(&mock_server)
+                    .await;
+            });
+            let mut tx =
                 test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
-            tx1.deleted = true;
-            let tx2 = test_transaction("tx-2", "a-1", NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
-            storage.upsert_transactions(vec![tx1, tx2]).unwrap();
+            tx.payee = Some("Starbucks".to_owned());
+            tx.tag = Some(vec![TagId::new("tag-food".to_owned())]);
             let client = ZenMoneyBlocking::builder()
-                .token("test")
-                .storage(storage)
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::builder().transactions(vec![tx]).build())
                 .build()
                 .unwrap();
-            let results = client
-                .filter_transactions(&TransactionFilter::new())
+            let resp = client
+                .suggest_or_local(&SuggestRequest {
+                    payee: Some("Starbucks".to_owned()),
+                    comment: None,
+                })
                 .unwrap();
-            assert_eq!(results.len(), 1);
-            assert_eq!(results[0].id, TransactionId::new("tx-2".to_owned()));
+            assert_eq!(resp.tag, Some(vec![TagId::new("tag-food".to_owned())]));
         }
 
         #[test]
-        fn transactions_by_date_delegates() {
+        fn suggest_or_local_propagates_unauthorized() {
+            use crate::models::SuggestRequest;
+
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/suggest/"))
+                    .respond_with(wiremock::ResponseTemplate::new(401))
+                    .mount(&mock_server)
+                    .await;
+            });
+            let mut tx =
+                test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+            tx.payee = Some("Starbucks".to_owned());
+            let client = ZenMoneyBlocking::builder()
+                .token("expired-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::builder().transactions(vec![tx]).build())
+                .build()
+                .unwrap();
+            let err = client
+                .suggest_or_local(&SuggestRequest {
+                    payee: Some("Starbucks".to_owned()),
+                    comment: None,
+                })
+                .unwrap_err();
+            assert!(matches!(err, ZenMoneyError::Unauthorized { .. }));
+        }
+
+        #[test]
+        fn api_error_returns_error() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(401).set_body_string("unauthorized"),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            });
+            let client = ZenMoneyBlocking::builder()
+                .token("bad-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            let err = client.sync().unwrap_err();
+            assert!(matches!(err, ZenMoneyError::Unauthorized { .. }));
+        }
+
+        #[test]
+        fn sync_rejects_regressing_server_timestamp() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            });
+            let stored = DateTime::from_timestamp(1_700_000_200, 0).unwrap();
             let storage = InMemoryStorage::new();
-            let tx1 =
-                test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
-            let tx2 =
-                test_transaction("tx-2", "a-1", NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
-            storage.upsert_transactions(vec![tx1, tx2]).unwrap();
+            storage.set_server_timestamp(stored).unwrap();
             let client = ZenMoneyBlocking::builder()
-                .token("test")
+                .token("test-token")
+                .base_url(mock_server.uri())
                 .storage(storage)
                 .build()
                 .unwrap();
-            let results = client
-                .transactions_by_date(
-                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
-                    NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(),
-                )
+            let err = client.sync().unwrap_err();
+            assert!(matches!(err, ZenMoneyError::TimestampRegression { .. }));
+            // The stale timestamp must not overwrite the stored one.
+            assert_eq!(client.storage().server_timestamp().unwrap(), Some(stored));
+        }
+
+        #[test]
+        fn pull_only_sends_no_entities() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            });
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![test_account("a-1", "Local", false)])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(storage)
+                .build()
+                .unwrap();
+            let _resp = client.pull_only().unwrap();
+
+            let requests = rt.block_on(mock_server.received_requests()).unwrap();
+            assert_eq!(requests.len(), 1);
+            let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+            let mut keys: Vec<&str> = body
+                .as_object()
+                .unwrap()
+                .keys()
+                .map(String::as_str)
+                .collect();
+            keys.sort_unstable();
+            assert_eq!(keys, ["currentClientTimestamp", "serverTimestamp"]);
+        }
+
+        #[cfg(feature = "metrics")]
+        #[test]
+        fn pull_only_and_sync_force_report_sync_metrics() {
+            use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            });
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::new())
+                .build()
                 .unwrap();
-            assert_eq!(results.len(), 1);
+            let recorder = DebuggingRecorder::new();
+            let snapshotter = recorder.snapshotter();
+
+            metrics::with_local_recorder(&recorder, || {
+                let _pulled = client.pull_only().unwrap();
+                let _forced = client.sync_force(&["instrument"]).unwrap();
+            });
+
+            let total: u64 = snapshotter
+                .snapshot()
+                .into_vec()
+                .into_iter()
+                .filter(|entry| entry.0.key().name() == "zenmoney_sync_total")
+                .map(|entry| match entry.3 {
+                    DebugValue::Counter(value) => value,
+                    DebugValue::Gauge(_) | DebugValue::Histogram(_) => 0,
+                })
+                .sum();
+            assert_eq!(total, 2);
         }
 
         #[test]
-        fn transactions_by_account_delegates() {
-            let storage = InMemoryStorage::new();
-            let tx1 = test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
-            let tx2 = test_transaction("tx-2", "a-2", NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
-            storage.upsert_transactions(vec![tx1, tx2]).unwrap();
+        fn archive_account_sets_flag_and_bumps_changed() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            });
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![test_account("a-1", "Cash", false)])
+                .build();
             let client = ZenMoneyBlocking::builder()
-                .token("test")
+                .token("test-token")
+                .base_url(mock_server.uri())
                 .storage(storage)
                 .build()
                 .unwrap();
-            let results = client
-                .transactions_by_account(&AccountId::new("a-1".to_owned()))
+            let _resp = client
+                .archive_account(&AccountId::new("a-1".to_owned()))
                 .unwrap();
-            assert_eq!(results.len(), 1);
+
+            let requests = rt.block_on(mock_server.received_requests()).unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+            assert_eq!(body["account"][0]["archive"], true);
+            assert_recently_changed(&body["account"][0]);
+
+            let err = client
+                .archive_account(&AccountId::new("a-missing".to_owned()))
+                .unwrap_err();
+            assert!(matches!(err, ZenMoneyError::NotFound { .. }));
         }
 
         #[test]
-        fn inner_client_and_storage_accessors() {
+        fn link_transaction_to_marker_sets_field_and_pushes() {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            });
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let storage = InMemoryStorage::builder()
+                .transactions(vec![test_transaction("tx-1", "a-1", date)])
+                .reminder_markers(vec![test_reminder_marker("rm-1")])
+                .build();
             let client = ZenMoneyBlocking::builder()
-                .token("test")
-                .storage(InMemoryStorage::new())
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(storage)
                 .build()
                 .unwrap();
-            // Just verify they don't panic and return references.
-            let _http = client.inner_client();
-            let _storage = client.storage();
+            let tx_id = TransactionId::new("tx-1".to_owned());
+            let marker_id = ReminderMarkerId::new("rm-1".to_owned());
+            let _resp = client
+                .link_transaction_to_marker(&tx_id, &marker_id)
+                .unwrap();
+
+            let requests = rt.block_on(mock_server.received_requests()).unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+            assert_eq!(body["transaction"][0]["reminderMarker"], "rm-1");
+            assert_recently_changed(&body["transaction"][0]);
+
+            let missing = ReminderMarkerId::new("rm-missing".to_owned());
+            let err = client
+                .link_transaction_to_marker(&tx_id, &missing)
+                .unwrap_err();
+            assert!(matches!(err, ZenMoneyError::NotFound { .. }));
         }
 
         #[test]
-        fn sync_fetches_and_applies_diff() {
+        fn merge_tags_repoints_transactions_and_deletes_source() {
             let rt = tokio::runtime::Runtime::new().unwrap();
             let mock_server = rt.block_on(wiremock::MockServer::start());
             rt.block_on(async {
@@ -1984,49 +5789,109 @@ mod tests {
                     .mount(&mock_server)
                     .await;
             });
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let old = TagId::new("tag-old".to_owned());
+            let new = TagId::new("tag-new".to_owned());
+            let mut only_old = test_transaction("tx-1", "a-1", date);
+            only_old.tag = Some(vec![old.clone()]);
+            let mut both = test_transaction("tx-2", "a-1", date);
+            both.tag = Some(vec![new.clone(), old.clone()]);
+            let mut unrelated = test_transaction("tx-3", "a-1", date);
+            unrelated.tag = Some(vec![TagId::new("tag-other".to_owned())]);
+            let mut deleted = test_transaction("tx-4", "a-1", date);
+            deleted.tag = Some(vec![old.clone()]);
+            deleted.deleted = true;
+            let mut reminder = test_reminder("r-1");
+            reminder.tag = Some(vec![old.clone()]);
+            let mut marker = test_reminder_marker("rm-1");
+            marker.tag = Some(vec![old.clone()]);
+            let mut moved = test_budget();
+            moved.tag = Some(old.clone());
+            let mut clashing = test_budget();
+            clashing.tag = Some(old.clone());
+            clashing.date = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+            let mut existing = clashing.clone();
+            existing.tag = Some(new.clone());
+            let storage = InMemoryStorage::builder()
+                .tags(vec![test_tag("tag-old", "Old"), test_tag("tag-new", "New")])
+                .transactions(vec![only_old, both, unrelated, deleted])
+                .reminders(vec![reminder])
+                .reminder_markers(vec![marker])
+                .budgets(vec![moved, clashing, existing])
+                .build();
             let client = ZenMoneyBlocking::builder()
                 .token("test-token")
                 .base_url(mock_server.uri())
-                .storage(InMemoryStorage::new())
+                .storage(storage)
                 .build()
                 .unwrap();
-            let response = client.sync().unwrap();
+            let _resp = client.merge_tags(&old, &new).unwrap();
+
+            let requests = rt.block_on(mock_server.received_requests()).unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+            let sent = body["transaction"].as_array().unwrap();
+            assert_eq!(sent.len(), 2);
+            for tx in sent {
+                assert_eq!(tx["tag"], serde_json::json!(["tag-new"]));
+                assert_recently_changed(tx);
+            }
+            assert_eq!(body["reminder"][0]["tag"], serde_json::json!(["tag-new"]));
             assert_eq!(
-                response.server_timestamp,
-                DateTime::from_timestamp(1_700_000_100, 0).unwrap()
+                body["reminderMarker"][0]["tag"],
+                serde_json::json!(["tag-new"])
             );
+            let budgets = body["budget"].as_array().unwrap();
+            assert_eq!(budgets.len(), 1);
+            assert_eq!(budgets[0]["tag"], "tag-new");
+            assert_eq!(budgets[0]["date"], "2024-01-01");
+            assert_eq!(body["deletion"][0]["id"], "tag-old");
+            assert_eq!(body["deletion"][0]["object"], "tag");
+            let tags = client.tags().unwrap();
+            assert_eq!(tags.len(), 1);
+            assert_eq!(tags[0].id, new);
+            assert!(client.merge_tags(&new, &new).is_err());
         }
 
         #[test]
-        fn full_sync_clears_and_syncs() {
+        fn delete_transactions_optimistic_restores_on_failure() {
             let rt = tokio::runtime::Runtime::new().unwrap();
             let mock_server = rt.block_on(wiremock::MockServer::start());
             rt.block_on(async {
                 wiremock::Mock::given(wiremock::matchers::method("POST"))
                     .and(wiremock::matchers::path("/v8/diff/"))
-                    .respond_with(
-                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
-                    )
+                    .respond_with(wiremock::ResponseTemplate::new(400))
                     .mount(&mock_server)
                     .await;
             });
-            let storage = InMemoryStorage::new();
-            storage
-                .upsert_accounts(vec![test_account("a-1", "Old", false)])
-                .unwrap();
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let storage = InMemoryStorage::builder()
+                .transactions(vec![
+                    test_transaction("tx-1", "a-1", date),
+                    test_transaction("tx-2", "a-1", date),
+                ])
+                .build();
             let client = ZenMoneyBlocking::builder()
                 .token("test-token")
                 .base_url(mock_server.uri())
                 .storage(storage)
                 .build()
                 .unwrap();
-            let _response = client.full_sync().unwrap();
-            // Storage should have been cleared before sync.
-            assert!(client.accounts().unwrap().is_empty());
+
+            let result =
+                client.delete_transactions_optimistic(&[TransactionId::new("tx-1".to_owned())]);
+            assert!(result.is_err());
+            let mut ids: Vec<String> = client
+                .transactions()
+                .unwrap()
+                .into_iter()
+                .map(|tx| tx.id.into_inner())
+                .collect();
+            ids.sort();
+            assert_eq!(ids, ["tx-1", "tx-2"]);
         }
 
         #[test]
-        fn push_all_entity_types() {
+        fn delete_transactions_optimistic_removes_on_success() {
             let rt = tokio::runtime::Runtime::new().unwrap();
             let mock_server = rt.block_on(wiremock::MockServer::start());
             rt.block_on(async {
@@ -2035,44 +5900,28 @@ mod tests {
                     .respond_with(
                         wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
                     )
-                    .expect(7_u64)
                     .mount(&mock_server)
                     .await;
             });
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let storage = InMemoryStorage::builder()
+                .transactions(vec![test_transaction("tx-1", "a-1", date)])
+                .build();
             let client = ZenMoneyBlocking::builder()
                 .token("test-token")
                 .base_url(mock_server.uri())
-                .storage(InMemoryStorage::new())
+                .storage(storage)
                 .build()
                 .unwrap();
 
-            drop(
-                client
-                    .push_accounts(vec![test_account("a-1", "Test", false)])
-                    .unwrap(),
-            );
-            drop(
-                client
-                    .push_transactions(vec![test_transaction(
-                        "tx-1",
-                        "a-1",
-                        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
-                    )])
-                    .unwrap(),
-            );
-            drop(client.push_tags(vec![test_tag("t-1", "Food")]).unwrap());
-            drop(client.push_merchants(vec![test_merchant("m-1")]).unwrap());
-            drop(client.push_reminders(vec![test_reminder("r-1")]).unwrap());
-            drop(
-                client
-                    .push_reminder_markers(vec![test_reminder_marker("rm-1")])
-                    .unwrap(),
-            );
-            drop(client.push_budgets(vec![test_budget()]).unwrap());
+            let _resp = client
+                .delete_transactions_optimistic(&[TransactionId::new("tx-1".to_owned())])
+                .unwrap();
+            assert!(client.transactions().unwrap().is_empty());
         }
 
         #[test]
-        fn delete_all_entity_types() {
+        fn merge_accounts_repoints_both_sides_and_deletes_source() {
             let rt = tokio::runtime::Runtime::new().unwrap();
             let mock_server = rt.block_on(wiremock::MockServer::start());
             rt.block_on(async {
@@ -2081,90 +5930,225 @@ mod tests {
                     .respond_with(
                         wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
                     )
-                    .expect(6_u64)
                     .mount(&mock_server)
                     .await;
             });
-            let storage = InMemoryStorage::new();
-            storage
-                .upsert_accounts(vec![test_account("a-1", "Test", false)])
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let old = AccountId::new("a-old".to_owned());
+            let new = AccountId::new("a-new".to_owned());
+            let self_transfer = test_transaction("tx-1", "a-old", date);
+            let mut outcome_only = test_transaction("tx-2", "a-other", date);
+            outcome_only.outcome_account = old.clone();
+            let unrelated = test_transaction("tx-3", "a-other", date);
+            let mut tombstone = test_transaction("tx-4", "a-old", date);
+            tombstone.deleted = true;
+            let storage = InMemoryStorage::builder()
+                .accounts(vec![
+                    test_account("a-old", "Old", false),
+                    test_account("a-new", "New", false),
+                ])
+                .transactions(vec![self_transfer, outcome_only, unrelated, tombstone])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(storage)
+                .build()
                 .unwrap();
-            storage
-                .upsert_transactions(vec![test_transaction(
-                    "tx-1",
-                    "a-1",
-                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
-                )])
+            let _resp = client.merge_accounts(&old, &new).unwrap();
+
+            let requests = rt.block_on(mock_server.received_requests()).unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+            let sent = body["transaction"].as_array().unwrap();
+            assert_eq!(sent.len(), 2);
+            assert_eq!(sent[0]["id"], "tx-1");
+            assert_eq!(sent[0]["incomeAccount"], "a-new");
+            assert_eq!(sent[0]["outcomeAccount"], "a-new");
+            assert_eq!(sent[1]["id"], "tx-2");
+            assert_eq!(sent[1]["incomeAccount"], "a-other");
+            assert_eq!(sent[1]["outcomeAccount"], "a-new");
+            for tx in sent {
+                assert_recently_changed(tx);
+            }
+            assert_eq!(body["deletion"][0]["id"], "a-old");
+            assert_eq!(body["deletion"][0]["object"], "account");
+            let accounts = client.accounts().unwrap();
+            assert_eq!(accounts.len(), 1);
+            assert_eq!(accounts[0].id, new);
+            assert!(client.merge_accounts(&new, &new).is_err());
+            assert!(matches!(
+                client.merge_accounts(&new, &old),
+                Err(ZenMoneyError::NotFound { .. })
+            ));
+            assert_eq!(
+                rt.block_on(mock_server.received_requests()).unwrap().len(),
+                1
+            );
+        }
+
+        #[test]
+        fn sync_if_stale_skips_within_interval() {
+            let mut response = empty_diff_response();
+            response.server_timestamp = Utc::now();
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(&response))
+                    .mount(&mock_server)
+                    .await;
+            });
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::new())
+                .build()
                 .unwrap();
-            storage.upsert_tags(vec![test_tag("t-1", "Food")]).unwrap();
-            storage
-                .upsert_merchants(vec![test_merchant("m-1")])
+
+            let interval = Duration::from_secs(3600);
+            assert!(client.sync_if_stale(interval).unwrap().is_some());
+            assert!(client.sync_if_stale(interval).unwrap().is_none());
+            let requests = rt.block_on(mock_server.received_requests()).unwrap();
+            assert_eq!(requests.len(), 1);
+        }
+
+        #[test]
+        fn export_diff_round_trips_through_json() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(InMemoryStorage::new())
+                .build()
                 .unwrap();
+            assert_eq!(
+                client.export_diff().unwrap().server_timestamp,
+                DateTime::UNIX_EPOCH
+            );
+
+            let mut response = empty_diff_response();
+            response.account = vec![test_account("a-1", "Card", false)];
+            response.transaction = vec![test_transaction("tx-1", "a-1", date)];
+            response.tag = vec![test_tag("tag-food", "Food")];
+            let storage = client.storage();
+            storage.upsert_accounts(response.account.clone()).unwrap();
             storage
-                .upsert_reminders(vec![test_reminder("r-1")])
+                .upsert_transactions(response.transaction.clone())
                 .unwrap();
+            storage.upsert_tags(response.tag.clone()).unwrap();
             storage
-                .upsert_reminder_markers(vec![test_reminder_marker("rm-1")])
+                .set_server_timestamp(response.server_timestamp)
                 .unwrap();
 
+            let exported = client.export_diff().unwrap();
+            assert_eq!(exported, response);
+            let json = serde_json::to_string(&exported).unwrap();
+            let parsed: DiffResponse = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, exported);
+        }
+
+        #[test]
+        fn storage_stats_reflect_synced_data() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let mut newer = test_transaction("tx-2", "a-1", date);
+            newer.changed = DateTime::from_timestamp(1_700_000_050, 0).unwrap();
+            let mut response = empty_diff_response();
+            response.account = vec![test_account("a-1", "Card", false)];
+            response.transaction = vec![test_transaction("tx-1", "a-1", date), newer];
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(&response))
+                    .mount(&mock_server)
+                    .await;
+            });
             let client = ZenMoneyBlocking::builder()
                 .token("test-token")
                 .base_url(mock_server.uri())
-                .storage(storage)
+                .storage(InMemoryStorage::new())
                 .build()
                 .unwrap();
+            assert_eq!(client.storage_stats().unwrap(), StorageStats::default());
 
-            drop(
-                client
-                    .delete_accounts(&[AccountId::new("a-1".to_owned())])
-                    .unwrap(),
+            let _resp = client.sync().unwrap();
+            let stats = client.storage_stats().unwrap();
+            assert_eq!(stats.server_timestamp, Some(response.server_timestamp));
+            assert_eq!(
+                stats.accounts,
+                EntityStats {
+                    count: 1,
+                    last_modified: DateTime::from_timestamp(1_700_000_000, 0),
+                }
             );
-            assert!(client.accounts().unwrap().is_empty());
-
-            drop(
-                client
-                    .delete_transactions(&[TransactionId::new("tx-1".to_owned())])
-                    .unwrap(),
+            assert_eq!(
+                stats.transactions,
+                EntityStats {
+                    count: 2,
+                    last_modified: DateTime::from_timestamp(1_700_000_050, 0),
+                }
             );
-            assert!(client.transactions().unwrap().is_empty());
-
-            drop(client.delete_tags(&[TagId::new("t-1".to_owned())]).unwrap());
-            assert!(client.tags().unwrap().is_empty());
+            assert_eq!(stats.tags, EntityStats::default());
+            assert_eq!(stats.countries.last_modified, None);
+        }
 
-            drop(
-                client
-                    .delete_merchants(&[MerchantId::new("m-1".to_owned())])
-                    .unwrap(),
-            );
-            assert!(client.merchants().unwrap().is_empty());
+        #[test]
+        fn unknown_deletion_type_flags_drift_until_full_sync() {
+            let mut response = empty_diff_response();
+            response.deletion = vec![Deletion {
+                id: "x-1".to_owned(),
+                object: "hologram".to_owned(),
+                stamp: DateTime::from_timestamp(1_700_000_050, 0).unwrap(),
+                user: 1_i64,
+            }];
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let mock_server = rt.block_on(wiremock::MockServer::start());
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(&response))
+                    .up_to_n_times(1)
+                    .mount(&mock_server)
+                    .await;
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(
+                        wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            });
+            let client = ZenMoneyBlocking::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            assert!(!client.drift_detected().unwrap());
 
-            drop(
-                client
-                    .delete_reminders(&[ReminderId::new("r-1".to_owned())])
-                    .unwrap(),
-            );
-            assert!(client.reminders().unwrap().is_empty());
+            let _resp = client.sync().unwrap();
+            assert!(client.drift_detected().unwrap());
 
-            drop(
-                client
-                    .delete_reminder_markers(&[ReminderMarkerId::new("rm-1".to_owned())])
-                    .unwrap(),
-            );
-            assert!(client.reminder_markers().unwrap().is_empty());
+            let _resp = client.full_sync().unwrap();
+            assert!(!client.drift_detected().unwrap());
         }
 
         #[test]
-        fn suggest_delegates_to_client() {
-            use crate::models::SuggestRequest;
-
+        fn budget_deletion_does_not_flag_drift() {
+            let mut response = empty_diff_response();
+            response.deletion = vec![Deletion {
+                id: "budget-1".to_owned(),
+                object: entity_type::BUDGET.to_owned(),
+                stamp: DateTime::from_timestamp(1_700_000_050, 0).unwrap(),
+                user: 1_i64,
+            }];
             let rt = tokio::runtime::Runtime::new().unwrap();
             let mock_server = rt.block_on(wiremock::MockServer::start());
-            rt.block_on(async {
-                wiremock::Mock::given(wiremock::matchers::method("POST"))
-                    .and(wiremock::matchers::path("/v8/suggest/"))
-                    .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(
-                        serde_json::json!({"payee": "Starbucks", "tag": null, "merchant": null}),
-                    ))
+            rt.block_on(async {
+                wiremock::Mock::given(wiremock::matchers::method("POST"))
+                    .and(wiremock::matchers::path("/v8/diff/"))
+                    .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(&response))
                     .mount(&mock_server)
                     .await;
             });
@@ -2174,36 +6158,73 @@ mod tests {
                 .storage(InMemoryStorage::new())
                 .build()
                 .unwrap();
-            let resp = client
-                .suggest(&SuggestRequest {
-                    payee: Some("star".to_owned()),
-                    comment: None,
-                })
+
+            let _resp = client.sync().unwrap();
+            assert!(!client.drift_detected().unwrap());
+        }
+
+        #[test]
+        fn dangling_reference_flags_drift() {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let storage = InMemoryStorage::builder()
+                .transactions(vec![test_transaction("tx-1", "a-missing", date)])
+                .build();
+            let client = ZenMoneyBlocking::builder()
+                .token("test")
+                .storage(storage)
+                .build()
                 .unwrap();
-            assert_eq!(resp.payee.unwrap(), "Starbucks");
+            assert!(client.drift_detected().unwrap());
         }
 
         #[test]
-        fn api_error_returns_error() {
+        fn sync_records_upserts_and_deletions_in_change_log() {
+            use crate::storage::{Change, ChangeOperation, EntityKind};
+
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let mut response = empty_diff_response();
+            response.transaction = vec![test_transaction("tx-new", "a-1", date)];
+            response.deletion = vec![Deletion {
+                id: "tx-old".to_owned(),
+                object: "transaction".to_owned(),
+                stamp: DateTime::from_timestamp(1_700_000_050, 0).unwrap(),
+                user: 1_i64,
+            }];
             let rt = tokio::runtime::Runtime::new().unwrap();
             let mock_server = rt.block_on(wiremock::MockServer::start());
             rt.block_on(async {
                 wiremock::Mock::given(wiremock::matchers::method("POST"))
                     .and(wiremock::matchers::path("/v8/diff/"))
-                    .respond_with(
-                        wiremock::ResponseTemplate::new(401).set_body_string("unauthorized"),
-                    )
+                    .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(&response))
                     .mount(&mock_server)
                     .await;
             });
+            let storage = InMemoryStorage::builder()
+                .transactions(vec![test_transaction("tx-old", "a-1", date)])
+                .change_log(16)
+                .build();
             let client = ZenMoneyBlocking::builder()
-                .token("bad-token")
+                .token("test-token")
                 .base_url(mock_server.uri())
-                .storage(InMemoryStorage::new())
+                .storage(storage)
                 .build()
                 .unwrap();
-            let err = client.sync().unwrap_err();
-            assert!(matches!(err, ZenMoneyError::Api { status: 401, .. }));
+            let _resp = client.sync().unwrap();
+            assert_eq!(
+                client.storage().recent_changes(16).unwrap(),
+                vec![
+                    Change {
+                        kind: EntityKind::Transaction,
+                        operation: ChangeOperation::Upsert,
+                        id: "tx-new".to_owned(),
+                    },
+                    Change {
+                        kind: EntityKind::Transaction,
+                        operation: ChangeOperation::Remove,
+                        id: "tx-old".to_owned(),
+                    },
+                ]
+            );
         }
     }
 
@@ -2595,6 +6616,33 @@ mod tests {
             assert_eq!(results.len(), 1);
         }
 
+        #[tokio::test]
+        async fn transactions_between_iterates_range() {
+            let storage = InMemoryStorage::new();
+            storage
+                .upsert_transactions(vec![
+                    test_transaction("tx-1", "a-1", NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
+                    test_transaction("tx-2", "a-1", NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()),
+                ])
+                .await
+                .unwrap();
+            let client = ZenMoney::builder()
+                .token("test")
+                .storage(storage)
+                .build()
+                .unwrap();
+            let ids: Vec<String> = client
+                .transactions_between(
+                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(),
+                )
+                .await
+                .unwrap()
+                .map(|tx| tx.id.to_string())
+                .collect();
+            assert_eq!(ids, ["tx-1"]);
+        }
+
         #[tokio::test]
         async fn transactions_by_account_delegates() {
             let storage = InMemoryStorage::new();
@@ -2647,6 +6695,360 @@ mod tests {
             );
         }
 
+        #[tokio::test]
+        async fn request_signer_signs_path_and_body() {
+            fn checksum(path: &str, body: &[u8]) -> String {
+                let sum: u64 = body.iter().map(|&byte| u64::from(byte)).sum();
+                format!("{path}:{}:{sum}", body.len())
+            }
+
+            let mock_server = wiremock::MockServer::start().await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .and(|request: &wiremock::Request| {
+                    request
+                        .headers
+                        .get("X-Gateway-Signature")
+                        .and_then(|value| value.to_str().ok())
+                        == Some(checksum(request.url.path(), &request.body).as_str())
+                })
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                )
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .request_signer(checksum)
+                .signature_header("X-Gateway-Signature")
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            let _response = client.sync().await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn user_agent_defaults_and_overrides() {
+            let mock_server = wiremock::MockServer::start().await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                )
+                .expect(2)
+                .mount(&mock_server)
+                .await;
+            for user_agent in [None, Some("budget-bot/1.2")] {
+                let mut builder = ZenMoney::builder()
+                    .token("test-token")
+                    .base_url(mock_server.uri())
+                    .storage(InMemoryStorage::new());
+                if let Some(value) = user_agent {
+                    builder = builder.user_agent(value);
+                }
+                let _response = builder.build().unwrap().sync().await.unwrap();
+            }
+
+            let requests = mock_server.received_requests().await.unwrap();
+            let sent: Vec<_> = requests
+                .iter()
+                .map(|request| request.headers.get("User-Agent").unwrap().to_str().unwrap())
+                .collect();
+            assert_eq!(
+                sent,
+                [
+                    concat!("zenmoney-rs/", env!("CARGO_PKG_VERSION")),
+                    "budget-bot/1.2"
+                ]
+            );
+        }
+
+        #[tokio::test]
+        async fn custom_headers_are_sent_without_replacing_authorization() {
+            let mock_server = wiremock::MockServer::start().await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .and(wiremock::matchers::header("X-Tenant", "family"))
+                .and(wiremock::matchers::header("Accept", "application/json"))
+                .and(wiremock::matchers::header(
+                    "Authorization",
+                    "Bearer test-token",
+                ))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                )
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .header("X-Tenant", "household")
+                .header("X-Tenant", "family")
+                .header("Accept", "application/json")
+                .header("Authorization", "Bearer other")
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            let _response = client.sync().await.unwrap();
+
+            let requests = mock_server.received_requests().await.unwrap();
+            assert_eq!(requests[0].headers.get_all("X-Tenant").iter().count(), 1);
+            assert_eq!(
+                requests[0].headers.get_all("Authorization").iter().count(),
+                1
+            );
+            let invalid = ZenMoney::builder()
+                .token("test-token")
+                .header("bad header", "value")
+                .storage(InMemoryStorage::new())
+                .build();
+            assert!(matches!(invalid, Err(ZenMoneyError::Validation(_))));
+        }
+
+        /// Mounts a diff endpoint that returns reference data only to
+        /// requests that force-fetch it.
+        async fn mount_reference_data_on_force_fetch(mock_server: &wiremock::MockServer) {
+            let mut reference = empty_diff_response();
+            reference.instrument = vec![test_instrument()];
+            reference.user = vec![test_user()];
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .and(|request: &wiremock::Request| {
+                    serde_json::from_slice::<serde_json::Value>(&request.body)
+                        .ok()
+                        .and_then(|body| body.get("forceFetch").cloned())
+                        .is_some_and(|kinds| kinds.as_array().is_some_and(|k| !k.is_empty()))
+                })
+                .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(&reference))
+                .with_priority(1)
+                .mount(mock_server)
+                .await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                )
+                .mount(mock_server)
+                .await;
+        }
+
+        #[tokio::test]
+        async fn auto_bootstrap_force_fetches_missing_reference_data() {
+            let mock_server = wiremock::MockServer::start().await;
+            mount_reference_data_on_force_fetch(&mock_server).await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .auto_bootstrap(true)
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+
+            let _response = client.sync().await.unwrap();
+            assert_eq!(client.instruments().await.unwrap().len(), 1);
+            assert_eq!(client.users().await.unwrap().len(), 1);
+
+            // Reference data is present now, so the next sync sends one request.
+            let before = mock_server.received_requests().await.unwrap().len();
+            let _response = client.sync().await.unwrap();
+            let after = mock_server.received_requests().await.unwrap().len();
+            assert_eq!(after - before, 1);
+        }
+
+        #[tokio::test]
+        async fn sync_force_requests_given_entity_types() {
+            let mock_server = wiremock::MockServer::start().await;
+            mount_reference_data_on_force_fetch(&mock_server).await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+
+            let err = client
+                .sync_force(&["instrument", "bogus"])
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ZenMoneyError::Validation(ref msg) if msg.contains("bogus")));
+            assert!(mock_server.received_requests().await.unwrap().is_empty());
+
+            let _response = client.sync_force(&["instrument", "user"]).await.unwrap();
+            let requests = mock_server.received_requests().await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+            assert_eq!(
+                body["forceFetch"],
+                serde_json::json!(["instrument", "user"])
+            );
+            assert_eq!(client.instruments().await.unwrap().len(), 1);
+            assert_eq!(client.users().await.unwrap().len(), 1);
+        }
+
+        #[tokio::test]
+        async fn sync_without_auto_bootstrap_skips_force_fetch() {
+            let mock_server = wiremock::MockServer::start().await;
+            mount_reference_data_on_force_fetch(&mock_server).await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+
+            let _response = client.sync().await.unwrap();
+            assert!(client.instruments().await.unwrap().is_empty());
+            assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+        }
+
+        #[tokio::test]
+        async fn suggest_retries_after_rate_limit() {
+            use crate::models::SuggestRequest;
+
+            let mock_server = wiremock::MockServer::start().await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/suggest/"))
+                .respond_with(wiremock::ResponseTemplate::new(429))
+                .up_to_n_times(1)
+                .with_priority(1)
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/suggest/"))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200).set_body_json(
+                        serde_json::json!({"payee": "Starbucks", "tag": ["tag-cafe"]}),
+                    ),
+                )
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .retry(crate::client::RetryPolicy::new(
+                    2,
+                    Duration::from_millis(1),
+                    Duration::from_millis(10),
+                ))
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+
+            let request = SuggestRequest {
+                payee: Some("starbucks".to_owned()),
+                comment: None,
+            };
+            let response = client.suggest(&request).await.unwrap();
+            assert_eq!(response.payee.as_deref(), Some("Starbucks"));
+        }
+
+        #[tokio::test]
+        async fn sync_retries_server_errors_but_not_client_errors() {
+            let policy = crate::client::RetryPolicy::new(
+                3,
+                Duration::from_millis(1),
+                Duration::from_millis(10),
+            );
+            let mock_server = wiremock::MockServer::start().await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .respond_with(wiremock::ResponseTemplate::new(503))
+                .up_to_n_times(2)
+                .expect(2)
+                .mount(&mock_server)
+                .await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200).set_body_json(&empty_diff_response()),
+                )
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .retry(policy)
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            let _resp = client.sync().await.unwrap();
+
+            let rejecting = wiremock::MockServer::start().await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .respond_with(wiremock::ResponseTemplate::new(400))
+                .expect(1)
+                .mount(&rejecting)
+                .await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(rejecting.uri())
+                .retry(policy)
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+            let err = client.sync().await.unwrap_err();
+            assert!(matches!(err, ZenMoneyError::BadRequest { .. }));
+        }
+
+        #[tokio::test]
+        async fn suggest_without_retry_policy_surfaces_rate_limit() {
+            use crate::models::SuggestRequest;
+
+            let mock_server = wiremock::MockServer::start().await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/suggest/"))
+                .respond_with(wiremock::ResponseTemplate::new(429))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+
+            let request = SuggestRequest {
+                payee: Some("starbucks".to_owned()),
+                comment: None,
+            };
+            let err = client.suggest(&request).await.unwrap_err();
+            assert!(matches!(
+                err,
+                ZenMoneyError::RateLimited { retry_after: None }
+            ));
+        }
+
+        #[tokio::test]
+        async fn slow_response_surfaces_timeout() {
+            let mock_server = wiremock::MockServer::start().await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v8/diff/"))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200)
+                        .set_body_json(&empty_diff_response())
+                        .set_delay(Duration::from_secs(2)),
+                )
+                .mount(&mock_server)
+                .await;
+            let client = ZenMoney::builder()
+                .token("test-token")
+                .base_url(mock_server.uri())
+                .timeout(Duration::from_millis(50))
+                .storage(InMemoryStorage::new())
+                .build()
+                .unwrap();
+
+            let err = client.sync().await.unwrap_err();
+            assert!(matches!(err, ZenMoneyError::Timeout(_)));
+        }
+
         #[tokio::test]
         async fn full_sync_clears_and_syncs() {
             let mock_server = wiremock::MockServer::start().await;
@@ -2868,7 +7270,7 @@ mod tests {
                 .build()
                 .unwrap();
             let err = client.sync().await.unwrap_err();
-            assert!(matches!(err, ZenMoneyError::Api { status: 401, .. }));
+            assert!(matches!(err, ZenMoneyError::Unauthorized { .. }));
         }
 
         #[tokio::test]
@@ -0,0 +1,35 @@
+//! Per-account balances for an overview screen.
+
+use super::split_cleared_and_pending;
+use crate::models::{Account, Transaction};
+
+/// An account together with the balances computed from local
+/// transactions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountSummary {
+    /// The stored account, including the server-reported `balance`.
+    pub account: Account,
+    /// `start_balance` (zero if unset) plus every non-deleted transaction
+    /// touching the account, including those on hold.
+    pub computed_balance: f64,
+    /// `computed_balance` plus the account's credit limit (zero if
+    /// unset).
+    pub available_balance: f64,
+}
+
+impl AccountSummary {
+    /// Computes the balances of `account` from `transactions`.
+    pub(super) fn of(account: Account, transactions: &[Transaction]) -> Self {
+        let (cleared, pending) = split_cleared_and_pending(
+            account.start_balance.unwrap_or(0.0_f64),
+            transactions,
+            &account.id,
+        );
+        let computed_balance = cleared + pending;
+        Self {
+            available_balance: computed_balance + account.credit_limit.unwrap_or(0.0_f64),
+            computed_balance,
+            account,
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Referential integrity checks for stored entities.
+
+use core::fmt;
+use core::hash::Hash;
+use std::collections::HashSet;
+
+use crate::models::{
+    Account, Budget, Instrument, Merchant, NaiveDate, Reminder, ReminderId, ReminderMarker,
+    ReminderMarkerId, Tag, TagId, Transaction, TransactionId,
+};
+
+/// Transaction field holding a dangling reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceField {
+    /// [`Transaction::income_account`].
+    IncomeAccount,
+    /// [`Transaction::outcome_account`].
+    OutcomeAccount,
+    /// [`Transaction::income_instrument`].
+    IncomeInstrument,
+    /// [`Transaction::outcome_instrument`].
+    OutcomeInstrument,
+    /// One of the IDs in [`Transaction::tag`].
+    Tag,
+    /// [`Transaction::merchant`].
+    Merchant,
+}
+
+impl fmt::Display for ReferenceField {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            Self::IncomeAccount => "income account",
+            Self::OutcomeAccount => "outcome account",
+            Self::IncomeInstrument => "income instrument",
+            Self::OutcomeInstrument => "outcome instrument",
+            Self::Tag => "tag",
+            Self::Merchant => "merchant",
+        })
+    }
+}
+
+/// A transaction reference with no corresponding stored entity.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReferenceError {
+    /// Transaction holding the reference.
+    pub transaction: TransactionId,
+    /// Field the reference was found in.
+    pub field: ReferenceField,
+    /// Referenced ID in its string form.
+    pub id: String,
+}
+
+impl fmt::Display for ReferenceError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "transaction {} references missing {} {}",
+            self.transaction, self.field, self.id
+        )
+    }
+}
+
+/// A stored entity that references another entity missing from storage.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum IntegrityIssue {
+    /// A transaction references a missing account, instrument, tag, or
+    /// merchant.
+    Transaction(ReferenceError),
+    /// A reminder marker references a missing reminder.
+    ReminderMarker {
+        /// Marker holding the reference.
+        marker: ReminderMarkerId,
+        /// Missing reminder.
+        reminder: ReminderId,
+    },
+    /// A budget references a missing tag.
+    Budget {
+        /// Month the budget applies to.
+        date: NaiveDate,
+        /// Missing tag.
+        tag: TagId,
+    },
+}
+
+impl fmt::Display for IntegrityIssue {
+    #[inline]
+    #[allow(
+        clippy::pattern_type_mismatch,
+        reason = "variants hold non-Copy IDs that are only borrowed"
+    )]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transaction(err) => err.fmt(f),
+            Self::ReminderMarker { marker, reminder } => write!(
+                f,
+                "reminder marker {marker} references missing reminder {reminder}"
+            ),
+            Self::Budget { date, tag } => {
+                write!(f, "budget for {date} references missing tag {tag}")
+            }
+        }
+    }
+}
+
+impl From<ReferenceError> for IntegrityIssue {
+    #[inline]
+    fn from(err: ReferenceError) -> Self {
+        Self::Transaction(err)
+    }
+}
+
+/// Collects every ID of a collection into a set.
+fn id_set<T, K: Eq + Hash>(items: &[T], key: impl Fn(&T) -> K) -> HashSet<K> {
+    items.iter().map(key).collect()
+}
+
+/// Reports references from non-deleted transactions to entities missing
+/// from the given collections, in transaction order.
+pub(super) fn find_dangling_references(
+    transactions: &[Transaction],
+    accounts: &[Account],
+    instruments: &[Instrument],
+    tags: &[Tag],
+    merchants: &[Merchant],
+) -> Vec<ReferenceError> {
+    let account_ids = id_set(accounts, |account| account.id.clone());
+    let instrument_ids = id_set(instruments, |instrument| instrument.id);
+    let tag_ids = id_set(tags, |tag| tag.id.clone());
+    let merchant_ids = id_set(merchants, |merchant| merchant.id.clone());
+
+    let mut errors = Vec::new();
+    for tx in transactions.iter().filter(|tx| !tx.deleted) {
+        let mut report = |field: ReferenceField, id: String| {
+            errors.push(ReferenceError {
+                transaction: tx.id.clone(),
+                field,
+                id,
+            });
+        };
+        if !account_ids.contains(&tx.income_account) {
+            report(ReferenceField::IncomeAccount, tx.income_account.to_string());
+        }
+        if !account_ids.contains(&tx.outcome_account) {
+            report(
+                ReferenceField::OutcomeAccount,
+                tx.outcome_account.to_string(),
+            );
+        }
+        if !instrument_ids.contains(&tx.income_instrument) {
+            report(
+                ReferenceField::IncomeInstrument,
+                tx.income_instrument.to_string(),
+            );
+        }
+        if !instrument_ids.contains(&tx.outcome_instrument) {
+            report(
+                ReferenceField::OutcomeInstrument,
+                tx.outcome_instrument.to_string(),
+            );
+        }
+        for tag in tx.tag.iter().flatten() {
+            if !tag_ids.contains(tag) {
+                report(ReferenceField::Tag, tag.to_string());
+            }
+        }
+        if let Some(merchant) = tx.merchant.as_ref()
+            && !merchant_ids.contains(merchant)
+        {
+            report(ReferenceField::Merchant, merchant.to_string());
+        }
+    }
+    errors
+}
+
+/// Reports reminder markers whose reminder is missing, and budgets whose
+/// tag is missing, in storage order.
+pub(super) fn find_orphaned_plans(
+    markers: &[ReminderMarker],
+    reminders: &[Reminder],
+    budgets: &[Budget],
+    tags: &[Tag],
+) -> Vec<IntegrityIssue> {
+    let reminder_ids = id_set(reminders, |reminder| reminder.id.clone());
+    let tag_ids = id_set(tags, |tag| tag.id.clone());
+
+    let orphaned_markers = markers
+        .iter()
+        .filter(|marker| !reminder_ids.contains(&marker.reminder))
+        .map(|marker| IntegrityIssue::ReminderMarker {
+            marker: marker.id.clone(),
+            reminder: marker.reminder.clone(),
+        });
+    let orphaned_budgets = budgets.iter().filter_map(|budget| {
+        budget
+            .tag
+            .as_ref()
+            .filter(|tag| !tag_ids.contains(*tag))
+            .map(|tag| IntegrityIssue::Budget {
+                date: budget.date,
+                tag: tag.clone(),
+            })
+    });
+    orphaned_markers.chain(orphaned_budgets).collect()
+}
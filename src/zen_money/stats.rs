@@ -0,0 +1,62 @@
+//! Summary of what the local storage currently holds.
+
+use chrono::{DateTime, Utc};
+
+/// Count and most recent modification of one stored entity type.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EntityStats {
+    /// Number of stored entities, including soft-deleted ones.
+    pub count: usize,
+    /// Latest `changed` timestamp among the stored entities.
+    ///
+    /// `None` when nothing is stored or the entity type carries no
+    /// modification time.
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl EntityStats {
+    /// Collects stats for entities with a `changed` timestamp.
+    pub(super) fn of<T>(items: &[T], changed: impl Fn(&T) -> DateTime<Utc>) -> Self {
+        Self {
+            count: items.len(),
+            last_modified: items.iter().map(changed).max(),
+        }
+    }
+
+    /// Collects stats for entities without a modification time.
+    pub(super) const fn counted<T>(items: &[T]) -> Self {
+        Self {
+            count: items.len(),
+            last_modified: None,
+        }
+    }
+}
+
+/// Per-entity counts and timestamps of the local storage.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Server timestamp of the last successful sync.
+    pub server_timestamp: Option<DateTime<Utc>>,
+    /// Stored accounts.
+    pub accounts: EntityStats,
+    /// Stored transactions.
+    pub transactions: EntityStats,
+    /// Stored tags.
+    pub tags: EntityStats,
+    /// Stored merchants.
+    pub merchants: EntityStats,
+    /// Stored instruments.
+    pub instruments: EntityStats,
+    /// Stored companies.
+    pub companies: EntityStats,
+    /// Stored countries (never carry a modification time).
+    pub countries: EntityStats,
+    /// Stored users.
+    pub users: EntityStats,
+    /// Stored reminders.
+    pub reminders: EntityStats,
+    /// Stored reminder markers.
+    pub reminder_markers: EntityStats,
+    /// Stored budgets.
+    pub budgets: EntityStats,
+}
@@ -0,0 +1,56 @@
+//! Suggest responses resolved against stored merchants and tags.
+
+use std::collections::HashMap;
+
+use crate::models::{Merchant, MerchantId, SuggestResponse, Tag, TagId};
+
+/// A suggested reference, either found in storage or not yet known
+/// locally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolved<T, I> {
+    /// The entity exists in local storage.
+    Existing(T),
+    /// The entity is missing from local storage; only its ID is known.
+    New(I),
+}
+
+/// A [`SuggestResponse`] with merchant and tag IDs replaced by stored
+/// entities where available.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSuggestion {
+    /// Normalized payee name.
+    pub payee: Option<String>,
+    /// Suggested merchant.
+    pub merchant: Option<Resolved<Merchant, MerchantId>>,
+    /// Suggested tags, in the order the server returned them.
+    pub tags: Vec<Resolved<Tag, TagId>>,
+}
+
+impl ResolvedSuggestion {
+    /// Resolves `response` against stored merchants and tags keyed by ID.
+    pub(super) fn of(
+        response: &SuggestResponse,
+        merchants: &HashMap<MerchantId, Merchant>,
+        tags: &HashMap<TagId, Tag>,
+    ) -> Self {
+        Self {
+            payee: response.payee.clone(),
+            merchant: response.merchant.as_ref().map(|id| {
+                merchants
+                    .get(id)
+                    .cloned()
+                    .map_or_else(|| Resolved::New(id.clone()), Resolved::Existing)
+            }),
+            tags: response
+                .tag
+                .iter()
+                .flatten()
+                .map(|id| {
+                    tags.get(id)
+                        .cloned()
+                        .map_or_else(|| Resolved::New(id.clone()), Resolved::Existing)
+                })
+                .collect(),
+        }
+    }
+}
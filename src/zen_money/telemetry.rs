@@ -0,0 +1,139 @@
+//! Optional sync metrics.
+//!
+//! With the `metrics` feature enabled, every sync (incremental, full,
+//! forced, or pull-only) reports to the global [`metrics`] recorder:
+//!
+//! - `zenmoney_sync_total` — counter of sync attempts
+//! - `zenmoney_sync_errors_total` — counter of failed syncs
+//! - `zenmoney_sync_duration_seconds` — histogram of sync durations
+//! - `zenmoney_sync_upserts_total{entity}` — counter of received entities
+//! - `zenmoney_sync_deletions_total{entity}` — counter of received deletions
+//!
+//! Without the feature, [`SyncTimer`] is zero-sized and its methods are
+//! empty.
+
+use crate::error::Result;
+use crate::models::DiffResponse;
+
+/// Measures one sync and reports its outcome when finished.
+#[derive(Debug)]
+pub(super) struct SyncTimer {
+    /// When the sync started.
+    #[cfg(feature = "metrics")]
+    started: std::time::Instant,
+}
+
+impl SyncTimer {
+    /// Starts timing a sync.
+    #[cfg_attr(
+        not(feature = "metrics"),
+        allow(
+            clippy::missing_const_for_fn,
+            reason = "reads the clock with metrics enabled"
+        )
+    )]
+    #[inline]
+    pub(super) fn start() -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            started: std::time::Instant::now(),
+        }
+    }
+
+    /// Records the sync's duration, outcome, and entity counts.
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub(super) fn finish(self, result: &Result<DiffResponse>) {
+        metrics::counter!("zenmoney_sync_total").increment(1);
+        metrics::histogram!("zenmoney_sync_duration_seconds")
+            .record(self.started.elapsed().as_secs_f64());
+        match result.as_ref() {
+            Ok(response) => record_entity_counts(response),
+            Err(_) => metrics::counter!("zenmoney_sync_errors_total").increment(1),
+        }
+    }
+
+    /// Records the sync's duration, outcome, and entity counts.
+    #[cfg(not(feature = "metrics"))]
+    #[allow(clippy::unused_self, reason = "no-op without the metrics feature")]
+    #[inline]
+    pub(super) const fn finish(self, _result: &Result<DiffResponse>) {}
+}
+
+/// Reports per-entity upsert and deletion counts of a diff response.
+#[cfg(feature = "metrics")]
+fn record_entity_counts(response: &DiffResponse) {
+    use super::entity_type;
+
+    let upserts = [
+        (entity_type::ACCOUNT, response.account.len()),
+        (entity_type::TRANSACTION, response.transaction.len()),
+        (entity_type::TAG, response.tag.len()),
+        (entity_type::MERCHANT, response.merchant.len()),
+        (entity_type::INSTRUMENT, response.instrument.len()),
+        (entity_type::COMPANY, response.company.len()),
+        (entity_type::COUNTRY, response.country.len()),
+        (entity_type::USER, response.user.len()),
+        (entity_type::REMINDER, response.reminder.len()),
+        (entity_type::REMINDER_MARKER, response.reminder_marker.len()),
+        (entity_type::BUDGET, response.budget.len()),
+    ];
+    for (entity, count) in upserts.into_iter().filter(|entry| entry.1 > 0) {
+        metrics::counter!("zenmoney_sync_upserts_total", "entity" => entity)
+            .increment(count as u64);
+    }
+    for deletion in &response.deletion {
+        metrics::counter!("zenmoney_sync_deletions_total", "entity" => deletion.object.clone())
+            .increment(1);
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use metrics_util::CompositeKey;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    use super::*;
+    use crate::error::ZenMoneyError;
+
+    /// Returns the value of counter `name`, or 0 if it was never emitted.
+    fn counter(snapshot: &[(CompositeKey, DebugValue)], name: &str) -> u64 {
+        snapshot
+            .iter()
+            .filter(|entry| entry.0.key().name() == name)
+            .map(|entry| match entry.1 {
+                DebugValue::Counter(value) => value,
+                DebugValue::Gauge(_) | DebugValue::Histogram(_) => 0,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn finish_increments_sync_counters() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let response: DiffResponse = serde_json::from_value(serde_json::json!({
+            "serverTimestamp": 1_700_000_100,
+            "deletion": [
+                {"id": "tx-1", "object": "transaction", "stamp": 1_700_000_000, "user": 1},
+            ],
+        }))
+        .unwrap();
+
+        metrics::with_local_recorder(&recorder, || {
+            SyncTimer::start().finish(&Ok(response));
+            SyncTimer::start().finish(&Err(ZenMoneyError::Validation("boom".to_owned())));
+        });
+
+        let snapshot: Vec<(CompositeKey, DebugValue)> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _unit, _description, value)| (key, value))
+            .collect();
+        assert_eq!(counter(&snapshot, "zenmoney_sync_total"), 2);
+        assert_eq!(counter(&snapshot, "zenmoney_sync_errors_total"), 1);
+        assert_eq!(counter(&snapshot, "zenmoney_sync_deletions_total"), 1);
+        assert_eq!(counter(&snapshot, "zenmoney_sync_upserts_total"), 0);
+    }
+}